@@ -0,0 +1,52 @@
+//! Benchmark for extracting JSON from input that is already pure JSON.
+//!
+//! Run with `cargo bench --bench clean_json`. `extract_json_to_cow` recognizes
+//! a single clean value with a byte scan and borrows it, while the parser
+//! walks every character of the same input.
+
+use std::borrow::Cow;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use surfing::utils::extract_json_to_cow;
+use surfing::JSONParser;
+
+const ITERATIONS: u32 = 200;
+
+fn time<F: FnMut()>(mut f: F) -> Duration {
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        f();
+    }
+    start.elapsed() / ITERATIONS
+}
+
+fn main() {
+    let record = "{\"id\": 7, \"tags\": [\"a\", \"b\"], \"ok\": true}";
+    let input = format!("[{}]\n", vec![record; 20_000].join(", "));
+
+    let borrowed = time(|| {
+        let json = extract_json_to_cow(black_box(&input)).unwrap();
+        assert!(matches!(json, Cow::Borrowed(_)));
+    });
+
+    let parsed = time(|| {
+        let mut buffer = Vec::with_capacity(input.len());
+        JSONParser::new()
+            .extract_json_from_stream(&mut buffer, black_box(&input))
+            .unwrap();
+    });
+
+    let megabytes = input.len() as f64 / (1024.0 * 1024.0);
+    println!("clean JSON input: {:.2} MiB", megabytes);
+    println!(
+        "cow fast path:  {:>10.2?} ({:.0} MiB/s)",
+        borrowed,
+        megabytes / borrowed.as_secs_f64()
+    );
+    println!(
+        "parser:         {:>10.2?} ({:.0} MiB/s)",
+        parsed,
+        megabytes / parsed.as_secs_f64()
+    );
+}
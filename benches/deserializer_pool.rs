@@ -0,0 +1,108 @@
+//! Benchmark for allocator traffic of short-lived streaming deserializers.
+//!
+//! Run with `cargo bench --bench deserializer_pool --features serde`. Each
+//! cycle creates a deserializer (or resets one), feeds it a value split across
+//! chunks and drops it, like a router handling one message per connection.
+//! A counting global allocator reports how many allocations each approach
+//! makes. The parser itself allocates while scanning each value, so the
+//! differences between the rows are the buffer traffic saved.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::hint::black_box;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+use serde::Deserialize;
+use surfing::serde::{BufferPool, StreamingDeserializer};
+
+const CYCLES: usize = 100_000;
+
+/// The system allocator, counting every allocation and reallocation.
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+#[derive(Deserialize)]
+struct Message {
+    #[allow(dead_code)]
+    id: u64,
+}
+
+const CHUNKS: [&str; 3] = [
+    "route=/orders payload={\"id\":",
+    "12345,\"padding\":\"0123456789abcdef0123456789abcdef\"",
+    "} done",
+];
+
+fn feed(deserializer: &mut StreamingDeserializer<Message>) {
+    for chunk in CHUNKS {
+        black_box(deserializer.process_chunk(black_box(chunk)));
+    }
+}
+
+fn measure(cycle: impl Fn()) -> (Duration, usize) {
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+    let start = Instant::now();
+
+    for _ in 0..CYCLES {
+        cycle();
+    }
+
+    (
+        start.elapsed(),
+        ALLOCATIONS.load(Ordering::Relaxed) - before,
+    )
+}
+
+fn main() {
+    let (fresh, fresh_allocations) = measure(|| {
+        let mut deserializer = StreamingDeserializer::<Message>::new();
+        feed(&mut deserializer);
+    });
+
+    let pool = BufferPool::new(64);
+    let (pooled, pooled_allocations) = measure(|| {
+        let mut deserializer = StreamingDeserializer::<Message>::with_pool(pool.clone());
+        feed(&mut deserializer);
+    });
+
+    let deserializer = std::cell::RefCell::new(StreamingDeserializer::<Message>::new());
+    let (reset, reset_allocations) = measure(|| {
+        let mut deserializer = deserializer.borrow_mut();
+        feed(&mut deserializer);
+        deserializer.reset();
+    });
+
+    println!("{} create/feed/drop cycles", CYCLES);
+    println!(
+        "new deserializer: {:>10.2?} {:>10} allocations",
+        fresh, fresh_allocations
+    );
+    println!(
+        "with pool:        {:>10.2?} {:>10} allocations",
+        pooled, pooled_allocations
+    );
+    println!(
+        "reset and reuse:  {:>10.2?} {:>10} allocations",
+        reset, reset_allocations
+    );
+}
@@ -0,0 +1,69 @@
+//! Benchmark for extracting JSON from text that mostly contains no JSON.
+//!
+//! Run with `cargo bench --bench fast_scan`. The bulk call skips marker-free
+//! text with a byte scan. The baseline pushes every character through the
+//! parser state machine with `push_char`, as extraction did before the scan,
+//! and feeding one-character chunks shows the cost of many small calls.
+
+use std::hint::black_box;
+use std::io::sink;
+use std::time::{Duration, Instant};
+
+use surfing::JSONParser;
+
+const ITERATIONS: u32 = 200;
+
+fn time<F: FnMut()>(mut f: F) -> Duration {
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        f();
+    }
+    start.elapsed() / ITERATIONS
+}
+
+fn main() {
+    let line = "2024-05-01T12:00:00Z INFO request handled in 12ms by worker-7 (status=ok)\n";
+    let input = line.repeat(10_000);
+    let chars: Vec<String> = input.chars().map(|c| c.to_string()).collect();
+
+    let baseline = time(|| {
+        let mut parser = JSONParser::new();
+        for item in black_box(&input).chars() {
+            parser.push_char(item, &mut sink()).unwrap();
+        }
+    });
+
+    let bulk = time(|| {
+        let mut parser = JSONParser::new();
+        parser
+            .extract_json_from_stream(&mut sink(), black_box(&input))
+            .unwrap();
+    });
+
+    let per_char = time(|| {
+        let mut parser = JSONParser::new();
+        for item in &chars {
+            parser
+                .extract_json_from_stream(&mut sink(), black_box(item))
+                .unwrap();
+        }
+    });
+
+    let megabytes = input.len() as f64 / (1024.0 * 1024.0);
+    println!("marker-free input: {:.2} MiB", megabytes);
+    println!(
+        "state machine:  {:>10.2?} ({:.0} MiB/s)",
+        baseline,
+        megabytes / baseline.as_secs_f64()
+    );
+    println!(
+        "fast scan:      {:>10.2?} ({:.0} MiB/s)",
+        bulk,
+        megabytes / bulk.as_secs_f64()
+    );
+    println!(
+        "char by char:   {:>10.2?} ({:.0} MiB/s)",
+        per_char,
+        megabytes / per_char.as_secs_f64()
+    );
+}
@@ -0,0 +1,65 @@
+//! Benchmark for listing the top-level keys of extracted JSON.
+//!
+//! Run with `cargo bench --bench top_level_keys --features serde`.
+//! `extract_top_level_keys` skips nested values structurally, while the
+//! baseline parses every extracted value into a `serde_json::Value`.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use serde_json::Value;
+use surfing::utils::{extract_json_to_string, extract_top_level_keys};
+use surfing::JSONParser;
+
+const ITERATIONS: u32 = 50;
+
+fn time<F: FnMut()>(mut f: F) -> Duration {
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        f();
+    }
+    start.elapsed() / ITERATIONS
+}
+
+fn main() {
+    let record = "{\"id\": 7, \"tags\": [\"a\", \"b\"], \"ok\": true, \"name\": \"surf\"}";
+    let payload = format!(
+        "{{\"event\": \"sync\", \"records\": [{}], \"meta\": {{\"count\": 20000}}}}",
+        vec![record; 20_000].join(", ")
+    );
+    let input = format!("INFO routing {} done", payload);
+    assert_eq!(extract_json_to_string(&input).unwrap().len(), payload.len());
+
+    let scanned = time(|| {
+        let keys = extract_top_level_keys(black_box(&input), false);
+        assert_eq!(keys[0].len(), 3);
+    });
+
+    let deserialized = time(|| {
+        let mut keys = Vec::new();
+        JSONParser::new()
+            .extract_values_from_stream(black_box(&input), |json| {
+                let value: Value = serde_json::from_str(json).unwrap();
+                let names: Vec<String> = value
+                    .as_object()
+                    .map(|object| object.keys().cloned().collect())
+                    .unwrap_or_default();
+                keys.push(names);
+            })
+            .unwrap();
+        assert_eq!(keys[0].len(), 3);
+    });
+
+    let megabytes = input.len() as f64 / (1024.0 * 1024.0);
+    println!("mixed text input: {:.2} MiB", megabytes);
+    println!(
+        "top-level keys: {:>10.2?} ({:.0} MiB/s)",
+        scanned,
+        megabytes / scanned.as_secs_f64()
+    );
+    println!(
+        "serde Value:    {:>10.2?} ({:.0} MiB/s)",
+        deserialized,
+        megabytes / deserialized.as_secs_f64()
+    );
+}
@@ -0,0 +1,63 @@
+//! Benchmark for checking streamed JSON without extracting it.
+//!
+//! Run with `cargo bench --bench validator`. `JsonValidator` only follows the
+//! grammar, while the parser also writes every character of the value.
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use surfing::parser::validator::{JsonValidator, ValidationStatus};
+use surfing::JSONParser;
+
+const ITERATIONS: u32 = 200;
+const CHUNK_SIZE: usize = 4096;
+
+fn time<F: FnMut()>(mut f: F) -> Duration {
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        f();
+    }
+    start.elapsed() / ITERATIONS
+}
+
+fn main() {
+    let record = "{\"id\": 7, \"tags\": [\"a\", \"b\"], \"ok\": true}";
+    let input = format!("[{}]", vec![record; 20_000].join(", "));
+    let chunks: Vec<&str> = input
+        .as_bytes()
+        .chunks(CHUNK_SIZE)
+        .map(|chunk| std::str::from_utf8(chunk).unwrap())
+        .collect();
+
+    let validated = time(|| {
+        let mut validator = JsonValidator::new();
+        let mut status = ValidationStatus::Incomplete;
+        for chunk in &chunks {
+            status = validator.feed(black_box(chunk));
+        }
+        assert!(matches!(status, ValidationStatus::Complete { .. }));
+    });
+
+    let parsed = time(|| {
+        let mut parser = JSONParser::new();
+        let mut buffer = Vec::with_capacity(input.len());
+        for chunk in &chunks {
+            parser
+                .extract_json_from_stream(&mut buffer, black_box(chunk))
+                .unwrap();
+        }
+    });
+
+    let megabytes = input.len() as f64 / (1024.0 * 1024.0);
+    println!("streamed JSON input: {:.2} MiB", megabytes);
+    println!(
+        "validator:      {:>10.2?} ({:.0} MiB/s)",
+        validated,
+        megabytes / validated.as_secs_f64()
+    );
+    println!(
+        "parser:         {:>10.2?} ({:.0} MiB/s)",
+        parsed,
+        megabytes / parsed.as_secs_f64()
+    );
+}
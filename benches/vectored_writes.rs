@@ -0,0 +1,77 @@
+//! Benchmark for the number of write calls needed to emit extracted JSON.
+//!
+//! Run with `cargo bench --bench vectored_writes`. The writer behaves like a
+//! pipe or socket: every call is counted as a syscall. Plain extraction hands
+//! all spans of a chunk to one vectored write, while `ascii_only` (which
+//! leaves this ASCII input unchanged) still writes one character at a time.
+
+use std::hint::black_box;
+use std::io::{IoSlice, Write};
+use std::time::{Duration, Instant};
+
+use surfing::parser::options::ParserOptions;
+use surfing::JSONParser;
+
+const ITERATIONS: u32 = 20;
+
+/// A writer counting its calls, accepting every slice of a vectored write.
+#[derive(Default)]
+struct SyscallWriter {
+    calls: usize,
+    bytes: usize,
+}
+
+impl Write for SyscallWriter {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.calls += 1;
+        self.bytes += buf.len();
+        Ok(buf.len())
+    }
+
+    fn write_vectored(&mut self, bufs: &[IoSlice<'_>]) -> std::io::Result<usize> {
+        self.calls += 1;
+        let written = bufs.iter().map(|buf| buf.len()).sum::<usize>();
+        self.bytes += written;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+fn run(options: ParserOptions, chunks: &[String]) -> (Duration, SyscallWriter) {
+    let mut writer = SyscallWriter::default();
+    let start = Instant::now();
+
+    for _ in 0..ITERATIONS {
+        writer = SyscallWriter::default();
+        let mut parser = JSONParser::with_options(options.clone());
+        for chunk in chunks {
+            parser
+                .extract_json_from_stream(&mut writer, black_box(chunk))
+                .unwrap();
+        }
+    }
+
+    (start.elapsed() / ITERATIONS, writer)
+}
+
+fn main() {
+    let line = "12:00:01 event {\"id\":42,\"kind\":\"order\",\"items\":[1,2,3]} accepted\n";
+    let chunks: Vec<String> = (0..2_000).map(|_| line.repeat(4)).collect();
+
+    let (vectored, vectored_writer) = run(ParserOptions::new(), &chunks);
+    let (per_char, per_char_writer) = run(ParserOptions::new().ascii_only(true), &chunks);
+
+    assert_eq!(vectored_writer.bytes, per_char_writer.bytes);
+    println!("output: {} bytes", vectored_writer.bytes);
+    println!(
+        "vectored:       {:>10.2?} {:>10} write calls",
+        vectored, vectored_writer.calls
+    );
+    println!(
+        "char by char:   {:>10.2?} {:>10} write calls",
+        per_char, per_char_writer.calls
+    );
+}
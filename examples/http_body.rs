@@ -0,0 +1,89 @@
+//! HTTP body example
+//!
+//! This example starts a local hyper server that dribbles mixed text and
+//! JSON in small pieces, then streams typed values out of the response body
+//! as it arrives.
+//!
+//! Run with: cargo run --example http_body --features http
+
+use std::convert::Infallible;
+use std::time::Duration;
+
+use bytes::Bytes;
+use futures::StreamExt;
+use http_body::Frame;
+use http_body_util::{Empty, StreamBody};
+use hyper::server::conn::http1;
+use hyper::service::service_fn;
+use hyper::{Request, Response};
+use hyper_util::rt::TokioIo;
+use serde::Deserialize;
+use surfing::serde::from_body;
+use tokio::net::{TcpListener, TcpStream};
+
+#[derive(Debug, Deserialize)]
+struct Progress {
+    step: u32,
+    status: String,
+}
+
+// The response, split at awkward places to show values spanning frames
+const PIECES: [&str; 6] = [
+    "starting job\n{\"step\":1,",
+    "\"status\":\"queued\"}\nworking",
+    "...\n{\"step\":2,\"status\":\"run",
+    "ning\"}\n{\"step\":3,\"status\":\"done\"}",
+    "\nsummary: {\"step\":",
+    "4,\"status\":\"archived\"} bye\n",
+];
+
+async fn dribble(
+    _: Request<hyper::body::Incoming>,
+) -> Result<
+    Response<StreamBody<impl futures::Stream<Item = Result<Frame<Bytes>, Infallible>>>>,
+    Infallible,
+> {
+    let frames = futures::stream::iter(PIECES).then(|piece| async move {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        Ok(Frame::data(Bytes::from(piece)))
+    });
+
+    Ok(Response::new(StreamBody::new(frames)))
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    println!("=== HTTP Body Example ===\n");
+
+    let listener = TcpListener::bind("127.0.0.1:0").await?;
+    let address = listener.local_addr()?;
+
+    // Serve a single connection
+    tokio::spawn(async move {
+        let (stream, _) = listener.accept().await?;
+        http1::Builder::new()
+            .serve_connection(TokioIo::new(stream), service_fn(dribble))
+            .await?;
+        Ok::<_, Box<dyn std::error::Error + Send + Sync>>(())
+    });
+
+    let stream = TcpStream::connect(address).await?;
+    let (mut sender, connection) =
+        hyper::client::conn::http1::handshake(TokioIo::new(stream)).await?;
+    tokio::spawn(connection);
+
+    let request = Request::get(format!("http://{}/job", address)).body(Empty::<Bytes>::new())?;
+    let response = sender.send_request(request).await?;
+    println!("Response status: {}\n", response.status());
+
+    let mut values = from_body::<Progress, _>(response.into_body());
+    while let Some(result) = values.next().await {
+        match result {
+            Ok(progress) => println!("Step {}: {}", progress.step, progress.status),
+            Err(e) => println!("Error: {}", e),
+        }
+    }
+
+    println!("\n=== Example Complete ===");
+    Ok(())
+}
@@ -107,6 +107,16 @@ fn main() {
     }
 
     println!("Stream processing complete.");
+
+    // A finite source can also be drained in one call, skipping the bracketed
+    // timestamps, which are extracted too but are not messages
+    let (messages, skipped) = StreamingDeserializer::<Message>::collect_all_lenient(stream_chunks);
+    println!(
+        "Collected {} messages in one call, skipping {} other values.\n",
+        messages.len(),
+        skipped.len()
+    );
+
     println!("The StreamingDeserializer automatically handles:");
     println!(" - Mixed text with embedded JSON");
     println!(" - JSON objects split across multiple chunks");
@@ -5,3 +5,6 @@ pub const MARKERS: [char; 2] = ['{', '['];
 
 /// All paired markers (opening and closing) used in JSON structures
 pub const PAIRED_MARKERS: [char; 4] = ['{', '}', '[', ']'];
+
+/// The byte order mark, which some producers prepend to every record
+pub const BYTE_ORDER_MARK: char = '\u{FEFF}';
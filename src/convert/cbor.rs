@@ -0,0 +1,224 @@
+//! CBOR output for extracted JSON.
+
+use std::io::Write;
+
+use crate::convert::{transcode_chunk, ConvertError};
+use crate::JSONParser;
+
+/// Extracts JSON from mixed text and writes it to the writer as a CBOR sequence.
+///
+/// Every extracted value is transcoded to one CBOR data item and the items are
+/// written back to back, forming a CBOR sequence (RFC 8742). CBOR items are
+/// self-delimiting, so a reader can decode them one after another.
+///
+/// # Arguments
+///
+/// * `input` - A string slice containing mixed text with embedded JSON.
+/// * `writer` - The writer receiving the CBOR sequence.
+///
+/// # Returns
+///
+/// * `Ok(usize)` - The number of values written.
+/// * `Err(ConvertError)` - If extraction or encoding of a value fails.
+///
+/// # Examples
+///
+/// ```
+/// use surfing::convert::extract_json_to_cbor;
+///
+/// let mut cbor = Vec::new();
+/// let count = extract_json_to_cbor("Result: {\"ok\":true} [1,2]", &mut cbor).unwrap();
+///
+/// assert_eq!(count, 2);
+/// ```
+pub fn extract_json_to_cbor<W: Write>(input: &str, writer: &mut W) -> Result<usize, ConvertError> {
+    let mut encoder = CborEncoder::new(writer);
+    encoder.process_chunk(input)?;
+    Ok(encoder.values_written())
+}
+
+/// A streaming encoder that writes each completed JSON value as CBOR.
+///
+/// The encoder feeds chunks of mixed text through a [`JSONParser`] and
+/// transcodes every value as soon as its closing marker arrives, so values
+/// may span several chunks.
+///
+/// # Examples
+///
+/// ```
+/// use surfing::convert::CborEncoder;
+///
+/// let mut cbor = Vec::new();
+/// let mut encoder = CborEncoder::new(&mut cbor);
+///
+/// assert_eq!(encoder.process_chunk("Status: {\"code\":").unwrap(), 0);
+/// assert_eq!(encoder.process_chunk("200} done").unwrap(), 1);
+/// ```
+pub struct CborEncoder<W: Write> {
+    parser: JSONParser,
+    writer: W,
+    values_seen: usize,
+    values_written: usize,
+}
+
+impl<W: Write> CborEncoder<W> {
+    /// Creates a new encoder writing CBOR to the given writer.
+    ///
+    /// # Arguments
+    ///
+    /// * `writer` - The writer receiving the CBOR sequence.
+    pub fn new(writer: W) -> Self {
+        Self::with_parser(JSONParser::new(), writer)
+    }
+
+    /// Creates a new encoder that extracts JSON with the given parser.
+    ///
+    /// # Arguments
+    ///
+    /// * `parser` - The parser used to extract JSON values.
+    /// * `writer` - The writer receiving the CBOR sequence.
+    pub fn with_parser(parser: JSONParser, writer: W) -> Self {
+        Self {
+            parser,
+            writer,
+            values_seen: 0,
+            values_written: 0,
+        }
+    }
+
+    /// Processes a chunk of text and writes every value completed by it.
+    ///
+    /// # Arguments
+    ///
+    /// * `chunk` - A string slice containing text data, potentially with embedded JSON.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(usize)` - The number of values written for this chunk.
+    /// * `Err(ConvertError)` - If a value failed to encode. The error carries
+    ///   the index of the failing value, counted from the first value seen by
+    ///   this encoder. The values before it are written, and the values after
+    ///   it in the same chunk are skipped.
+    pub fn process_chunk(&mut self, chunk: &str) -> Result<usize, ConvertError> {
+        let writer = &mut self.writer;
+        let mut written = 0;
+
+        let result = transcode_chunk(
+            &mut self.parser,
+            chunk,
+            &mut self.values_seen,
+            |value, index| {
+                ciborium::into_writer(&value, &mut *writer).map_err(|e| ConvertError::Encode {
+                    index,
+                    message: e.to_string(),
+                })?;
+                written += 1;
+                Ok(())
+            },
+        );
+
+        self.values_written += written;
+        result
+    }
+
+    /// Returns the number of values written by this encoder so far.
+    pub fn values_written(&self) -> usize {
+        self.values_written
+    }
+
+    /// Consumes the encoder, returning the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::Value;
+
+    /// A writer refusing the CBOR encoding of `true`, so encoding can be made to fail.
+    struct RejectTrue(Vec<u8>);
+
+    impl Write for RejectTrue {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            if buf == [0xf5] {
+                return Err(std::io::Error::other("rejected"));
+            }
+            self.0.extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn decode_all(mut cbor: &[u8]) -> Vec<Value> {
+        let mut values = Vec::new();
+        while !cbor.is_empty() {
+            values.push(ciborium::from_reader(&mut cbor).unwrap());
+        }
+        values
+    }
+
+    #[test]
+    fn test_extract_json_to_cbor_round_trip() {
+        let input =
+            "Start {\"id\":1,\"tags\":[\"a\",\"b\"],\"nested\":{\"ok\":true,\"none\":null}} \
+                     middle [1,-2,3.5,\"é\"] end";
+
+        let mut cbor = Vec::new();
+        let count = extract_json_to_cbor(input, &mut cbor).unwrap();
+        assert_eq!(count, 2);
+
+        let expected: Vec<Value> = vec![
+            serde_json::from_str(
+                "{\"id\":1,\"tags\":[\"a\",\"b\"],\"nested\":{\"ok\":true,\"none\":null}}",
+            )
+            .unwrap(),
+            serde_json::from_str("[1,-2,3.5,\"é\"]").unwrap(),
+        ];
+        assert_eq!(decode_all(&cbor), expected);
+    }
+
+    #[test]
+    fn test_cbor_encoder_across_chunks() {
+        let mut encoder = CborEncoder::new(Vec::new());
+
+        assert_eq!(encoder.process_chunk("a {\"x\":").unwrap(), 0);
+        assert_eq!(encoder.process_chunk("10} b {\"y\":20} {\"z\"").unwrap(), 2);
+        assert_eq!(encoder.process_chunk(":30}").unwrap(), 1);
+        assert_eq!(encoder.values_written(), 3);
+
+        let values = decode_all(&encoder.into_inner());
+        assert_eq!(values[2], serde_json::json!({"z": 30}));
+    }
+
+    #[test]
+    fn test_extract_json_to_cbor_skips_invalid_candidates() {
+        let mut cbor = Vec::new();
+        let count =
+            extract_json_to_cbor("[INFO] user {\"ok\":true} {not json} [2]", &mut cbor).unwrap();
+
+        assert_eq!(count, 2);
+        assert_eq!(
+            decode_all(&cbor),
+            vec![serde_json::json!({"ok": true}), serde_json::json!([2])]
+        );
+    }
+
+    #[test]
+    fn test_cbor_encoder_counts_only_written_values() {
+        let mut encoder = CborEncoder::new(RejectTrue(Vec::new()));
+
+        let result = encoder.process_chunk("{\"ok\":1} [true] {\"ok\":2}");
+        assert!(matches!(result, Err(ConvertError::Encode { index: 1, .. })));
+        assert_eq!(encoder.values_written(), 1);
+
+        // The value skipped after the failure is not counted at all
+        let result = encoder.process_chunk("[3] [INFO] [true]");
+        assert!(matches!(result, Err(ConvertError::Encode { index: 3, .. })));
+        assert_eq!(encoder.values_written(), 2);
+    }
+}
@@ -0,0 +1,453 @@
+//! CSV flattening for extracted JSON objects.
+
+use std::io::Write;
+
+use serde_json::{Map, Value};
+
+use crate::convert::{transcode_chunk, ConvertError};
+use crate::JSONParser;
+
+/// The order of the columns in the CSV header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ColumnOrder {
+    /// Columns appear in the order their keys are first seen.
+    #[default]
+    FirstSeen,
+    /// Columns are sorted alphabetically.
+    Sorted,
+}
+
+/// How nested objects and arrays inside a row are written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NestedValues {
+    /// Nested values are written as their JSON text.
+    #[default]
+    Stringify,
+    /// Nested values are rejected with a [`ConvertError::Encode`].
+    Error,
+}
+
+/// Options controlling how extracted JSON is flattened into CSV.
+///
+/// # Examples
+///
+/// ```
+/// use surfing::convert::{ColumnOrder, CsvOptions};
+///
+/// let options = CsvOptions::new()
+///     .missing_value("N/A")
+///     .column_order(ColumnOrder::Sorted);
+/// ```
+#[derive(Debug, Clone)]
+pub struct CsvOptions {
+    missing_value: String,
+    column_order: ColumnOrder,
+    nested_values: NestedValues,
+    columns: Option<Vec<String>>,
+    sample_size: usize,
+}
+
+impl CsvOptions {
+    /// Creates the default options: empty placeholders, first-seen column
+    /// order, stringified nested values and a header sampled from the first
+    /// 100 rows.
+    pub fn new() -> Self {
+        Self {
+            missing_value: String::new(),
+            column_order: ColumnOrder::FirstSeen,
+            nested_values: NestedValues::Stringify,
+            columns: None,
+            sample_size: 100,
+        }
+    }
+
+    /// Sets the placeholder written for missing keys and `null` values.
+    pub fn missing_value(mut self, placeholder: impl Into<String>) -> Self {
+        self.missing_value = placeholder.into();
+        self
+    }
+
+    /// Sets the order of the sampled columns.
+    pub fn column_order(mut self, order: ColumnOrder) -> Self {
+        self.column_order = order;
+        self
+    }
+
+    /// Sets how nested objects and arrays are written.
+    pub fn nested_values(mut self, nested: NestedValues) -> Self {
+        self.nested_values = nested;
+        self
+    }
+
+    /// Uses the given columns as the header instead of sampling rows.
+    ///
+    /// With a fixed header no rows are buffered at all.
+    pub fn columns<I, S>(mut self, columns: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.columns = Some(columns.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Sets how many rows are buffered to build the header.
+    ///
+    /// The header is the union of the keys of the sampled rows. Keys that
+    /// first appear after the sample are not part of the header and are
+    /// dropped from their rows.
+    pub fn sample_size(mut self, rows: usize) -> Self {
+        self.sample_size = rows.max(1);
+        self
+    }
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Extracts JSON objects from mixed text and writes them as CSV rows.
+///
+/// Every extracted object becomes a row, and every extracted array of objects
+/// contributes one row per element. Scalars are written as text, `null` and
+/// missing keys as the configured placeholder.
+///
+/// # Arguments
+///
+/// * `input` - A string slice containing mixed text with embedded JSON.
+/// * `writer` - The writer receiving the CSV output.
+/// * `options` - The options controlling the header and cell values.
+///
+/// # Returns
+///
+/// * `Ok(usize)` - The number of rows written, excluding the header.
+/// * `Err(ConvertError)` - If a value is not an object or array of objects,
+///   holds a rejected nested value, or fails to be written.
+///
+/// # Examples
+///
+/// ```
+/// use surfing::convert::{extracted_json_to_csv, CsvOptions};
+///
+/// let input = "rows: [{\"id\":1,\"name\":\"Alice\"},{\"id\":2,\"role\":\"admin\"}]";
+/// let mut csv = Vec::new();
+/// let rows = extracted_json_to_csv(input, &mut csv, CsvOptions::new()).unwrap();
+///
+/// assert_eq!(rows, 2);
+/// assert_eq!(String::from_utf8(csv).unwrap(), "id,name,role\n1,Alice,\n2,,admin\n");
+/// ```
+pub fn extracted_json_to_csv<W: Write>(
+    input: &str,
+    writer: &mut W,
+    options: CsvOptions,
+) -> Result<usize, ConvertError> {
+    let mut encoder = CsvEncoder::new(writer, options);
+    encoder.process_chunk(input)?;
+    encoder.finish()
+}
+
+/// A streaming encoder that flattens completed JSON values into CSV rows.
+///
+/// Until the header is known, at most `sample_size` rows are buffered; after
+/// that every row is written as soon as its value completes. Call
+/// [`finish`](Self::finish) once the input ends so a header built from fewer
+/// rows than the sample size is still written.
+///
+/// # Examples
+///
+/// ```
+/// use surfing::convert::{CsvEncoder, CsvOptions};
+///
+/// let mut csv = Vec::new();
+/// let mut encoder = CsvEncoder::new(&mut csv, CsvOptions::new().columns(["id"]));
+///
+/// encoder.process_chunk("{\"id\":").unwrap();
+/// encoder.process_chunk("7} {\"id\":8}").unwrap();
+/// assert_eq!(encoder.finish().unwrap(), 2);
+///
+/// drop(encoder);
+/// assert_eq!(String::from_utf8(csv).unwrap(), "id\n7\n8\n");
+/// ```
+pub struct CsvEncoder<W: Write> {
+    parser: JSONParser,
+    writer: ::csv::Writer<W>,
+    options: CsvOptions,
+    header: Option<Vec<String>>,
+    sample: Vec<(usize, Map<String, Value>)>,
+    values_seen: usize,
+    rows_written: usize,
+    header_written: bool,
+}
+
+impl<W: Write> CsvEncoder<W> {
+    /// Creates a new encoder writing CSV to the given writer.
+    ///
+    /// # Arguments
+    ///
+    /// * `writer` - The writer receiving the CSV output.
+    /// * `options` - The options controlling the header and cell values.
+    pub fn new(writer: W, options: CsvOptions) -> Self {
+        Self {
+            parser: JSONParser::new(),
+            writer: ::csv::WriterBuilder::new()
+                .flexible(true)
+                .from_writer(writer),
+            header: options.columns.clone(),
+            options,
+            sample: Vec::new(),
+            values_seen: 0,
+            rows_written: 0,
+            header_written: false,
+        }
+    }
+
+    /// Processes a chunk of text and writes the rows of every value completed by it.
+    ///
+    /// # Arguments
+    ///
+    /// * `chunk` - A string slice containing text data, potentially with embedded JSON.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If every completed value was flattened.
+    /// * `Err(ConvertError)` - If a value could not be flattened or written.
+    pub fn process_chunk(&mut self, chunk: &str) -> Result<(), ConvertError> {
+        let mut rows = Vec::new();
+
+        let result = transcode_chunk(
+            &mut self.parser,
+            chunk,
+            &mut self.values_seen,
+            |value, index| {
+                for row in split_rows(value, index)? {
+                    rows.push((index, row));
+                }
+                Ok(())
+            },
+        );
+
+        // Rows completed before a failing value are still written
+        for (index, row) in rows {
+            self.add_row(index, row)?;
+        }
+
+        result.map(|_| ())
+    }
+
+    /// Writes any buffered rows and flushes the writer.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(usize)` - The total number of rows written, excluding the header.
+    /// * `Err(ConvertError)` - If a row could not be written.
+    pub fn finish(&mut self) -> Result<usize, ConvertError> {
+        if self.header.is_none() && !self.sample.is_empty() {
+            self.write_sample()?;
+        }
+
+        self.writer.flush().map_err(|e| ConvertError::Encode {
+            index: self.values_seen,
+            message: e.to_string(),
+        })?;
+
+        Ok(self.rows_written)
+    }
+
+    /// Adds a row, buffering it while the header is still being sampled.
+    fn add_row(&mut self, index: usize, row: Map<String, Value>) -> Result<(), ConvertError> {
+        if self.header.is_some() {
+            return self.write_row(index, &row);
+        }
+
+        self.sample.push((index, row));
+        if self.sample.len() >= self.options.sample_size {
+            self.write_sample()?;
+        }
+
+        Ok(())
+    }
+
+    /// Builds the header from the sampled rows and writes them.
+    fn write_sample(&mut self) -> Result<(), ConvertError> {
+        let mut header: Vec<String> = Vec::new();
+        for (_, row) in &self.sample {
+            for key in row.keys() {
+                if !header.contains(key) {
+                    header.push(key.clone());
+                }
+            }
+        }
+
+        if self.options.column_order == ColumnOrder::Sorted {
+            header.sort();
+        }
+
+        self.header = Some(header);
+
+        let sample = std::mem::take(&mut self.sample);
+        for (index, row) in &sample {
+            self.write_row(*index, row)?;
+        }
+
+        Ok(())
+    }
+
+    /// Writes a single row, writing the header first if needed.
+    ///
+    /// The row is checked before anything is written, so a rejected row
+    /// leaves the output untouched.
+    fn write_row(&mut self, index: usize, row: &Map<String, Value>) -> Result<(), ConvertError> {
+        let header = self.header.as_ref().expect("header is known when writing");
+        let encode_error = |message: String| ConvertError::Encode { index, message };
+
+        let mut record = Vec::with_capacity(header.len());
+        for column in header {
+            let cell = match row.get(column) {
+                None | Some(Value::Null) => self.options.missing_value.clone(),
+                Some(Value::String(text)) => text.clone(),
+                Some(nested @ (Value::Object(_) | Value::Array(_))) => {
+                    if self.options.nested_values == NestedValues::Error {
+                        return Err(encode_error(format!(
+                            "column \"{}\" holds a nested value",
+                            column
+                        )));
+                    }
+                    nested.to_string()
+                }
+                Some(scalar) => scalar.to_string(),
+            };
+            record.push(cell);
+        }
+
+        if !self.header_written {
+            self.writer
+                .write_record(header)
+                .map_err(|e| encode_error(e.to_string()))?;
+            self.header_written = true;
+        }
+        self.writer
+            .write_record(&record)
+            .map_err(|e| encode_error(e.to_string()))?;
+        self.rows_written += 1;
+
+        Ok(())
+    }
+}
+
+/// Splits an extracted value into rows, one per object.
+fn split_rows(value: Value, index: usize) -> Result<Vec<Map<String, Value>>, ConvertError> {
+    let not_an_object = || ConvertError::Encode {
+        index,
+        message: "expected an object or an array of objects".to_string(),
+    };
+
+    match value {
+        Value::Object(row) => Ok(vec![row]),
+        Value::Array(elements) => elements
+            .into_iter()
+            .map(|element| match element {
+                Value::Object(row) => Ok(row),
+                _ => Err(not_an_object()),
+            })
+            .collect(),
+        _ => Err(not_an_object()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn to_csv(input: &str, options: CsvOptions) -> Result<String, ConvertError> {
+        let mut csv = Vec::new();
+        extracted_json_to_csv(input, &mut csv, options)?;
+        Ok(String::from_utf8(csv).unwrap())
+    }
+
+    #[test]
+    fn test_csv_heterogeneous_keys() {
+        let input = "log {\"id\":1,\"name\":\"a, b\"} log {\"id\":2,\"extra\":true} log {\"id\":3,\"name\":null}";
+
+        let csv = to_csv(input, CsvOptions::new().missing_value("-")).unwrap();
+        assert_eq!(csv, "id,name,extra\n1,\"a, b\",-\n2,-,true\n3,-,-\n");
+
+        let csv = to_csv(input, CsvOptions::new().column_order(ColumnOrder::Sorted)).unwrap();
+        assert_eq!(csv, "extra,id,name\n,1,\"a, b\"\ntrue,2,\n,3,\n");
+    }
+
+    #[test]
+    fn test_csv_nested_values() {
+        let input = "[{\"id\":1,\"meta\":{\"k\":[1,2]}}]";
+
+        let csv = to_csv(input, CsvOptions::new()).unwrap();
+        assert_eq!(csv, "id,meta\n1,\"{\"\"k\"\":[1,2]}\"\n");
+
+        let result = to_csv(input, CsvOptions::new().nested_values(NestedValues::Error));
+        match result {
+            Err(ConvertError::Encode { index, message }) => {
+                assert_eq!(index, 0);
+                assert!(message.contains("meta"));
+            }
+            other => panic!("Expected encode error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_csv_encoder_writes_header_once_after_rejected_row() {
+        let options = CsvOptions::new()
+            .columns(["id", "meta"])
+            .nested_values(NestedValues::Error);
+        let mut output = Vec::new();
+        {
+            let mut encoder = CsvEncoder::new(&mut output, options);
+            assert!(encoder.process_chunk("{\"id\":1,\"meta\":{}}").is_err());
+            encoder.process_chunk("{\"id\":2}").unwrap();
+            assert_eq!(encoder.finish().unwrap(), 1);
+        }
+
+        assert_eq!(String::from_utf8(output).unwrap(), "id,meta\n2,\n");
+    }
+
+    #[test]
+    fn test_csv_sample_size_and_fixed_columns() {
+        let input = "{\"a\":1} {\"a\":2,\"b\":3}";
+
+        // Keys first seen after the sample are dropped
+        let csv = to_csv(input, CsvOptions::new().sample_size(1)).unwrap();
+        assert_eq!(csv, "a\n1\n2\n");
+
+        let csv = to_csv(input, CsvOptions::new().columns(["b", "a"])).unwrap();
+        assert_eq!(csv, "b,a\n,1\n3,2\n");
+    }
+
+    #[test]
+    fn test_csv_encoder_streams_rows_across_chunks() {
+        let mut output = Vec::new();
+        {
+            let mut encoder = CsvEncoder::new(&mut output, CsvOptions::new().sample_size(2));
+            encoder.process_chunk("{\"x\":1} {\"x\"").unwrap();
+            encoder.process_chunk(":2,\"y\":3} {\"y\":4}").unwrap();
+            assert_eq!(encoder.finish().unwrap(), 3);
+        }
+
+        assert_eq!(String::from_utf8(output).unwrap(), "x,y\n1,\n2,3\n,4\n");
+    }
+
+    #[test]
+    fn test_csv_skips_invalid_candidates() {
+        let csv = to_csv(
+            "[INFO] user {\"id\":1} [WARN] {\"id\":2}",
+            CsvOptions::new(),
+        )
+        .unwrap();
+        assert_eq!(csv, "id\n1\n2\n");
+    }
+
+    #[test]
+    fn test_csv_rejects_scalars() {
+        let result = to_csv("{\"a\":1} [1,2]", CsvOptions::new());
+        assert!(matches!(result, Err(ConvertError::Encode { index: 1, .. })));
+    }
+}
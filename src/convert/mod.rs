@@ -0,0 +1,116 @@
+//! Conversion of extracted JSON into other data formats.
+//!
+//! Each format lives behind its own feature flag:
+//!
+//! ```toml
+//! [dependencies]
+//! surfing = { version = "0.1.0", features = ["cbor", "csv", "msgpack", "yaml"] }
+//! ```
+//!
+//! Every extracted value is transcoded independently, so a failure names the
+//! index of the value that could not be converted. Extracted structures that
+//! are not valid JSON, such as the `[INFO]` of a log line, are skipped
+//! without taking an index, as with
+//! [`extract_json_objects`](crate::utils::extract_json_objects).
+
+#[cfg(feature = "cbor")]
+mod cbor;
+#[cfg(feature = "csv")]
+mod csv;
+#[cfg(feature = "msgpack")]
+mod msgpack;
+#[cfg(feature = "yaml")]
+mod yaml;
+
+#[cfg(feature = "csv")]
+pub use self::csv::{extracted_json_to_csv, ColumnOrder, CsvEncoder, CsvOptions, NestedValues};
+#[cfg(feature = "cbor")]
+pub use cbor::{extract_json_to_cbor, CborEncoder};
+#[cfg(feature = "msgpack")]
+pub use msgpack::{extract_json_to_msgpack, MsgpackEncoder};
+#[cfg(feature = "yaml")]
+pub use yaml::extract_json_to_yaml;
+
+use serde_json::Value;
+
+use crate::JSONParser;
+
+/// Error type for conversion failures.
+#[derive(Debug)]
+pub enum ConvertError {
+    /// Error extracting JSON from text
+    Extraction(String),
+    /// An extracted value could not be encoded in the target format
+    Encode {
+        /// Zero-based index of the extracted value
+        index: usize,
+        /// Description of the encoding failure
+        message: String,
+    },
+}
+
+impl std::fmt::Display for ConvertError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConvertError::Extraction(e) => write!(f, "JSON extraction error: {}", e),
+            ConvertError::Encode { index, message } => {
+                write!(f, "failed to encode value {}: {}", index, message)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ConvertError {}
+
+/// Feeds a chunk through the parser and encodes every value it completes.
+///
+/// Completed candidates that `serde_json` rejects are skipped and do not
+/// count towards the value indexes.
+///
+/// # Arguments
+///
+/// * `parser` - The parser used to extract JSON values.
+/// * `chunk` - A string slice containing text data, potentially with embedded JSON.
+/// * `values_seen` - The running count of values, used to index errors.
+/// * `encode` - The callback encoding a parsed value with its index.
+///
+/// # Returns
+///
+/// * `Ok(usize)` - The number of values encoded for this chunk.
+/// * `Err(ConvertError)` - The first encoding failure; later values of the chunk are skipped.
+fn transcode_chunk<F>(
+    parser: &mut JSONParser,
+    chunk: &str,
+    values_seen: &mut usize,
+    mut encode: F,
+) -> Result<usize, ConvertError>
+where
+    F: FnMut(Value, usize) -> Result<(), ConvertError>,
+{
+    let mut encoded = 0;
+    let mut failure = None;
+
+    parser
+        .extract_values_from_stream(chunk, |json| {
+            if failure.is_some() {
+                return;
+            }
+            let Ok(value) = serde_json::from_str(json) else {
+                return;
+            };
+
+            let index = *values_seen;
+            *values_seen += 1;
+
+            match encode(value, index) {
+                Ok(()) => encoded += 1,
+                Err(e) => failure = Some(e),
+            }
+        })
+        .map_err(|e| ConvertError::Extraction(e.to_string()))?;
+
+    match failure {
+        Some(e) => Err(e),
+        None => Ok(encoded),
+    }
+}
@@ -0,0 +1,236 @@
+//! MessagePack output for extracted JSON.
+
+use std::io::Write;
+
+use crate::convert::{transcode_chunk, ConvertError};
+use crate::JSONParser;
+
+/// Extracts JSON from mixed text and encodes each value as MessagePack.
+///
+/// Every extracted value becomes an independent MessagePack document in its
+/// own buffer.
+///
+/// # Arguments
+///
+/// * `input` - A string slice containing mixed text with embedded JSON.
+///
+/// # Returns
+///
+/// * `Ok(Vec<Vec<u8>>)` - One MessagePack document per extracted value.
+/// * `Err(ConvertError)` - If extraction or encoding of a value fails.
+///
+/// # Examples
+///
+/// ```
+/// use surfing::convert::extract_json_to_msgpack;
+///
+/// let documents = extract_json_to_msgpack("Result: {\"ok\":true} [1,2]").unwrap();
+///
+/// assert_eq!(documents.len(), 2);
+/// assert_eq!(documents[1], vec![0x92, 0x01, 0x02]);
+/// ```
+pub fn extract_json_to_msgpack(input: &str) -> Result<Vec<Vec<u8>>, ConvertError> {
+    let mut parser = JSONParser::new();
+    let mut documents = Vec::new();
+    let mut values_seen = 0;
+
+    transcode_chunk(&mut parser, input, &mut values_seen, |value, index| {
+        let document = rmp_serde::to_vec(&value).map_err(|e| ConvertError::Encode {
+            index,
+            message: e.to_string(),
+        })?;
+        documents.push(document);
+        Ok(())
+    })?;
+
+    Ok(documents)
+}
+
+/// A streaming encoder that writes each completed JSON value as MessagePack.
+///
+/// Each value is written as an independent MessagePack document, one after
+/// another, so consumers can read them back with a standard decoder loop.
+/// Values may span several chunks.
+///
+/// # Examples
+///
+/// ```
+/// use surfing::convert::MsgpackEncoder;
+///
+/// let mut msgpack = Vec::new();
+/// let mut encoder = MsgpackEncoder::new(&mut msgpack);
+///
+/// assert_eq!(encoder.process_chunk("Status: {\"code\":").unwrap(), 0);
+/// assert_eq!(encoder.process_chunk("200} done").unwrap(), 1);
+/// ```
+pub struct MsgpackEncoder<W: Write> {
+    parser: JSONParser,
+    writer: W,
+    values_seen: usize,
+    values_written: usize,
+}
+
+impl<W: Write> MsgpackEncoder<W> {
+    /// Creates a new encoder writing MessagePack to the given writer.
+    ///
+    /// # Arguments
+    ///
+    /// * `writer` - The writer receiving the MessagePack documents.
+    pub fn new(writer: W) -> Self {
+        Self::with_parser(JSONParser::new(), writer)
+    }
+
+    /// Creates a new encoder that extracts JSON with the given parser.
+    ///
+    /// # Arguments
+    ///
+    /// * `parser` - The parser used to extract JSON values.
+    /// * `writer` - The writer receiving the MessagePack documents.
+    pub fn with_parser(parser: JSONParser, writer: W) -> Self {
+        Self {
+            parser,
+            writer,
+            values_seen: 0,
+            values_written: 0,
+        }
+    }
+
+    /// Processes a chunk of text and writes every value completed by it.
+    ///
+    /// # Arguments
+    ///
+    /// * `chunk` - A string slice containing text data, potentially with embedded JSON.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(usize)` - The number of values written for this chunk.
+    /// * `Err(ConvertError)` - If a value failed to encode. The error carries
+    ///   the index of the failing value, counted from the first value seen by
+    ///   this encoder. The values before it are written, and the values after
+    ///   it in the same chunk are skipped.
+    pub fn process_chunk(&mut self, chunk: &str) -> Result<usize, ConvertError> {
+        let writer = &mut self.writer;
+        let mut written = 0;
+
+        let result = transcode_chunk(
+            &mut self.parser,
+            chunk,
+            &mut self.values_seen,
+            |value, index| {
+                rmp_serde::encode::write(&mut *writer, &value).map_err(|e| {
+                    ConvertError::Encode {
+                        index,
+                        message: e.to_string(),
+                    }
+                })?;
+                written += 1;
+                Ok(())
+            },
+        );
+
+        self.values_written += written;
+        result
+    }
+
+    /// Returns the number of values written by this encoder so far.
+    pub fn values_written(&self) -> usize {
+        self.values_written
+    }
+
+    /// Consumes the encoder, returning the underlying writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::Value;
+
+    /// A writer refusing the MessagePack encoding of `true`, so encoding can be made to fail.
+    struct RejectTrue(Vec<u8>);
+
+    impl Write for RejectTrue {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            if buf == [0xc3] {
+                return Err(std::io::Error::other("rejected"));
+            }
+            self.0.extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_extract_json_to_msgpack_round_trip() {
+        let input =
+            "Start {\"id\":1,\"tags\":[\"a\",\"b\"],\"nested\":{\"ok\":true,\"none\":null}} \
+                     middle [1,-2,3.5,\"é\"] end";
+
+        let documents = extract_json_to_msgpack(input).unwrap();
+        assert_eq!(documents.len(), 2);
+
+        let decoded: Vec<Value> = documents
+            .iter()
+            .map(|document| rmp_serde::from_slice(document).unwrap())
+            .collect();
+        assert_eq!(
+            decoded,
+            vec![
+                serde_json::json!({"id": 1, "tags": ["a", "b"], "nested": {"ok": true, "none": null}}),
+                serde_json::json!([1, -2, 3.5, "é"]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_msgpack_encoder_decoder_loop() {
+        let mut encoder = MsgpackEncoder::new(Vec::new());
+
+        assert_eq!(encoder.process_chunk("a {\"x\":").unwrap(), 0);
+        assert_eq!(encoder.process_chunk("10} b {\"y\":20} {\"z\"").unwrap(), 2);
+        assert_eq!(encoder.process_chunk(":30}").unwrap(), 1);
+
+        let bytes = encoder.into_inner();
+        let mut reader = bytes.as_slice();
+        let mut decoded = Vec::new();
+        while !reader.is_empty() {
+            let value: Value = rmp_serde::decode::from_read(&mut reader).unwrap();
+            decoded.push(value);
+        }
+
+        assert_eq!(
+            decoded,
+            vec![
+                serde_json::json!({"x": 10}),
+                serde_json::json!({"y": 20}),
+                serde_json::json!({"z": 30}),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_extract_json_to_msgpack_skips_invalid_candidates() {
+        let documents = extract_json_to_msgpack("[INFO] {\"ok\":1} [oops] [1,2]").unwrap();
+
+        assert_eq!(documents.len(), 2);
+        assert_eq!(documents[1], vec![0x92, 0x01, 0x02]);
+    }
+
+    #[test]
+    fn test_msgpack_encoder_skips_values_after_failure() {
+        let mut encoder = MsgpackEncoder::new(RejectTrue(Vec::new()));
+
+        let result = encoder.process_chunk("[1] [oops] [true] [2] [3]");
+        assert!(matches!(result, Err(ConvertError::Encode { index: 1, .. })));
+        assert_eq!(encoder.values_written(), 1);
+
+        // The next chunk carries on after the skipped values
+        assert_eq!(encoder.process_chunk("[4]").unwrap(), 1);
+        assert_eq!(encoder.values_written(), 2);
+    }
+}
@@ -0,0 +1,84 @@
+//! YAML rendering for extracted JSON.
+
+use crate::convert::{transcode_chunk, ConvertError};
+use crate::JSONParser;
+
+/// Extracts JSON from mixed text and renders it as a multi-document YAML stream.
+///
+/// Every extracted value becomes one YAML document, and each document starts
+/// with a `---` separator so the output can be read back as a YAML stream.
+///
+/// # Arguments
+///
+/// * `input` - A string slice containing mixed text with embedded JSON.
+///
+/// # Returns
+///
+/// * `Ok(String)` - The YAML stream, empty if no JSON was found.
+/// * `Err(ConvertError)` - If extraction or rendering of a value fails.
+///
+/// # Examples
+///
+/// ```
+/// use surfing::convert::extract_json_to_yaml;
+///
+/// let yaml = extract_json_to_yaml("User: {\"name\":\"Alice\"} Roles: [\"admin\"]").unwrap();
+///
+/// assert_eq!(yaml, "---\nname: Alice\n---\n- admin\n");
+/// ```
+pub fn extract_json_to_yaml(input: &str) -> Result<String, ConvertError> {
+    let mut parser = JSONParser::new();
+    let mut yaml = String::new();
+    let mut values_seen = 0;
+
+    transcode_chunk(&mut parser, input, &mut values_seen, |value, index| {
+        let document = serde_yaml::to_string(&value).map_err(|e| ConvertError::Encode {
+            index,
+            message: e.to_string(),
+        })?;
+
+        yaml.push_str("---\n");
+        yaml.push_str(&document);
+        Ok(())
+    })?;
+
+    Ok(yaml)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+    use serde_json::Value;
+
+    #[test]
+    fn test_extract_json_to_yaml_multiple_documents() {
+        let input = "first {\"id\":1,\"tags\":[\"a\",\"b\"]} then [1,2.5,null] \
+                     and {\"nested\":{\"text\":\"multi\\nline\"}}";
+
+        let yaml = extract_json_to_yaml(input).unwrap();
+
+        let documents: Vec<Value> = serde_yaml::Deserializer::from_str(&yaml)
+            .map(|document| Value::deserialize(document).unwrap())
+            .collect();
+        assert_eq!(
+            documents,
+            vec![
+                serde_json::json!({"id": 1, "tags": ["a", "b"]}),
+                serde_json::json!([1, 2.5, null]),
+                serde_json::json!({"nested": {"text": "multi\nline"}}),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_extract_json_to_yaml_skips_invalid_candidates() {
+        let yaml = extract_json_to_yaml("[INFO] user {\"ok\":true} [1]").unwrap();
+        assert_eq!(yaml, "---\nok: true\n---\n- 1\n");
+    }
+
+    #[test]
+    fn test_extract_json_to_yaml_no_json() {
+        assert_eq!(extract_json_to_yaml("plain text").unwrap(), "");
+    }
+}
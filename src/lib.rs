@@ -10,6 +10,15 @@
 //! - **Streaming support**: Process data in chunks
 //! - **Utility functions**: Convenient high-level API
 //! - **Serde integration**: Deserialize JSON directly from mixed text (requires the `serde` feature)
+//! - **Format conversion**: Transcode extracted JSON to CBOR, MessagePack, YAML or CSV (requires the matching feature)
+//! - **Spilling**: Write oversized values to temporary files instead of memory (requires the `spill` feature)
+//! - **Async I/O**: Extract from any `futures-io` reader or writer, on async-std, smol, or tokio through `tokio-util`'s compat layer (requires the `futures-io` feature)
+//! - **HTTP bodies**: Stream typed values out of an `http_body::Body`, such as a hyper response (requires the `http` feature)
+//! - **JSON Schema**: Validate an extracted value against a schema before deserializing it (requires the `jsonschema` feature)
+//! - **Background extraction**: Hand chunks to a spawned task and receive typed values over bounded channels (requires the `tokio` feature)
+//! - **Legacy encodings**: Extract from Windows-1252, Shift-JIS or any other input encoding, decoded as it streams (requires the `encoding` feature)
+//! - **Prompt hints**: Describe the JSON shape of a type for a prompt, from its JSON Schema or its default value (requires the `prompt` feature)
+//! - **Logging**: Capture the JSON embedded in `log` records while passing them on to another logger (requires the `log` feature)
 //!
 //! # Examples
 //!
@@ -99,6 +108,20 @@ pub mod utils;
 #[cfg(feature = "serde")]
 pub mod serde;
 
+#[cfg(any(
+    feature = "cbor",
+    feature = "csv",
+    feature = "msgpack",
+    feature = "yaml"
+))]
+pub mod convert;
+
+#[cfg(feature = "log")]
+pub mod logging;
+
+#[cfg(feature = "encoding")]
+pub use encoding_rs;
+
 // Re-export the main types and functions for convenience
 pub use parser::json_parser::JSONParser;
 pub use utils::string_extract::extract_json_to_string;
@@ -0,0 +1,208 @@
+//! Integration with the `log` facade.
+//!
+//! [`JsonCapturingLogger`] wraps the logger an application already installs
+//! and captures the JSON embedded in its records, so call sites stay as
+//! they are:
+//!
+//! ```
+//! use std::sync::{Arc, Mutex};
+//!
+//! use log::{Level, Log, Metadata, Record};
+//! use surfing::logging::{CapturedJson, JsonCapturingLogger};
+//!
+//! struct Discard;
+//!
+//! impl Log for Discard {
+//!     fn enabled(&self, _: &Metadata) -> bool {
+//!         true
+//!     }
+//!     fn log(&self, _: &Record) {}
+//!     fn flush(&self) {}
+//! }
+//!
+//! let captured = Arc::new(Mutex::new(Vec::new()));
+//! let sink = Arc::clone(&captured);
+//! let logger = JsonCapturingLogger::new(Box::new(Discard), move |json: CapturedJson| {
+//!     sink.lock().unwrap().push(json)
+//! });
+//!
+//! logger.log(
+//!     &Record::builder()
+//!         .args(format_args!("request done {}", "{\"status\":200}"))
+//!         .level(Level::Info)
+//!         .target("http")
+//!         .build(),
+//! );
+//!
+//! let captured = captured.lock().unwrap();
+//! assert_eq!(captured[0].json, "{\"status\":200}");
+//! assert_eq!(captured[0].level, Level::Info);
+//! assert_eq!(captured[0].target, "http");
+//! ```
+
+use log::{Level, Log, Metadata, Record};
+
+use crate::JSONParser;
+
+/// A JSON value captured from a log record.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CapturedJson {
+    /// The extracted JSON
+    pub json: String,
+    /// The level of the record holding the value
+    pub level: Level,
+    /// The target of the record holding the value
+    pub target: String,
+}
+
+/// A logger forwarding to another one while capturing the JSON in each record.
+///
+/// Every record is passed to the inner logger unchanged. Its formatted
+/// message is then searched for JSON with a parser of its own, so a value
+/// left open by one record never continues into the next, and each
+/// complete value is handed to the sink.
+pub struct JsonCapturingLogger {
+    inner: Box<dyn Log>,
+    sink: Box<dyn Fn(CapturedJson) + Send + Sync>,
+}
+
+impl JsonCapturingLogger {
+    /// Creates a logger wrapping another one.
+    ///
+    /// # Arguments
+    ///
+    /// * `inner` - The logger receiving every record.
+    /// * `sink` - The callback receiving each value captured from a record.
+    ///
+    /// # Returns
+    ///
+    /// A new JsonCapturingLogger instance, ready to be installed with
+    /// [`log::set_boxed_logger`].
+    pub fn new<F>(inner: Box<dyn Log>, sink: F) -> Self
+    where
+        F: Fn(CapturedJson) + Send + Sync + 'static,
+    {
+        Self {
+            inner,
+            sink: Box::new(sink),
+        }
+    }
+}
+
+impl Log for JsonCapturingLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        self.inner.enabled(metadata)
+    }
+
+    fn log(&self, record: &Record) {
+        self.inner.log(record);
+
+        let message = record.args().to_string();
+        // Values cannot fail to be reported, as nothing is written
+        let _ = JSONParser::new().extract_values_from_stream(&message, |json| {
+            (self.sink)(CapturedJson {
+                json: json.to_string(),
+                level: record.level(),
+                target: record.target().to_string(),
+            })
+        });
+    }
+
+    fn flush(&self) {
+        self.inner.flush();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    /// A logger recording the messages it receives.
+    struct Recorder(Arc<Mutex<Vec<String>>>);
+
+    impl Log for Recorder {
+        fn enabled(&self, metadata: &Metadata) -> bool {
+            metadata.level() <= Level::Info
+        }
+
+        fn log(&self, record: &Record) {
+            self.0.lock().unwrap().push(record.args().to_string());
+        }
+
+        fn flush(&self) {}
+    }
+
+    type Shared<T> = Arc<Mutex<Vec<T>>>;
+
+    fn capturing_logger() -> (JsonCapturingLogger, Shared<String>, Shared<CapturedJson>) {
+        let messages = Arc::new(Mutex::new(Vec::new()));
+        let captured = Arc::new(Mutex::new(Vec::new()));
+        let sink = Arc::clone(&captured);
+        let logger =
+            JsonCapturingLogger::new(Box::new(Recorder(Arc::clone(&messages))), move |json| {
+                sink.lock().unwrap().push(json)
+            });
+
+        (logger, messages, captured)
+    }
+
+    fn log(logger: &JsonCapturingLogger, level: Level, target: &str, message: &str) {
+        logger.log(
+            &Record::builder()
+                .args(format_args!("{}", message))
+                .level(level)
+                .target(target)
+                .build(),
+        );
+    }
+
+    #[test]
+    fn test_json_capturing_logger_forwards_and_captures() {
+        let (logger, messages, captured) = capturing_logger();
+
+        log(&logger, Level::Info, "plain", "no JSON here");
+        log(
+            &logger,
+            Level::Warn,
+            "api",
+            "retry {\"attempt\":2} of [1,2,3]",
+        );
+
+        assert_eq!(
+            *messages.lock().unwrap(),
+            vec!["no JSON here", "retry {\"attempt\":2} of [1,2,3]"]
+        );
+        assert_eq!(
+            *captured.lock().unwrap(),
+            vec![
+                CapturedJson {
+                    json: "{\"attempt\":2}".to_string(),
+                    level: Level::Warn,
+                    target: "api".to_string(),
+                },
+                CapturedJson {
+                    json: "[1,2,3]".to_string(),
+                    level: Level::Warn,
+                    target: "api".to_string(),
+                },
+            ]
+        );
+        assert!(logger.enabled(&Metadata::builder().level(Level::Error).build()));
+        assert!(!logger.enabled(&Metadata::builder().level(Level::Debug).build()));
+    }
+
+    #[test]
+    fn test_json_capturing_logger_does_not_carry_state_between_records() {
+        let (logger, messages, captured) = capturing_logger();
+
+        log(&logger, Level::Info, "a", "truncated {\"a\":");
+        log(&logger, Level::Info, "b", "1} then {\"b\":2}");
+
+        assert_eq!(messages.lock().unwrap().len(), 2);
+        let captured = captured.lock().unwrap();
+        assert_eq!(captured.len(), 1);
+        assert_eq!(captured[0].json, "{\"b\":2}");
+        assert_eq!(captured[0].target, "b");
+    }
+}
@@ -0,0 +1,178 @@
+//! Asynchronous extraction over the `futures-io` traits.
+//!
+//! The methods here are runtime independent: any reader implementing
+//! [`AsyncRead`] and writer implementing [`AsyncWrite`] works, which covers
+//! async-std and smol directly. Tokio types can be adapted with the compat
+//! layer of `tokio-util` (`TokioAsyncReadCompatExt::compat`).
+
+use futures_io::{AsyncRead, AsyncWrite};
+use futures_util::io::{AsyncReadExt, AsyncWriteExt};
+
+use crate::parser::error::ParseError;
+use crate::JSONParser;
+
+/// The number of bytes read from an async reader at a time.
+const ASYNC_READ_BLOCK_SIZE: usize = 8 * 1024;
+
+impl JSONParser {
+    /// Extracts JSON content from an async reader until it is exhausted.
+    ///
+    /// This is the asynchronous counterpart of
+    /// [`extract_json_from_reader`](Self::extract_json_from_reader). Each block
+    /// read is extracted in memory and its output written to `writer` before
+    /// the next read, so memory use does not grow with the input.
+    ///
+    /// # Arguments
+    ///
+    /// * `reader` - The async reader supplying the text.
+    /// * `writer` - The async writer receiving the extracted JSON.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the reader was consumed completely.
+    /// * `Err(ParseError)` - If there was an error reading or writing.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use futures::executor::block_on;
+    /// use surfing::JSONParser;
+    ///
+    /// let mut parser = JSONParser::new();
+    /// let mut output = Vec::new();
+    /// let input = "Log: {\"level\":\"info\"} and [1, 2]".as_bytes();
+    ///
+    /// block_on(parser.extract_json_from_async_reader(input, &mut output)).unwrap();
+    /// assert_eq!(String::from_utf8(output).unwrap(), "{\"level\":\"info\"}[1, 2]");
+    /// ```
+    pub async fn extract_json_from_async_reader<R, W>(
+        &mut self,
+        mut reader: R,
+        writer: &mut W,
+    ) -> Result<(), ParseError>
+    where
+        R: AsyncRead + Unpin,
+        W: AsyncWrite + Unpin,
+    {
+        let mut block = vec![0; ASYNC_READ_BLOCK_SIZE];
+        let mut filled = 0;
+        let mut staged = Vec::new();
+
+        loop {
+            let read = match reader.read(&mut block[filled..]).await {
+                Ok(read) => read,
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e.into()),
+            };
+            if read == 0 {
+                break;
+            }
+
+            filled += read;
+            let consumed = self.consume_block(&block[..filled], &mut staged)?;
+            block.copy_within(consumed..filled, 0);
+            filled -= consumed;
+
+            writer.write_all(&staged).await?;
+            staged.clear();
+        }
+
+        if filled > 0 {
+            let rest = String::from_utf8_lossy(&block[..filled]);
+            self.consume_block(rest.as_bytes(), &mut staged)?;
+            writer.write_all(&staged).await?;
+        }
+
+        self.take_string_error()
+    }
+
+    /// Extracts JSON content from a chunk and writes it to an async writer.
+    ///
+    /// This is the asynchronous counterpart of
+    /// [`extract_json_from_stream`](Self::extract_json_from_stream), for chunks
+    /// that arrive from elsewhere, such as a streamed model response. Parser
+    /// state is kept between calls in the same way.
+    ///
+    /// # Arguments
+    ///
+    /// * `writer` - The async writer receiving the extracted JSON.
+    /// * `chunk` - The chunk to process.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the chunk was processed and its output written.
+    /// * `Err(ParseError)` - If there was an error writing.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use futures::executor::block_on;
+    /// use surfing::JSONParser;
+    ///
+    /// let mut parser = JSONParser::new();
+    /// let mut output = Vec::new();
+    ///
+    /// block_on(async {
+    ///     parser.extract_json_to_async_writer(&mut output, "Data: {\"id\":").await?;
+    ///     parser.extract_json_to_async_writer(&mut output, "1} done").await
+    /// })
+    /// .unwrap();
+    ///
+    /// assert_eq!(String::from_utf8(output).unwrap(), "{\"id\":1}");
+    /// ```
+    pub async fn extract_json_to_async_writer<W>(
+        &mut self,
+        writer: &mut W,
+        chunk: &str,
+    ) -> Result<(), ParseError>
+    where
+        W: AsyncWrite + Unpin,
+    {
+        let mut staged = Vec::new();
+        self.consume_from(chunk.as_bytes(), &mut staged)?;
+        writer.write_all(&staged).await?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio_util::compat::{TokioAsyncReadCompatExt, TokioAsyncWriteCompatExt};
+
+    #[test]
+    fn test_extract_json_from_async_reader_smol() {
+        let data = "é {\"name\":\"José\"} ü".as_bytes().to_vec();
+        let reader = futures::io::Cursor::new(data);
+        let mut parser = JSONParser::new();
+        let mut output = Vec::new();
+
+        smol::block_on(parser.extract_json_from_async_reader(reader, &mut output)).unwrap();
+        assert_eq!(String::from_utf8(output).unwrap(), "{\"name\":\"José\"}");
+    }
+
+    #[tokio::test]
+    async fn test_extract_json_from_async_reader_tokio() {
+        let (client, server) = tokio::io::duplex(16);
+        let mut output = Vec::new();
+
+        let producer = tokio::spawn(async move {
+            use tokio::io::AsyncWriteExt;
+            let mut client = client;
+            for chunk in ["noise {\"a\":", "[1,2]} more ", "[3] end"] {
+                client.write_all(chunk.as_bytes()).await.unwrap();
+            }
+        });
+
+        let mut parser = JSONParser::new();
+        let mut writer = (&mut output).compat_write();
+        parser
+            .extract_json_from_async_reader(server.compat(), &mut writer)
+            .await
+            .unwrap();
+        producer.await.unwrap();
+
+        assert_eq!(String::from_utf8(output).unwrap(), "{\"a\":[1,2]}[3]");
+    }
+}
@@ -0,0 +1,201 @@
+//! Extraction from input in encodings other than UTF-8.
+//!
+//! The input is decoded with an `encoding_rs` decoder as it is read, so a
+//! file in Windows-1252 or Shift-JIS is never converted as a whole. The
+//! extracted JSON is written as UTF-8.
+
+use std::io::{Read, Write};
+
+use encoding_rs::{DecoderResult, Encoding};
+
+use crate::parser::error::ParseError;
+use crate::parser::options::MalformedInputPolicy;
+use crate::JSONParser;
+
+/// The number of bytes read from the input at a time.
+const ENCODED_BLOCK_SIZE: usize = 8 * 1024;
+
+impl JSONParser {
+    /// Extracts JSON content from a reader in the given encoding until it is exhausted.
+    ///
+    /// This is the counterpart of
+    /// [`extract_json_from_reader`](Self::extract_json_from_reader) for input
+    /// that is not UTF-8. Each block read is decoded and extracted before the
+    /// next one, and characters split between reads are carried over by the
+    /// decoder. A byte order mark at the start of the input takes precedence
+    /// over `encoding`. Malformed byte sequences are handled according to
+    /// `policy`.
+    ///
+    /// # Arguments
+    ///
+    /// * `reader` - The reader supplying the encoded text.
+    /// * `writer` - The writer receiving the extracted JSON as UTF-8.
+    /// * `encoding` - The encoding of the input, such as [`encoding_rs::SHIFT_JIS`].
+    /// * `policy` - How malformed byte sequences are treated.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the reader was consumed completely.
+    /// * `Err(ParseError)` - If there was an error reading or writing, or the
+    ///   input is malformed under [`MalformedInputPolicy::Error`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use surfing::encoding_rs::WINDOWS_1252;
+    /// use surfing::parser::options::MalformedInputPolicy;
+    /// use surfing::JSONParser;
+    ///
+    /// let mut parser = JSONParser::new();
+    /// let mut output = Vec::new();
+    /// let input: &[u8] = b"user {\"name\":\"Ren\xe9e\"} logged in";
+    ///
+    /// parser
+    ///     .extract_json_from_reader_with_encoding(
+    ///         input,
+    ///         &mut output,
+    ///         WINDOWS_1252,
+    ///         MalformedInputPolicy::Replace,
+    ///     )
+    ///     .unwrap();
+    /// assert_eq!(String::from_utf8(output).unwrap(), "{\"name\":\"Renée\"}");
+    /// ```
+    pub fn extract_json_from_reader_with_encoding<R: Read, W: Write>(
+        &mut self,
+        mut reader: R,
+        writer: &mut W,
+        encoding: &'static Encoding,
+        policy: MalformedInputPolicy,
+    ) -> Result<(), ParseError> {
+        let mut decoder = encoding.new_decoder();
+        let mut block = vec![0; ENCODED_BLOCK_SIZE];
+        let mut decoded = String::with_capacity(ENCODED_BLOCK_SIZE);
+        let mut offset: u64 = 0;
+
+        loop {
+            let read = match reader.read(&mut block) {
+                Ok(read) => read,
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e.into()),
+            };
+            let last = read == 0;
+            let mut input = &block[..read];
+
+            loop {
+                let (result, consumed) =
+                    decoder.decode_to_string_without_replacement(input, &mut decoded, last);
+                input = &input[consumed..];
+                offset += consumed as u64;
+
+                match result {
+                    DecoderResult::InputEmpty => break,
+                    DecoderResult::OutputFull => {
+                        self.consume_block(decoded.as_bytes(), writer)?;
+                        decoded.clear();
+                    }
+                    DecoderResult::Malformed(length, after) => match policy {
+                        MalformedInputPolicy::Replace => decoded.push(char::REPLACEMENT_CHARACTER),
+                        MalformedInputPolicy::Error => {
+                            // The malformed sequence may have started in an earlier block
+                            let offset =
+                                offset.saturating_sub(u64::from(length) + u64::from(after));
+                            return Err(ParseError::MalformedInput { offset });
+                        }
+                    },
+                }
+            }
+
+            self.consume_block(decoded.as_bytes(), writer)?;
+            decoded.clear();
+
+            if last {
+                break;
+            }
+        }
+
+        self.take_string_error()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use encoding_rs::{SHIFT_JIS, WINDOWS_1252};
+
+    /// A reader handing out one byte per read, splitting every character.
+    struct ByteReader<'a>(&'a [u8]);
+
+    impl Read for ByteReader<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            match self.0.split_first() {
+                Some((&byte, rest)) => {
+                    buf[0] = byte;
+                    self.0 = rest;
+                    Ok(1)
+                }
+                None => Ok(0),
+            }
+        }
+    }
+
+    fn extract<R: Read>(
+        reader: R,
+        encoding: &'static Encoding,
+        policy: MalformedInputPolicy,
+    ) -> Result<String, ParseError> {
+        let mut output = Vec::new();
+        JSONParser::new().extract_json_from_reader_with_encoding(
+            reader,
+            &mut output,
+            encoding,
+            policy,
+        )?;
+        Ok(String::from_utf8(output).unwrap())
+    }
+
+    #[test]
+    fn test_extract_json_from_reader_with_encoding_fixtures() {
+        let windows_1252 = include_bytes!("../../tests/fixtures/windows-1252.log");
+        let shift_jis = include_bytes!("../../tests/fixtures/shift_jis.log");
+        let policy = MalformedInputPolicy::Error;
+
+        let expected = "{\"user\":\"Zoë\",\"note\":\"café – 5€\"}[\"naïve\"]";
+        assert_eq!(
+            extract(&windows_1252[..], WINDOWS_1252, policy).unwrap(),
+            expected
+        );
+        assert_eq!(
+            extract(ByteReader(windows_1252), WINDOWS_1252, policy).unwrap(),
+            expected
+        );
+
+        let expected = "{\"ユーザー\":\"山田\",\"状態\":\"完了\"}[\"ｶﾅ\"]";
+        assert_eq!(
+            extract(&shift_jis[..], SHIFT_JIS, policy).unwrap(),
+            expected
+        );
+        assert_eq!(
+            extract(ByteReader(shift_jis), SHIFT_JIS, policy).unwrap(),
+            expected
+        );
+    }
+
+    #[test]
+    fn test_extract_json_from_reader_with_encoding_malformed_input() {
+        // 0x82 0x20 is not a Shift-JIS character
+        let input: &[u8] = b"log {\"a\":\"\x82 \"} [1]";
+
+        assert_eq!(
+            extract(ByteReader(input), SHIFT_JIS, MalformedInputPolicy::Replace).unwrap(),
+            "{\"a\":\"\u{FFFD} \"}[1]"
+        );
+        assert!(matches!(
+            extract(input, SHIFT_JIS, MalformedInputPolicy::Error),
+            Err(ParseError::MalformedInput { offset: 10 })
+        ));
+        assert!(matches!(
+            extract(ByteReader(input), SHIFT_JIS, MalformedInputPolicy::Error),
+            Err(ParseError::MalformedInput { offset: 10 })
+        ));
+    }
+}
@@ -0,0 +1,166 @@
+//! Error module for parser failures.
+
+/// Error type for parser failures.
+#[derive(Debug)]
+pub enum ParseError {
+    /// Error writing the extracted JSON
+    Io(std::io::Error),
+    /// Output was suppressed because the configured byte limit was reached
+    OutputTruncated {
+        /// Number of output bytes that were not written
+        suppressed: usize,
+    },
+    /// A NUL character was found inside a string under [`NulPolicy::Error`](crate::parser::options::NulPolicy::Error)
+    NulInString,
+    /// A value was abandoned because one of its strings exceeded [`ParserOptions::max_string_bytes`](crate::parser::options::ParserOptions::max_string_bytes)
+    StringTooLong,
+    /// A value was rejected because one of its strings holds a raw control character under [`ControlCharPolicy::Reject`](crate::parser::options::ControlCharPolicy::Reject)
+    ControlCharInString,
+    /// A value was abandoned because it nests deeper than [`ParserOptions::max_depth`](crate::parser::options::ParserOptions::max_depth)
+    DepthLimitExceeded {
+        /// The deepest nesting allowed
+        limit: usize,
+    },
+    /// A value was abandoned because it grew past [`ParserOptions::max_buffer_size`](crate::parser::options::ParserOptions::max_buffer_size)
+    BufferLimitExceeded {
+        /// The largest number of bytes held for a value
+        limit: usize,
+    },
+    /// A value was skipped because it spans more than [`ParserOptions::max_object_bytes`](crate::parser::options::ParserOptions::max_object_bytes)
+    ObjectTooLarge {
+        /// The largest number of bytes in a value
+        limit: usize,
+    },
+    /// A value was rejected because one of its strings holds a lone surrogate escape under [`LoneSurrogatePolicy::Reject`](crate::parser::options::LoneSurrogatePolicy::Reject)
+    LoneSurrogate,
+    /// The input holds a byte sequence that is malformed in its encoding, under [`MalformedInputPolicy::Error`](crate::parser::options::MalformedInputPolicy::Error)
+    MalformedInput {
+        /// Offset of the malformed sequence in the input
+        offset: u64,
+    },
+}
+
+impl std::fmt::Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseError::Io(e) => write!(f, "failed to write JSON: {}", e),
+            ParseError::OutputTruncated { suppressed } => {
+                write!(f, "output truncated, {} bytes suppressed", suppressed)
+            }
+            ParseError::NulInString => write!(f, "NUL character inside a JSON string"),
+            ParseError::StringTooLong => {
+                write!(f, "JSON value abandoned, a string exceeds the length limit")
+            }
+            ParseError::ControlCharInString => {
+                write!(
+                    f,
+                    "JSON value rejected, a string holds a raw control character"
+                )
+            }
+            ParseError::DepthLimitExceeded { limit } => {
+                write!(
+                    f,
+                    "JSON value abandoned, it nests deeper than the limit of {}",
+                    limit
+                )
+            }
+            ParseError::BufferLimitExceeded { limit } => {
+                write!(
+                    f,
+                    "JSON value abandoned, it exceeds the buffer limit of {} bytes",
+                    limit
+                )
+            }
+            ParseError::ObjectTooLarge { limit } => {
+                write!(
+                    f,
+                    "JSON value skipped, it exceeds the size limit of {} bytes",
+                    limit
+                )
+            }
+            ParseError::LoneSurrogate => {
+                write!(f, "JSON value rejected, a string holds a lone surrogate")
+            }
+            ParseError::MalformedInput { offset } => {
+                write!(f, "malformed input at byte {}", offset)
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ParseError::Io(e) => Some(e),
+            ParseError::OutputTruncated { .. }
+            | ParseError::NulInString
+            | ParseError::StringTooLong
+            | ParseError::ControlCharInString
+            | ParseError::DepthLimitExceeded { .. }
+            | ParseError::BufferLimitExceeded { .. }
+            | ParseError::ObjectTooLarge { .. }
+            | ParseError::LoneSurrogate
+            | ParseError::MalformedInput { .. } => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for ParseError {
+    fn from(error: std::io::Error) -> Self {
+        // Parse errors raised while writing travel inside an I/O error
+        if error
+            .get_ref()
+            .is_some_and(|inner| inner.is::<ParseError>())
+        {
+            let kind = error.kind();
+            return match error
+                .into_inner()
+                .map(|inner| inner.downcast::<ParseError>())
+            {
+                Some(Ok(inner)) => *inner,
+                _ => ParseError::Io(kind.into()),
+            };
+        }
+
+        ParseError::Io(error)
+    }
+}
+
+impl From<ParseError> for std::io::Error {
+    fn from(error: ParseError) -> Self {
+        match error {
+            ParseError::Io(error) => error,
+            error => std::io::Error::new(std::io::ErrorKind::InvalidData, error),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::extract_json_to_string;
+    use crate::JSONParser;
+
+    fn assert_send_sync<T: Send + Sync + 'static>() {}
+
+    #[tokio::test]
+    async fn test_parse_error_crosses_spawn() {
+        assert_send_sync::<ParseError>();
+
+        let task = tokio::spawn(async {
+            // Results are held across await points, as in a service handler
+            let json = extract_json_to_string("log {\"a\":1}");
+            tokio::task::yield_now().await;
+
+            let mut parser = JSONParser::new();
+            let mut output = Vec::new();
+            let result = parser.extract_json_from_stream(&mut output, &json?);
+            tokio::task::yield_now().await;
+            result?;
+
+            Ok::<_, ParseError>(String::from_utf8(output).unwrap())
+        });
+
+        assert_eq!(task.await.unwrap().unwrap(), "{\"a\":1}");
+    }
+}
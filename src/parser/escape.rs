@@ -0,0 +1,133 @@
+//! Escape module for decoding `\u` escape sequences inside JSON strings.
+
+/// The result of decoding a pending escape sequence.
+#[derive(Debug, PartialEq)]
+pub(crate) enum Unescaped {
+    /// More input is needed before the sequence can be decoded.
+    Incomplete,
+    /// The sequence decoded to a character that may be written unescaped.
+    Char(char),
+    /// The sequence must be written exactly as it appeared in the input.
+    Verbatim,
+    /// The first six bytes are the escape of a lone surrogate, and the rest
+    /// must be processed again.
+    LoneSurrogate,
+}
+
+/// The result of reading a single `\uXXXX` code unit.
+pub(crate) enum Unit {
+    Incomplete,
+    Invalid,
+    Complete(u32),
+}
+
+/// Decodes a pending escape sequence starting with a backslash.
+///
+/// # Arguments
+///
+/// * `pending` - The escape sequence collected so far, including the backslash.
+///
+/// # Returns
+///
+/// The decoding decision for the sequence.
+pub(crate) fn unescape_sequence(pending: &str) -> Unescaped {
+    let high = match read_unit(pending) {
+        Unit::Incomplete => return Unescaped::Incomplete,
+        Unit::Invalid => return Unescaped::Verbatim,
+        Unit::Complete(unit) => unit,
+    };
+
+    if is_low_surrogate(high) {
+        return Unescaped::LoneSurrogate;
+    }
+
+    if !is_high_surrogate(high) {
+        return match char::from_u32(high) {
+            Some(item) if !requires_escape(item) => Unescaped::Char(item),
+            _ => Unescaped::Verbatim,
+        };
+    }
+
+    // A high surrogate is only decodable together with the low surrogate after it
+    match read_unit(&pending[6..]) {
+        Unit::Incomplete => Unescaped::Incomplete,
+        Unit::Complete(low) if is_low_surrogate(low) => {
+            let code = 0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00);
+            char::from_u32(code).map_or(Unescaped::Verbatim, Unescaped::Char)
+        }
+        _ => Unescaped::LoneSurrogate,
+    }
+}
+
+/// Reads a `\uXXXX` code unit from the start of the text.
+pub(crate) fn read_unit(text: &str) -> Unit {
+    let mut chars = text.chars();
+
+    for expected in ['\\', 'u'] {
+        match chars.next() {
+            None => return Unit::Incomplete,
+            Some(item) if item == expected => {}
+            Some(_) => return Unit::Invalid,
+        }
+    }
+
+    let mut unit = 0;
+    for _ in 0..4 {
+        match chars.next().map(|item| item.to_digit(16)) {
+            None => return Unit::Incomplete,
+            Some(Some(digit)) => unit = unit * 16 + digit,
+            Some(None) => return Unit::Invalid,
+        }
+    }
+
+    Unit::Complete(unit)
+}
+
+/// Checks if the character must stay escaped inside a JSON string.
+fn requires_escape(item: char) -> bool {
+    item == '"' || item == '\\' || (item as u32) < 0x20
+}
+
+fn is_high_surrogate(unit: u32) -> bool {
+    (0xD800..0xDC00).contains(&unit)
+}
+
+fn is_low_surrogate(unit: u32) -> bool {
+    (0xDC00..0xE000).contains(&unit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unescape_sequence_incomplete() {
+        assert_eq!(unescape_sequence("\\"), Unescaped::Incomplete);
+        assert_eq!(unescape_sequence("\\u00"), Unescaped::Incomplete);
+        assert_eq!(unescape_sequence("\\ud83d\\u"), Unescaped::Incomplete);
+    }
+
+    #[test]
+    fn test_unescape_sequence_decodes() {
+        assert_eq!(unescape_sequence("\\u00e9"), Unescaped::Char('é'));
+        assert_eq!(unescape_sequence("\\ud83d\\ude00"), Unescaped::Char('😀'));
+    }
+
+    #[test]
+    fn test_unescape_sequence_keeps_required_escapes() {
+        assert_eq!(unescape_sequence("\\u0022"), Unescaped::Verbatim);
+        assert_eq!(unescape_sequence("\\u005c"), Unescaped::Verbatim);
+        assert_eq!(unescape_sequence("\\u000a"), Unescaped::Verbatim);
+        assert_eq!(unescape_sequence("\\n"), Unescaped::Verbatim);
+    }
+
+    #[test]
+    fn test_unescape_sequence_lone_surrogates() {
+        assert_eq!(unescape_sequence("\\ude00"), Unescaped::LoneSurrogate);
+        assert_eq!(unescape_sequence("\\ud83d\""), Unescaped::LoneSurrogate);
+        assert_eq!(
+            unescape_sequence("\\ud83d\\ud83d"),
+            Unescaped::LoneSurrogate
+        );
+    }
+}
@@ -0,0 +1,389 @@
+//! Incremental checking of JSON grammar.
+//!
+//! The one checker backs strict extraction, [`JsonValidator`] and the
+//! validity checks of extracted values, so they all accept the same JSON.
+//!
+//! [`JsonValidator`]: crate::parser::validator::JsonValidator
+
+use crate::parser::options::is_json_whitespace;
+use crate::parser::validator::InvalidReason;
+
+/// What a structure expects next, outside of any token.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Expect {
+    /// A key or the end of an empty object
+    FirstKey,
+    /// A key after a comma
+    Key,
+    /// The colon after a key
+    Colon,
+    /// A value or the end of an empty array
+    FirstValue,
+    /// A value after a colon or comma
+    Value,
+    /// A comma or the end of the structure
+    CommaOrClose,
+}
+
+/// An open object or array and what it expects next.
+#[derive(Debug, Clone, Copy)]
+struct Frame {
+    object: bool,
+    expect: Expect,
+}
+
+/// The position inside a number, following the JSON number grammar.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Number {
+    /// After the minus sign
+    Minus,
+    /// After a leading zero
+    Zero,
+    /// In the integer digits
+    Integer,
+    /// After the decimal point
+    Dot,
+    /// In the fraction digits
+    Fraction,
+    /// After the exponent marker
+    Exponent,
+    /// After the sign of the exponent
+    ExponentSign,
+    /// In the exponent digits
+    ExponentDigits,
+}
+
+impl Number {
+    /// Returns the position after `item`, if it continues the number.
+    fn next(self, item: char) -> Option<Self> {
+        let digit = item.is_ascii_digit();
+        match (self, item) {
+            (Number::Minus, '0') => Some(Number::Zero),
+            (Number::Minus, _) if digit => Some(Number::Integer),
+            (Number::Integer, _) if digit => Some(Number::Integer),
+            (Number::Zero | Number::Integer, '.') => Some(Number::Dot),
+            (Number::Dot | Number::Fraction, _) if digit => Some(Number::Fraction),
+            (Number::Zero | Number::Integer | Number::Fraction, 'e' | 'E') => {
+                Some(Number::Exponent)
+            }
+            (Number::Exponent, '+' | '-') => Some(Number::ExponentSign),
+            (Number::Exponent | Number::ExponentSign | Number::ExponentDigits, _) if digit => {
+                Some(Number::ExponentDigits)
+            }
+            _ => None,
+        }
+    }
+
+    /// Checks if the number may end at this position.
+    fn is_complete(self) -> bool {
+        matches!(
+            self,
+            Number::Zero | Number::Integer | Number::Fraction | Number::ExponentDigits
+        )
+    }
+}
+
+/// The token being read, if any.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+enum Token {
+    /// Between tokens
+    #[default]
+    None,
+    /// Inside a string, after a backslash if `escaped`
+    String { escaped: bool },
+    /// Inside a `\u` escape, with the number of hex digits still expected
+    Unicode(u8),
+    /// Inside a number
+    Number(Number),
+    /// Inside a literal, with the rest of it still expected
+    Literal(&'static str),
+}
+
+/// Checks the characters of a value against the JSON grammar as they arrive.
+///
+/// Keys must be strings followed by a colon, members and elements must be
+/// separated by single commas, strings may only hold valid escapes and no
+/// control characters, and numbers and literals must be well formed. The
+/// value may be a scalar and may be surrounded by whitespace, but nothing
+/// else may follow it. Only the open structures and the token in progress
+/// are kept, so the memory used does not grow with the length of a valid
+/// value, and nesting is only limited by memory.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct GrammarChecker {
+    frames: Vec<Frame>,
+    token: Token,
+    /// Whether the top-level value has started
+    started: bool,
+}
+
+impl GrammarChecker {
+    /// Feeds the next character of a value, starting with its opening marker.
+    ///
+    /// # Arguments
+    ///
+    /// * `item` - The character to check.
+    ///
+    /// # Returns
+    ///
+    /// `false` if the character breaks the grammar, after which the checker
+    /// is reset.
+    pub(crate) fn push(&mut self, item: char) -> bool {
+        self.check(item).is_ok()
+    }
+
+    /// Feeds the next character of a value, reporting why it breaks the grammar if it does.
+    ///
+    /// # Arguments
+    ///
+    /// * `item` - The character to check.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the character continues the value.
+    /// * `Err(InvalidReason)` - If it breaks the grammar, after which the
+    ///   checker is reset.
+    pub(crate) fn check(&mut self, item: char) -> Result<(), InvalidReason> {
+        let result = self.step(item);
+        if result.is_err() {
+            self.reset();
+        }
+        result
+    }
+
+    /// Checks if the characters fed so far form exactly one complete value.
+    ///
+    /// A number at the top level has no closing character, so it is
+    /// complete as soon as it could end.
+    pub(crate) fn is_complete(&self) -> bool {
+        let token_complete = match self.token {
+            Token::None => true,
+            Token::Number(number) => number.is_complete(),
+            _ => false,
+        };
+
+        self.started && self.frames.is_empty() && token_complete
+    }
+
+    /// Forgets the value being checked.
+    pub(crate) fn reset(&mut self) {
+        self.frames.clear();
+        self.token = Token::None;
+        self.started = false;
+    }
+
+    /// Checks a character, leaving the state as it is on failure.
+    fn step(&mut self, item: char) -> Result<(), InvalidReason> {
+        match self.token {
+            Token::None => self.step_structure(item),
+            Token::String { escaped: false } => {
+                match item {
+                    '"' => self.token = Token::None,
+                    '\\' => self.token = Token::String { escaped: true },
+                    _ if item < ' ' => return Err(InvalidReason::ControlChar),
+                    _ => {}
+                }
+                Ok(())
+            }
+            Token::String { escaped: true } => {
+                self.token = match item {
+                    '"' | '\\' | '/' | 'b' | 'f' | 'n' | 'r' | 't' => {
+                        Token::String { escaped: false }
+                    }
+                    'u' => Token::Unicode(4),
+                    _ => return Err(InvalidReason::InvalidEscape),
+                };
+                Ok(())
+            }
+            Token::Unicode(remaining) => {
+                if !item.is_ascii_hexdigit() {
+                    return Err(InvalidReason::InvalidEscape);
+                }
+                self.token = match remaining {
+                    1 => Token::String { escaped: false },
+                    _ => Token::Unicode(remaining - 1),
+                };
+                Ok(())
+            }
+            Token::Number(number) => match number.next(item) {
+                Some(next) => {
+                    self.token = Token::Number(next);
+                    Ok(())
+                }
+                // The character after a number belongs to what follows it
+                None if number.is_complete() => {
+                    self.token = Token::None;
+                    self.step_structure(item)
+                }
+                None => Err(InvalidReason::UnexpectedChar(item)),
+            },
+            Token::Literal(rest) => {
+                let Some(remaining) = rest.strip_prefix(item) else {
+                    return Err(InvalidReason::UnexpectedChar(item));
+                };
+                self.token = if remaining.is_empty() {
+                    Token::None
+                } else {
+                    Token::Literal(remaining)
+                };
+                Ok(())
+            }
+        }
+    }
+
+    /// Checks a character between tokens.
+    fn step_structure(&mut self, item: char) -> Result<(), InvalidReason> {
+        if is_json_whitespace(item) {
+            return Ok(());
+        }
+
+        let Some(frame) = self.frames.last_mut() else {
+            // Nothing but whitespace may follow the top-level value
+            if self.started {
+                return Err(InvalidReason::UnexpectedChar(item));
+            }
+            self.started = true;
+            return self.start_value(item);
+        };
+
+        match (frame.expect, item) {
+            (Expect::FirstKey | Expect::Key, '"') => {
+                frame.expect = Expect::Colon;
+                self.token = Token::String { escaped: false };
+            }
+            (Expect::Colon, ':') => frame.expect = Expect::Value,
+            (Expect::CommaOrClose, ',') => {
+                frame.expect = if frame.object {
+                    Expect::Key
+                } else {
+                    Expect::Value
+                };
+            }
+            (Expect::FirstKey, '}') | (Expect::FirstValue, ']') => {
+                self.frames.pop();
+            }
+            (Expect::CommaOrClose, '}') if frame.object => {
+                self.frames.pop();
+            }
+            (Expect::CommaOrClose, ']') if !frame.object => {
+                self.frames.pop();
+            }
+            (Expect::FirstValue | Expect::Value, _) => {
+                frame.expect = Expect::CommaOrClose;
+                return self.start_value(item);
+            }
+            _ => return Err(InvalidReason::UnexpectedChar(item)),
+        }
+
+        Ok(())
+    }
+
+    /// Checks the first character of a value.
+    fn start_value(&mut self, item: char) -> Result<(), InvalidReason> {
+        self.token = match item {
+            '"' => Token::String { escaped: false },
+            '-' => Token::Number(Number::Minus),
+            '0' => Token::Number(Number::Zero),
+            '1'..='9' => Token::Number(Number::Integer),
+            't' => Token::Literal("rue"),
+            'f' => Token::Literal("alse"),
+            'n' => Token::Literal("ull"),
+            _ => return self.open(item),
+        };
+        Ok(())
+    }
+
+    /// Opens a structure.
+    fn open(&mut self, item: char) -> Result<(), InvalidReason> {
+        let (object, expect) = match item {
+            '{' => (true, Expect::FirstKey),
+            '[' => (false, Expect::FirstValue),
+            _ => return Err(InvalidReason::UnexpectedChar(item)),
+        };
+        self.frames.push(Frame { object, expect });
+        Ok(())
+    }
+}
+
+/// Checks if the text is exactly one valid JSON value, apart from surrounding whitespace.
+///
+/// The text is checked one character at a time with a [`GrammarChecker`],
+/// so values of any depth are accepted.
+///
+/// # Arguments
+///
+/// * `text` - The text to check.
+pub(crate) fn is_valid_json(text: &str) -> bool {
+    let mut checker = GrammarChecker::default();
+    text.chars().all(|item| checker.push(item)) && checker.is_complete()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_valid_json_accepts_values() {
+        for text in [
+            "{}",
+            " [ ] ",
+            "{ \"a\" : [1, -0.5e+3, 0, 10E2, true, false, null, \"x\\u00e9\\n\"], \"b\": {} }",
+            "[[[]], {\"k\":\"v\"}, \"\\\"\"]",
+            "0",
+            " 12.5e3 ",
+            "\"text\"",
+        ] {
+            assert!(is_valid_json(text), "{}", text);
+        }
+    }
+
+    #[test]
+    fn test_is_valid_json_rejects_invalid() {
+        for text in [
+            "",
+            "{",
+            "{pid=1234}",
+            "{\"a\" 1}",
+            "{\"a\":1,}",
+            "[1,,2]",
+            "[1 2]",
+            "[01]",
+            "[1.]",
+            "[-]",
+            "[nul]",
+            "[truex]",
+            "{\"a\":1]",
+            "[\"\\x\"]",
+            "[\"\\u12g4\"]",
+            "[\"a\nb\"]",
+            "{1:2}",
+            "[1] [2]",
+            "{\"a\":",
+            "tru",
+            &"[".repeat(200),
+        ] {
+            assert!(!is_valid_json(text), "{}", text);
+        }
+    }
+
+    #[test]
+    fn test_is_valid_json_accepts_deep_nesting() {
+        let deep = format!("{}{}", "[".repeat(200), "]".repeat(200));
+        assert!(is_valid_json(&deep));
+    }
+
+    #[test]
+    fn test_grammar_checker_reports_reasons() {
+        let first_error = |text: &str| {
+            let mut checker = GrammarChecker::default();
+            text.chars().find_map(|item| checker.check(item).err())
+        };
+
+        assert_eq!(first_error("[\"\\x\"]"), Some(InvalidReason::InvalidEscape));
+        assert_eq!(first_error("[\"\t\"]"), Some(InvalidReason::ControlChar));
+        assert_eq!(
+            first_error("[1 2]"),
+            Some(InvalidReason::UnexpectedChar('2'))
+        );
+        assert_eq!(first_error("[1, 2]"), None);
+    }
+}
@@ -1,9 +1,35 @@
 //! JSON Parser module for extracting JSON from text streams.
 
-use std::io::Write;
+use std::io::{IoSlice, Read, Write};
+use std::ops::Range;
 
-use crate::constants::PAIRED_MARKERS;
-use crate::parser::marker::Marker;
+use crate::constants::{BYTE_ORDER_MARK, MARKERS};
+use crate::parser::error::ParseError;
+use crate::parser::escape::{unescape_sequence, Unescaped};
+use crate::parser::grammar::GrammarChecker;
+use crate::parser::limit::OutputLimit;
+use crate::parser::marker::{ContainerKind, Marker};
+use crate::parser::options::{
+    is_json_whitespace, ControlCharPolicy, LoneSurrogatePolicy, NulPolicy, ParserOptions,
+};
+use crate::parser::outcome::{CharOutcome, CountingWriter, StreamEnd, ValueDecision};
+use crate::parser::percent::{PercentDecoder, PercentStep};
+use crate::parser::relaxed::RelaxedTranslator;
+use crate::parser::scalar::{ScalarScanner, ScalarStep};
+#[cfg(feature = "spill")]
+use crate::parser::spill::{Spill, ValuePayload};
+use crate::parser::sse::SseFramer;
+use crate::parser::string_scanner::StringScanner;
+use crate::parser::token::Token;
+
+/// The number of bytes read at once by [`JSONParser::extract_json_from_reader`].
+const READ_BLOCK_SIZE: usize = 8 * 1024;
+
+/// A callback receiving the depth and kind of a structure entered or left.
+type NestingHook = Box<dyn FnMut(usize, ContainerKind) + Send>;
+
+/// A callback deciding whether to keep a value from its first bytes.
+type PrefixHook = Box<dyn FnMut(&str) -> ValueDecision + Send>;
 
 /// A parser that extracts JSON objects and arrays from a stream of text.
 ///
@@ -11,6 +37,14 @@ use crate::parser::marker::Marker;
 /// and will extract only the JSON parts. It handles both complete and partial
 /// JSON documents, allowing for incremental parsing of streaming data.
 ///
+/// A closing brace or bracket that does not match the innermost open one,
+/// as in `{"a":[1,2}`, makes the value malformed: it is dropped at that
+/// character and extraction starts afresh after it. Nothing of a dropped
+/// value is reported or written, except that writer-based extraction
+/// streams a value spanning several calls, so the part given in earlier
+/// calls is already written unless [`ParserOptions::atomic_values`] holds it
+/// back.
+///
 /// # Examples
 ///
 /// ```
@@ -36,6 +70,47 @@ use crate::parser::marker::Marker;
 pub struct JSONParser {
     buffer: String,
     markers: Vec<Marker>,
+    options: ParserOptions,
+    in_string: bool,
+    escaped_value: bool,
+    string_scanner: StringScanner,
+    smart_string: bool,
+    abandoned: bool,
+    in_split_array: bool,
+    awaiting_content: bool,
+    start_pending: String,
+    grammar: GrammarChecker,
+    overflow_depth: usize,
+    percent: PercentDecoder,
+    percent_decoded: bool,
+    scalar: ScalarScanner,
+    scalar_completed: bool,
+    string_bytes: usize,
+    discarding: bool,
+    suppress_next: bool,
+    string_error: Option<ParseError>,
+    values_written: usize,
+    #[cfg(feature = "serde")]
+    unwrap_checked: bool,
+    #[cfg(feature = "serde")]
+    unwrap_held: Vec<u8>,
+    #[cfg(feature = "serde")]
+    validate_held: Vec<u8>,
+    pending_escape: String,
+    value_output: Vec<u8>,
+    completed_values: Vec<String>,
+    sse: SseFramer,
+    relaxed: RelaxedTranslator,
+    relaxed_output: String,
+    limit: OutputLimit,
+    on_open: Option<NestingHook>,
+    on_close: Option<NestingHook>,
+    on_value_prefix: Option<(usize, PrefixHook)>,
+    prefix_held: Vec<u8>,
+    prefix_pending: bool,
+    pending_bytes: Vec<u8>,
+    #[cfg(feature = "spill")]
+    spill: Spill,
 }
 
 impl JSONParser {
@@ -49,12 +124,242 @@ impl JSONParser {
     /// let parser = JSONParser::new();
     /// ```
     pub fn new() -> Self {
+        Self::with_options(ParserOptions::default())
+    }
+
+    /// Creates a new JSONParser instance configured with the given options.
+    ///
+    /// # Arguments
+    ///
+    /// * `options` - The options controlling how extracted JSON is emitted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use surfing::parser::options::ParserOptions;
+    /// use surfing::JSONParser;
+    ///
+    /// let parser = JSONParser::with_options(ParserOptions::new().ascii_only(true));
+    /// ```
+    pub fn with_options(options: ParserOptions) -> Self {
         Self {
             buffer: String::new(),
             markers: Vec::new(),
+            options,
+            in_string: false,
+            escaped_value: false,
+            string_scanner: StringScanner::new(),
+            smart_string: false,
+            abandoned: false,
+            in_split_array: false,
+            awaiting_content: false,
+            start_pending: String::new(),
+            grammar: GrammarChecker::default(),
+            overflow_depth: 0,
+            percent: PercentDecoder::default(),
+            percent_decoded: false,
+            scalar: ScalarScanner::default(),
+            scalar_completed: false,
+            string_bytes: 0,
+            discarding: false,
+            suppress_next: false,
+            string_error: None,
+            values_written: 0,
+            #[cfg(feature = "serde")]
+            unwrap_checked: false,
+            #[cfg(feature = "serde")]
+            unwrap_held: Vec::new(),
+            #[cfg(feature = "serde")]
+            validate_held: Vec::new(),
+            pending_escape: String::new(),
+            value_output: Vec::new(),
+            completed_values: Vec::new(),
+            sse: SseFramer::default(),
+            relaxed: RelaxedTranslator::default(),
+            relaxed_output: String::new(),
+            limit: OutputLimit::default(),
+            on_open: None,
+            on_close: None,
+            on_value_prefix: None,
+            prefix_held: Vec::new(),
+            prefix_pending: false,
+            pending_bytes: Vec::new(),
+            #[cfg(feature = "spill")]
+            spill: Spill::default(),
         }
     }
 
+    /// Creates a parser that only extracts values `serde_json` accepts.
+    ///
+    /// This enables both [`ParserOptions::strict`], which drops a value as
+    /// soon as it breaks the JSON grammar, and
+    /// [`ParserOptions::validate_values`], which parses each completed value
+    /// with `serde_json` before it is written or reported. Balanced but
+    /// invalid candidates such as `{"a": }` or `{key: 1}` are skipped silently.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use surfing::JSONParser;
+    ///
+    /// let mut parser = JSONParser::strict();
+    /// let mut buffer = Vec::new();
+    /// parser
+    ///     .extract_json_from_stream(&mut buffer, "{\"a\": } {key: 1} {\"a\": 1}")
+    ///     .unwrap();
+    ///
+    /// assert_eq!(String::from_utf8(buffer).unwrap(), "{\"a\": 1}");
+    /// ```
+    #[cfg(feature = "serde")]
+    pub fn strict() -> Self {
+        Self::with_options(ParserOptions::new().strict(true).validate_values(true))
+    }
+
+    /// Creates a parser reading newline-delimited JSON.
+    ///
+    /// This enables [`ParserOptions::ndjson`], so every line is a record of
+    /// its own and a line truncated in the middle of a value cannot merge
+    /// with the lines after it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use surfing::JSONParser;
+    ///
+    /// let mut parser = JSONParser::ndjson();
+    /// parser.feed("{\"id\":1,\"tags\":[\n{\"id\":2}\n").unwrap();
+    ///
+    /// assert_eq!(parser.take_completed_values(), vec!["{\"id\":2}"]);
+    /// ```
+    pub fn ndjson() -> Self {
+        Self::with_options(ParserOptions::new().ndjson(true))
+    }
+
+    /// Sets a callback fired whenever a JSON object or array is entered.
+    ///
+    /// The callback receives the depth of the structure, which is the number
+    /// of structures enclosing it (a top-level value opens at depth 0), and
+    /// its kind. Structural events fire as the markers are read, before the
+    /// value is complete, which makes them suitable for progress reporting or
+    /// a live view of the structure.
+    ///
+    /// # Arguments
+    ///
+    /// * `hook` - The callback receiving the depth and kind.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::{Arc, Mutex};
+    /// use surfing::parser::marker::ContainerKind;
+    /// use surfing::JSONParser;
+    ///
+    /// let opened = Arc::new(Mutex::new(Vec::new()));
+    /// let events = Arc::clone(&opened);
+    /// let mut parser = JSONParser::new().on_open(move |depth, kind| {
+    ///     events.lock().unwrap().push((depth, kind));
+    /// });
+    ///
+    /// let mut buffer = Vec::new();
+    /// parser.extract_json_from_stream(&mut buffer, "{\"ids\": [1]}").unwrap();
+    ///
+    /// assert_eq!(
+    ///     *opened.lock().unwrap(),
+    ///     vec![(0, ContainerKind::Object), (1, ContainerKind::Array)]
+    /// );
+    /// ```
+    pub fn on_open<F>(mut self, hook: F) -> Self
+    where
+        F: FnMut(usize, ContainerKind) + Send + 'static,
+    {
+        self.on_open = Some(Box::new(hook));
+        self
+    }
+
+    /// Sets a callback fired whenever a JSON object or array is left.
+    ///
+    /// The callback receives the same depth and kind that
+    /// [`on_open`](Self::on_open) reported for the structure.
+    ///
+    /// # Arguments
+    ///
+    /// * `hook` - The callback receiving the depth and kind.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::sync::{Arc, Mutex};
+    /// use surfing::parser::marker::ContainerKind;
+    /// use surfing::JSONParser;
+    ///
+    /// let closed = Arc::new(Mutex::new(Vec::new()));
+    /// let events = Arc::clone(&closed);
+    /// let mut parser = JSONParser::new().on_close(move |depth, kind| {
+    ///     events.lock().unwrap().push((depth, kind));
+    /// });
+    ///
+    /// let mut buffer = Vec::new();
+    /// parser.extract_json_from_stream(&mut buffer, "{\"ids\": [1]}").unwrap();
+    ///
+    /// assert_eq!(
+    ///     *closed.lock().unwrap(),
+    ///     vec![(1, ContainerKind::Array), (0, ContainerKind::Object)]
+    /// );
+    /// ```
+    pub fn on_close<F>(mut self, hook: F) -> Self
+    where
+        F: FnMut(usize, ContainerKind) + Send + 'static,
+    {
+        self.on_close = Some(Box::new(hook));
+        self
+    }
+
+    /// Sets a callback deciding whether to keep each value from its first bytes.
+    ///
+    /// The output of every value is held back until it reaches `max_bytes`
+    /// or the value ends, whichever comes first. The callback then receives
+    /// that prefix, which may run a few bytes past `max_bytes` to end on a
+    /// character boundary. On [`ValueDecision::Keep`], the prefix is written
+    /// and the rest of the value streams as usual. On
+    /// [`ValueDecision::Skip`], the prefix is dropped and the rest of the
+    /// value is suppressed as with
+    /// [`suppress_current_value`](Self::suppress_current_value), so none of
+    /// it is written or reported.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_bytes` - The number of bytes of each value to buffer before deciding.
+    /// * `hook` - The callback receiving the prefix and returning the decision.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use surfing::parser::outcome::ValueDecision;
+    /// use surfing::JSONParser;
+    ///
+    /// let mut parser = JSONParser::new().on_value_prefix(16, |prefix| {
+    ///     if prefix.starts_with("{\"debug\"") {
+    ///         ValueDecision::Skip
+    ///     } else {
+    ///         ValueDecision::Keep
+    ///     }
+    /// });
+    ///
+    /// let mut buffer = Vec::new();
+    /// parser
+    ///     .extract_json_from_stream(&mut buffer, "{\"debug\": [1, 2, 3]} {\"event\": \"login\"}")
+    ///     .unwrap();
+    ///
+    /// assert_eq!(String::from_utf8(buffer).unwrap(), "{\"event\": \"login\"}");
+    /// ```
+    pub fn on_value_prefix<F>(mut self, max_bytes: usize, hook: F) -> Self
+    where
+        F: FnMut(&str) -> ValueDecision + Send + 'static,
+    {
+        self.on_value_prefix = Some((max_bytes, Box::new(hook)));
+        self
+    }
+
     /// Checks if the parser is currently processing a JSON structure.
     ///
     /// This method returns `true` when the parser is in the middle of processing
@@ -86,26 +391,336 @@ impl JSONParser {
         !self.markers.is_empty()
     }
 
+    /// Returns the input text of the value in progress, collected since its opening marker.
+    ///
+    /// The text is as it appeared in the input, before any rewriting such as
+    /// [`ParserOptions::relaxed`] applies, and is empty outside of a value.
+    /// It is cleared once the value completes or is dropped, so it only ever
+    /// reflects the value in flight. Nothing is collected for a value being
+    /// discarded, such as one past [`ParserOptions::max_object_bytes`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use surfing::JSONParser;
+    ///
+    /// let mut parser = JSONParser::new();
+    /// let mut buffer = Vec::new();
+    ///
+    /// parser.extract_json_from_stream(&mut buffer, "Result: {\"score\": 9").unwrap();
+    /// assert_eq!(parser.current_json(), "{\"score\": 9");
+    ///
+    /// parser.extract_json_from_stream(&mut buffer, "5} done").unwrap();
+    /// assert_eq!(parser.current_json(), "");
+    /// ```
+    pub fn current_json(&self) -> &str {
+        &self.buffer
+    }
+
+    /// Takes the input text of the value in progress, leaving it empty.
+    ///
+    /// This hands over what [`current_json`](Self::current_json) returns.
+    /// The parser still follows the value, and collects its text from the
+    /// next character on, so [`ParserOptions::max_buffer_size`] counts from
+    /// there too.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use surfing::JSONParser;
+    ///
+    /// let mut parser = JSONParser::new();
+    /// let mut buffer = Vec::new();
+    ///
+    /// parser.extract_json_from_stream(&mut buffer, "[1, 2").unwrap();
+    /// assert_eq!(parser.take_partial(), "[1, 2");
+    ///
+    /// parser.extract_json_from_stream(&mut buffer, ", 3").unwrap();
+    /// assert_eq!(parser.take_partial(), ", 3");
+    /// assert!(parser.is_in_json());
+    /// ```
+    pub fn take_partial(&mut self) -> String {
+        std::mem::take(&mut self.buffer)
+    }
+
+    /// Returns how many objects and arrays are currently open.
+    ///
+    /// This is 0 outside of a value and 1 inside a top-level value. Structures
+    /// past [`ParserOptions::max_depth`] are counted too.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use surfing::JSONParser;
+    ///
+    /// let mut parser = JSONParser::new();
+    /// let mut buffer = Vec::new();
+    ///
+    /// parser.extract_json_from_stream(&mut buffer, "{\"a\": [ {").unwrap();
+    /// assert_eq!(parser.depth(), 3);
+    ///
+    /// parser.extract_json_from_stream(&mut buffer, "} ] }").unwrap();
+    /// assert_eq!(parser.depth(), 0);
+    /// ```
+    pub fn depth(&self) -> usize {
+        self.markers.len() + self.overflow_depth
+    }
+
+    /// Returns the closing markers the open structures are waiting for, innermost first.
+    ///
+    /// Collected into a string, this reads as the text that would complete
+    /// the current value, which suits progress displays such as
+    /// "waiting for ]}". Structures past [`ParserOptions::max_depth`] are not
+    /// tracked, so their closers are not included.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use surfing::JSONParser;
+    ///
+    /// let mut parser = JSONParser::new();
+    /// let mut buffer = Vec::new();
+    ///
+    /// parser.extract_json_from_stream(&mut buffer, "{\"a\": [ {").unwrap();
+    /// assert_eq!(parser.expected_closers().collect::<String>(), "}]}");
+    ///
+    /// parser.extract_json_from_stream(&mut buffer, "} ] }").unwrap();
+    /// assert_eq!(parser.expected_closers().count(), 0);
+    /// ```
+    pub fn expected_closers(&self) -> impl Iterator<Item = char> + '_ {
+        self.markers
+            .iter()
+            .rev()
+            .map(|marker| marker.expected_counterpart)
+    }
+
+    /// Resets the parser so it can process a new, unrelated stream.
+    ///
+    /// Options and nesting hooks are kept. Internal buffers are cleared
+    /// rather than replaced, so their allocated capacity is reused.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use surfing::JSONParser;
+    ///
+    /// let mut parser = JSONParser::new();
+    /// let mut buffer = Vec::new();
+    /// parser.extract_json_from_stream(&mut buffer, "{\"partial\":").unwrap();
+    ///
+    /// parser.reset();
+    /// assert!(!parser.is_in_json());
+    ///
+    /// buffer.clear();
+    /// parser.extract_json_from_stream(&mut buffer, "text [1] text").unwrap();
+    /// assert_eq!(String::from_utf8(buffer).unwrap(), "[1]");
+    /// ```
+    pub fn reset(&mut self) {
+        self.buffer.clear();
+        self.markers.clear();
+        self.in_string = false;
+        self.escaped_value = false;
+        self.string_scanner.reset();
+        self.smart_string = false;
+        self.abandoned = false;
+        self.in_split_array = false;
+        self.awaiting_content = false;
+        self.start_pending.clear();
+        self.grammar.reset();
+        self.overflow_depth = 0;
+        self.percent.reset();
+        self.percent_decoded = false;
+        self.scalar.reset();
+        self.scalar_completed = false;
+        self.string_bytes = 0;
+        self.discarding = false;
+        self.suppress_next = false;
+        self.prefix_held.clear();
+        self.prefix_pending = false;
+        self.pending_bytes.clear();
+        self.string_error = None;
+        self.values_written = 0;
+        #[cfg(feature = "serde")]
+        {
+            self.unwrap_checked = false;
+            self.unwrap_held.clear();
+            self.validate_held.clear();
+        }
+        self.pending_escape.clear();
+        self.value_output.clear();
+        self.completed_values.clear();
+        self.sse.reset();
+        self.relaxed.reset();
+        self.relaxed_output.clear();
+        self.limit.reset();
+        #[cfg(feature = "spill")]
+        self.spill.discard();
+    }
+
+    /// Tells the parser that the input is over, reporting whether it ended inside a value.
+    ///
+    /// A value still open at the end of the input will never complete, so
+    /// callers tailing a socket or a file can log it as truncated rather
+    /// than drop it silently. The partial text is the input of the value
+    /// collected so far, which is not held for a value being discarded,
+    /// such as one past [`ParserOptions::max_object_bytes`]. The parser is
+    /// then [`reset`](Self::reset), so output held back for the value is
+    /// dropped and the parser can be reused for another stream.
+    ///
+    /// # Returns
+    ///
+    /// * [`StreamEnd::Clean`] - If the input ended outside of any value.
+    /// * [`StreamEnd::Truncated`] - If it ended inside a value, with the
+    ///   number of structures still open and the partial text.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use surfing::parser::outcome::StreamEnd;
+    /// use surfing::JSONParser;
+    ///
+    /// let mut parser = JSONParser::new();
+    /// let mut buffer = Vec::new();
+    /// parser
+    ///     .extract_json_from_stream(&mut buffer, "{\"id\":1} {\"items\":[1, 2")
+    ///     .unwrap();
+    ///
+    /// assert_eq!(
+    ///     parser.finish(),
+    ///     StreamEnd::Truncated {
+    ///         open_markers: 2,
+    ///         partial: "{\"items\":[1, 2".to_string(),
+    ///     }
+    /// );
+    /// assert_eq!(parser.finish(), StreamEnd::Clean);
+    /// ```
+    pub fn finish(&mut self) -> StreamEnd {
+        let end = if self.is_in_json() {
+            StreamEnd::Truncated {
+                open_markers: self.depth(),
+                partial: self.buffer.clone(),
+            }
+        } else {
+            StreamEnd::Clean
+        };
+
+        self.reset();
+        end
+    }
+
+    /// Stops emitting the value currently being extracted.
+    ///
+    /// The parser keeps following the structure of the value so it knows
+    /// where the value ends, but writes nothing more of it. Output held back
+    /// for the value, such as a partial value kept between calls to
+    /// [`extract_values_from_stream`](Self::extract_values_from_stream), is
+    /// dropped, so value-based methods never report it. Writer-based methods
+    /// stream each character as it arrives, so whatever of the value was
+    /// written before this call stays written, unless
+    /// [`ParserOptions::atomic_values`] holds it back. Emission resumes with the
+    /// next value. Does nothing outside of a value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use surfing::JSONParser;
+    ///
+    /// let mut parser = JSONParser::new();
+    /// let mut values = Vec::new();
+    /// parser
+    ///     .extract_values_from_stream("[1] {\"echo\": \"my pro", |value| values.push(value.to_string()))
+    ///     .unwrap();
+    ///
+    /// parser.suppress_current_value();
+    /// parser
+    ///     .extract_values_from_stream("mpt\"} [2]", |value| values.push(value.to_string()))
+    ///     .unwrap();
+    ///
+    /// assert_eq!(values, vec!["[1]", "[2]"]);
+    /// ```
+    pub fn suppress_current_value(&mut self) {
+        if !self.is_in_json() {
+            return;
+        }
+
+        self.discarding = true;
+        self.value_output.clear();
+        self.prefix_held.clear();
+        self.prefix_pending = false;
+        #[cfg(feature = "serde")]
+        {
+            self.unwrap_held.clear();
+            self.validate_held.clear();
+        }
+        #[cfg(feature = "spill")]
+        self.spill.discard();
+    }
+
+    /// Suppresses the next value to start, so none of it is written.
+    ///
+    /// This is [`suppress_current_value`](Self::suppress_current_value)
+    /// decided before the value begins, for example when the text preceding
+    /// it shows that it is not wanted. The parser still follows the structure
+    /// of the value to find its end. Only that one value is suppressed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use surfing::JSONParser;
+    ///
+    /// let mut parser = JSONParser::new();
+    /// let mut buffer = Vec::new();
+    /// parser.extract_json_from_stream(&mut buffer, "Echo: ").unwrap();
+    ///
+    /// parser.suppress_next_value();
+    /// parser
+    ///     .extract_json_from_stream(&mut buffer, "{\"prompt\": [1]} Reply: {\"answer\": 2}")
+    ///     .unwrap();
+    ///
+    /// assert_eq!(String::from_utf8(buffer).unwrap(), "{\"answer\": 2}");
+    /// ```
+    pub fn suppress_next_value(&mut self) {
+        self.suppress_next = true;
+    }
+
     /// Removes the marker pair when a closing marker is found.
     ///
+    /// Only the innermost open marker can be closed, as JSON is strictly
+    /// nested. A closer of the other kind is left to
+    /// [`closes_mismatched_marker`](Self::closes_mismatched_marker).
+    ///
     /// # Arguments
     ///
     /// * `item` - The character to check as a potential closing marker.
     fn remove_markers_pair(&mut self, item: &char) {
-        // Create reversed markers for finding the ending marker
-        let mut markers_to_reverse: Vec<Marker> = self.markers.clone();
-        markers_to_reverse.reverse();
+        if !self
+            .markers
+            .last()
+            .is_some_and(|marker| marker.is_counter_part(item))
+        {
+            return;
+        }
 
-        // Look for a start marker
-        for marker in markers_to_reverse.iter() {
-            // If we find a start marker, we remove the marker from the buffer
-            if marker.is_counter_part(item) {
-                self.markers.pop();
-                return;
+        if let Some(closed) = self.markers.pop() {
+            if let Some(hook) = self.on_close.as_mut() {
+                hook(self.markers.len(), closed.kind());
             }
         }
     }
 
+    /// Checks if a character is a closer that does not match the innermost open marker.
+    ///
+    /// # Arguments
+    ///
+    /// * `item` - The character to check.
+    fn closes_mismatched_marker(&self, item: char) -> bool {
+        matches!(item, '}' | ']')
+            && self
+                .markers
+                .last()
+                .is_some_and(|marker| !marker.is_counter_part(&item))
+    }
+
     /// Updates the internal markers state based on the current character.
     ///
     /// # Arguments
@@ -115,189 +730,4676 @@ impl JSONParser {
         // Store the valid start marker.
         // We only check the end marker.
         if let Some(marker) = Marker::new(item) {
+            if let Some(hook) = self.on_open.as_mut() {
+                hook(self.markers.len(), marker.kind());
+            }
             self.markers.push(marker);
+            self.awaiting_content = self.options.validate_value_starts || self.options.strict;
             return;
         }
 
         self.remove_markers_pair(item);
 
-        // If we have no markers left, return
+        // If we have no markers left, the structure is complete
         if self.markers.is_empty() {
             self.buffer.clear();
-            return;
+            self.in_string = false;
+            self.escaped_value = false;
+            self.string_scanner.reset();
+            self.relaxed.reset();
         }
     }
 
-    /// Extracts JSON content from a string and writes it to the provided writer.
+    /// Updates the string tracking state based on the current character.
     ///
-    /// This method processes each character in the input string and:
-    /// - Extracts only valid JSON objects and arrays
-    /// - Ignores text outside of JSON structures
-    /// - Maintains state across multiple calls for incremental processing
+    /// # Arguments
+    ///
+    /// * `item` - The character to process.
+    fn update_string_state(&mut self, item: &char) {
+        if !self.in_string {
+            self.in_string = *item == '"';
+            self.string_scanner.reset();
+            return;
+        }
+
+        if self.string_scanner.push(*item) {
+            self.in_string = false;
+        }
+    }
+
+    /// Writes a character of a JSON structure, applying the output options.
     ///
     /// # Arguments
     ///
-    /// * `writer` - A mutable reference to an object implementing the `Write` trait.
-    /// * `json_object` - The string slice to process.
+    /// * `writer` - The writer receiving the extracted JSON.
+    /// * `item` - The character to write.
+    fn emit<W: Write>(&mut self, writer: &mut W, item: char) -> std::io::Result<()> {
+        let starts_escape = self.string_scanner.is_after_backslash() && item == '\\';
+        if self.reads_escapes() && (starts_escape || !self.pending_escape.is_empty()) {
+            return self.unescape(writer, item);
+        }
+
+        self.write_char(writer, item)
+    }
+
+    /// Checks if escape sequences must be collected to decode them or to find lone surrogates.
+    fn reads_escapes(&self) -> bool {
+        self.options.unescape_unicode
+            || self.options.lone_surrogate_policy != LoneSurrogatePolicy::Preserve
+    }
+
+    /// Collects an escape sequence and writes it once it can be decoded.
     ///
-    /// # Returns
+    /// # Arguments
     ///
-    /// * `Ok(())` - If processing completed successfully.
-    /// * `Err(Error)` - If there was an error writing to the writer.
+    /// * `writer` - The writer receiving the extracted JSON.
+    /// * `item` - The next character of the escape sequence.
+    fn unescape<W: Write>(&mut self, writer: &mut W, item: char) -> std::io::Result<()> {
+        self.pending_escape.push(item);
+
+        match unescape_sequence(&self.pending_escape) {
+            Unescaped::Incomplete => Ok(()),
+            Unescaped::Char(decoded) if self.options.unescape_unicode => {
+                self.pending_escape.clear();
+                self.write_char(writer, decoded)
+            }
+            Unescaped::Char(_) | Unescaped::Verbatim => {
+                let pending = std::mem::take(&mut self.pending_escape);
+                pending
+                    .chars()
+                    .try_for_each(|item| self.write_char(writer, item))
+            }
+            Unescaped::LoneSurrogate => {
+                let pending = std::mem::take(&mut self.pending_escape);
+                let (escape, rest) = pending.split_at(6);
+                match self.options.lone_surrogate_policy {
+                    LoneSurrogatePolicy::Preserve => writer.write_all(escape.as_bytes())?,
+                    LoneSurrogatePolicy::Replace if self.options.unescape_unicode => {
+                        self.write_char(writer, char::REPLACEMENT_CHARACTER)?
+                    }
+                    LoneSurrogatePolicy::Replace => writer.write_all(b"\\ufffd")?,
+                    LoneSurrogatePolicy::Reject => {
+                        // The rest of the value is followed without being written
+                        self.discarding = true;
+                        self.string_error.get_or_insert(ParseError::LoneSurrogate);
+                        return Ok(());
+                    }
+                }
+
+                // The rest may start a new escape sequence of its own
+                for item in rest.chars() {
+                    if item == '\\' || !self.pending_escape.is_empty() {
+                        self.unescape(writer, item)?;
+                    } else {
+                        self.write_char(writer, item)?;
+                    }
+                }
+
+                Ok(())
+            }
+        }
+    }
+
+    /// Writes a single character, escaping it if required by the output options.
     ///
-    /// # Examples
+    /// # Arguments
+    ///
+    /// * `writer` - The writer receiving the extracted JSON.
+    /// * `item` - The character to write.
+    fn write_char<W: Write>(&self, writer: &mut W, item: char) -> std::io::Result<()> {
+        if self.options.ascii_only && self.in_string && !item.is_ascii() {
+            let mut units = [0u16; 2];
+            for unit in item.encode_utf16(&mut units) {
+                write!(writer, "\\u{:04x}", unit)?;
+            }
+            return Ok(());
+        }
+
+        write!(writer, "{}", item)
+    }
+
+    /// Processes a single character, writing it to the writer if it belongs to JSON.
+    ///
+    /// # Arguments
+    ///
+    /// * `writer` - The writer receiving the extracted JSON.
+    /// * `item` - The character to process.
+    fn process_char<W: Write>(&mut self, writer: &mut W, item: char) -> std::io::Result<()> {
+        self.abandoned = false;
+        self.percent_decoded = false;
+        self.scalar_completed = false;
+
+        if self.on_value_prefix.is_some() && (self.prefix_pending || !self.is_in_json()) {
+            return self.process_prefix_candidate(writer, item);
+        }
+
+        self.process_char_unfiltered(writer, item)
+    }
+
+    /// Processes a character of a value whose prefix is held until it is decided on.
+    ///
+    /// # Arguments
+    ///
+    /// * `writer` - The writer receiving the extracted JSON.
+    /// * `item` - The character to process.
+    fn process_prefix_candidate<W: Write>(
+        &mut self,
+        writer: &mut W,
+        item: char,
+    ) -> std::io::Result<()> {
+        let was_in_json = self.is_in_json();
+        let mut held = std::mem::take(&mut self.prefix_held);
+        let result = self.process_char_unfiltered(&mut held, item);
+
+        let max_bytes = self.on_value_prefix.as_ref().map_or(0, |(max, _)| *max);
+        let ended = self.value_ended(was_in_json);
+        if !was_in_json && !ended && !self.is_in_json() {
+            // Anything written outside of a value goes out now
+            writer.write_all(&held)?;
+        } else if self.is_in_json() && !self.discarding && held.len() < max_bytes {
+            self.prefix_pending = true;
+            self.prefix_held = held;
+            return result;
+        } else if !self.abandoned && !self.discarding && self.decide_prefix(&held) {
+            writer.write_all(&held)?;
+        } else if self.is_in_json() {
+            self.discarding = true;
+        } else {
+            self.abandoned = true;
+        }
+
+        held.clear();
+        self.prefix_held = held;
+        self.prefix_pending = false;
+        result
+    }
+
+    /// Asks the prefix callback whether to keep the value.
+    ///
+    /// # Arguments
+    ///
+    /// * `prefix` - The held output of the value.
+    fn decide_prefix(&mut self, prefix: &[u8]) -> bool {
+        let Some((_, hook)) = self.on_value_prefix.as_mut() else {
+            return true;
+        };
+
+        hook(&String::from_utf8_lossy(prefix)) == ValueDecision::Keep
+    }
+
+    /// Processes a single character once any prefix decision is made.
+    ///
+    /// # Arguments
+    ///
+    /// * `writer` - The writer receiving the extracted JSON.
+    /// * `item` - The character to process.
+    fn process_char_unfiltered<W: Write>(
+        &mut self,
+        writer: &mut W,
+        item: char,
+    ) -> std::io::Result<()> {
+        #[cfg(feature = "serde")]
+        if self.options.auto_unwrap_json_string && !self.unwrap_checked {
+            return self.process_unwrap_candidate(writer, item);
+        }
+
+        self.process_char_validated(writer, item)
+    }
+
+    /// Processes a single character, holding each value for validation if that is enabled.
+    ///
+    /// # Arguments
+    ///
+    /// * `writer` - The writer receiving the extracted JSON.
+    /// * `item` - The character to process.
+    fn process_char_validated<W: Write>(
+        &mut self,
+        writer: &mut W,
+        item: char,
+    ) -> std::io::Result<()> {
+        #[cfg(feature = "serde")]
+        if self.options.validate_values {
+            return self.process_validation_candidate(writer, item);
+        }
+
+        self.process_char_limited(writer, item)
+    }
+
+    /// Processes a single character, applying the output limit if one is set.
+    ///
+    /// # Arguments
+    ///
+    /// * `writer` - The writer receiving the extracted JSON.
+    /// * `item` - The character to process.
+    fn process_char_limited<W: Write>(
+        &mut self,
+        writer: &mut W,
+        item: char,
+    ) -> std::io::Result<()> {
+        if self.options.max_output_bytes.is_some() {
+            return self.limit_output(writer, |parser, output| {
+                parser.process_char_unlimited(output, item)
+            });
+        }
+
+        self.process_char_unlimited(writer, item)
+    }
+
+    /// Processes a character of the first value, which may be a double-encoded JSON string.
+    ///
+    /// The first value is held until it completes and is then written
+    /// unwrapped if it turns out to be the content of a JSON string that
+    /// holds JSON itself.
+    ///
+    /// # Arguments
+    ///
+    /// * `writer` - The writer receiving the extracted JSON.
+    /// * `item` - The character to process.
+    #[cfg(feature = "serde")]
+    fn process_unwrap_candidate<W: Write>(
+        &mut self,
+        writer: &mut W,
+        item: char,
+    ) -> std::io::Result<()> {
+        let was_in_json = self.is_in_json();
+        let mut held = std::mem::take(&mut self.unwrap_held);
+        let result = self.process_char_validated(&mut held, item);
+
+        if !self.is_in_json() {
+            if was_in_json && self.abandoned {
+                held.clear();
+            } else if was_in_json {
+                self.unwrap_checked = true;
+                if let Some(unwrapped) = unwrap_json_string(&held) {
+                    held = unwrapped;
+                }
+            }
+
+            // Completed values and anything written outside of one go out now
+            writer.write_all(&held)?;
+            held.clear();
+        }
+
+        self.unwrap_held = held;
+        result
+    }
+
+    /// Processes a character of a value that is held until `serde_json` accepts it.
+    ///
+    /// A completed value that does not parse is dropped as if it had been
+    /// abandoned at its last character.
+    ///
+    /// # Arguments
+    ///
+    /// * `writer` - The writer receiving the extracted JSON.
+    /// * `item` - The character to process.
+    #[cfg(feature = "serde")]
+    fn process_validation_candidate<W: Write>(
+        &mut self,
+        writer: &mut W,
+        item: char,
+    ) -> std::io::Result<()> {
+        let was_in_json = self.is_in_json();
+        let mut held = std::mem::take(&mut self.validate_held);
+        let result = self.process_char_limited(&mut held, item);
+
+        if self.abandoned {
+            // A value the dropped one gave way to has written nothing yet
+            held.clear();
+        } else if self.value_ended(was_in_json) {
+            if is_serde_json(&held) {
+                writer.write_all(&held)?;
+            } else {
+                self.abandoned = true;
+            }
+            held.clear();
+        } else if !self.is_in_json() {
+            // Anything written outside of a value goes out now
+            writer.write_all(&held)?;
+            held.clear();
+        }
+
+        self.validate_held = held;
+        result
+    }
+
+    /// Abandons the value being extracted at a record separator or a mismatched closer.
+    ///
+    /// Nothing more is written: the value is left unclosed and the parser
+    /// starts afresh with the next character.
+    fn abandon_value(&mut self) {
+        if !self.is_in_json() {
+            return;
+        }
+
+        self.markers.clear();
+        self.buffer.clear();
+        self.in_string = false;
+        self.escaped_value = false;
+        self.string_scanner.reset();
+        self.smart_string = false;
+        self.awaiting_content = false;
+        self.start_pending.clear();
+        self.grammar.reset();
+        self.overflow_depth = 0;
+        self.string_bytes = 0;
+        self.discarding = false;
+        self.pending_escape.clear();
+        self.relaxed.reset();
+        self.abandoned = true;
+    }
+
+    /// Holds text of the value in progress, abandoning the value past [`ParserOptions::max_buffer_size`].
+    ///
+    /// Past [`ParserOptions::max_object_bytes`], the value is discarded
+    /// instead, and text is no longer held while it is. The error is
+    /// recorded for the extraction call to return.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The input text belonging to the value.
+    ///
+    /// # Returns
+    ///
+    /// `false` if the text would exceed the limit and was dropped.
+    fn buffer_text(&mut self, text: &str) -> bool {
+        if let Some(limit) = self.options.max_buffer_size {
+            if self.buffer.len() + text.len() > limit {
+                self.string_error
+                    .get_or_insert(ParseError::BufferLimitExceeded { limit });
+                self.abandon_value();
+                return false;
+            }
+        }
+        if let Some(limit) = self.options.max_object_bytes {
+            if self.buffer.len() + text.len() > limit && !self.discarding {
+                self.string_error
+                    .get_or_insert(ParseError::ObjectTooLarge { limit });
+                if self.is_in_json() {
+                    self.suppress_current_value();
+                } else {
+                    // The opening marker is the text, so the value it starts is suppressed
+                    self.suppress_next = true;
+                }
+            }
+        }
+
+        // A discarded value is only followed to its end, so nothing of it is held
+        if !self.discarding {
+            self.buffer.push_str(text);
+        }
+        true
+    }
+
+    /// Processes a single character without applying the output limit.
+    ///
+    /// # Arguments
+    ///
+    /// * `writer` - The writer receiving the extracted JSON.
+    /// * `item` - The character to process.
+    fn process_char_unlimited<W: Write>(
+        &mut self,
+        writer: &mut W,
+        item: char,
+    ) -> std::io::Result<()> {
+        if self.options.strip_interior_bom && item == BYTE_ORDER_MARK && !self.in_string {
+            return Ok(());
+        }
+
+        let ends_record = !self.in_string
+            && ((self.options.ndjson && item == '\n')
+                || Some(item) == self.options.record_separator);
+        if ends_record {
+            self.abandon_value();
+            self.in_split_array = false;
+            self.scalar.reset();
+            return Ok(());
+        }
+
+        if item == '\0' && self.in_string && self.is_in_json() {
+            match self.nul_policy() {
+                NulPolicy::Preserve => {}
+                NulPolicy::Strip => return Ok(()),
+                NulPolicy::Error => return Err(ParseError::NulInString.into()),
+            }
+        }
+
+        if self.options.normalize_smart_quotes && self.is_in_json() {
+            if let Some(straight) = straighten_quote(item) {
+                return self.process_straightened_quote(writer, straight);
+            }
+            if item == '"' && !self.in_string {
+                self.smart_string = false;
+            }
+        }
+
+        if self.options.extract_scalars && !self.is_in_json() && !self.in_split_array {
+            match self.scalar.push(item) {
+                ScalarStep::Ignored => {}
+                ScalarStep::Pending => return Ok(()),
+                ScalarStep::Completed(json) => {
+                    self.scalar_completed = true;
+                    return writer.write_all(json.as_bytes());
+                }
+                ScalarStep::Ended(json) => {
+                    // The character ending the scalar cannot start a value, but is processed as usual
+                    self.scalar_completed = true;
+                    writer.write_all(json.as_bytes())?;
+                }
+            }
+        }
+
+        if self.options.percent_decode_candidates && !self.is_in_json() {
+            match self.percent.push(item) {
+                PercentStep::Ignored => {}
+                PercentStep::Pending => return Ok(()),
+                PercentStep::Decoded(json) => {
+                    self.percent_decoded = true;
+                    return json
+                        .chars()
+                        .try_for_each(|item| self.process_char_unlimited(writer, item));
+                }
+            }
+        }
+
+        if self.is_in_json() {
+            // Configured whitespace JSON does not accept is written as a space
+            let item = if !self.in_string && self.options.is_extra_whitespace(item) {
+                ' '
+            } else {
+                item
+            };
+            if !self.buffer_text(item.encode_utf8(&mut [0; 4])) {
+                return Ok(());
+            }
+
+            if !self.options.relaxed {
+                return self.process_json_char(writer, item);
+            }
+
+            let mut translated = std::mem::take(&mut self.relaxed_output);
+            self.relaxed.translate(item, &mut translated);
+            // A value dropped part way stops taking the rest of the translation
+            let result = translated.chars().try_for_each(|item| {
+                if !self.is_in_json() {
+                    return Ok(());
+                }
+                self.process_json_char(writer, item)
+            });
+
+            translated.clear();
+            self.relaxed_output = translated;
+            return result;
+        }
+
+        if self.in_split_array && !self.process_split_array_char(item) {
+            return Ok(());
+        }
+
+        if item == '[' && self.options.split_top_level_arrays && !self.in_split_array {
+            self.in_split_array = true;
+            return Ok(());
+        }
+
+        // Closers outside of a value have nothing to close and are dropped
+        if MARKERS.contains(&item) {
+            if self.exceeds_depth(item) {
+                return Ok(());
+            }
+            if !self.buffer_text(item.encode_utf8(&mut [0; 4])) {
+                return Ok(());
+            }
+            if self.options.strict {
+                self.grammar.reset();
+                self.grammar.push(item);
+            }
+            self.update_markers(&item);
+            if self.is_in_json() && std::mem::take(&mut self.suppress_next) {
+                self.discarding = true;
+                return Ok(());
+            }
+            if self.awaiting_content {
+                self.start_pending.push(item);
+                return Ok(());
+            }
+            write!(writer, "{}", item)?;
+        }
+
+        Ok(())
+    }
+
+    /// Processes a character of a split top-level array between its elements.
+    ///
+    /// Commas, whitespace and scalar elements are dropped, and the closing
+    /// bracket ends the array. Strings are followed so that brackets inside
+    /// them are not taken for elements.
+    ///
+    /// # Arguments
+    ///
+    /// * `item` - The character to process.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the character opens an element and is to be processed as
+    /// the start of a value.
+    fn process_split_array_char(&mut self, item: char) -> bool {
+        if self.in_string || item == '"' {
+            self.update_string_state(&item);
+            return false;
+        }
+
+        match item {
+            '{' | '[' => true,
+            ']' => {
+                self.in_split_array = false;
+                false
+            }
+            _ => false,
+        }
+    }
+
+    /// Runs `process` against the held output and commits it within the output limit.
+    ///
+    /// Output is held back while an escape sequence is incomplete, so the
+    /// committed output always ends on a point where it can be closed.
+    ///
+    /// # Arguments
+    ///
+    /// * `writer` - The writer receiving the extracted JSON.
+    /// * `process` - The processing step producing output.
+    fn limit_output<W, F>(&mut self, writer: &mut W, process: F) -> std::io::Result<()>
+    where
+        W: Write,
+        F: FnOnce(&mut Self, &mut Vec<u8>) -> std::io::Result<()>,
+    {
+        let mut held = std::mem::take(self.limit.held());
+        let result = process(self, &mut held);
+        *self.limit.held() = held;
+        result?;
+
+        if self.string_scanner.is_in_escape() || !self.pending_escape.is_empty() {
+            return Ok(());
+        }
+
+        let limit = self.options.max_output_bytes.unwrap_or(usize::MAX);
+        self.limit.commit(
+            writer,
+            limit,
+            self.options.truncation_policy,
+            &self.markers,
+            self.in_string,
+        )
+    }
+
+    /// Returns the number of output bytes suppressed by the output limit.
+    ///
+    /// # Returns
+    ///
+    /// The number of bytes that were not written because of
+    /// [`ParserOptions::max_output_bytes`], or `0` if nothing was suppressed.
+    ///
+    /// # Examples
     ///
     /// ```
-    /// use std::io::{BufWriter, stdout, StdoutLock};
-    /// use surfing::parser::json_parser::JSONParser;
+    /// use surfing::parser::options::ParserOptions;
+    /// use surfing::JSONParser;
     ///
-    /// // Example with BufWriter
-    /// let mut parser = JSONParser::new();
+    /// let mut parser = JSONParser::with_options(ParserOptions::new().max_output_bytes(4));
     /// let mut buffer = Vec::new();
-    /// {
-    ///     let mut writer = BufWriter::new(&mut buffer);
-    ///     parser.extract_json_from_stream(&mut writer, "Text {\"id\": 123}").unwrap();
-    /// }
+    /// parser.extract_json_from_stream(&mut buffer, "[1, 2] [3]").unwrap();
     ///
-    /// // Example with StdoutLock
-    /// let mut parser = JSONParser::new();
-    /// let stdout = stdout();
-    /// {
-    ///     let mut handle = stdout.lock();
-    ///     // In a real program, you would handle the result
-    ///     let _ = parser.extract_json_from_stream(&mut handle, "{\"key\": \"value\"}");
-    /// }
+    /// assert_eq!(parser.suppressed_bytes(), 5);
     /// ```
-    pub fn extract_json_from_stream<W: Write>(
+    pub fn suppressed_bytes(&self) -> usize {
+        self.limit.suppressed()
+    }
+
+    /// Reports whether the output limit suppressed any output.
+    ///
+    /// The parser keeps consuming input after reaching
+    /// [`ParserOptions::max_output_bytes`], so extraction calls succeed; this
+    /// method turns the truncation into an error once the caller is done.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If every byte of output was written.
+    /// * `Err(ParseError::OutputTruncated)` - If output was suppressed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use surfing::parser::error::ParseError;
+    /// use surfing::parser::options::ParserOptions;
+    /// use surfing::JSONParser;
+    ///
+    /// let mut parser = JSONParser::with_options(ParserOptions::new().max_output_bytes(2));
+    /// let mut buffer = Vec::new();
+    /// parser.extract_json_from_stream(&mut buffer, "{\"a\":1}").unwrap();
+    ///
+    /// assert!(matches!(
+    ///     parser.check_output_limit(),
+    ///     Err(ParseError::OutputTruncated { suppressed: 5 })
+    /// ));
+    /// ```
+    pub fn check_output_limit(&self) -> Result<(), ParseError> {
+        match self.limit.suppressed() {
+            0 => Ok(()),
+            suppressed => Err(ParseError::OutputTruncated { suppressed }),
+        }
+    }
+
+    /// Returns the NUL policy in effect, which defaults on the strictness of the parser.
+    fn nul_policy(&self) -> NulPolicy {
+        match self.options.nul_policy {
+            Some(policy) => policy,
+            None if self.options.strict => NulPolicy::Error,
+            None => NulPolicy::Preserve,
+        }
+    }
+
+    /// Processes a typographic quote after converting it to a straight quote.
+    ///
+    /// A typographic double quote closes a string only if one opened it.
+    /// Inside a string opened by a straight quote it is content and is
+    /// written escaped.
+    ///
+    /// # Arguments
+    ///
+    /// * `writer` - The writer receiving the extracted JSON.
+    /// * `straight` - The straight quote replacing the typographic one.
+    fn process_straightened_quote<W: Write>(
         &mut self,
         writer: &mut W,
-        json_object: &str,
-    ) -> Result<(), Box<dyn std::error::Error>> {
-        for item in json_object.chars() {
-            if self.is_in_json() {
-                self.buffer.push(item);
-                self.update_markers(&item);
-                write!(writer, "{}", item)?;
-                continue;
+        straight: char,
+    ) -> std::io::Result<()> {
+        if straight == '"' && self.in_string && !self.smart_string {
+            self.process_char_unlimited(writer, '\\')?;
+            return self.process_char_unlimited(writer, '"');
+        }
+
+        let opens_string = !self.in_string;
+        self.process_char_unlimited(writer, straight)?;
+        if straight == '"' {
+            self.smart_string = opens_string;
+        }
+
+        Ok(())
+    }
+
+    /// Processes a character that belongs to the current JSON structure.
+    ///
+    /// # Arguments
+    ///
+    /// * `writer` - The writer receiving the extracted JSON.
+    /// * `item` - The character to process.
+    fn process_json_char<W: Write>(&mut self, writer: &mut W, item: char) -> std::io::Result<()> {
+        let was_in_string = self.in_string;
+        if self.awaiting_content && !was_in_string && !self.check_value_start(writer, item)? {
+            return Ok(());
+        }
+        if !was_in_string && self.follow_overflow(item) {
+            return Ok(());
+        }
+        // The grammar of structures past the depth limit is not followed
+        if self.options.strict && self.overflow_depth == 0 && !self.grammar.push(item) {
+            self.abandon_value();
+            // The character breaking the grammar may start a value of its own
+            if MARKERS.contains(&item) {
+                return self.process_char_unlimited(writer, item);
             }
+            return Ok(());
+        }
+
+        // A closer of the wrong kind makes the value malformed, so it is dropped
+        if !was_in_string && self.closes_mismatched_marker(item) {
+            self.abandon_value();
+            return Ok(());
+        }
+
+        // A backslash outside of a string means the value is the escaped
+        // content of a string, such as a double-encoded value, whose quotes
+        // are escaped too, so its strings cannot be told apart
+        if item == '\\' && !was_in_string {
+            self.escaped_value = true;
+        }
+        if !self.escaped_value {
+            self.update_string_state(&item);
+        }
+        self.track_string_length(was_in_string, item);
+        self.emit_json_char(writer, was_in_string, item)?;
+
+        // Braces and brackets inside strings are content, not structure
+        if !was_in_string {
+            self.update_markers(&item);
+        }
+        self.finish_discarding();
+        Ok(())
+    }
+
+    /// Checks the first character of a structure under [`ParserOptions::validate_value_starts`].
+    ///
+    /// Whitespace after the opening marker of a top-level value is held back
+    /// with the marker until the check is made, and written once it passes.
+    ///
+    /// # Arguments
+    ///
+    /// * `writer` - The writer receiving the extracted JSON.
+    /// * `item` - The character following the opening marker.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the character is to be processed as part of the value.
+    fn check_value_start<W: Write>(&mut self, writer: &mut W, item: char) -> std::io::Result<bool> {
+        let top_level = self.markers.len() == 1;
+        if is_json_whitespace(item) {
+            if top_level && !self.discarding {
+                self.start_pending.push(item);
+            }
+            return Ok(!top_level);
+        }
+
+        self.awaiting_content = false;
+        if self
+            .markers
+            .last()
+            .is_some_and(|marker| starts_content(marker.kind(), item))
+        {
+            if !self.discarding {
+                writer.write_all(self.start_pending.as_bytes())?;
+            }
+            self.start_pending.clear();
+            return Ok(true);
+        }
+
+        self.abandon_value();
+        // Nothing of a top-level value was written, so the character may start one of its own
+        if top_level && MARKERS.contains(&item) {
+            self.process_char_unlimited(writer, item)?;
+        }
+        Ok(false)
+    }
+
+    /// Checks if a character opens a structure deeper than [`ParserOptions::max_depth`].
+    ///
+    /// The error is recorded for the extraction call to return.
+    ///
+    /// # Arguments
+    ///
+    /// * `item` - The character to check.
+    fn exceeds_depth(&mut self, item: char) -> bool {
+        let Some(limit) = self.options.max_depth else {
+            return false;
+        };
+        if !MARKERS.contains(&item) || self.markers.len() < limit {
+            return false;
+        }
+
+        self.string_error
+            .get_or_insert(ParseError::DepthLimitExceeded { limit });
+        true
+    }
+
+    /// Follows the markers of structures past [`ParserOptions::max_depth`] without tracking them.
+    ///
+    /// The first structure past the limit starts discarding the value. Its
+    /// markers and those nested in it are only counted, so memory stays
+    /// bounded however deep the input goes, and closers past the limit are
+    /// not checked against their openers.
+    ///
+    /// # Arguments
+    ///
+    /// * `item` - A character outside of any string.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the character was a marker past the limit.
+    fn follow_overflow(&mut self, item: char) -> bool {
+        if self.overflow_depth > 0 {
+            match item {
+                '{' | '[' => self.overflow_depth += 1,
+                '}' | ']' => self.overflow_depth -= 1,
+                _ => return false,
+            }
+            return true;
+        }
+
+        if !self.exceeds_depth(item) {
+            return false;
+        }
+        self.overflow_depth = 1;
+        self.discarding = true;
+        true
+    }
+
+    /// Counts the bytes of the current string and starts discarding its value past the limit.
+    ///
+    /// # Arguments
+    ///
+    /// * `was_in_string` - Whether the parser was inside a string before `item`.
+    /// * `item` - The character just processed.
+    fn track_string_length(&mut self, was_in_string: bool, item: char) {
+        let Some(limit) = self.options.max_string_bytes else {
+            return;
+        };
+
+        // Quotes delimit the string and are not counted
+        if !was_in_string || !self.in_string {
+            self.string_bytes = 0;
+            return;
+        }
+
+        self.string_bytes += item.len_utf8();
+        if self.string_bytes > limit && !self.discarding {
+            self.discarding = true;
+            self.string_error.get_or_insert(ParseError::StringTooLong);
+        }
+    }
+
+    /// Writes a character of the current value, applying the control character policy.
+    ///
+    /// # Arguments
+    ///
+    /// * `writer` - The writer receiving the extracted JSON.
+    /// * `was_in_string` - Whether the parser was inside a string before `item`.
+    /// * `item` - The character to write.
+    fn emit_json_char<W: Write>(
+        &mut self,
+        writer: &mut W,
+        was_in_string: bool,
+        item: char,
+    ) -> std::io::Result<()> {
+        // NUL characters are left to the NUL policy
+        if was_in_string && self.in_string && matches!(item, '\u{1}'..='\u{1F}') {
+            match self.options.control_char_policy {
+                ControlCharPolicy::Preserve => {}
+                ControlCharPolicy::Reject if !self.discarding => {
+                    self.discarding = true;
+                    self.string_error
+                        .get_or_insert(ParseError::ControlCharInString);
+                }
+                ControlCharPolicy::Reject => {}
+                ControlCharPolicy::Escape if !self.discarding => {
+                    return writer.write_all(escape_control_char(item).as_bytes());
+                }
+                ControlCharPolicy::Escape => {}
+            }
+        }
+
+        if self.discarding {
+            self.emit(&mut std::io::sink(), item)
+        } else {
+            self.emit(writer, item)
+        }
+    }
+
+    /// Ends discarding once a suppressed or rejected value is closed.
+    fn finish_discarding(&mut self) {
+        if self.discarding && !self.is_in_json() {
+            self.discarding = false;
+            self.abandoned = true;
+        }
+    }
+
+    /// Returns the error for a value abandoned since the last call, if any.
+    pub(crate) fn take_string_error(&mut self) -> Result<(), ParseError> {
+        match self.string_error.take() {
+            Some(error) => Err(error),
+            None => Ok(()),
+        }
+    }
+
+    /// Feeds a single character, reporting what became of it.
+    ///
+    /// This is the character-at-a-time form of
+    /// [`extract_json_from_stream`](Self::extract_json_from_stream), for callers
+    /// producing characters one by one who would otherwise build strings just
+    /// to hand them over. Both share the same processing, so feeding the
+    /// characters of a text writes exactly what extracting the text does. The
+    /// returned [`CharOutcome`] gives a completion signal without callbacks.
+    ///
+    /// # Arguments
+    ///
+    /// * `item` - The character to process.
+    /// * `writer` - A mutable reference to an object implementing the `Write` trait.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(CharOutcome)` - What the parser did with the character.
+    /// * `Err(ParseError)` - If writing failed, or the character made a string
+    ///   exceed [`ParserOptions::max_string_bytes`]. The parser can still be fed
+    ///   after an error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use surfing::parser::outcome::CharOutcome;
+    /// use surfing::JSONParser;
+    ///
+    /// let mut parser = JSONParser::new();
+    /// let mut buffer = Vec::new();
+    /// let outcomes: Vec<CharOutcome> = "a[1]"
+    ///     .chars()
+    ///     .map(|item| parser.push_char(item, &mut buffer).unwrap())
+    ///     .collect();
+    ///
+    /// assert_eq!(
+    ///     outcomes,
+    ///     vec![
+    ///         CharOutcome::Skipped,
+    ///         CharOutcome::Started,
+    ///         CharOutcome::Emitted,
+    ///         CharOutcome::Completed,
+    ///     ]
+    /// );
+    /// assert_eq!(String::from_utf8(buffer).unwrap(), "[1]");
+    /// ```
+    pub fn push_char<W: Write>(
+        &mut self,
+        item: char,
+        writer: &mut W,
+    ) -> Result<CharOutcome, ParseError> {
+        let outcome = self.with_held_output(writer, |parser, writer, output| {
+            parser.step_held(writer, output, item)
+        })?;
+        self.take_string_error()?;
+        Ok(outcome)
+    }
+
+    /// Checks if the last character ended a value, complete or abandoned.
+    ///
+    /// # Arguments
+    ///
+    /// * `was_in_json` - Whether the parser was inside a value before the character.
+    fn value_ended(&self, was_in_json: bool) -> bool {
+        (was_in_json && !self.is_in_json()) || self.percent_decoded || self.scalar_completed
+    }
+
+    /// Returns the number of complete values written since the parser was created or reset.
+    pub(crate) fn values_written(&self) -> usize {
+        self.values_written
+    }
+
+    /// Counts a value written in full, flushing the writer under [`ParserOptions::flush_per_value`].
+    ///
+    /// # Arguments
+    ///
+    /// * `writer` - The writer that received the value.
+    fn finish_written_value<W: Write>(&mut self, writer: &mut W) -> std::io::Result<()> {
+        // Past the output limit a value is completed but not written
+        if self.limit.suppressed() == 0 {
+            self.values_written += 1;
+        }
+        if self.options.flush_per_value {
+            writer.flush()?;
+        }
+        Ok(())
+    }
+
+    /// Processes a single character and classifies what became of it.
+    ///
+    /// # Arguments
+    ///
+    /// * `writer` - The writer receiving the extracted JSON.
+    /// * `item` - The character to process.
+    fn step<W: Write>(&mut self, writer: &mut W, item: char) -> std::io::Result<CharOutcome> {
+        let was_in_json = self.is_in_json();
+        let mut counted = CountingWriter::new(writer);
+        self.process_char(&mut counted, item)?;
+
+        let outcome = if self.value_ended(was_in_json) && !self.abandoned {
+            CharOutcome::Completed
+        } else if self.is_in_json() && (!was_in_json || self.abandoned) {
+            // A value dropped at its start may give way to one started by the same character
+            CharOutcome::Started
+        } else if counted.written() > 0 {
+            CharOutcome::Emitted
+        } else {
+            CharOutcome::Skipped
+        };
+
+        Ok(outcome)
+    }
+
+    /// Processes a single character, holding the output of the value in progress.
+    ///
+    /// # Arguments
+    ///
+    /// * `writer` - The writer receiving the extracted JSON.
+    /// * `output` - The held output of the value in progress.
+    /// * `item` - The character to process.
+    fn step_held<W: Write>(
+        &mut self,
+        writer: &mut W,
+        output: &mut Vec<u8>,
+        item: char,
+    ) -> std::io::Result<CharOutcome> {
+        let outcome = self.step(output, item)?;
+        if outcome == CharOutcome::Completed && self.scalar_completed {
+            // Scalars have no closing marker, so written ones would run together
+            output.push(b'\n');
+        }
+        self.deliver_output(writer, output, outcome == CharOutcome::Completed)?;
+        Ok(outcome)
+    }
+
+    /// Writes held output once its value completes, and drops it if the value is abandoned.
+    ///
+    /// # Arguments
+    ///
+    /// * `writer` - The writer receiving the extracted JSON.
+    /// * `output` - The held output of the value in progress.
+    /// * `completed` - Whether the last input completed a value.
+    fn deliver_output<W: Write>(
+        &mut self,
+        writer: &mut W,
+        output: &mut Vec<u8>,
+        completed: bool,
+    ) -> std::io::Result<()> {
+        if self.abandoned {
+            // A value the dropped one gave way to has written nothing yet
+            output.clear();
+            return Ok(());
+        }
+        if !completed && self.is_in_json() {
+            return Ok(());
+        }
+
+        writer.write_all(output)?;
+        output.clear();
+        if completed {
+            self.finish_written_value(writer)?;
+        }
+        Ok(())
+    }
+
+    /// Runs `process` against the held output of the value in progress.
+    ///
+    /// Unless values are held until they complete, the output of a value
+    /// still in progress is written once `process` returns, so values keep
+    /// streaming across calls.
+    ///
+    /// # Arguments
+    ///
+    /// * `writer` - The writer receiving the extracted JSON.
+    /// * `process` - The processing step producing output.
+    fn with_held_output<W, F, R>(&mut self, writer: &mut W, process: F) -> std::io::Result<R>
+    where
+        W: Write,
+        F: FnOnce(&mut Self, &mut W, &mut Vec<u8>) -> std::io::Result<R>,
+    {
+        let mut output = std::mem::take(&mut self.value_output);
+        let mut result = process(self, writer, &mut output);
+        if result.is_ok() && !self.holds_values() {
+            if let Err(e) = writer.write_all(&output) {
+                result = Err(e);
+            }
+            output.clear();
+        }
+
+        self.value_output = output;
+        result
+    }
+
+    /// Checks if the output of a value is held until the value completes.
+    ///
+    /// This is the case under [`ParserOptions::atomic_values`], and under
+    /// options that may drop a value part way, so that nothing of a dropped
+    /// value is written.
+    fn holds_values(&self) -> bool {
+        self.options.atomic_values || self.options.drops_values()
+    }
+
+    /// Extracts JSON content from a string and writes it to the provided writer.
+    ///
+    /// This method processes each character in the input string and:
+    /// - Extracts only valid JSON objects and arrays
+    /// - Ignores text outside of JSON structures
+    /// - Maintains state across multiple calls for incremental processing
+    ///
+    /// # Arguments
+    ///
+    /// * `writer` - A mutable reference to an object implementing the `Write` trait.
+    /// * `json_object` - The string slice to process.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If processing completed successfully.
+    /// * `Err(ParseError)` - If there was an error writing to the writer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::{BufWriter, stdout, StdoutLock};
+    /// use surfing::parser::json_parser::JSONParser;
+    ///
+    /// // Example with BufWriter
+    /// let mut parser = JSONParser::new();
+    /// let mut buffer = Vec::new();
+    /// {
+    ///     let mut writer = BufWriter::new(&mut buffer);
+    ///     parser.extract_json_from_stream(&mut writer, "Text {\"id\": 123}").unwrap();
+    /// }
+    ///
+    /// // Example with StdoutLock
+    /// let mut parser = JSONParser::new();
+    /// let stdout = stdout();
+    /// {
+    ///     let mut handle = stdout.lock();
+    ///     // In a real program, you would handle the result
+    ///     let _ = parser.extract_json_from_stream(&mut handle, "{\"key\": \"value\"}");
+    /// }
+    /// ```
+    pub fn extract_json_from_stream<W: Write>(
+        &mut self,
+        writer: &mut W,
+        json_object: &str,
+    ) -> Result<(), ParseError> {
+        self.extract_json_from_chunk(writer, json_object)?;
+        self.take_string_error()
+    }
+
+    /// Extracts JSON content from the bytes of a buffer, returning how many bytes were consumed.
+    ///
+    /// This is meant for ring buffers and other fixed-size read buffers: the
+    /// caller advances its read position by the returned count and calls
+    /// again once more data is available. A trailing partial UTF-8 code point
+    /// is not consumed, so it is retried together with the bytes completing
+    /// it. Invalid UTF-8 sequences are consumed and processed as U+FFFD, which
+    /// keeps the read position moving.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The bytes available for reading.
+    /// * `writer` - A mutable reference to an object implementing the `Write` trait.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(usize)` - The number of bytes consumed from `data`.
+    /// * `Err(ParseError)` - If there was an error writing to the writer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use surfing::JSONParser;
+    ///
+    /// let mut parser = JSONParser::new();
+    /// let mut buffer = Vec::new();
+    ///
+    /// // The last byte is the first half of "é"
+    /// let data = "{\"name\":\"Jos\u{e9}\"}".as_bytes();
+    /// let consumed = parser.consume_from(&data[..13], &mut buffer).unwrap();
+    /// assert_eq!(consumed, 12);
+    ///
+    /// let consumed = parser.consume_from(&data[12..], &mut buffer).unwrap();
+    /// assert_eq!(consumed, 4);
+    /// assert_eq!(String::from_utf8(buffer).unwrap(), "{\"name\":\"José\"}");
+    /// ```
+    pub fn consume_from<W: Write>(
+        &mut self,
+        data: &[u8],
+        writer: &mut W,
+    ) -> Result<usize, ParseError> {
+        let consumed = self.consume_block(data, writer)?;
+        self.take_string_error()?;
+        Ok(consumed)
+    }
+
+    /// Extracts JSON content from raw bytes and writes it to the provided writer.
+    ///
+    /// This is the byte form of
+    /// [`extract_json_from_stream`](Self::extract_json_from_stream), for
+    /// callers reading sockets or files who would otherwise validate UTF-8
+    /// themselves. A chunk may end in the middle of a character: its bytes
+    /// are held by the parser and processed with the start of the next
+    /// chunk. Invalid UTF-8 sequences are processed as U+FFFD. Unlike with
+    /// [`consume_from`](Self::consume_from), every byte is taken, so the
+    /// caller does not keep track of a read position.
+    ///
+    /// # Arguments
+    ///
+    /// * `writer` - A mutable reference to an object implementing the `Write` trait.
+    /// * `bytes` - The bytes to process.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If processing completed successfully.
+    /// * `Err(ParseError)` - If there was an error writing to the writer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use surfing::JSONParser;
+    ///
+    /// let mut parser = JSONParser::new();
+    /// let mut buffer = Vec::new();
+    ///
+    /// // The first chunk ends with the first half of "é"
+    /// let data = "{\"name\":\"caf\u{e9}\"}".as_bytes();
+    /// parser.extract_json_from_bytes(&mut buffer, &data[..13]).unwrap();
+    /// parser.extract_json_from_bytes(&mut buffer, &data[13..]).unwrap();
+    ///
+    /// assert_eq!(String::from_utf8(buffer).unwrap(), "{\"name\":\"café\"}");
+    /// ```
+    pub fn extract_json_from_bytes<W: Write>(
+        &mut self,
+        writer: &mut W,
+        bytes: &[u8],
+    ) -> Result<(), ParseError> {
+        let mut start = 0;
+
+        if !self.pending_bytes.is_empty() {
+            // A character has at most four bytes, so a few more complete the held one
+            let held = self.pending_bytes.len();
+            let mut joined = std::mem::take(&mut self.pending_bytes);
+            joined.extend_from_slice(&bytes[..bytes.len().min(4 - held)]);
+
+            let consumed = self.consume_block(&joined, writer)?;
+            if consumed < held {
+                // The chunk is too short to complete the character
+                self.pending_bytes = joined;
+                return self.take_string_error();
+            }
+            start = consumed - held;
+        }
+
+        let consumed = self.consume_block(&bytes[start..], writer)?;
+        self.pending_bytes
+            .extend_from_slice(&bytes[start + consumed..]);
+        self.take_string_error()
+    }
+
+    /// Extracts JSON content from the bytes of a buffer, leaving a string length error pending.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - The bytes to process.
+    /// * `writer` - The writer receiving the extracted JSON.
+    pub(crate) fn consume_block<W: Write>(
+        &mut self,
+        data: &[u8],
+        writer: &mut W,
+    ) -> Result<usize, ParseError> {
+        let mut consumed = 0;
+
+        loop {
+            let error = match std::str::from_utf8(&data[consumed..]) {
+                Ok(text) => {
+                    self.extract_json_from_chunk(writer, text)?;
+                    return Ok(data.len());
+                }
+                Err(error) => error,
+            };
+
+            let valid_end = consumed + error.valid_up_to();
+            if let Ok(text) = std::str::from_utf8(&data[consumed..valid_end]) {
+                self.extract_json_from_chunk(writer, text)?;
+            }
+            consumed = valid_end;
+
+            match error.error_len() {
+                None => return Ok(consumed),
+                Some(length) => {
+                    self.extract_json_from_chunk(writer, "\u{FFFD}")?;
+                    consumed += length;
+                }
+            }
+        }
+    }
+
+    /// Extracts JSON content from a chunk, applying the input framing options.
+    ///
+    /// # Arguments
+    ///
+    /// * `writer` - The writer receiving the extracted JSON.
+    /// * `chunk` - The chunk to process.
+    fn extract_json_from_chunk<W: Write>(
+        &mut self,
+        writer: &mut W,
+        chunk: &str,
+    ) -> std::io::Result<()> {
+        if self.options.sse_mode {
+            for payload in self.sse.feed(chunk) {
+                self.extract_json_from_text(writer, &payload)?;
+                self.end_sse_event();
+            }
+            return Ok(());
+        }
+
+        self.extract_json_from_text(writer, chunk)
+    }
+
+    /// Drops the value the payload of an SSE event left open.
+    ///
+    /// Events are unrelated, so a value cut off by the end of its event is
+    /// abandoned like one cut off by a record separator, and nothing held
+    /// for it is written.
+    fn end_sse_event(&mut self) {
+        self.suppress_current_value();
+        self.abandon_value();
+        self.in_split_array = false;
+        self.scalar.reset();
+    }
+
+    /// Extracts JSON content from unframed text and writes it to the writer.
+    ///
+    /// # Arguments
+    ///
+    /// * `writer` - The writer receiving the extracted JSON.
+    /// * `text` - The text to process.
+    fn extract_json_from_text<W: Write>(
+        &mut self,
+        writer: &mut W,
+        text: &str,
+    ) -> std::io::Result<()> {
+        if self.writes_verbatim() {
+            return self.extract_json_spans(writer, text);
+        }
+
+        self.with_held_output(writer, |parser, writer, output| {
+            let mut position = 0;
+
+            while let Some((item, next)) = parser.next_char(text, position) {
+                position = next;
+                parser.step_held(writer, output, item)?;
+            }
+
+            Ok(())
+        })
+    }
+
+    /// Extracts JSON content from text whose output is a copy of the input.
+    ///
+    /// Instead of writing every character on its own, the written characters
+    /// are collected as spans of the input and handed to the writer together
+    /// once the text is processed. The spans of a value dropped within the
+    /// text are removed, so nothing of it is written.
+    ///
+    /// # Arguments
+    ///
+    /// * `writer` - The writer receiving the extracted JSON.
+    /// * `text` - The text to process.
+    fn extract_json_spans<W: Write>(&mut self, writer: &mut W, text: &str) -> std::io::Result<()> {
+        let mut spans: Vec<Range<usize>> = Vec::new();
+        let mut written = Vec::with_capacity(4);
+        let mut value_start = 0;
+        let mut position = 0;
+
+        while let Some((item, next)) = self.next_char(text, position) {
+            let start = next - item.len_utf8();
+            position = next;
+
+            let was_in_json = self.is_in_json();
+            if !was_in_json {
+                value_start = start;
+            }
+            written.clear();
+            self.process_char(&mut written, item)?;
+            if self.abandoned {
+                truncate_spans(&mut spans, value_start);
+                continue;
+            }
+            if written.is_empty() {
+                continue;
+            }
+
+            match spans.last_mut() {
+                Some(span) if span.end == start => span.end = next,
+                _ => spans.push(start..next),
+            }
+
+            if self.value_ended(was_in_json) && !self.abandoned {
+                // Hand over the value written so far before flushing it
+                if self.options.flush_per_value {
+                    write_spans(writer, text, &spans)?;
+                    spans.clear();
+                }
+                self.finish_written_value(writer)?;
+            }
+        }
+
+        write_spans(writer, text, &spans)
+    }
+
+    /// Checks if every written character is the input character itself.
+    fn writes_verbatim(&self) -> bool {
+        if self.on_value_prefix.is_some() {
+            return false;
+        }
+
+        #[cfg(feature = "serde")]
+        if self.options.auto_unwrap_json_string && !self.unwrap_checked {
+            return false;
+        }
+
+        !self.options.ascii_only
+            && !self.options.unescape_unicode
+            && !self.options.relaxed
+            && !self.options.normalize_smart_quotes
+            && !self.options.percent_decode_candidates
+            && !self.options.validate_value_starts
+            && !self.options.strict
+            && !self.options.extract_scalars
+            && self.options.control_char_policy != ControlCharPolicy::Escape
+            && self.options.lone_surrogate_policy == LoneSurrogatePolicy::Preserve
+            && !self.options.has_extra_whitespace()
+            && self.options.max_output_bytes.is_none()
+            && !self.holds_values()
+    }
+
+    /// Extracts JSON content from a reader until it is exhausted.
+    ///
+    /// The reader is consumed in fixed-size blocks through
+    /// [`consume_from`](Self::consume_from), so multi-byte characters split
+    /// between reads are handled. The output of each block is written with a
+    /// single vectored write where the writer supports it. An incomplete
+    /// character at the very end of the input is processed as U+FFFD.
+    ///
+    /// # Arguments
+    ///
+    /// * `reader` - The reader supplying the text.
+    /// * `writer` - A mutable reference to an object implementing the `Write` trait.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the reader was consumed completely.
+    /// * `Err(ParseError)` - If there was an error reading or writing.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use surfing::JSONParser;
+    ///
+    /// let mut parser = JSONParser::new();
+    /// let mut buffer = Vec::new();
+    /// let input = "Log: {\"level\":\"info\"} and [1, 2]".as_bytes();
+    ///
+    /// parser.extract_json_from_reader(input, &mut buffer).unwrap();
+    /// assert_eq!(String::from_utf8(buffer).unwrap(), "{\"level\":\"info\"}[1, 2]");
+    /// ```
+    pub fn extract_json_from_reader<R: Read, W: Write>(
+        &mut self,
+        mut reader: R,
+        writer: &mut W,
+    ) -> Result<(), ParseError> {
+        let mut block = vec![0; READ_BLOCK_SIZE];
+        let mut filled = 0;
+
+        loop {
+            let read = match reader.read(&mut block[filled..]) {
+                Ok(read) => read,
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e.into()),
+            };
+            if read == 0 {
+                break;
+            }
+
+            filled += read;
+            let consumed = self.consume_block(&block[..filled], writer)?;
+            block.copy_within(consumed..filled, 0);
+            filled -= consumed;
+        }
+
+        if filled > 0 {
+            let rest = String::from_utf8_lossy(&block[..filled]);
+            self.extract_json_from_chunk(writer, &rest)?;
+        }
+
+        self.take_string_error()
+    }
+
+    /// Extracts JSON values from a string, calling `on_value` with each completed value.
+    ///
+    /// Unlike [`extract_json_from_stream`](Self::extract_json_from_stream), which
+    /// writes characters as they arrive, this method hands each top-level value
+    /// to the callback as a whole once its closing marker is seen. Values that
+    /// span several calls are kept inside the parser until they complete, so a
+    /// parser should be driven through one of the two methods, not both.
+    ///
+    /// # Arguments
+    ///
+    /// * `json_object` - The string slice to process.
+    /// * `on_value` - The callback receiving each completed JSON value.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If processing completed successfully.
+    /// * `Err(ParseError)` - If there was an error collecting a value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use surfing::JSONParser;
+    ///
+    /// let mut parser = JSONParser::new();
+    /// let mut values = Vec::new();
+    ///
+    /// parser
+    ///     .extract_values_from_stream("First {\"id\":1} then [2", |value| values.push(value.to_string()))
+    ///     .unwrap();
+    /// parser
+    ///     .extract_values_from_stream("] done", |value| values.push(value.to_string()))
+    ///     .unwrap();
+    ///
+    /// assert_eq!(values, vec!["{\"id\":1}", "[2]"]);
+    /// ```
+    pub fn extract_values_from_stream<F>(
+        &mut self,
+        json_object: &str,
+        mut on_value: F,
+    ) -> Result<(), ParseError>
+    where
+        F: FnMut(&str),
+    {
+        if self.options.sse_mode {
+            for payload in self.sse.feed(json_object) {
+                self.extract_values_from_text(&payload, &mut on_value)?;
+                self.end_sse_event();
+            }
+        } else {
+            self.extract_values_from_text(json_object, &mut on_value)?;
+        }
+
+        self.take_string_error()
+    }
+
+    /// Extracts JSON values from a string, keeping them until they are taken.
+    ///
+    /// This is the pull-based counterpart of
+    /// [`extract_values_from_stream`](Self::extract_values_from_stream): no
+    /// writer or callback is involved. Every value completed by the chunk is
+    /// kept in order, and a partial value stays buffered until a later chunk
+    /// completes it. Collect the values with
+    /// [`take_completed_values`](Self::take_completed_values). As with the
+    /// other value-based methods, a parser should not also be driven through
+    /// the writer-based ones.
+    ///
+    /// # Arguments
+    ///
+    /// * `chunk` - The string slice to process.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If processing completed successfully.
+    /// * `Err(ParseError)` - If a value was rejected, as under
+    ///   [`ParserOptions::max_string_bytes`]. The values completed before are kept.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use surfing::JSONParser;
+    ///
+    /// let mut parser = JSONParser::new();
+    /// parser.feed("First {\"id\":1} then [2] and {\"id\":").unwrap();
+    /// assert_eq!(parser.take_completed_values(), vec!["{\"id\":1}", "[2]"]);
+    ///
+    /// parser.feed("3}").unwrap();
+    /// assert_eq!(parser.take_completed_values(), vec!["{\"id\":3}"]);
+    /// assert!(parser.take_completed_values().is_empty());
+    /// ```
+    pub fn feed(&mut self, chunk: &str) -> Result<(), ParseError> {
+        let mut completed = std::mem::take(&mut self.completed_values);
+        let result =
+            self.extract_values_from_stream(chunk, |value| completed.push(value.to_string()));
+        self.completed_values = completed;
+
+        result
+    }
+
+    /// Takes the values completed since the last call.
+    ///
+    /// The values are removed from the parser, whose storage for them is
+    /// released, so values that are never taken are the only ones held.
+    ///
+    /// # Returns
+    ///
+    /// The values completed by [`feed`](Self::feed) since the last call, in
+    /// order of appearance.
+    pub fn take_completed_values(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.completed_values)
+    }
+
+    /// Extracts JSON values from unframed text, calling `on_value` with each completed value.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The text to process.
+    /// * `on_value` - The callback receiving each completed JSON value.
+    fn extract_values_from_text<F>(
+        &mut self,
+        text: &str,
+        on_value: &mut F,
+    ) -> Result<(), ParseError>
+    where
+        F: FnMut(&str),
+    {
+        let mut output = std::mem::take(&mut self.value_output);
+        let mut position = 0;
+
+        while let Some((item, next)) = self.next_char(text, position) {
+            position = next;
+
+            if !self.is_in_json() {
+                // Drop anything written outside of a value
+                output.clear();
+            }
+
+            let outcome = match self.step(&mut output, item) {
+                Ok(outcome) => outcome,
+                Err(e) => {
+                    self.value_output = output;
+                    return Err(e.into());
+                }
+            };
+            if self.abandoned && self.is_in_json() {
+                // A value dropped at this character gave way to one that has written nothing yet
+                output.clear();
+            }
+
+            if outcome == CharOutcome::Completed {
+                // Values are copied from the input, so they are valid UTF-8
+                match std::str::from_utf8(&output) {
+                    Ok(value) => on_value(value),
+                    Err(e) => {
+                        self.value_output = output;
+                        return Err(std::io::Error::new(std::io::ErrorKind::InvalidData, e).into());
+                    }
+                }
+                output.clear();
+            }
+        }
+
+        self.value_output = output;
+        Ok(())
+    }
+
+    /// Extracts JSON values from a string, spilling oversized values to temporary files.
+    ///
+    /// This works like [`extract_values_from_stream`](Self::extract_values_from_stream),
+    /// except that a value growing beyond
+    /// [`ParserOptions::spill_threshold`] is written to a temporary file
+    /// instead of being kept in memory, and the callback receives a
+    /// [`ValuePayload::Spilled`] for it. Without a threshold every value is
+    /// inline.
+    ///
+    /// If a temporary file cannot be created or written (for example because
+    /// the disk is full), that value is dropped, the rest of the chunk is
+    /// still processed and the first error is returned at the end of the call.
+    ///
+    /// # Arguments
+    ///
+    /// * `json_object` - The string slice to process.
+    /// * `on_payload` - The callback receiving each completed JSON value.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If processing completed successfully.
+    /// * `Err(ParseError)` - If a value could not be spilled.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use surfing::parser::options::ParserOptions;
+    /// use surfing::parser::spill::ValuePayload;
+    /// use surfing::JSONParser;
+    ///
+    /// let mut parser = JSONParser::with_options(ParserOptions::new().spill_threshold(16));
+    /// let mut payloads = Vec::new();
+    /// parser
+    ///     .extract_payloads_from_stream("{\"id\":1} {\"blob\":\"0123456789abcdef\"}", |payload| {
+    ///         payloads.push(payload)
+    ///     })
+    ///     .unwrap();
+    ///
+    /// assert!(matches!(&payloads[0], ValuePayload::Inline(value) if value == "{\"id\":1}"));
+    /// match &payloads[1] {
+    ///     ValuePayload::Spilled(value) => {
+    ///         assert_eq!(value.read_to_string().unwrap(), "{\"blob\":\"0123456789abcdef\"}");
+    ///     }
+    ///     ValuePayload::Inline(_) => panic!("expected a spilled value"),
+    /// }
+    /// ```
+    #[cfg(feature = "spill")]
+    pub fn extract_payloads_from_stream<F>(
+        &mut self,
+        json_object: &str,
+        mut on_payload: F,
+    ) -> Result<(), ParseError>
+    where
+        F: FnMut(ValuePayload),
+    {
+        let mut result = Ok(());
+        if self.options.sse_mode {
+            for payload in self.sse.feed(json_object) {
+                let chunk_result = self.extract_payloads_from_text(&payload, &mut on_payload);
+                result = result.and(chunk_result);
+                self.end_sse_event();
+            }
+        } else {
+            result = self.extract_payloads_from_text(json_object, &mut on_payload);
+        }
+
+        result.and(self.take_string_error())
+    }
+
+    /// Extracts JSON values from unframed text, spilling oversized values.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The text to process.
+    /// * `on_payload` - The callback receiving each completed JSON value.
+    #[cfg(feature = "spill")]
+    fn extract_payloads_from_text<F>(
+        &mut self,
+        text: &str,
+        on_payload: &mut F,
+    ) -> Result<(), ParseError>
+    where
+        F: FnMut(ValuePayload),
+    {
+        let threshold = self.options.spill_threshold.unwrap_or(usize::MAX);
+        let mut output = std::mem::take(&mut self.value_output);
+        let mut result = Ok(());
+        let mut position = 0;
+
+        while let Some((item, next)) = self.next_char(text, position) {
+            position = next;
+
+            let was_in_json = self.is_in_json();
+            if !was_in_json {
+                // Drop anything written outside of a value
+                output.clear();
+            }
+
+            self.process_char(&mut output, item)?;
+            if !self.is_in_json() && !self.value_ended(was_in_json) {
+                continue;
+            }
+            if self.abandoned && self.is_in_json() {
+                // A value dropped at this character gave way to one that has written nothing yet
+                self.spill.discard();
+                output.clear();
+            }
+
+            if let Err(e) = self.spill.absorb(&mut output, threshold) {
+                result = result.and(Err(e));
+            }
+
+            if self.value_ended(was_in_json) && self.abandoned {
+                self.spill.discard();
+                output.clear();
+            } else if self.value_ended(was_in_json) {
+                match self.spill.finish(&mut output) {
+                    Ok(Some(payload)) => on_payload(payload),
+                    Ok(None) => {}
+                    Err(e) => result = result.and(Err(e)),
+                }
+                output.clear();
+            }
+        }
+
+        self.value_output = output;
+        result.map_err(ParseError::Io)
+    }
+
+    /// Reports the byte range of every value that completes within the text.
+    ///
+    /// Ranges refer to `text` itself, so this is only meaningful for a parser
+    /// that is not inside a value when called and has no input framing
+    /// options. Nothing is written.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The text to scan.
+    /// * `on_span` - The callback receiving the range of each completed value.
+    pub(crate) fn extract_value_spans<F>(&mut self, text: &str, mut on_span: F)
+    where
+        F: FnMut(Range<usize>),
+    {
+        let mut position = 0;
+
+        while let Some(span) = self.next_value_span(text, &mut position) {
+            on_span(span);
+        }
+    }
+
+    /// Scans the text from `position` up to the end of the next completed value.
+    ///
+    /// The same caveats as for [`extract_value_spans`](Self::extract_value_spans)
+    /// apply. Scanning stops right after the value, so the rest of the text is
+    /// only looked at by later calls.
+    ///
+    /// # Arguments
+    ///
+    /// * `text` - The text to scan.
+    /// * `position` - The byte offset to continue from, advanced past the scanned text.
+    ///
+    /// # Returns
+    ///
+    /// * `Some(Range<usize>)` - The range of the next completed value.
+    /// * `None` - If no further value completes within the text.
+    pub(crate) fn next_value_span(
+        &mut self,
+        text: &str,
+        position: &mut usize,
+    ) -> Option<Range<usize>> {
+        let mut start = *position;
+
+        while let Some((item, next)) = self.next_char(text, *position) {
+            *position = next;
+
+            let was_in_json = self.is_in_json();
+            if !was_in_json {
+                start = next - item.len_utf8();
+            }
+
+            // Writing to a sink cannot fail
+            let _ = self.process_char(&mut std::io::sink(), item);
+            if self.abandoned {
+                // The character may start a value after dropping the one before
+                start = next - item.len_utf8();
+            }
+
+            if was_in_json && !self.is_in_json() && !self.abandoned {
+                return Some(start..next);
+            }
+        }
+
+        *position = text.len();
+        None
+    }
+
+    /// Returns the next character to process and the position following it.
+    ///
+    /// Outside of JSON, this skips straight to the next marker instead of
+    /// inspecting every character of plain text. With
+    /// [`ParserOptions::percent_decode_candidates`], a `%` also stops the
+    /// skip and nothing is skipped while a percent-encoded run is collected.
+    /// Nothing is skipped between the elements of a split top-level array
+    /// either, as strings there must be followed, nor anywhere with
+    /// [`ParserOptions::extract_scalars`], as any character may end a scalar.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The string slice being processed.
+    /// * `position` - The byte offset to continue from.
+    fn next_char(&self, input: &str, position: usize) -> Option<(char, usize)> {
+        let mut position = position;
+        if !self.is_in_json()
+            && !self.percent.is_active()
+            && !self.in_split_array
+            && !self.options.extract_scalars
+        {
+            let rest = input.get(position..)?;
+            position += if self.options.percent_decode_candidates {
+                find_opening_marker_or_percent(rest)?
+            } else {
+                find_opening_marker(rest)?
+            };
+        }
+
+        let item = input[position..].chars().next()?;
+        Some((item, position + item.len_utf8()))
+    }
+
+    /// Processes a token produced by an external lexer.
+    ///
+    /// This is a lower-level alternative to [`extract_json_from_stream`](Self::extract_json_from_stream)
+    /// for callers that already tokenize their input. Structural tokens update
+    /// the marker state, string tokens are written without being inspected for
+    /// markers, and whitespace or text tokens are copied verbatim while inside
+    /// a JSON structure. Feeding the tokens of a text produces the same output
+    /// as feeding the text itself.
+    ///
+    /// # Arguments
+    ///
+    /// * `writer` - A mutable reference to an object implementing the `Write` trait.
+    /// * `token` - The token to process.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If processing completed successfully.
+    /// * `Err(ParseError)` - If there was an error writing to the writer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use surfing::parser::token::Token;
+    /// use surfing::JSONParser;
+    ///
+    /// let mut parser = JSONParser::new();
+    /// let mut buffer = Vec::new();
+    ///
+    /// let tokens = [
+    ///     Token::Text("id"),
+    ///     Token::Structural('{'),
+    ///     Token::String("\"id\""),
+    ///     Token::Structural(':'),
+    ///     Token::Whitespace(" "),
+    ///     Token::Text("7"),
+    ///     Token::Structural('}'),
+    /// ];
+    /// for token in tokens {
+    ///     parser.feed_token(&mut buffer, token).unwrap();
+    /// }
+    ///
+    /// assert_eq!(String::from_utf8(buffer).unwrap(), "{\"id\": 7}");
+    /// ```
+    pub fn feed_token<W: Write>(
+        &mut self,
+        writer: &mut W,
+        token: Token<'_>,
+    ) -> Result<(), ParseError> {
+        let was_in_json = self.is_in_json();
+        self.abandoned = false;
+        self.with_held_output(writer, |parser, writer, output| {
+            if parser.options.max_output_bytes.is_some() {
+                parser
+                    .limit_output(output, |parser, output| parser.process_token(output, token))?;
+            } else {
+                parser.process_token(output, token)?;
+            }
+
+            let ended = was_in_json && !parser.is_in_json();
+            #[cfg(feature = "serde")]
+            if ended && parser.options.validate_values && !is_serde_json(output) {
+                parser.abandoned = true;
+            }
+            let completed = ended && !parser.abandoned;
+            parser.deliver_output(writer, output, completed)
+        })?;
+        self.take_string_error()
+    }
+
+    /// Processes a token without applying the output limit.
+    ///
+    /// # Arguments
+    ///
+    /// * `writer` - The writer receiving the extracted JSON.
+    /// * `token` - The token to process.
+    fn process_token<W: Write>(&mut self, writer: &mut W, token: Token<'_>) -> std::io::Result<()> {
+        match token {
+            Token::Structural(item) => self.process_char_unlimited(writer, item)?,
+            _ if !self.is_in_json() => {}
+            Token::String(literal) => {
+                for item in literal.chars() {
+                    if !self.buffer_text(item.encode_utf8(&mut [0; 4])) {
+                        break;
+                    }
+                    let was_in_string = self.in_string;
+                    self.update_string_state(&item);
+                    self.track_string_length(was_in_string, item);
+                    self.emit_json_char(writer, was_in_string, item)?;
+                }
+            }
+            Token::Whitespace(text) | Token::Text(text) => {
+                if !self.buffer_text(text) {
+                    return Ok(());
+                }
+                if !self.discarding {
+                    writer.write_all(text.as_bytes())?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Writes spans of the text, using vectored writes where the writer supports them.
+///
+/// Writers without vectored write support write the first span of each call,
+/// which falls back to writing the spans one after another.
+fn write_spans<W: Write>(
+    writer: &mut W,
+    text: &str,
+    spans: &[Range<usize>],
+) -> std::io::Result<()> {
+    let mut slices: Vec<IoSlice<'_>> = spans
+        .iter()
+        .map(|span| IoSlice::new(text[span.clone()].as_bytes()))
+        .collect();
+    let mut remaining = &mut slices[..];
+
+    while !remaining.is_empty() {
+        match writer.write_vectored(remaining) {
+            Ok(0) => return Err(std::io::ErrorKind::WriteZero.into()),
+            Ok(written) => IoSlice::advance_slices(&mut remaining, written),
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+
+    Ok(())
+}
+
+/// Removes the parts of the spans from `end` on.
+///
+/// # Arguments
+///
+/// * `spans` - The spans of the text to write, in order.
+/// * `end` - The offset in the text from which nothing is to be written.
+fn truncate_spans(spans: &mut Vec<Range<usize>>, end: usize) {
+    spans.retain(|span| span.start < end);
+    if let Some(span) = spans.last_mut() {
+        span.end = span.end.min(end);
+    }
+}
+
+/// Returns the JSON escape sequence for a control character.
+fn escape_control_char(item: char) -> String {
+    match item {
+        '\n' => "\\n".to_string(),
+        '\t' => "\\t".to_string(),
+        '\r' => "\\r".to_string(),
+        '\u{8}' => "\\b".to_string(),
+        '\u{C}' => "\\f".to_string(),
+        item => format!("\\u{:04x}", item as u32),
+    }
+}
+
+/// Returns the straight quote replacing a typographic quote.
+///
+/// Converts U+201C, U+201D, U+201E and U+201F to `"`, and U+2018, U+2019,
+/// U+201A and U+201B to `'`.
+fn straighten_quote(item: char) -> Option<char> {
+    match item {
+        '\u{201C}' | '\u{201D}' | '\u{201E}' | '\u{201F}' => Some('"'),
+        '\u{2018}' | '\u{2019}' | '\u{201A}' | '\u{201B}' => Some('\''),
+        _ => None,
+    }
+}
+
+/// Checks if `serde_json` accepts the output of a value.
+#[cfg(feature = "serde")]
+fn is_serde_json(value: &[u8]) -> bool {
+    serde_json::from_slice::<serde::de::IgnoredAny>(value).is_ok()
+}
+
+/// Decodes a value that is the content of a JSON string holding JSON.
+///
+/// A double-encoded value such as `"{\"a\":1}"` is extracted as
+/// `{\"a\":1}`. Reading it back as the content of a JSON string yields the
+/// inner JSON, which is only accepted if it parses and differs from the value.
+#[cfg(feature = "serde")]
+fn unwrap_json_string(value: &[u8]) -> Option<Vec<u8>> {
+    let value = std::str::from_utf8(value).ok()?;
+    let inner: String = serde_json::from_str(&format!("\"{}\"", value)).ok()?;
+    if inner == value {
+        return None;
+    }
+
+    serde_json::from_str::<serde::de::IgnoredAny>(&inner).ok()?;
+    Some(inner.into_bytes())
+}
+
+/// Checks if a character can follow the opening marker of a structure of the given kind.
+///
+/// Whitespace is skipped before the check. A backslash is accepted for
+/// structures whose strings are escaped.
+///
+/// # Arguments
+///
+/// * `kind` - The kind of the structure just opened.
+/// * `item` - The first character after its opening marker.
+fn starts_content(kind: ContainerKind, item: char) -> bool {
+    match kind {
+        ContainerKind::Object => matches!(item, '"' | '}' | '\\'),
+        ContainerKind::Array => matches!(
+            item,
+            '"' | '{' | '[' | ']' | '-' | '0'..='9' | 't' | 'f' | 'n' | '\\'
+        ),
+    }
+}
+
+/// Finds the byte offset of the first opening marker in the text.
+///
+/// All markers are ASCII, so scanning bytes is enough and never lands inside
+/// a multi-byte character.
+fn find_opening_marker(text: &str) -> Option<usize> {
+    text.bytes().position(|byte| matches!(byte, b'{' | b'['))
+}
+
+/// Checks if a parser with default options would extract the text as exactly one value.
+///
+/// This is a byte scan replaying the marker tracking of
+/// [`JSONParser::update_markers`], quirks included, so it must change along
+/// with it. The text must start with the value's opening marker, and the
+/// value must close on the last byte. NUL characters are preserved by the
+/// default NUL policy, so they are scanned like any other byte.
+pub(crate) fn is_single_value(text: &str) -> bool {
+    let bytes = text.as_bytes();
+    if !matches!(bytes.first(), Some(b'{' | b'[')) {
+        return false;
+    }
+
+    let mut markers: Vec<u8> = Vec::new();
+    let mut in_string = false;
+    let mut escaped = false;
+    let mut escaped_value = false;
+    for (index, byte) in bytes.iter().enumerate() {
+        if in_string {
+            match byte {
+                _ if escaped => escaped = false,
+                b'\\' => escaped = true,
+                b'"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+
+        match byte {
+            b'\\' => escaped_value = true,
+            b'"' if !escaped_value => in_string = true,
+            b'{' => markers.push(b'}'),
+            b'[' => markers.push(b']'),
+            // A closer must match the innermost marker, or the value is dropped
+            b'}' | b']' if markers.last() != Some(byte) => return false,
+            b'}' | b']' => {
+                markers.pop();
+                if markers.is_empty() {
+                    return index + 1 == bytes.len();
+                }
+            }
+            _ => {}
+        }
+    }
+
+    false
+}
+
+/// Finds the byte offset of the first opening marker or `%` in the text.
+fn find_opening_marker_or_percent(text: &str) -> Option<usize> {
+    text.bytes()
+        .position(|byte| matches!(byte, b'{' | b'[' | b'%'))
+}
+
+impl Default for JSONParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::options::TruncationPolicy;
+    use crate::utils::extract_json_to_string;
+    use std::io::BufWriter;
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn test_json_parser_empty() {
+        let parser = JSONParser::new();
+        assert!(!parser.is_in_json());
+        assert!(parser.markers.is_empty());
+    }
+
+    #[test]
+    fn test_json_parser_extract_simple_json() {
+        let mut parser = JSONParser::new();
+        let mut buffer = Vec::new();
+
+        {
+            let mut writer = BufWriter::new(&mut buffer);
+            parser.extract_json_from_stream(&mut writer, "{}").unwrap();
+            // Flush the writer to ensure all data is written to the underlying buffer
+            writer.flush().unwrap();
+        }
+
+        assert!(!parser.is_in_json());
+
+        let output = String::from_utf8(buffer).unwrap();
+        assert_eq!(output, "{}");
+    }
+
+    #[test]
+    fn test_json_parser_extract_nested_json() {
+        let mut parser = JSONParser::new();
+        let mut buffer = Vec::new();
+
+        {
+            let mut writer = BufWriter::new(&mut buffer);
+            parser
+                .extract_json_from_stream(&mut writer, "{\"key\": [1, 2, 3]}")
+                .unwrap();
+            assert!(!parser.is_in_json());
+        }
+
+        let output = String::from_utf8(buffer).unwrap();
+        assert_eq!(output, "{\"key\": [1, 2, 3]}");
+    }
+
+    #[test]
+    fn test_json_parser_extract_partial_json() {
+        let mut parser = JSONParser::new();
+        let mut buffer = Vec::new();
+
+        {
+            let mut writer = BufWriter::new(&mut buffer);
+            parser
+                .extract_json_from_stream(&mut writer, "{\"key")
+                .unwrap();
+            assert!(parser.is_in_json());
+
+            parser
+                .extract_json_from_stream(&mut writer, "\": [1, 2, 3]}")
+                .unwrap();
+            assert!(!parser.is_in_json());
+        }
+
+        let output = String::from_utf8(buffer).unwrap();
+        assert_eq!(output, "{\"key\": [1, 2, 3]}");
+    }
+
+    #[test]
+    fn test_json_parser_extract_json_mixed_with_text() {
+        let mut parser = JSONParser::new();
+        let mut buffer = Vec::new();
+
+        {
+            let mut writer = BufWriter::new(&mut buffer);
+
+            // Plain text followed by JSON
+            parser
+                .extract_json_from_stream(&mut writer, "Some plain text {\"id\": 123, \"data\": ")
+                .unwrap();
+            assert!(parser.is_in_json());
+
+            // More JSON with nested structure
+            parser
+                .extract_json_from_stream(&mut writer, "{\"nested\": [1, 2, {\"deep\": true}]}}")
+                .unwrap();
+            assert!(!parser.is_in_json());
+
+            // JSON followed by plain text
+            parser
+                .extract_json_from_stream(&mut writer, " followed by more text")
+                .unwrap();
+            assert!(!parser.is_in_json());
+
+            // Another JSON object
+            parser
+                .extract_json_from_stream(&mut writer, " and another {\"array\": [4, 5, 6]}")
+                .unwrap();
+            assert!(!parser.is_in_json());
+        }
+
+        let output = String::from_utf8(buffer).unwrap();
+        assert_eq!(
+            output,
+            "{\"id\": 123, \"data\": {\"nested\": [1, 2, {\"deep\": true}]}}{\"array\": [4, 5, 6]}"
+        );
+    }
+
+    #[test]
+    fn test_json_parser_ascii_only_emoji() {
+        let mut parser = JSONParser::with_options(ParserOptions::new().ascii_only(true));
+        let mut buffer = Vec::new();
+
+        parser
+            .extract_json_from_stream(&mut buffer, "Mood: {\"mood\":\"😀\"}")
+            .unwrap();
+
+        let output = String::from_utf8(buffer).unwrap();
+        assert_eq!(output, "{\"mood\":\"\\ud83d\\ude00\"}");
+    }
+
+    #[test]
+    fn test_json_parser_ascii_only_cjk_across_chunks() {
+        let mut parser = JSONParser::with_options(ParserOptions::new().ascii_only(true));
+        let mut buffer = Vec::new();
+
+        parser
+            .extract_json_from_stream(&mut buffer, "{\"city\":\"東")
+            .unwrap();
+        parser
+            .extract_json_from_stream(&mut buffer, "京\"}")
+            .unwrap();
+
+        let output = String::from_utf8(buffer).unwrap();
+        assert_eq!(output, "{\"city\":\"\\u6771\\u4eac\"}");
+    }
+
+    #[test]
+    fn test_json_parser_ascii_only_keeps_existing_escapes() {
+        let mut parser = JSONParser::with_options(ParserOptions::new().ascii_only(true));
+        let mut buffer = Vec::new();
+
+        parser
+            .extract_json_from_stream(&mut buffer, "{\"name\":\"Jos\\u00e9 é\\\\\"}")
+            .unwrap();
+
+        let output = String::from_utf8(buffer).unwrap();
+        assert_eq!(output, "{\"name\":\"Jos\\u00e9 \\u00e9\\\\\"}");
+    }
+
+    #[test]
+    fn test_json_parser_ascii_only_disabled_by_default() {
+        let mut parser = JSONParser::new();
+        let mut buffer = Vec::new();
+
+        parser
+            .extract_json_from_stream(&mut buffer, "{\"mood\":\"😀\"}")
+            .unwrap();
+
+        let output = String::from_utf8(buffer).unwrap();
+        assert_eq!(output, "{\"mood\":\"😀\"}");
+    }
+
+    #[test]
+    fn test_json_parser_fast_scan_matches_char_by_char() {
+        let inputs = [
+            "no markers at all, just a plain log line",
+            "prefix {\"a\":[1,2]} middle é [3] suffix ] stray",
+            "ünïcödé text before {\"k\":\"v\"} and after ✓",
+        ];
+
+        for input in inputs {
+            let mut bulk_parser = JSONParser::new();
+            let mut bulk = Vec::new();
+            bulk_parser
+                .extract_json_from_stream(&mut bulk, input)
+                .unwrap();
+
+            let mut char_parser = JSONParser::new();
+            let mut per_char = Vec::new();
+            for item in input.chars() {
+                char_parser
+                    .extract_json_from_stream(&mut per_char, &item.to_string())
+                    .unwrap();
+            }
+
+            assert_eq!(bulk, per_char);
+        }
+    }
+
+    #[test]
+    fn test_json_parser_fast_scan_no_json() {
+        let mut parser = JSONParser::new();
+        let mut buffer = Vec::new();
+
+        parser
+            .extract_json_from_stream(&mut buffer, "2024-01-01 INFO nothing to see here")
+            .unwrap();
+
+        assert!(buffer.is_empty());
+        assert!(!parser.is_in_json());
+    }
+
+    #[test]
+    fn test_json_parser_unescape_unicode() {
+        let (output, result) = writer_output(
+            ParserOptions::new().unescape_unicode(true),
+            &["Name: {\"name\":\"Jos\\u00e9 \\u6771\\u4eac\"} done"],
+        );
+        result.unwrap();
+        assert_eq!(output, "{\"name\":\"José 東京\"}");
+    }
+
+    #[test]
+    fn test_json_parser_unescape_unicode_keeps_required_escapes() {
+        let input = "{\"text\":\"\\u0022 \\u005C \\u000a \\n \\\\u00e9\"}";
+        let (output, result) = writer_output(ParserOptions::new().unescape_unicode(true), &[input]);
+        result.unwrap();
+        assert_eq!(output, input);
+    }
+
+    #[test]
+    fn test_json_parser_unescape_unicode_surrogate_pair_split_everywhere() {
+        let input = "{\"mood\":\"\\ud83d\\ude00!\"}";
+
+        for split in 1..input.len() {
+            let (output, result) = writer_output(
+                ParserOptions::new().unescape_unicode(true),
+                &[&input[..split], &input[split..]],
+            );
+            result.unwrap();
+            assert_eq!(output, "{\"mood\":\"😀!\"}", "split at {}", split);
+        }
+    }
+
+    #[test]
+    fn test_json_parser_unescape_unicode_lone_surrogates_pass_through() {
+        let input = "{\"a\":\"\\ud83d\",\"b\":\"\\ude00\",\"c\":\"\\ud83d\\u0041\"}";
+        let (output, result) = writer_output(ParserOptions::new().unescape_unicode(true), &[input]);
+        result.unwrap();
+        assert_eq!(
+            output,
+            "{\"a\":\"\\ud83d\",\"b\":\"\\ude00\",\"c\":\"\\ud83dA\"}"
+        );
+    }
+
+    #[test]
+    fn test_json_parser_unescape_unicode_invalid_escape_passes_through() {
+        let input = "{\"a\":\"\\u12zz\",\"b\":\"\\ud83d\\uZZ\"}";
+        let (output, result) = writer_output(ParserOptions::new().unescape_unicode(true), &[input]);
+        result.unwrap();
+        assert_eq!(output, input);
+    }
+
+    #[test]
+    fn test_json_parser_unescape_unicode_with_ascii_only() {
+        let options = ParserOptions::new().unescape_unicode(true).ascii_only(true);
+        let mut parser = JSONParser::with_options(options);
+        let mut buffer = Vec::new();
+
+        parser
+            .extract_json_from_stream(&mut buffer, "{\"a\":\"\\u00E9\"}")
+            .unwrap();
+
+        let output = String::from_utf8(buffer).unwrap();
+        assert_eq!(output, "{\"a\":\"\\u00e9\"}");
+    }
+
+    #[test]
+    fn test_json_parser_feed_token_matches_text() {
+        let input = "log {\"key\": [1, \"two\", {\"three\": null}]} end";
+        let tokens = [
+            Token::Text("log"),
+            Token::Whitespace(" "),
+            Token::Structural('{'),
+            Token::String("\"key\""),
+            Token::Structural(':'),
+            Token::Whitespace(" "),
+            Token::Structural('['),
+            Token::Text("1"),
+            Token::Structural(','),
+            Token::Whitespace(" "),
+            Token::String("\"two\""),
+            Token::Structural(','),
+            Token::Whitespace(" "),
+            Token::Structural('{'),
+            Token::String("\"three\""),
+            Token::Structural(':'),
+            Token::Whitespace(" "),
+            Token::Text("null"),
+            Token::Structural('}'),
+            Token::Structural(']'),
+            Token::Structural('}'),
+            Token::Whitespace(" "),
+            Token::Text("end"),
+        ];
+
+        let mut text_parser = JSONParser::new();
+        let mut expected = Vec::new();
+        text_parser
+            .extract_json_from_stream(&mut expected, input)
+            .unwrap();
+
+        let mut token_parser = JSONParser::new();
+        let mut output = Vec::new();
+        for token in tokens {
+            token_parser.feed_token(&mut output, token).unwrap();
+        }
+
+        assert_eq!(output, expected);
+        assert!(!token_parser.is_in_json());
+    }
+
+    #[test]
+    fn test_json_parser_feed_token_split_string() {
+        let mut parser = JSONParser::with_options(ParserOptions::new().ascii_only(true));
+        let mut buffer = Vec::new();
+
+        parser
+            .feed_token(&mut buffer, Token::Structural('['))
+            .unwrap();
+        parser
+            .feed_token(&mut buffer, Token::String("\"caf"))
+            .unwrap();
+        assert!(parser.is_in_json());
+        parser
+            .feed_token(&mut buffer, Token::String("é\""))
+            .unwrap();
+        parser
+            .feed_token(&mut buffer, Token::Structural(']'))
+            .unwrap();
+
+        assert!(!parser.is_in_json());
+        assert_eq!(String::from_utf8(buffer).unwrap(), "[\"caf\\u00e9\"]");
+    }
+
+    #[test]
+    fn test_json_parser_extract_values_from_stream() {
+        let mut parser = JSONParser::with_options(ParserOptions::new().ascii_only(true));
+        let mut values = Vec::new();
+
+        parser
+            .extract_values_from_stream("] stray {\"a\":\"é\"} text [1, {\"b\"", |value| {
+                values.push(value.to_string())
+            })
+            .unwrap();
+        assert_eq!(values, vec!["{\"a\":\"\\u00e9\"}"]);
+        assert!(parser.is_in_json());
+
+        parser
+            .extract_values_from_stream(":2}] tail", |value| values.push(value.to_string()))
+            .unwrap();
+        assert_eq!(values, vec!["{\"a\":\"\\u00e9\"}", "[1, {\"b\":2}]"]);
+        assert!(!parser.is_in_json());
+    }
+
+    #[test]
+    fn test_json_parser_feed_and_take_completed_values() {
+        let mut parser = JSONParser::new();
+
+        parser.feed("a [1] b {\"c\":").unwrap();
+        parser.feed("2} [3][4] {\"open\"").unwrap();
+        let values = parser.take_completed_values();
+        assert_eq!(values, vec!["[1]", "{\"c\":2}", "[3]", "[4]"]);
+
+        // Taking releases the storage and leaves the partial value buffered
+        assert_eq!(parser.completed_values.capacity(), 0);
+        assert!(parser.take_completed_values().is_empty());
+        parser.feed(": true}").unwrap();
+        assert_eq!(parser.take_completed_values(), vec!["{\"open\": true}"]);
+
+        // A rejected value keeps the values completed before it
+        let mut parser = JSONParser::with_options(ParserOptions::new().max_string_bytes(3));
+        assert!(parser.feed("[1] [\"long\"] [2]").is_err());
+        assert_eq!(parser.take_completed_values(), vec!["[1]", "[2]"]);
+    }
+
+    #[test]
+    fn test_json_parser_strip_interior_bom() {
+        let mut parser = JSONParser::with_options(ParserOptions::new().strip_interior_bom(true));
+        let mut values = Vec::new();
+
+        parser
+            .extract_values_from_stream("\u{FEFF}{\"a\":1}\u{FEFF}{\"b\":2}", |value| {
+                values.push(value.to_string())
+            })
+            .unwrap();
+
+        assert_eq!(values, vec!["{\"a\":1}", "{\"b\":2}"]);
+    }
+
+    #[test]
+    fn test_json_parser_strip_interior_bom_inside_container() {
+        let mut parser = JSONParser::with_options(ParserOptions::new().strip_interior_bom(true));
+        let mut buffer = Vec::new();
+
+        parser
+            .extract_json_from_stream(&mut buffer, "[\u{FEFF}{\"a\":1},\u{FEFF}")
+            .unwrap();
+        parser
+            .extract_json_from_stream(&mut buffer, "{\"b\":\"\u{FEFF}kept\"}]")
+            .unwrap();
+
+        let output = String::from_utf8(buffer).unwrap();
+        assert_eq!(output, "[{\"a\":1},{\"b\":\"\u{FEFF}kept\"}]");
+        assert!(!output.starts_with(BYTE_ORDER_MARK));
+    }
+
+    #[test]
+    fn test_json_parser_sse_mode() {
+        let mut parser = JSONParser::with_options(ParserOptions::new().sse_mode(true));
+        let mut values = Vec::new();
+
+        let chunks = [
+            "event: delta\nid: 7\ndata: {\"choice\":",
+            "\ndata: {\"text\":\"hi\"}}\n",
+            "\n: comment {ignored}\nevent: {\"not\":\"data\"}\n\n",
+            "data: [1,2]\n\n",
+        ];
+        for chunk in chunks {
+            parser
+                .extract_values_from_stream(chunk, |value| values.push(value.to_string()))
+                .unwrap();
+        }
+
+        assert_eq!(values, vec!["{\"choice\":\n{\"text\":\"hi\"}}", "[1,2]"]);
+    }
+
+    #[test]
+    fn test_json_parser_sse_mode_waits_for_blank_line() {
+        let mut parser = JSONParser::with_options(ParserOptions::new().sse_mode(true));
+        let mut buffer = Vec::new();
+
+        parser
+            .extract_json_from_stream(&mut buffer, "data: {\"a\":\ndata: 1}\n")
+            .unwrap();
+        assert!(buffer.is_empty());
+
+        parser
+            .extract_json_from_stream(&mut buffer, "\r\n")
+            .unwrap();
+        assert_eq!(String::from_utf8(buffer).unwrap(), "{\"a\":\n1}");
+    }
+
+    #[test]
+    fn test_json_parser_sse_mode_drops_values_cut_off_by_event_end() {
+        let input = "data: {\"a\":\n\ndata: {\"b\":1}\n\ndata: [2\n\n";
+        let options = || ParserOptions::new().sse_mode(true);
+
+        let (output, result) = writer_output(options(), &[input]);
+        result.unwrap();
+        assert_eq!(output, "{\"b\":1}");
+
+        let mut parser = JSONParser::with_options(options());
+        parser.feed(input).unwrap();
+        assert_eq!(parser.take_completed_values(), vec!["{\"b\":1}"]);
+        assert!(!parser.is_in_json());
+    }
+
+    #[test]
+    fn test_json_parser_sse_mode_skips_done_marker() {
+        let input = "data: {\"a\":1}\n\ndata: [DONE]\n\n";
+
+        let (output, result) = writer_output(ParserOptions::new().sse_mode(true), &[input]);
+        result.unwrap();
+        assert_eq!(output, "{\"a\":1}");
+    }
+
+    #[test]
+    fn test_json_parser_relaxed_python_dict() {
+        let mut parser = JSONParser::with_options(ParserOptions::new().relaxed(true));
+        let mut buffer = Vec::new();
+
+        let chunks = [
+            "print: {'name': 'Ann \"A\"', 'active': Tr",
+            "ue, 'manager': None, 'id': 00",
+            "42, 'scores': [-01.5, 0], 'note': 'True'}",
+        ];
+        for chunk in chunks {
+            parser.extract_json_from_stream(&mut buffer, chunk).unwrap();
+        }
+
+        assert!(!parser.is_in_json());
+        assert_eq!(
+            String::from_utf8(buffer).unwrap(),
+            "{\"name\": \"Ann \\\"A\\\"\", \"active\": true, \"manager\": null, \
+             \"id\": 42, \"scores\": [-1.5, 0], \"note\": \"True\"}"
+        );
+    }
+
+    #[test]
+    fn test_json_parser_relaxed_disabled_by_default() {
+        let mut parser = JSONParser::new();
+        let mut buffer = Vec::new();
+
+        parser
+            .extract_json_from_stream(&mut buffer, "{\"ok\": True, \"n\": 01}")
+            .unwrap();
+
+        assert_eq!(
+            String::from_utf8(buffer).unwrap(),
+            "{\"ok\": True, \"n\": 01}"
+        );
+    }
+
+    #[test]
+    fn test_json_parser_max_output_bytes_cut_mid_string() {
+        let input = ["log {\"msg\": \"héllo", " world\"} tail {\"n\":1}"];
+        let options = ParserOptions::new()
+            .max_output_bytes(12)
+            .truncation_policy(TruncationPolicy::Cut);
+        let (output, result) = writer_output(options, &input);
+
+        assert_eq!(output, "{\"msg\": \"hé");
+        assert!(matches!(
+            result,
+            Err(ParseError::OutputTruncated { suppressed }) if suppressed == 11 + 7
+        ));
+    }
+
+    #[test]
+    fn test_json_parser_max_output_bytes_balance_mid_string() {
+        let input = ["log {\"msg\": \"héllo", " world\"} tail {\"n\":1}"];
+        let options = ParserOptions::new()
+            .max_output_bytes(12)
+            .truncation_policy(TruncationPolicy::Balance);
+        let (output, result) = writer_output(options, &input);
+
+        assert_eq!(output, "{\"msg\": \"h\"}");
+        assert!(matches!(
+            result,
+            Err(ParseError::OutputTruncated { suppressed }) if suppressed == 13 + 7
+        ));
+    }
+
+    #[test]
+    fn test_json_parser_max_output_bytes_cut_mid_nesting() {
+        let input = ["{\"a\":[{\"b\":[1,2,3]}]}"];
+        let options = ParserOptions::new()
+            .max_output_bytes(13)
+            .truncation_policy(TruncationPolicy::Cut);
+        let (output, result) = writer_output(options, &input);
+
+        assert_eq!(output, "{\"a\":[{\"b\":[1");
+        assert!(matches!(
+            result,
+            Err(ParseError::OutputTruncated { suppressed }) if suppressed == 8
+        ));
+    }
+
+    #[test]
+    fn test_json_parser_max_output_bytes_balance_mid_nesting() {
+        let input = ["{\"a\":[{\"b\":[1,2,3]}]}"];
+        let options = ParserOptions::new()
+            .max_output_bytes(17)
+            .truncation_policy(TruncationPolicy::Balance);
+        let (output, _) = writer_output(options, &input);
+
+        assert_eq!(output, "{\"a\":[{\"b\":[1]}]}");
+        assert!(output.len() <= 17);
+    }
+
+    #[test]
+    fn test_json_parser_max_output_bytes_holds_escapes() {
+        let input = ["[\"\\u00e9\\n\"]"];
+        let options = ParserOptions::new()
+            .max_output_bytes(8)
+            .truncation_policy(TruncationPolicy::Balance);
+        let (output, _) = writer_output(options, &input);
+
+        assert_eq!(output, "[\"\"]");
+
+        let options = ParserOptions::new()
+            .max_output_bytes(10)
+            .truncation_policy(TruncationPolicy::Balance);
+        let (output, _) = writer_output(options, &input);
+        assert_eq!(output, "[\"\\u00e9\"]");
+    }
+
+    #[test]
+    fn test_json_parser_max_output_bytes_keeps_consuming() {
+        let options = ParserOptions::new().max_output_bytes(3);
+        let mut parser = JSONParser::with_options(options);
+        let mut buffer = Vec::new();
+
+        parser
+            .extract_json_from_stream(&mut buffer, "[1, [2]] text {\"a\":")
+            .unwrap();
+        assert!(parser.is_in_json());
+        parser.extract_json_from_stream(&mut buffer, "1}").unwrap();
+
+        assert!(!parser.is_in_json());
+        assert_eq!(String::from_utf8(buffer).unwrap(), "[1,");
+        assert!(matches!(
+            parser.check_output_limit(),
+            Err(ParseError::OutputTruncated { suppressed: 12 })
+        ));
+    }
+
+    #[test]
+    fn test_json_parser_max_output_bytes_within_limit() {
+        let parser = JSONParser::with_options(ParserOptions::new().max_output_bytes(100));
+        assert!(parser.check_output_limit().is_ok());
+
+        let options = ParserOptions::new()
+            .max_output_bytes(7)
+            .truncation_policy(TruncationPolicy::Balance);
+        let (output, result) = writer_output(options, &["x {\"a\":1} y"]);
+        assert_eq!(output, "{\"a\":1}");
+        result.unwrap();
+    }
+
+    #[test]
+    fn test_json_parser_consume_from_partial_code_point() {
+        let mut parser = JSONParser::new();
+        let mut buffer = Vec::new();
+        let data = "log {\"emoji\":\"😀\"} end".as_bytes();
+
+        // Stop inside the four bytes of the emoji
+        let first = parser.consume_from(&data[..16], &mut buffer).unwrap();
+        assert_eq!(first, 14);
+        assert!(parser.is_in_json());
+
+        let second = parser.consume_from(&data[first..], &mut buffer).unwrap();
+        assert_eq!(first + second, data.len());
+        assert_eq!(String::from_utf8(buffer).unwrap(), "{\"emoji\":\"😀\"}");
+    }
+
+    #[test]
+    fn test_json_parser_extract_json_from_bytes_split_character() {
+        let data = "caf\u{e9} {\"drink\":\"caf\u{e9}\",\"cup\":\"\u{2615}\"} done".as_bytes();
+
+        for split in 0..=data.len() {
+            let mut parser = JSONParser::new();
+            let mut buffer = Vec::new();
+            parser
+                .extract_json_from_bytes(&mut buffer, &data[..split])
+                .unwrap();
+            parser
+                .extract_json_from_bytes(&mut buffer, &data[split..])
+                .unwrap();
+
+            assert_eq!(
+                String::from_utf8(buffer).unwrap(),
+                "{\"drink\":\"café\",\"cup\":\"☕\"}",
+                "split at {}",
+                split
+            );
+        }
+
+        // Held bytes survive chunks too short to complete them
+        let mut parser = JSONParser::new();
+        let mut buffer = Vec::new();
+        for byte in b"[\"\xe2\x98\x95\", \"\xff\"]" {
+            parser
+                .extract_json_from_bytes(&mut buffer, &[*byte])
+                .unwrap();
+        }
+        parser
+            .extract_json_from_bytes(&mut buffer, b"[\"\xe2\x98\"] [1]")
+            .unwrap();
+        assert_eq!(
+            String::from_utf8(buffer).unwrap(),
+            "[\"☕\", \"\u{FFFD}\"][\"\u{FFFD}\"][1]"
+        );
+    }
+
+    #[test]
+    fn test_json_parser_consume_from_invalid_bytes() {
+        let mut parser = JSONParser::new();
+        let mut buffer = Vec::new();
+
+        let consumed = parser
+            .consume_from(b"\xff [\"a\xffb\"] \xc3", &mut buffer)
+            .unwrap();
+
+        assert_eq!(consumed, 10);
+        assert_eq!(String::from_utf8(buffer).unwrap(), "[\"a\u{FFFD}b\"]");
+    }
+
+    #[test]
+    fn test_json_parser_nesting_hooks() {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let opened = Arc::clone(&events);
+        let closed = Arc::clone(&events);
+        let mut parser = JSONParser::new()
+            .on_open(move |depth, kind| opened.lock().unwrap().push(("open", depth, kind)))
+            .on_close(move |depth, kind| closed.lock().unwrap().push(("close", depth, kind)));
+
+        let mut buffer = Vec::new();
+        parser
+            .extract_json_from_stream(&mut buffer, "a {\"x\":[{\"y\":1},[]]} b")
+            .unwrap();
+        parser.extract_json_from_stream(&mut buffer, "[2]").unwrap();
+
+        use ContainerKind::{Array, Object};
+        assert_eq!(
+            *events.lock().unwrap(),
+            vec![
+                ("open", 0, Object),
+                ("open", 1, Array),
+                ("open", 2, Object),
+                ("close", 2, Object),
+                ("open", 2, Array),
+                ("close", 2, Array),
+                ("close", 1, Array),
+                ("close", 0, Object),
+                ("open", 0, Array),
+                ("close", 0, Array),
+            ]
+        );
+    }
+
+    /// A writer that counts calls and accepts a few bytes per slice, like a socket.
+    #[derive(Default)]
+    struct CountingWriter {
+        bytes: Vec<u8>,
+        calls: usize,
+    }
+
+    impl Write for CountingWriter {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.write_vectored(&[IoSlice::new(buf)])
+        }
+
+        fn write_vectored(&mut self, bufs: &[IoSlice<'_>]) -> std::io::Result<usize> {
+            self.calls += 1;
+            let mut written = 0;
+            for buf in bufs {
+                let length = buf.len().min(5);
+                self.bytes.extend_from_slice(&buf[..length]);
+                written += length;
+                if length < buf.len() {
+                    break;
+                }
+            }
+            Ok(written)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_json_parser_vectored_writes_match_char_writes() {
+        let input = "a {\"x\": \"é}\"} ] b [1, [2]] {\"open\": [tr";
+        let chunkings: [&[&str]; 2] = [
+            &[input],
+            &["a {\"x\": \"é", "}\"} ] b [1, [2", "]] {\"open\": [tr"],
+        ];
+
+        for chunks in chunkings {
+            let mut vectored = JSONParser::new();
+            let mut writer = CountingWriter::default();
+            // ASCII-only leaves this ASCII-safe input unchanged but writes char by char
+            let mut per_char = JSONParser::with_options(ParserOptions::new().ascii_only(true));
+            let mut expected = Vec::new();
+
+            for chunk in chunks {
+                let ascii_chunk = chunk.replace('é', "e");
+                vectored
+                    .extract_json_from_stream(&mut writer, &ascii_chunk)
+                    .unwrap();
+                per_char
+                    .extract_json_from_stream(&mut expected, &ascii_chunk)
+                    .unwrap();
+            }
+
+            assert_eq!(writer.bytes, expected);
+        }
+    }
+
+    #[test]
+    fn test_json_parser_vectored_writes_reduce_calls() {
+        let input = "x {\"a\":1} y {\"b\":2} z [3]";
+
+        let mut parser = JSONParser::new();
+        let mut writer = CountingWriter::default();
+        parser.extract_json_from_stream(&mut writer, input).unwrap();
+
+        assert_eq!(writer.bytes, b"{\"a\":1}{\"b\":2}[3]");
+        // 17 bytes at no more than 5 per slice and 3 spans
+        assert!(writer.calls <= 6, "{} write calls", writer.calls);
+    }
+
+    #[test]
+    fn test_json_parser_extract_json_from_reader() {
+        struct TrickleReader<'a>(&'a [u8]);
+
+        impl Read for TrickleReader<'_> {
+            fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+                let length = self.0.len().min(buf.len()).min(3);
+                buf[..length].copy_from_slice(&self.0[..length]);
+                self.0 = &self.0[length..];
+                Ok(length)
+            }
+        }
+
+        // Ends with the first byte of a three-byte character
+        let mut bytes = "log {\"name\":\"José 😀\"} end [1] ".as_bytes().to_vec();
+        bytes.push(0xe2);
+
+        let mut parser = JSONParser::new();
+        let mut buffer = Vec::new();
+        parser
+            .extract_json_from_reader(TrickleReader(&bytes), &mut buffer)
+            .unwrap();
+
+        assert_eq!(
+            String::from_utf8(buffer).unwrap(),
+            "{\"name\":\"José 😀\"}[1]"
+        );
+    }
+
+    #[test]
+    fn test_json_parser_normalize_smart_quotes() {
+        let mut parser =
+            JSONParser::with_options(ParserOptions::new().normalize_smart_quotes(true));
+        let mut buffer = Vec::new();
+
+        let input = "Sure! {\u{201C}name\u{201D}: \u{201C}Bob\u{2019}s\u{201D}, \
+                     \u{201C}quote\u{201D}: \"say \u{201C}hi\u{201D}\"} Done.";
+        parser.extract_json_from_stream(&mut buffer, input).unwrap();
+
+        assert!(!parser.is_in_json());
+        assert_eq!(
+            String::from_utf8(buffer).unwrap(),
+            "{\"name\": \"Bob's\", \"quote\": \"say \\\"hi\\\"\"}"
+        );
+    }
+
+    #[test]
+    fn test_json_parser_smart_quotes_kept_by_default() {
+        let mut parser = JSONParser::new();
+        let mut buffer = Vec::new();
+
+        parser
+            .extract_json_from_stream(&mut buffer, "[\"\u{201C}a\u{201D}\"]")
+            .unwrap();
+
+        assert_eq!(
+            String::from_utf8(buffer).unwrap(),
+            "[\"\u{201C}a\u{201D}\"]"
+        );
+    }
+
+    #[cfg(feature = "spill")]
+    #[test]
+    fn test_json_parser_spill_oversized_values() {
+        use crate::parser::spill::ValuePayload;
+
+        let mut parser = JSONParser::with_options(ParserOptions::new().spill_threshold(10));
+        let mut payloads = Vec::new();
+
+        for chunk in [
+            "a [1,2] b {\"data\":\"abc",
+            "defghij\"} c {\"n\":",
+            "0} {\"x\":[\"é\"]}",
+        ] {
+            parser
+                .extract_payloads_from_stream(chunk, |payload| payloads.push(payload))
+                .unwrap();
+        }
+
+        assert_eq!(payloads.len(), 4);
+        assert!(matches!(&payloads[0], ValuePayload::Inline(value) if value == "[1,2]"));
+        assert!(matches!(&payloads[2], ValuePayload::Inline(value) if value == "{\"n\":0}"));
+        assert!(matches!(&payloads[3], ValuePayload::Spilled(value) if value.len() == 12));
+
+        let ValuePayload::Spilled(spilled) = payloads.swap_remove(1) else {
+            panic!("expected a spilled value");
+        };
+        assert_eq!(spilled.len(), 21);
+        assert_eq!(
+            spilled.read_to_string().unwrap(),
+            "{\"data\":\"abcdefghij\"}"
+        );
+
+        // The temporary file is removed with the value
+        let path = spilled.path().to_path_buf();
+        assert!(path.exists());
+        drop(spilled);
+        assert!(!path.exists());
+    }
+
+    #[cfg(feature = "spill")]
+    #[test]
+    fn test_json_parser_spill_disabled_without_threshold() {
+        use crate::parser::spill::ValuePayload;
+
+        let mut parser = JSONParser::new();
+        let mut payloads = Vec::new();
+        let large = format!("[\"{}\"]", "x".repeat(10_000));
+
+        parser
+            .extract_payloads_from_stream(&large, |payload| payloads.push(payload))
+            .unwrap();
+
+        assert!(matches!(&payloads[..], [ValuePayload::Inline(value)] if *value == large));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_json_parser_auto_unwrap_json_string() {
+        let options = ParserOptions::new().auto_unwrap_json_string(true);
+
+        let (output, result) = writer_output(
+            options.clone(),
+            &char_chunks("payload: \"{\\\"a\\\":1,\\\"b\\\":[\\\"x\\\"]}\""),
+        );
+        result.unwrap();
+        assert_eq!(output, "{\"a\":1,\"b\":[\"x\"]}");
+
+        // Only the first value is unwrapped
+        let (output, result) = writer_output(
+            options.clone(),
+            &char_chunks("\"[1]\" then \"{\\\"a\\\":\\\"\\\\u00e9\\\"}\""),
+        );
+        result.unwrap();
+        assert_eq!(output, "[1]{\\\"a\\\":\\\"\\\\u00e9\\\"}");
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_json_parser_auto_unwrap_leaves_normal_values() {
+        let options = ParserOptions::new().auto_unwrap_json_string(true);
+
+        let (output, result) = writer_output(options.clone(), &char_chunks("x {\"a\":\"q\"} y"));
+        result.unwrap();
+        assert_eq!(output, "{\"a\":\"q\"}");
+        let (output, result) =
+            writer_output(options.clone(), &char_chunks("\"{\\\"note\\\": plain}\""));
+        result.unwrap();
+        assert_eq!(output, "{\\\"note\\\": plain}");
+        let (output, result) =
+            writer_output(options.clone(), &char_chunks("\"text with [brackets]\""));
+        result.unwrap();
+        assert_eq!(output, "[brackets]");
+    }
+
+    #[test]
+    fn test_json_parser_nul_policy() {
+        let input = ["log\0 {\"bin\":\"a\0b\"}"];
+
+        let (output, result) =
+            writer_output(ParserOptions::new().nul_policy(NulPolicy::Preserve), &input);
+        result.unwrap();
+        assert_eq!(output, "{\"bin\":\"a\0b\"}");
+
+        let (output, result) =
+            writer_output(ParserOptions::new().nul_policy(NulPolicy::Strip), &input);
+        result.unwrap();
+        assert_eq!(output, "{\"bin\":\"ab\"}");
+
+        let (_, result) = writer_output(ParserOptions::new().nul_policy(NulPolicy::Error), &input);
+        assert!(matches!(result, Err(ParseError::NulInString)));
+    }
+
+    #[test]
+    fn test_json_parser_nul_policy_defaults() {
+        let input = ["log\0 {\"bin\":\"a\0b\"}"];
+
+        for options in [ParserOptions::new(), ParserOptions::new().relaxed(true)] {
+            let (output, result) = writer_output(options, &input);
+            result.unwrap();
+            assert_eq!(output, "{\"bin\":\"a\0b\"}");
+        }
+        let (_, result) = writer_output(ParserOptions::new().strict(true), &input);
+        assert!(matches!(result, Err(ParseError::NulInString)));
+
+        // A NUL does not cost the values around it
+        let mut parser = JSONParser::new();
+        let mut buffer = Vec::new();
+        parser
+            .extract_json_from_stream(&mut buffer, "{\"a\":\"x\0\"} {\"d\":1}")
+            .unwrap();
+        assert_eq!(
+            String::from_utf8(buffer).unwrap(),
+            "{\"a\":\"x\0\"}{\"d\":1}"
+        );
+
+        let mut parser = JSONParser::with_options(ParserOptions::new().strict(true));
+        let error = parser
+            .extract_json_from_stream(&mut Vec::new(), "[\"\0\"]")
+            .unwrap_err();
+        assert_eq!(error.to_string(), "NUL character inside a JSON string");
+    }
+
+    /// Writes the JSON of the chunks, returning it with the first error, output limit included.
+    fn writer_output<S: AsRef<str>>(
+        options: ParserOptions,
+        chunks: &[S],
+    ) -> (String, Result<(), ParseError>) {
+        let mut parser = JSONParser::with_options(options);
+        let mut buffer = Vec::new();
+        let mut result = Ok(());
+
+        for chunk in chunks {
+            let extracted = parser.extract_json_from_stream(&mut buffer, chunk.as_ref());
+            result = result.and(extracted);
+        }
+
+        let result = result.and(parser.check_output_limit());
+        (String::from_utf8(buffer).unwrap(), result)
+    }
+
+    /// Extracts the values of the chunks, returning them with the first error.
+    fn values_output<S: AsRef<str>>(
+        options: ParserOptions,
+        chunks: &[S],
+    ) -> (Vec<String>, Result<(), ParseError>) {
+        let mut parser = JSONParser::with_options(options);
+        let mut values = Vec::new();
+        let mut result = Ok(());
+
+        for chunk in chunks {
+            let extracted = parser
+                .extract_values_from_stream(chunk.as_ref(), |value| values.push(value.to_string()));
+            result = result.and(extracted);
+        }
+
+        (values, result)
+    }
+
+    /// Splits the input into chunks of one character each.
+    #[cfg(feature = "serde")]
+    fn char_chunks(input: &str) -> Vec<String> {
+        input.chars().map(String::from).collect()
+    }
+
+    #[test]
+    fn test_json_parser_record_separator_recovers_from_corrupt_record() {
+        let options = ParserOptions::new().record_separator(0x1E);
+        let (values, result) = values_output(
+            options,
+            &["{\"n\":1}\u{1E}{\"n\":[2, {\"m\":\u{1E}junk {\"n\":3}\u{1E}"],
+        );
+        result.unwrap();
+        assert_eq!(values, vec!["{\"n\":1}", "{\"n\":3}"]);
+
+        // Without the option, the corrupt record swallows the one after it
+        let mut parser = JSONParser::new();
+        let mut values = Vec::new();
+        parser
+            .extract_values_from_stream(
+                "{\"n\":1}\u{1E}{\"n\":[2, {\"m\":\u{1E}junk {\"n\":3}\u{1E}",
+                |value| values.push(value.to_string()),
+            )
+            .unwrap();
+        assert_eq!(values, vec!["{\"n\":1}"]);
+    }
+
+    #[test]
+    fn test_json_parser_ndjson_drops_truncated_lines() {
+        let input = "{\"id\":1,\"msg\":\"cut\",\n\
+                     {\"id\":2,\"tags\":[\"a\"]}\r\n\
+                     {\"id\":3,\"tags\":[\n\
+                     {\"id\":4}\n";
+
+        // Without the mode, the truncated lines swallow the ones after them
+        let mut lenient = Vec::new();
+        JSONParser::new()
+            .extract_values_from_stream(input, |value| lenient.push(value.to_string()))
+            .unwrap();
+        assert!(!lenient.contains(&"{\"id\":2,\"tags\":[\"a\"]}".to_string()));
+
+        for split in 0..=input.len() {
+            let options = ParserOptions::new().ndjson(true);
+            let mut parser = JSONParser::with_options(options.clone());
+            let mut values = Vec::new();
+            let mut writer_parser = JSONParser::with_options(options);
+            let mut buffer = Vec::new();
+            for chunk in [&input[..split], &input[split..]] {
+                parser
+                    .extract_values_from_stream(chunk, |value| values.push(value.to_string()))
+                    .unwrap();
+                writer_parser
+                    .extract_json_from_stream(&mut buffer, chunk)
+                    .unwrap();
+            }
+
+            let expected = vec!["{\"id\":2,\"tags\":[\"a\"]}", "{\"id\":4}"];
+            assert_eq!(values, expected, "split at {}", split);
+            assert_eq!(String::from_utf8(buffer).unwrap(), expected.concat());
+        }
+
+        let (output, result) = writer_output(
+            ParserOptions::new().ndjson(true),
+            &["{\"a\":1,\"b\":[2\n{\"ok\":1}\n"],
+        );
+        result.unwrap();
+        assert_eq!(output, "{\"ok\":1}");
+    }
+
+    #[test]
+    fn test_json_parser_ndjson_newline_inside_string() {
+        let mut parser = JSONParser::ndjson();
+        parser
+            .feed("{\"text\":\"two\nlines\"}\n{\"id\":2}\n")
+            .unwrap();
+
+        assert_eq!(
+            parser.take_completed_values(),
+            vec!["{\"text\":\"two\nlines\"}", "{\"id\":2}"]
+        );
+    }
+
+    #[test]
+    fn test_json_parser_record_separator_writes_nothing_of_corrupt_record() {
+        let (output, result) = writer_output(
+            ParserOptions::new().record_separator(0x1E),
+            &["{\"id\":1}\u{1E}{\"id\":\u{1E}{\"id\":3}"],
+        );
+        result.unwrap();
+        assert_eq!(output, "{\"id\":1}{\"id\":3}");
+    }
+
+    #[test]
+    #[should_panic(expected = "record separator 0xe9 is not an ASCII byte")]
+    fn test_json_parser_record_separator_rejects_non_ascii_bytes() {
+        // 0xE9 would otherwise match every "é" of the input
+        ParserOptions::new().record_separator(0xE9);
+    }
+
+    #[test]
+    fn test_json_parser_record_separator_inside_string() {
+        let options = ParserOptions::new().record_separator(0x1E);
+        let (values, result) =
+            values_output(options, &["{\"s\":\"a\u{1E}b\"}\u{1E}[\"\\\"\u{1E}\"]"]);
+        result.unwrap();
+        assert_eq!(values, vec!["{\"s\":\"a\u{1E}b\"}", "[\"\\\"\u{1E}\"]"]);
+    }
+
+    #[test]
+    fn test_json_parser_max_string_bytes_abandons_value() {
+        let options = ParserOptions::new().max_string_bytes(6);
+        let mut parser = JSONParser::with_options(options);
+        let mut values = Vec::new();
+
+        let result = parser.extract_values_from_stream(
+            "[\"sixsix\"] {\"blob\":\"seven77\",\"next\":{\"n\":[1]}} {\"after\":true}",
+            |value| values.push(value.to_string()),
+        );
+
+        assert!(matches!(result, Err(ParseError::StringTooLong)));
+        assert_eq!(values, vec!["[\"sixsix\"]", "{\"after\":true}"]);
+
+        // The error is reported once
+        parser
+            .extract_values_from_stream("[3]", |value| values.push(value.to_string()))
+            .unwrap();
+        assert_eq!(values.last().unwrap(), "[3]");
+    }
+
+    #[test]
+    fn test_json_parser_max_string_bytes_writes_nothing_of_value() {
+        let (output, result) = writer_output(
+            ParserOptions::new().max_string_bytes(4),
+            &["{\"k\":\"toolong\",\"x\":[1]} [2]"],
+        );
+        assert!(matches!(result, Err(ParseError::StringTooLong)));
+        assert_eq!(output, "[2]");
+
+        let (output, result) = writer_output(
+            ParserOptions::new().max_string_bytes(8),
+            &["[1] {\"blob\":\"aGVsbG8gd29ybGQ=\"} [2]"],
+        );
+        assert!(matches!(result, Err(ParseError::StringTooLong)));
+        assert_eq!(output, "[1][2]");
+    }
+
+    #[test]
+    fn test_json_parser_percent_decode_surfaces_encoded_object() {
+        let options = ParserOptions::new().percent_decode_candidates(true);
+        let mut parser = JSONParser::with_options(options);
+        let mut values = Vec::new();
+
+        // The encoded run is split across chunks
+        for chunk in [
+            "url: /search?q=%7B%22a%22%3A",
+            "%5B1%2C2%5D%7D&page=2 then [3]",
+        ] {
+            parser
+                .extract_values_from_stream(chunk, |value| values.push(value.to_string()))
+                .unwrap();
+        }
+
+        assert_eq!(values, vec!["{\"a\":[1,2]}", "[3]"]);
+    }
+
+    #[test]
+    fn test_json_parser_percent_decode_leaves_stray_percent_alone() {
+        let options = ParserOptions::new().percent_decode_candidates(true);
+        let mut parser = JSONParser::with_options(options);
+        let mut buffer = Vec::new();
+
+        parser
+            .extract_json_from_stream(
+                &mut buffer,
+                "100% sure, 50%off %7Bbroken {\"discount\":\"50%7B%\"} %5B1,%7D",
+            )
+            .unwrap();
+
+        assert_eq!(
+            String::from_utf8(buffer).unwrap(),
+            "{\"discount\":\"50%7B%\"}"
+        );
+    }
+
+    #[test]
+    fn test_json_parser_push_char_matches_bulk_extraction() {
+        let text = "log {\"a\":[1,{\"b\":\"}\"}]} ] mid [2, 3]\u{1e}{\"c\":";
+        let options = || ParserOptions::new().record_separator(0x1E);
+
+        let mut bulk = Vec::new();
+        JSONParser::with_options(options())
+            .extract_json_from_stream(&mut bulk, text)
+            .unwrap();
+
+        let mut parser = JSONParser::with_options(options());
+        let mut pushed = Vec::new();
+        let mut completed = 0;
+        for item in text.chars() {
+            if parser.push_char(item, &mut pushed).unwrap() == CharOutcome::Completed {
+                completed += 1;
+            }
+        }
+
+        assert_eq!(pushed, bulk);
+        assert_eq!(completed, 2);
+    }
+
+    #[test]
+    fn test_json_parser_push_char_reports_abandoned_value_as_skipped() {
+        let mut parser = JSONParser::with_options(ParserOptions::new().max_string_bytes(2));
+        let mut buffer = Vec::new();
+
+        let outcomes: Vec<_> = "[\"abc\"]"
+            .chars()
+            .map(|item| parser.push_char(item, &mut buffer))
+            .collect();
+
+        assert!(matches!(outcomes[4], Err(ParseError::StringTooLong)));
+        assert_eq!(outcomes[6].as_ref().unwrap(), &CharOutcome::Skipped);
+        assert!(buffer.is_empty());
+    }
+
+    #[test]
+    fn test_json_parser_suppress_current_value_writes_no_more_of_it() {
+        let mut parser = JSONParser::new();
+        let mut buffer = Vec::new();
+
+        parser
+            .extract_json_from_stream(&mut buffer, "[1] {\"echo\": [\"a")
+            .unwrap();
+        buffer.clear();
+
+        parser.suppress_current_value();
+        parser
+            .extract_json_from_stream(&mut buffer, "]\", {\"x\": \"y\"}]} tail [2]")
+            .unwrap();
+
+        assert_eq!(String::from_utf8(buffer).unwrap(), "[2]");
+        assert!(!parser.is_in_json());
+    }
+
+    #[test]
+    fn test_json_parser_suppress_next_value_writes_nothing_of_it() {
+        let mut parser = JSONParser::new();
+        let mut buffer = Vec::new();
+        let mut outcomes = Vec::new();
+
+        parser.suppress_next_value();
+        for item in "x {\"a\": [1]} [2]".chars() {
+            outcomes.push(parser.push_char(item, &mut buffer).unwrap());
+        }
+
+        assert_eq!(String::from_utf8(buffer).unwrap(), "[2]");
+        assert_eq!(
+            outcomes
+                .iter()
+                .filter(|outcome| **outcome == CharOutcome::Completed)
+                .count(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_json_parser_suppress_current_value_drops_buffered_value() {
+        let mut parser = JSONParser::new();
+        let mut values = Vec::new();
+
+        parser
+            .extract_values_from_stream("{\"echo\": \"par", |value| values.push(value.to_string()))
+            .unwrap();
+        parser.suppress_current_value();
+        parser
+            .extract_values_from_stream("tial\"} {\"reply\": 1}", |value| {
+                values.push(value.to_string())
+            })
+            .unwrap();
+
+        assert_eq!(values, vec!["{\"reply\": 1}"]);
+    }
+
+    fn skip_debug(prefix: &str) -> ValueDecision {
+        if prefix.starts_with("{\"debug\"") {
+            ValueDecision::Skip
+        } else {
+            ValueDecision::Keep
+        }
+    }
+
+    #[test]
+    fn test_json_parser_value_prefix_keeps_value() {
+        let prefixes = Arc::new(Mutex::new(Vec::new()));
+        let seen = Arc::clone(&prefixes);
+        let mut parser = JSONParser::new().on_value_prefix(8, move |prefix| {
+            seen.lock().unwrap().push(prefix.to_string());
+            ValueDecision::Keep
+        });
+        let mut buffer = Vec::new();
+
+        for chunk in ["x {\"event\":", " \"login\", \"n\": [1]} y"] {
+            parser.extract_json_from_stream(&mut buffer, chunk).unwrap();
+        }
+
+        assert_eq!(
+            String::from_utf8(buffer).unwrap(),
+            "{\"event\": \"login\", \"n\": [1]}"
+        );
+        assert_eq!(*prefixes.lock().unwrap(), vec!["{\"event\""]);
+    }
+
+    #[test]
+    fn test_json_parser_value_prefix_skips_value() {
+        let mut parser = JSONParser::new().on_value_prefix(10, skip_debug);
+        let mut values = Vec::new();
+        let mut buffer = Vec::new();
+
+        parser
+            .extract_values_from_stream(
+                "{\"debug\": {\"trace\": [1, 2, 3]}} {\"id\": 1}",
+                |value| values.push(value.to_string()),
+            )
+            .unwrap();
+        parser
+            .extract_json_from_stream(&mut buffer, "{\"debug\": \"verbose\"} [2]")
+            .unwrap();
+
+        assert_eq!(values, vec!["{\"id\": 1}"]);
+        assert_eq!(String::from_utf8(buffer).unwrap(), "[2]");
+    }
+
+    #[test]
+    fn test_json_parser_value_prefix_decides_short_values_at_their_end() {
+        let prefixes = Arc::new(Mutex::new(Vec::new()));
+        let seen = Arc::clone(&prefixes);
+        let mut parser = JSONParser::new().on_value_prefix(100, move |prefix| {
+            seen.lock().unwrap().push(prefix.to_string());
+            skip_debug(prefix)
+        });
+        let mut buffer = Vec::new();
+
+        parser
+            .extract_json_from_stream(&mut buffer, "[1] {\"debug\":2} {\"a\":3}")
+            .unwrap();
+
+        assert_eq!(String::from_utf8(buffer).unwrap(), "[1]{\"a\":3}");
+        assert_eq!(
+            *prefixes.lock().unwrap(),
+            vec!["[1]", "{\"debug\":2}", "{\"a\":3}"]
+        );
+    }
+
+    #[test]
+    fn test_json_parser_control_char_escape_across_chunks() {
+        let options = ParserOptions::new().control_char_policy(ControlCharPolicy::Escape);
+        let mut parser = JSONParser::with_options(options);
+        let mut buffer = Vec::new();
+
+        for chunk in ["{\n\"msg\": \"a\tb\n", "c\r\u{1b}\",\t\"n\": 1}"] {
+            parser.extract_json_from_stream(&mut buffer, chunk).unwrap();
+        }
+
+        let output = String::from_utf8(buffer).unwrap();
+        assert_eq!(output, "{\n\"msg\": \"a\\tb\\nc\\r\\u001b\",\t\"n\": 1}");
+    }
+
+    #[test]
+    fn test_json_parser_control_char_reject_drops_value() {
+        let options = ParserOptions::new().control_char_policy(ControlCharPolicy::Reject);
+        let mut parser = JSONParser::with_options(options);
+        let mut values = Vec::new();
+
+        let first = parser.extract_values_from_stream("[1] {\"msg\": \"line", |value| {
+            values.push(value.to_string())
+        });
+        let second = parser.extract_values_from_stream("\n\tnext\"} {\"ok\":\n true}", |value| {
+            values.push(value.to_string())
+        });
+
+        assert!(first.is_ok());
+        assert!(matches!(second, Err(ParseError::ControlCharInString)));
+        assert_eq!(values, vec!["[1]", "{\"ok\":\n true}"]);
+    }
+
+    /// A writer recording its output at each flush, failing flushes once `fail` is set.
+    #[derive(Default)]
+    struct FlushRecorder {
+        bytes: Vec<u8>,
+        flushed: Vec<String>,
+        fail: bool,
+    }
+
+    impl Write for FlushRecorder {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.bytes.extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            if self.fail {
+                return Err(std::io::ErrorKind::BrokenPipe.into());
+            }
+            self.flushed
+                .push(String::from_utf8(self.bytes.clone()).unwrap());
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_json_parser_flush_per_value() {
+        let chunks = ["log {\"a\":", "1} [2] text [3", ", \"x\"] {\"open\": "];
+
+        for ascii_only in [false, true] {
+            let options = ParserOptions::new()
+                .flush_per_value(true)
+                .ascii_only(ascii_only);
+            let mut parser = JSONParser::with_options(options);
+            let mut writer = FlushRecorder::default();
+
+            for chunk in chunks {
+                parser.extract_json_from_stream(&mut writer, chunk).unwrap();
+            }
+
+            assert_eq!(
+                writer.flushed,
+                vec!["{\"a\":1}", "{\"a\":1}[2]", "{\"a\":1}[2][3, \"x\"]"]
+            );
+        }
+
+        let mut parser = JSONParser::with_options(ParserOptions::new().flush_per_value(true));
+        let mut writer = FlushRecorder::default();
+        for token in [
+            Token::Structural('['),
+            Token::Text("1"),
+            Token::Structural(']'),
+        ] {
+            parser.feed_token(&mut writer, token).unwrap();
+        }
+        assert_eq!(writer.flushed, vec!["[1]"]);
+    }
+
+    #[test]
+    fn test_json_parser_atomic_values_rotating_writers() {
+        let options = ParserOptions::new()
+            .atomic_values(true)
+            .max_string_bytes(8)
+            .flush_per_value(true);
+        let mut parser = JSONParser::with_options(options);
+        let mut first = FlushRecorder::default();
+        let mut second = FlushRecorder::default();
+
+        parser
+            .extract_json_from_stream(&mut first, "[0] next {\"id\":1,\"tags\":[\"a\",")
+            .unwrap();
+        assert_eq!(parser.value_output, b"{\"id\":1,\"tags\":[\"a\",");
+        parser
+            .extract_json_from_stream(&mut second, "\"b\"]} ]] [2")
+            .unwrap();
+
+        assert_eq!(first.bytes, b"[0]");
+        assert_eq!(first.flushed, vec!["[0]"]);
+        assert_eq!(second.bytes, b"{\"id\":1,\"tags\":[\"a\",\"b\"]}");
+        assert_eq!(second.flushed.len(), 1);
+
+        // An abandoned value leaves nothing behind, not even its start
+        let mut third = Vec::new();
+        let result = parser.extract_json_from_stream(&mut third, "] [\"far too long\"] [3]");
+        assert!(matches!(result, Err(ParseError::StringTooLong)));
+        assert_eq!(third, b"[2][3]");
+    }
+
+    #[test]
+    fn test_json_parser_flush_per_value_reports_flush_errors() {
+        let mut parser = JSONParser::with_options(ParserOptions::new().flush_per_value(true));
+        let mut writer = FlushRecorder {
+            fail: true,
+            ..Default::default()
+        };
+
+        let result = parser.extract_json_from_stream(&mut writer, "{\"a\":1}");
+        assert!(matches!(result, Err(ParseError::Io(_))));
+
+        // Without the option the writer is never flushed
+        let mut parser = JSONParser::new();
+        assert!(parser
+            .extract_json_from_stream(&mut writer, "{\"a\":1}")
+            .is_ok());
+    }
+
+    #[test]
+    fn test_json_parser_lone_surrogate_policies_split_everywhere() {
+        let input = "{\"hi\":\"\\ud83d!\",\"lo\":\"\\uDE00\",\"pair\":\"\\ud83d\\ude00\"} [1]";
+        let cases = [
+            (
+                LoneSurrogatePolicy::Preserve,
+                false,
+                false,
+                "\\ud83d!",
+                "\\uDE00",
+                "\\ud83d\\ude00",
+            ),
+            (
+                LoneSurrogatePolicy::Preserve,
+                true,
+                false,
+                "\\ud83d!",
+                "\\uDE00",
+                "😀",
+            ),
+            (
+                LoneSurrogatePolicy::Preserve,
+                true,
+                true,
+                "\\ud83d!",
+                "\\uDE00",
+                "\\ud83d\\ude00",
+            ),
+            (
+                LoneSurrogatePolicy::Replace,
+                false,
+                false,
+                "\\ufffd!",
+                "\\ufffd",
+                "\\ud83d\\ude00",
+            ),
+            (
+                LoneSurrogatePolicy::Replace,
+                false,
+                true,
+                "\\ufffd!",
+                "\\ufffd",
+                "\\ud83d\\ude00",
+            ),
+            (
+                LoneSurrogatePolicy::Replace,
+                true,
+                false,
+                "\u{fffd}!",
+                "\u{fffd}",
+                "😀",
+            ),
+            (
+                LoneSurrogatePolicy::Replace,
+                true,
+                true,
+                "\\ufffd!",
+                "\\ufffd",
+                "\\ud83d\\ude00",
+            ),
+        ];
+
+        for (policy, unescape, ascii_only, hi, lo, pair) in cases {
+            let options = ParserOptions::new()
+                .lone_surrogate_policy(policy)
+                .unescape_unicode(unescape)
+                .ascii_only(ascii_only);
+            let expected = format!("{{\"hi\":\"{hi}\",\"lo\":\"{lo}\",\"pair\":\"{pair}\"}}");
+
+            for split in 0..=input.len() {
+                let (values, result) =
+                    values_output(options.clone(), &[&input[..split], &input[split..]]);
+                result.unwrap();
+                assert_eq!(
+                    values,
+                    vec![expected.as_str(), "[1]"],
+                    "{policy:?} {unescape} {ascii_only} split at {split}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_json_parser_lone_surrogate_reject_split_everywhere() {
+        let inputs = [
+            "[\"\\ud83d\"] {\"ok\":\"\\ud83d\\ude00\"}",
+            "[\"\\ude00x\"] {\"ok\":\"\\ud83d\\ude00\"}",
+            "[\"\\ud83d\\u0041\"] {\"ok\":\"\\ud83d\\ude00\"}",
+        ];
+
+        for unescape in [false, true] {
+            let options = ParserOptions::new()
+                .lone_surrogate_policy(LoneSurrogatePolicy::Reject)
+                .unescape_unicode(unescape);
+            let ok = if unescape {
+                "{\"ok\":\"😀\"}"
+            } else {
+                "{\"ok\":\"\\ud83d\\ude00\"}"
+            };
+
+            for input in inputs {
+                for split in 0..=input.len() {
+                    let (values, result) =
+                        values_output(options.clone(), &[&input[..split], &input[split..]]);
+                    assert!(
+                        matches!(result, Err(ParseError::LoneSurrogate)),
+                        "{input} split at {split}"
+                    );
+                    assert_eq!(values, vec![ok], "{input} split at {split}");
+                }
+            }
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_json_parser_lone_surrogate_in_unwrapped_string() {
+        let input = "\"{\\\"a\\\":\\\"\\\\ud83d\\\"}\"";
+        for policy in [LoneSurrogatePolicy::Preserve, LoneSurrogatePolicy::Replace] {
+            let options = ParserOptions::new()
+                .auto_unwrap_json_string(true)
+                .lone_surrogate_policy(policy);
+            let (values, result) = values_output(options.clone(), &[input]);
+            result.unwrap();
+            assert_eq!(values.len(), 1);
+        }
+    }
+
+    #[test]
+    fn test_json_parser_whitespace_terminates_scalar() {
+        let text = "{\"n\": 1\u{b}, \"ok\": true\u{b}} [\"a\u{b}b\"\u{b}]";
 
-            if PAIRED_MARKERS.contains(&item) {
-                self.buffer.push(item);
-                self.update_markers(&item);
-                write!(writer, "{}", item)?;
-            }
+        let options = ParserOptions::new().whitespace(&[' ', '\u{b}']);
+        let mut parser = JSONParser::with_options(options);
+        let mut values = Vec::new();
+        parser
+            .extract_values_from_stream(text, |value| values.push(value.to_string()))
+            .unwrap();
+
+        assert_eq!(values, vec!["{\"n\": 1 , \"ok\": true }", "[\"a\u{b}b\" ]"]);
+        assert!(crate::parser::grammar::is_valid_json(&values[0]));
+
+        let mut buffer = Vec::new();
+        JSONParser::new()
+            .extract_json_from_stream(&mut buffer, "[1\u{b}]")
+            .unwrap();
+        assert_eq!(String::from_utf8(buffer).unwrap(), "[1\u{b}]");
+    }
+
+    #[test]
+    fn test_json_parser_split_top_level_arrays() {
+        let input = "rows: [ {\"a\":[1,{\"b\":[]}]}, [[2], {}], \"[x]\", 3,\n [] ] then {\"c\":4}";
+        let expected = vec!["{\"a\":[1,{\"b\":[]}]}", "[[2], {}]", "[]", "{\"c\":4}"];
+        let options = ParserOptions::new().split_top_level_arrays(true);
+
+        // Every split point, including inside elements and between them
+        for split in 0..=input.len() {
+            let chunks = [&input[..split], &input[split..]];
+            let (output, result) = writer_output(options.clone(), &chunks);
+            result.unwrap();
+            let (values, result) = values_output(options.clone(), &chunks);
+            result.unwrap();
+            assert_eq!(output, expected.concat(), "split at {}", split);
+            assert_eq!(values, expected, "split at {}", split);
         }
+    }
 
-        Ok(())
+    #[test]
+    fn test_json_parser_split_top_level_arrays_per_value_options() {
+        let options = ParserOptions::new()
+            .split_top_level_arrays(true)
+            .flush_per_value(true);
+        let mut parser = JSONParser::with_options(options);
+        let mut writer = FlushRecorder::default();
+        parser
+            .extract_json_from_stream(&mut writer, "[{\"id\":1},{\"id\"")
+            .unwrap();
+        parser
+            .extract_json_from_stream(&mut writer, ":2}]")
+            .unwrap();
+        assert_eq!(writer.flushed, vec!["{\"id\":1}", "{\"id\":1}{\"id\":2}"]);
+
+        // A record separator closes the array and drops the element in progress
+        let options = ParserOptions::new()
+            .split_top_level_arrays(true)
+            .record_separator(0x1E);
+        let input = ["[[1], [2\u{1e}[[3]] [{}, 5]"];
+        let (output, result) = writer_output(options.clone(), &input);
+        result.unwrap();
+        let (values, result) = values_output(options, &input);
+        result.unwrap();
+        assert_eq!(output, "[1][3]{}");
+        assert_eq!(values, vec!["[1]", "[3]", "{}"]);
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use std::io::BufWriter;
+    #[test]
+    fn test_json_parser_markers_inside_strings() {
+        let inputs = [
+            "{\"msg\":\"use {braces} carefully\"}",
+            "{\"path\":\"a[0]\"}",
+            "{\"open\":\"{\",\"close\":\"]\"}",
+            "{\"{key}\":1,\"[\":2}",
+            "[\"}\", \"[[\", \"x{\"]",
+        ];
+
+        for json in inputs {
+            let input = format!("log {} tail [1]", json);
+            let expected = format!("{}[1]", json);
+
+            // Every chunk boundary, including inside the strings
+            for split in 0..=input.len() {
+                let mut parser = JSONParser::new();
+                let mut buffer = Vec::new();
+                parser
+                    .extract_json_from_stream(&mut buffer, &input[..split])
+                    .unwrap();
+                parser
+                    .extract_json_from_stream(&mut buffer, &input[split..])
+                    .unwrap();
+
+                assert_eq!(String::from_utf8(buffer).unwrap(), expected, "{}", split);
+                assert!(!parser.is_in_json());
+            }
+        }
+    }
 
     #[test]
-    fn test_json_parser_empty() {
-        let parser = JSONParser::new();
+    fn test_json_parser_escaped_quotes_inside_strings() {
+        let input = "a {\"quoted\":\"say \\\"}\\\" {\"} \
+                     b {\"dir\":\"C:\\\\dir\\\\{weird}\\\\\"} \
+                     c [\"\\\"[\\\"\", \"]\"] d";
+        let expected = vec![
+            "{\"quoted\":\"say \\\"}\\\" {\"}",
+            "{\"dir\":\"C:\\\\dir\\\\{weird}\\\\\"}",
+            "[\"\\\"[\\\"\", \"]\"]",
+        ];
+
+        let mut values = Vec::new();
+        let mut parser = JSONParser::new();
+        for chunk in input.split_inclusive('\\') {
+            parser
+                .extract_values_from_stream(chunk, |value| values.push(value.to_string()))
+                .unwrap();
+        }
+        assert_eq!(values, expected);
         assert!(!parser.is_in_json());
-        assert!(parser.markers.is_empty());
+
+        for json in expected {
+            assert!(is_single_value(json), "{}", json);
+            assert_eq!(crate::extract_json_to_string(json).unwrap(), json);
+        }
     }
 
     #[test]
-    fn test_json_parser_extract_simple_json() {
+    fn test_json_parser_escaped_quote_split_after_backslash() {
+        let input = "{\"quote\":\"he said \\\"hi\\\"\", \"end\":\"\\\\\"} done";
+        let expected = "{\"quote\":\"he said \\\"hi\\\"\", \"end\":\"\\\\\"}";
+
+        // Split right after every backslash, so the escaped character starts the next chunk
+        for (index, _) in input.match_indices('\\') {
+            let (first, second) = input.split_at(index + 1);
+            let mut parser = JSONParser::new();
+            let mut buffer = Vec::new();
+
+            parser.extract_json_from_stream(&mut buffer, first).unwrap();
+            assert!(parser.is_in_json(), "split at {}", index + 1);
+            parser
+                .extract_json_from_stream(&mut buffer, second)
+                .unwrap();
+
+            assert_eq!(String::from_utf8(buffer).unwrap(), expected);
+            assert!(!parser.is_in_json(), "split at {}", index + 1);
+        }
+    }
+
+    #[test]
+    fn test_json_parser_backslashes_before_quotes() {
+        // An escaped backslash ends the string at the next quote, and a quote
+        // after an escaped backslash and another backslash is escaped itself
+        let values = [
+            "{\"msg\":\"he said \\\"hi\\\"\"}",
+            "{\"a\":\"ends with backslash\\\\\",\"b\":\"{\"}",
+            "[\"\\\\\\\"}\", \"\\\\\\\\\"]",
+        ];
+
+        for value in values {
+            let input = format!("x {} y", value);
+            for split in 0..=input.len() {
+                let mut parser = JSONParser::new();
+                let mut buffer = Vec::new();
+                parser
+                    .extract_json_from_stream(&mut buffer, &input[..split])
+                    .unwrap();
+                parser
+                    .extract_json_from_stream(&mut buffer, &input[split..])
+                    .unwrap();
+
+                assert_eq!(String::from_utf8(buffer).unwrap(), value, "{}", split);
+                assert!(!parser.is_in_json());
+            }
+        }
+    }
+
+    #[test]
+    fn test_json_parser_backslash_runs_before_quote() {
+        for run in 0..=6 {
+            let backslashes = "\\".repeat(run);
+            // An odd run escapes the quote, which then needs a second one to close the string
+            let value = if run % 2 == 0 {
+                format!("{{\"path\":\"C:{}\"}}", backslashes)
+            } else {
+                format!("{{\"path\":\"C:{}\"\"}}", backslashes)
+            };
+            let input = format!("{} rest", value);
+
+            for split in 0..=input.len() {
+                let mut parser = JSONParser::new();
+                let mut buffer = Vec::new();
+                parser
+                    .extract_json_from_stream(&mut buffer, &input[..split])
+                    .unwrap();
+                // The marker stack empties exactly at the closing brace
+                assert_eq!(parser.is_in_json(), split > 0 && split < value.len());
+                parser
+                    .extract_json_from_stream(&mut buffer, &input[split..])
+                    .unwrap();
+
+                assert_eq!(buffer, value.as_bytes(), "run {} split at {}", run, split);
+                assert!(!parser.is_in_json());
+            }
+        }
+    }
+
+    #[test]
+    fn test_json_parser_mismatched_closers() {
+        let input = "a {\"a\":[1,2} b {] c [} d [{\"ok\":[]}] e";
+
+        let mut values = Vec::new();
         let mut parser = JSONParser::new();
+        parser
+            .extract_values_from_stream(input, |value| values.push(value.to_string()))
+            .unwrap();
+        assert_eq!(values, vec!["[{\"ok\":[]}]"]);
+        assert!(!parser.is_in_json());
+
+        // Nothing of a value dropped within the call is written
+        for options in [ParserOptions::new(), ParserOptions::new().ascii_only(true)] {
+            let (output, result) = writer_output(options.clone(), &[input]);
+            result.unwrap();
+            assert_eq!(output, "[{\"ok\":[]}]");
+
+            let (output, result) = writer_output(options.clone(), &["x {\"a\":[1,2} y {\"b\":1}"]);
+            result.unwrap();
+            assert_eq!(output, "{\"b\":1}");
+
+            let (output, result) = writer_output(options, &["[1,2}"]);
+            result.unwrap();
+            assert_eq!(output, "");
+        }
+        assert_eq!(extract_json_to_string("[1,2}").unwrap(), "");
+        assert!(!is_single_value("{\"a\":[1,2}"));
+        assert!(!is_single_value("[}"));
+    }
+
+    #[test]
+    fn test_is_single_value_scans_nul_characters() {
+        for json in ["{\"bin\":\"a\0b\"}", "[1,\0 2]"] {
+            assert!(is_single_value(json), "{:?}", json);
+            assert_eq!(crate::extract_json_to_string(json).unwrap(), json);
+        }
+    }
+
+    #[test]
+    fn test_json_parser_mismatched_closers_chunked() {
+        let cases = [
+            ("x [{] [1]", vec!["[1]"]),
+            ("x {[} {}", vec!["{}"]),
+            ("x {\"a\":[{\"b\":[1}]}} {\"c\":[]}", vec!["{\"c\":[]}"]),
+            ("x [[{\"d\":{]}]] [[2]]", vec!["[[2]]"]),
+        ];
+
+        for (input, expected) in cases {
+            for split in 0..=input.len() {
+                let mut parser = JSONParser::new();
+                let mut values = Vec::new();
+                for chunk in [&input[..split], &input[split..]] {
+                    parser
+                        .extract_values_from_stream(chunk, |value| values.push(value.to_string()))
+                        .unwrap();
+                }
+
+                assert_eq!(values, expected, "{:?} split at {}", input, split);
+                assert!(!parser.is_in_json());
+            }
+        }
+    }
+
+    #[test]
+    fn test_json_parser_stray_closers() {
+        let input = "} ] before {\"a\":1} ]} between [2] } after ]";
+
+        for split in 0..=input.len() {
+            let mut parser = JSONParser::new();
+            let mut buffer = Vec::new();
+            for chunk in [&input[..split], &input[split..]] {
+                parser.extract_json_from_stream(&mut buffer, chunk).unwrap();
+            }
+
+            assert_eq!(
+                String::from_utf8(buffer).unwrap(),
+                "{\"a\":1}[2]",
+                "split at {}",
+                split
+            );
+            assert!(!parser.is_in_json());
+        }
+
+        let mut values = Vec::new();
+        JSONParser::new()
+            .extract_values_from_stream(input, |value| values.push(value.to_string()))
+            .unwrap();
+        assert_eq!(values, vec!["{\"a\":1}", "[2]"]);
+
         let mut buffer = Vec::new();
+        JSONParser::new()
+            .extract_json_from_stream(&mut buffer, "end of section ] see }")
+            .unwrap();
+        assert!(buffer.is_empty());
+    }
 
-        {
-            let mut writer = BufWriter::new(&mut buffer);
-            parser.extract_json_from_stream(&mut writer, "{}").unwrap();
-            // Flush the writer to ensure all data is written to the underlying buffer
-            writer.flush().unwrap();
+    #[test]
+    fn test_json_parser_validate_value_starts_template_braces() {
+        let input = "render the template {user_name} and then the payload {\"id\":1}, \
+                     or {{ name }} and { \"ok\": [ ] } and [x] [ 2 ]";
+        let options = ParserOptions::new().validate_value_starts(true);
+
+        for split in 0..=input.len() {
+            let chunks = [&input[..split], &input[split..]];
+            let (output, result) = writer_output(options.clone(), &chunks);
+            result.unwrap();
+            let (values, result) = values_output(options.clone(), &chunks);
+            result.unwrap();
+            assert_eq!(
+                output, "{\"id\":1}{ \"ok\": [ ] }[ 2 ]",
+                "split at {}",
+                split
+            );
+            assert_eq!(values, vec!["{\"id\":1}", "{ \"ok\": [ ] }", "[ 2 ]"]);
         }
 
-        assert!(!parser.is_in_json());
+        // Without the option, the placeholder is taken for a value
+        let mut buffer = Vec::new();
+        JSONParser::new()
+            .extract_json_from_stream(&mut buffer, "hi {user_name}")
+            .unwrap();
+        assert_eq!(String::from_utf8(buffer).unwrap(), "{user_name}");
+    }
 
-        let output = String::from_utf8(buffer).unwrap();
-        assert_eq!(output, "{}");
+    #[test]
+    fn test_json_parser_validate_value_starts_code_snippets() {
+        let input = "int main() { if (a[i] > 0) { return 0; } }\n\
+                     log({\"event\":\"exit\",\"codes\":[0,-1]});";
+
+        let options = ParserOptions::new().validate_value_starts(true);
+        let (output, result) = writer_output(options.clone(), &[input]);
+        result.unwrap();
+        let (values, result) = values_output(options, &[input]);
+        result.unwrap();
+        assert_eq!(output, "{\"event\":\"exit\",\"codes\":[0,-1]}");
+        assert_eq!(values, vec!["{\"event\":\"exit\",\"codes\":[0,-1]}"]);
     }
 
     #[test]
-    fn test_json_parser_extract_nested_json() {
-        let mut parser = JSONParser::new();
+    fn test_json_parser_validate_value_starts_false_positive_then_object() {
+        // The character failing the check starts the genuine value
+        let options = ParserOptions::new().validate_value_starts(true);
+        let input = ["{{\"a\":1} [{\"b\":[]}] {[1]}"];
+        let (output, result) = writer_output(options.clone(), &input);
+        result.unwrap();
+        let (values, result) = values_output(options.clone(), &input);
+        result.unwrap();
+        assert_eq!(output, "{\"a\":1}[{\"b\":[]}][1]");
+        assert_eq!(values, vec!["{\"a\":1}", "[{\"b\":[]}]", "[1]"]);
+
+        // A nested structure failing the check drops the whole value
+        let (values, result) = values_output(options, &["[1, {x}] then {\"c\":true}"]);
+        result.unwrap();
+        assert_eq!(values, vec!["{\"c\":true}"]);
+
+        let options = ParserOptions::new().validate_value_starts(true);
+        let mut parser = JSONParser::with_options(options);
         let mut buffer = Vec::new();
+        let outcomes: Vec<CharOutcome> = "{ {}"
+            .chars()
+            .map(|item| parser.push_char(item, &mut buffer).unwrap())
+            .collect();
+        assert_eq!(
+            outcomes,
+            vec![
+                CharOutcome::Started,
+                CharOutcome::Skipped,
+                CharOutcome::Started,
+                CharOutcome::Completed,
+            ]
+        );
+        assert_eq!(String::from_utf8(buffer).unwrap(), "{}");
+    }
 
-        {
-            let mut writer = BufWriter::new(&mut buffer);
-            parser
-                .extract_json_from_stream(&mut writer, "{\"key\": [1, 2, 3]}")
-                .unwrap();
-            assert!(!parser.is_in_json());
+    #[test]
+    fn test_json_parser_extract_scalars() {
+        let input = "result: true, count: 42, name: \"Alice\", temperature: 98.6 degrees, \
+                     build v1.2 on 2024-01-01 gave {\"ok\":1} and null\n";
+        let expected = vec!["true", "42", "\"Alice\"", "98.6", "{\"ok\":1}", "null"];
+        let written = "true\n42\n\"Alice\"\n98.6\n{\"ok\":1}null\n";
+
+        for split in 0..=input.len() {
+            let options = ParserOptions::new().extract_scalars(true);
+            let mut parser = JSONParser::with_options(options.clone());
+            let mut buffer = Vec::new();
+            let mut values = Vec::new();
+            let mut value_parser = JSONParser::with_options(options);
+            for chunk in [&input[..split], &input[split..]] {
+                parser.extract_json_from_stream(&mut buffer, chunk).unwrap();
+                value_parser
+                    .extract_values_from_stream(chunk, |value| values.push(value.to_string()))
+                    .unwrap();
+            }
+
+            assert_eq!(
+                String::from_utf8(buffer).unwrap(),
+                written,
+                "split at {}",
+                split
+            );
+            assert_eq!(values, expected, "split at {}", split);
         }
 
-        let output = String::from_utf8(buffer).unwrap();
-        assert_eq!(output, "{\"key\": [1, 2, 3]}");
+        // Scalars are left alone by default
+        let mut buffer = Vec::new();
+        JSONParser::new()
+            .extract_json_from_stream(&mut buffer, "count: 42 ")
+            .unwrap();
+        assert!(buffer.is_empty());
     }
 
     #[test]
-    fn test_json_parser_extract_partial_json() {
-        let mut parser = JSONParser::new();
+    fn test_json_parser_extract_scalars_needs_following_character() {
+        let options = ParserOptions::new().extract_scalars(true);
+        let mut parser = JSONParser::with_options(options);
         let mut buffer = Vec::new();
+        let outcomes: Vec<CharOutcome> = "n=12 "
+            .chars()
+            .map(|item| parser.push_char(item, &mut buffer).unwrap())
+            .collect();
 
-        {
-            let mut writer = BufWriter::new(&mut buffer);
-            parser
-                .extract_json_from_stream(&mut writer, "{\"key")
-                .unwrap();
-            assert!(parser.is_in_json());
+        assert_eq!(
+            outcomes,
+            vec![
+                CharOutcome::Skipped,
+                CharOutcome::Skipped,
+                CharOutcome::Skipped,
+                CharOutcome::Skipped,
+                CharOutcome::Completed,
+            ]
+        );
+        assert_eq!(String::from_utf8(buffer).unwrap(), "12\n");
 
-            parser
-                .extract_json_from_stream(&mut writer, "\": [1, 2, 3]}")
-                .unwrap();
-            assert!(!parser.is_in_json());
+        // Nothing follows the last number, so it could still go on
+        let mut buffer = Vec::new();
+        parser.extract_json_from_stream(&mut buffer, "n=7").unwrap();
+        assert!(buffer.is_empty());
+        parser.extract_json_from_stream(&mut buffer, "5;").unwrap();
+        assert_eq!(String::from_utf8(buffer).unwrap(), "75\n");
+    }
+
+    #[test]
+    fn test_json_parser_extract_scalars_keeps_written_scalars_apart() {
+        let options = || ParserOptions::new().extract_scalars(true);
+
+        let (output, result) = writer_output(options(), &["version 2 released in 2024!"]);
+        result.unwrap();
+        assert_eq!(output, "2\n2024\n");
+
+        let (output, result) = writer_output(options(), &["true 42 \"Alice\" "]);
+        result.unwrap();
+        let values: Vec<serde_json::Value> = output
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(
+            values,
+            vec![
+                serde_json::json!(true),
+                serde_json::json!(42),
+                serde_json::json!("Alice")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_json_parser_strict_drops_grammar_violations() {
+        let input = "12:00 worker{pid=1234} up {\"job\":7} [tid 5] {\"a\" 1} [1, 2,] \
+                     {user=bob, ok=true} [true, nul] {\"ok\":[1.5e3,-0,\"x\\u00e9\"]}";
+
+        let mut lenient = Vec::new();
+        JSONParser::new()
+            .extract_values_from_stream(input, |value| lenient.push(value.to_string()))
+            .unwrap();
+        assert_eq!(
+            lenient,
+            vec![
+                "{pid=1234}",
+                "{\"job\":7}",
+                "[tid 5]",
+                "{\"a\" 1}",
+                "[1, 2,]",
+                "{user=bob, ok=true}",
+                "[true, nul]",
+                "{\"ok\":[1.5e3,-0,\"x\\u00e9\"]}",
+            ]
+        );
+
+        for split in 0..=input.len() {
+            let options = ParserOptions::new().strict(true);
+            let mut parser = JSONParser::with_options(options.clone());
+            let mut values = Vec::new();
+            let mut writer_parser = JSONParser::with_options(options.clone());
+            let mut buffer = Vec::new();
+            for chunk in [&input[..split], &input[split..]] {
+                parser
+                    .extract_values_from_stream(chunk, |value| values.push(value.to_string()))
+                    .unwrap();
+                writer_parser
+                    .extract_json_from_stream(&mut buffer, chunk)
+                    .unwrap();
+            }
+
+            let expected = vec!["{\"job\":7}", "{\"ok\":[1.5e3,-0,\"x\\u00e9\"]}"];
+            assert_eq!(values, expected, "split at {}", split);
+            assert_eq!(String::from_utf8(buffer).unwrap(), expected.concat());
         }
 
-        let output = String::from_utf8(buffer).unwrap();
-        assert_eq!(output, "{\"key\": [1, 2, 3]}");
+        // Nothing of a value failing part way is written
+        let (output, result) = writer_output(
+            ParserOptions::new().strict(true),
+            &["{pid=1} [tid 5] [1 2] {\"a\": } {key: 1} {\"ok\":[1,2]}"],
+        );
+        result.unwrap();
+        assert_eq!(output, "{\"ok\":[1,2]}");
     }
 
     #[test]
-    fn test_json_parser_extract_json_mixed_with_text() {
-        let mut parser = JSONParser::new();
+    fn test_json_parser_max_depth() {
+        let options = ParserOptions::new().max_depth(64);
+        let nested = |depth: usize| format!("{}{}", "[".repeat(depth), "]".repeat(depth));
+
+        // At the limit
+        let mut parser = JSONParser::with_options(options.clone());
         let mut buffer = Vec::new();
+        parser
+            .extract_json_from_stream(&mut buffer, &nested(64))
+            .unwrap();
+        assert_eq!(String::from_utf8(buffer).unwrap(), nested(64));
 
-        {
-            let mut writer = BufWriter::new(&mut buffer);
+        // Past the limit, the rest of the value is dropped and the next one is extracted
+        let mut parser = JSONParser::with_options(options);
+        let mut buffer = Vec::new();
+        let input = format!("{} {{\"[\":[1]}}", nested(10_000));
+        let result = parser.extract_json_from_stream(&mut buffer, &input);
+        assert!(matches!(
+            result,
+            Err(ParseError::DepthLimitExceeded { limit: 64 })
+        ));
+        assert!(parser.markers.capacity() <= 64);
+        assert_eq!(String::from_utf8(buffer).unwrap(), "{\"[\":[1]}");
+        assert!(!parser.is_in_json());
 
-            // Plain text followed by JSON
-            parser
-                .extract_json_from_stream(&mut writer, "Some plain text {\"id\": 123, \"data\": ")
-                .unwrap();
-            assert!(parser.is_in_json());
+        // Brackets inside strings do not count
+        let mut parser = JSONParser::with_options(ParserOptions::new().max_depth(1));
+        let mut values = Vec::new();
+        parser
+            .extract_values_from_stream("{\"a\":\"[[{\"} [[1]] [2]", |value| {
+                values.push(value.to_string())
+            })
+            .unwrap_err();
+        assert_eq!(values, vec!["{\"a\":\"[[{\"}", "[2]"]);
 
-            // More JSON with nested structure
-            parser
-                .extract_json_from_stream(&mut writer, "{\"nested\": [1, 2, {\"deep\": true}]}}")
-                .unwrap();
-            assert!(!parser.is_in_json());
+        // Writers receive nothing of the deep value either
+        let (output, result) = writer_output(
+            ParserOptions::new().max_depth(2),
+            &["[[1]] [[[2]]] {\"a\":[3]}"],
+        );
+        assert!(matches!(
+            result,
+            Err(ParseError::DepthLimitExceeded { limit: 2 })
+        ));
+        assert_eq!(output, "[[1]]{\"a\":[3]}");
+    }
 
-            // JSON followed by plain text
-            parser
-                .extract_json_from_stream(&mut writer, " followed by more text")
-                .unwrap();
-            assert!(!parser.is_in_json());
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_json_parser_strict_writes_only_serde_parsable_values() {
+        let input = "a {\"a\": } b {key: 1} c {\"a\": [1, {}]} d [,] e {\"b\":\"\\q\"} f [null]";
+        let mut buffer = Vec::new();
+        let mut values = Vec::new();
 
-            // Another JSON object
-            parser
-                .extract_json_from_stream(&mut writer, " and another {\"array\": [4, 5, 6]}")
-                .unwrap();
+        JSONParser::strict()
+            .extract_json_from_stream(&mut buffer, input)
+            .unwrap();
+        JSONParser::strict()
+            .extract_values_from_stream(input, |value| values.push(value.to_string()))
+            .unwrap();
+
+        assert_eq!(String::from_utf8(buffer).unwrap(), "{\"a\": [1, {}]}[null]");
+        assert_eq!(values, vec!["{\"a\": [1, {}]}", "[null]"]);
+        for value in values {
+            assert!(serde_json::from_str::<serde_json::Value>(&value).is_ok());
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_json_parser_validate_values_skips_balanced_invalid_values() {
+        let input = "x {\"a\": } y {key: 1} z {\"ok\": [1, 2]} [1,]";
+        let options = ParserOptions::new().validate_values(true);
+
+        let (written, result) = writer_output(options.clone(), &[input]);
+        result.unwrap();
+        assert_eq!(written, "{\"ok\": [1, 2]}");
+
+        let mut values = Vec::new();
+        JSONParser::with_options(options.clone())
+            .extract_values_from_stream(input, |value| values.push(value.to_string()))
+            .unwrap();
+        assert_eq!(values, vec!["{\"ok\": [1, 2]}"]);
+
+        // Chunks ending inside a value hold it until it can be checked
+        let mut parser = JSONParser::with_options(options);
+        let mut buffer = Vec::new();
+        for chunk in ["{\"a\": ", "} {\"b\"", ": 2}"] {
+            parser.extract_json_from_stream(&mut buffer, chunk).unwrap();
+        }
+        assert_eq!(String::from_utf8(buffer).unwrap(), "{\"b\": 2}");
+    }
+
+    #[test]
+    fn test_json_parser_strict_recovers_after_corrupt_value() {
+        let input = "{\"a\": 1,,} then {\"b\": 2} and {\"x\" [{\"c\": [3]}]}";
+
+        for split in 0..=input.len() {
+            let mut parser = JSONParser::with_options(ParserOptions::new().strict(true));
+            let mut values = Vec::new();
+            for chunk in [&input[..split], &input[split..]] {
+                parser
+                    .extract_values_from_stream(chunk, |value| values.push(value.to_string()))
+                    .unwrap();
+            }
+
+            assert_eq!(
+                values,
+                vec!["{\"b\": 2}", "[{\"c\": [3]}]"],
+                "split at {}",
+                split
+            );
             assert!(!parser.is_in_json());
         }
 
-        let output = String::from_utf8(buffer).unwrap();
+        let (output, result) = writer_output(
+            ParserOptions::new().strict(true),
+            &["{\"a\":1,,} {\"b\":2} {\"c\":3}"],
+        );
+        result.unwrap();
+        assert_eq!(output, "{\"b\":2}{\"c\":3}");
+    }
+
+    #[test]
+    fn test_json_parser_max_buffer_size() {
+        let options = ParserOptions::new().max_buffer_size(1024);
+        let runaway = format!("model says {{ {}", "and on ".repeat(1_000));
+
+        // The call holding the runaway value fails, and later calls recover
+        let mut parser = JSONParser::with_options(options.clone());
+        let mut values = Vec::new();
+        let mut results = Vec::new();
+        for chunk in [runaway.as_str(), " {\"ok\":1}", " [2]"] {
+            results.push(
+                parser.extract_values_from_stream(chunk, |value| values.push(value.to_string())),
+            );
+        }
+        assert!(matches!(
+            results[0],
+            Err(ParseError::BufferLimitExceeded { limit: 1024 })
+        ));
+        assert!(results[1].is_ok() && results[2].is_ok());
+        assert_eq!(values, vec!["{\"ok\":1}", "[2]"]);
+        assert!(parser.buffer.capacity() <= 1024);
+
+        // Writers receive nothing of the runaway value
+        let (output, result) = writer_output(
+            ParserOptions::new().max_buffer_size(16),
+            &["a stray { brace and a long tail of text, then {\"ok\":1}"],
+        );
+        assert!(matches!(
+            result,
+            Err(ParseError::BufferLimitExceeded { limit: 16 })
+        ));
+        assert_eq!(output, "{\"ok\":1}");
+
+        // A value as long as the limit is kept
+        let value = format!("[\"{}\"]", "x".repeat(1020));
+        let mut parser = JSONParser::with_options(options);
+        let mut buffer = Vec::new();
+        parser
+            .extract_json_from_stream(&mut buffer, &format!("a {} b", value))
+            .unwrap();
+        assert_eq!(String::from_utf8(buffer).unwrap(), value);
+    }
+
+    #[test]
+    fn test_json_parser_max_object_bytes() {
+        let options = ParserOptions::new().max_object_bytes(1024);
+        let huge = format!("{{\"blob\":[{}0]}}", "{\"a\":[1]},".repeat(10_000));
+        let chunks: Vec<&str> = huge
+            .as_bytes()
+            .chunks(4096)
+            .map(|chunk| std::str::from_utf8(chunk).unwrap())
+            .chain(["log line {\"small\":1}"])
+            .collect();
+
+        // The limit holds across calls, and nothing of the huge object is taken for a value
+        let mut parser = JSONParser::with_options(options.clone());
+        let mut values = Vec::new();
+        let mut errors = 0;
+        for chunk in &chunks {
+            if let Err(error) =
+                parser.extract_values_from_stream(chunk, |value| values.push(value.to_string()))
+            {
+                assert!(matches!(error, ParseError::ObjectTooLarge { limit: 1024 }));
+                errors += 1;
+            }
+        }
+        assert_eq!(errors, 1);
+        assert_eq!(values, vec!["{\"small\":1}"]);
+        assert!(parser.buffer.capacity() <= 1024);
+
+        // Writers receive only the small object
+        let mut parser = JSONParser::with_options(options);
+        let mut buffer = Vec::new();
+        let errors = chunks
+            .iter()
+            .filter(|chunk| parser.extract_json_from_stream(&mut buffer, chunk).is_err())
+            .count();
+        assert_eq!(errors, 1);
+        assert_eq!(String::from_utf8(buffer).unwrap(), "{\"small\":1}");
+
+        let (output, result) = writer_output(
+            ParserOptions::new().max_object_bytes(16),
+            &["{\"blob\":[{\"a\":1},{\"b\":2}]} {\"ok\":1}"],
+        );
+        assert!(matches!(
+            result,
+            Err(ParseError::ObjectTooLarge { limit: 16 })
+        ));
+        assert_eq!(output, "{\"ok\":1}");
+    }
+
+    #[test]
+    fn test_json_parser_current_json_across_chunks() {
+        let mut parser = JSONParser::new();
+        let mut buffer = Vec::new();
+        let steps = [
+            ("note {\"a\"", "{\"a\""),
+            (": [1, \"}\"", "{\"a\": [1, \"}\""),
+            ("]} then [", "["),
+            ("tail", "[tail"),
+            ("] and {\"b\": 2, ", "{\"b\": 2, "),
+        ];
+
+        for (chunk, partial) in steps {
+            parser.extract_json_from_stream(&mut buffer, chunk).unwrap();
+            assert_eq!(parser.current_json(), partial, "after {:?}", chunk);
+        }
+
+        // The text taken is not collected again
+        assert_eq!(parser.take_partial(), "{\"b\": 2, ");
+        parser
+            .extract_json_from_stream(&mut buffer, "\"c\": 3")
+            .unwrap();
+        assert_eq!(parser.current_json(), "\"c\": 3");
+        parser.extract_json_from_stream(&mut buffer, "}").unwrap();
+        assert_eq!(parser.current_json(), "");
+        assert!(!parser.is_in_json());
+    }
+
+    #[test]
+    fn test_json_parser_finish() {
+        let mut parser = JSONParser::new();
+        let mut buffer = Vec::new();
+        parser
+            .extract_json_from_stream(&mut buffer, "a {\"b\":1} c")
+            .unwrap();
+        assert_eq!(parser.finish(), StreamEnd::Clean);
+
+        parser
+            .extract_json_from_stream(&mut buffer, "x {\"s\":\"brace } in string")
+            .unwrap();
         assert_eq!(
-            output,
-            "{\"id\": 123, \"data\": {\"nested\": [1, 2, {\"deep\": true}]}}{\"array\": [4, 5, 6]}"
+            parser.finish(),
+            StreamEnd::Truncated {
+                open_markers: 1,
+                partial: "{\"s\":\"brace } in string".to_string(),
+            }
         );
+
+        // The parser is reset, so the next stream starts outside of any value
+        buffer.clear();
+        parser
+            .extract_json_from_stream(&mut buffer, "\"} [[1, [2")
+            .unwrap();
+        assert_eq!(
+            parser.finish(),
+            StreamEnd::Truncated {
+                open_markers: 3,
+                partial: "[[1, [2".to_string(),
+            }
+        );
+        assert_eq!(String::from_utf8(buffer).unwrap(), "[[1, [2");
+        assert!(!parser.is_in_json());
+    }
+
+    #[test]
+    fn test_json_parser_reset_keeps_capacity() {
+        let mut parser = JSONParser::new();
+        let mut buffer = Vec::new();
+        parser
+            .extract_json_from_stream(&mut buffer, "{\"a\":[[[{\"b\":\"a long partial value")
+            .unwrap();
+        assert!(parser.is_in_json());
+        let buffer_capacity = parser.buffer.capacity();
+        let markers_capacity = parser.markers.capacity();
+
+        parser.reset();
+        assert!(!parser.is_in_json());
+        assert!(parser.buffer.is_empty());
+        assert_eq!(parser.buffer.capacity(), buffer_capacity);
+        assert_eq!(parser.markers.capacity(), markers_capacity);
+
+        // Values never taken and held output are dropped, but their storage is kept
+        let mut limited = JSONParser::with_options(ParserOptions::new().max_output_bytes(64));
+        limited.feed("[1] [2] [3").unwrap();
+        let values_capacity = limited.completed_values.capacity();
+        let held_capacity = limited.limit.held().capacity();
+        limited.reset();
+        assert!(limited.completed_values.is_empty());
+        assert_eq!(limited.completed_values.capacity(), values_capacity);
+        assert_eq!(limited.limit.held().capacity(), held_capacity);
+
+        // Nothing of the previous stream leaks into the next one
+        let mut buffer = Vec::new();
+        parser
+            .extract_json_from_stream(&mut buffer, "\" text {\"c\":1}")
+            .unwrap();
+        assert_eq!(String::from_utf8(buffer).unwrap(), "{\"c\":1}");
+        assert!(!parser.is_in_json());
     }
 }
@@ -0,0 +1,112 @@
+//! Limit module for capping the number of bytes a parser writes.
+
+use std::io::Write;
+
+use crate::parser::marker::Marker;
+use crate::parser::options::TruncationPolicy;
+
+/// Tracks the output budget of a parser with a byte limit.
+///
+/// Output is produced into a holding buffer and committed to the writer one
+/// input character at a time, so a limit never splits a character. Escape
+/// sequences are held until they are complete, which keeps the committed
+/// output closable at any point.
+#[derive(Debug, Default)]
+pub(crate) struct OutputLimit {
+    written: usize,
+    suppressed: usize,
+    truncated: bool,
+    held: Vec<u8>,
+    /// Closers needed to balance the committed output, innermost last.
+    closers: Vec<char>,
+    /// Whether the committed output ends inside a string.
+    in_string: bool,
+}
+
+impl OutputLimit {
+    /// Returns the buffer receiving output that has not been committed yet.
+    pub(crate) fn held(&mut self) -> &mut Vec<u8> {
+        &mut self.held
+    }
+
+    /// Forgets the output budget used so far, keeping the allocated buffers.
+    pub(crate) fn reset(&mut self) {
+        self.written = 0;
+        self.suppressed = 0;
+        self.truncated = false;
+        self.held.clear();
+        self.closers.clear();
+        self.in_string = false;
+    }
+
+    /// Returns the number of output bytes suppressed so far.
+    pub(crate) fn suppressed(&self) -> usize {
+        self.suppressed
+    }
+
+    /// Commits the held output if it fits within the limit.
+    ///
+    /// Once something does not fit, every later output is counted as
+    /// suppressed. With [`TruncationPolicy::Balance`] the closers of the
+    /// committed output are written at that point, and room for them is
+    /// reserved on every commit before it.
+    ///
+    /// # Arguments
+    ///
+    /// * `writer` - The writer receiving the extracted JSON.
+    /// * `limit` - The maximum number of bytes to write.
+    /// * `policy` - How to end the output once the limit is reached.
+    /// * `markers` - The open markers after the held output.
+    /// * `in_string` - Whether the held output ends inside a string.
+    pub(crate) fn commit<W: Write>(
+        &mut self,
+        writer: &mut W,
+        limit: usize,
+        policy: TruncationPolicy,
+        markers: &[Marker],
+        in_string: bool,
+    ) -> std::io::Result<()> {
+        if self.truncated {
+            self.suppressed += self.held.len();
+            self.held.clear();
+            return Ok(());
+        }
+
+        let reserve = match policy {
+            TruncationPolicy::Cut => 0,
+            TruncationPolicy::Balance => markers.len() + usize::from(in_string),
+        };
+
+        if self.written + self.held.len() + reserve > limit {
+            self.truncated = true;
+            self.suppressed += self.held.len();
+            self.held.clear();
+
+            if policy == TruncationPolicy::Balance {
+                let mut closers = String::new();
+                if self.in_string {
+                    closers.push('"');
+                }
+                closers.extend(self.closers.iter().rev());
+
+                writer.write_all(closers.as_bytes())?;
+                self.written += closers.len();
+            }
+
+            return Ok(());
+        }
+
+        writer.write_all(&self.held)?;
+        self.written += self.held.len();
+        self.held.clear();
+
+        // A commit changes the depth by at most one marker
+        self.closers.truncate(markers.len());
+        for marker in &markers[self.closers.len()..] {
+            self.closers.push(marker.expected_counterpart);
+        }
+        self.in_string = in_string;
+
+        Ok(())
+    }
+}
@@ -2,6 +2,15 @@
 
 use crate::constants::MARKERS;
 
+/// The kind of JSON structure a marker opens.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ContainerKind {
+    /// A JSON object, opened by '{'
+    Object,
+    /// A JSON array, opened by '['
+    Array,
+}
+
 /// Represents a JSON marker with its expected counterpart.
 ///
 /// A marker is a character that indicates the beginning of a JSON structure
@@ -83,6 +92,24 @@ impl Marker {
     pub fn is_counter_part(&self, marker: &char) -> bool {
         self.expected_counterpart == *marker
     }
+
+    /// Returns the kind of structure this marker opens.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use surfing::parser::marker::{ContainerKind, Marker};
+    ///
+    /// assert_eq!(Marker::new(&'{').unwrap().kind(), ContainerKind::Object);
+    /// assert_eq!(Marker::new(&'[').unwrap().kind(), ContainerKind::Array);
+    /// ```
+    pub fn kind(&self) -> ContainerKind {
+        if self.expected_counterpart == '}' {
+            ContainerKind::Object
+        } else {
+            ContainerKind::Array
+        }
+    }
 }
 
 #[cfg(test)]
@@ -1,4 +1,23 @@
 //! Parser module for extracting JSON from text streams.
 
+#[cfg(feature = "futures-io")]
+mod async_io;
+#[cfg(feature = "encoding")]
+mod encoding;
+pub mod error;
+pub(crate) mod escape;
+pub(crate) mod grammar;
 pub mod json_parser;
+mod limit;
 pub mod marker;
+pub mod options;
+pub mod outcome;
+mod percent;
+mod relaxed;
+mod scalar;
+#[cfg(feature = "spill")]
+pub mod spill;
+mod sse;
+pub mod string_scanner;
+pub mod token;
+pub mod validator;
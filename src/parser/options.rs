@@ -0,0 +1,1200 @@
+//! Options module for configuring JSON extraction.
+
+/// Options controlling how a [`JSONParser`](crate::JSONParser) emits extracted JSON.
+///
+/// Options are built with chained setters and handed to
+/// [`JSONParser::with_options`](crate::JSONParser::with_options).
+///
+/// # Examples
+///
+/// ```
+/// use surfing::parser::options::ParserOptions;
+/// use surfing::JSONParser;
+///
+/// let options = ParserOptions::new().ascii_only(true);
+/// let parser = JSONParser::with_options(options);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ParserOptions {
+    pub(crate) ascii_only: bool,
+    pub(crate) unescape_unicode: bool,
+    pub(crate) strip_interior_bom: bool,
+    pub(crate) sse_mode: bool,
+    pub(crate) relaxed: bool,
+    pub(crate) normalize_smart_quotes: bool,
+    pub(crate) max_output_bytes: Option<usize>,
+    pub(crate) truncation_policy: TruncationPolicy,
+    pub(crate) nul_policy: Option<NulPolicy>,
+    pub(crate) record_separator: Option<char>,
+    pub(crate) ndjson: bool,
+    pub(crate) max_string_bytes: Option<usize>,
+    pub(crate) max_depth: Option<usize>,
+    pub(crate) max_buffer_size: Option<usize>,
+    pub(crate) max_object_bytes: Option<usize>,
+    pub(crate) percent_decode_candidates: bool,
+    pub(crate) control_char_policy: ControlCharPolicy,
+    pub(crate) lone_surrogate_policy: LoneSurrogatePolicy,
+    pub(crate) flush_per_value: bool,
+    pub(crate) atomic_values: bool,
+    pub(crate) split_top_level_arrays: bool,
+    pub(crate) validate_value_starts: bool,
+    pub(crate) extract_scalars: bool,
+    pub(crate) strict: bool,
+    pub(crate) whitespace: Option<Vec<char>>,
+    #[cfg(feature = "spill")]
+    pub(crate) spill_threshold: Option<usize>,
+    #[cfg(feature = "serde")]
+    pub(crate) auto_unwrap_json_string: bool,
+    #[cfg(feature = "serde")]
+    pub(crate) validate_values: bool,
+}
+
+/// How a parser ends its output once [`ParserOptions::max_output_bytes`] is reached.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TruncationPolicy {
+    /// Stop writing after the last character that fits.
+    #[default]
+    Cut,
+    /// Stop early enough to close the open string and structures within the limit.
+    Balance,
+}
+
+/// How a parser treats NUL characters inside JSON strings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NulPolicy {
+    /// Write the NUL character unchanged.
+    Preserve,
+    /// Drop the NUL character.
+    Strip,
+    /// Fail the extraction call with [`ParseError::NulInString`](crate::parser::error::ParseError::NulInString).
+    Error,
+}
+
+/// How a parser treats raw control characters, U+0001 to U+001F, inside JSON strings.
+///
+/// JSON requires these to be escaped, but log sources often embed raw tabs
+/// and newlines in string values. NUL characters are governed by [`NulPolicy`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ControlCharPolicy {
+    /// Write control characters unchanged.
+    #[default]
+    Preserve,
+    /// Drop the value holding a control character, reporting
+    /// [`ParseError::ControlCharInString`](crate::parser::error::ParseError::ControlCharInString).
+    Reject,
+    /// Write control characters as escape sequences, such as `\n` or `\u001b`.
+    Escape,
+}
+
+/// How a parser treats `\u` escapes of lone surrogates inside JSON strings.
+///
+/// A surrogate is only a character as part of a pair, so a high surrogate
+/// such as `\ud83d` not followed by a low one, or a low surrogate on its
+/// own, cannot be decoded.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LoneSurrogatePolicy {
+    /// Write the escape sequence unchanged.
+    #[default]
+    Preserve,
+    /// Write U+FFFD in place of the escape sequence, as the character itself
+    /// when decoding with [`ParserOptions::unescape_unicode`] and as `\ufffd`
+    /// otherwise.
+    Replace,
+    /// Drop the value holding the escape sequence, reporting
+    /// [`ParseError::LoneSurrogate`](crate::parser::error::ParseError::LoneSurrogate).
+    Reject,
+}
+
+/// How [`JSONParser::extract_json_from_reader_with_encoding`](crate::JSONParser::extract_json_from_reader_with_encoding)
+/// treats byte sequences that are malformed in the input encoding.
+#[cfg(feature = "encoding")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MalformedInputPolicy {
+    /// Decode the sequence to U+FFFD.
+    #[default]
+    Replace,
+    /// Fail the extraction with [`ParseError::MalformedInput`](crate::parser::error::ParseError::MalformedInput).
+    Error,
+}
+
+impl ParserOptions {
+    /// Creates a new set of options with every option disabled.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use surfing::parser::options::ParserOptions;
+    ///
+    /// let options = ParserOptions::new();
+    /// ```
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Escapes non-ASCII characters inside JSON strings as `\uXXXX` sequences.
+    ///
+    /// Characters outside the Basic Multilingual Plane are written as a
+    /// UTF-16 surrogate pair (`😀` becomes `\ud83d\ude00`). Structural
+    /// characters and escape sequences already present in the input are left
+    /// untouched.
+    ///
+    /// # Arguments
+    ///
+    /// * `enabled` - Whether non-ASCII characters should be escaped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use surfing::parser::options::ParserOptions;
+    /// use surfing::JSONParser;
+    ///
+    /// let mut parser = JSONParser::with_options(ParserOptions::new().ascii_only(true));
+    /// let mut buffer = Vec::new();
+    /// parser.extract_json_from_stream(&mut buffer, "Name: {\"name\":\"José\"}").unwrap();
+    ///
+    /// assert_eq!(String::from_utf8(buffer).unwrap(), "{\"name\":\"Jos\\u00e9\"}");
+    /// ```
+    pub fn ascii_only(mut self, enabled: bool) -> Self {
+        self.ascii_only = enabled;
+        self
+    }
+
+    /// Decodes `\uXXXX` escape sequences inside JSON strings to UTF-8 characters.
+    ///
+    /// Surrogate pairs (`\ud83d\ude00`) are combined into a single character.
+    /// Escapes that JSON requires (quote, backslash and control characters)
+    /// stay escaped, and invalid escapes are passed through unchanged, as are
+    /// lone surrogates unless [`lone_surrogate_policy`](Self::lone_surrogate_policy)
+    /// says otherwise. Escape sequences split across chunks are decoded
+    /// once the remainder arrives.
+    ///
+    /// When combined with [`ascii_only`](Self::ascii_only), decoded non-ASCII
+    /// characters are escaped again.
+    ///
+    /// # Arguments
+    ///
+    /// * `enabled` - Whether `\u` escapes should be decoded.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use surfing::parser::options::ParserOptions;
+    /// use surfing::JSONParser;
+    ///
+    /// let mut parser = JSONParser::with_options(ParserOptions::new().unescape_unicode(true));
+    /// let mut buffer = Vec::new();
+    /// parser.extract_json_from_stream(&mut buffer, "{\"name\":\"Jos\\u00e9\"}").unwrap();
+    ///
+    /// assert_eq!(String::from_utf8(buffer).unwrap(), "{\"name\":\"José\"}");
+    /// ```
+    pub fn unescape_unicode(mut self, enabled: bool) -> Self {
+        self.unescape_unicode = enabled;
+        self
+    }
+
+    /// Removes byte order marks (U+FEFF) found outside of JSON strings.
+    ///
+    /// Text between top-level values is never emitted, so a BOM in front of
+    /// each record is already dropped there. Some producers also concatenate
+    /// BOM-prefixed records inside a container, such as
+    /// `[\u{FEFF}{"a":1},\u{FEFF}{"b":2}]`; with this option those marks are
+    /// removed as well. A BOM inside a string value is content and is always
+    /// kept.
+    ///
+    /// # Arguments
+    ///
+    /// * `enabled` - Whether byte order marks outside strings should be removed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use surfing::parser::options::ParserOptions;
+    /// use surfing::JSONParser;
+    ///
+    /// let mut parser = JSONParser::with_options(ParserOptions::new().strip_interior_bom(true));
+    /// let mut buffer = Vec::new();
+    /// parser.extract_json_from_stream(&mut buffer, "[\u{FEFF}{\"a\":1}]").unwrap();
+    ///
+    /// assert_eq!(String::from_utf8(buffer).unwrap(), "[{\"a\":1}]");
+    /// ```
+    pub fn strip_interior_bom(mut self, enabled: bool) -> Self {
+        self.strip_interior_bom = enabled;
+        self
+    }
+
+    /// Reads the input as a Server-Sent Events stream.
+    ///
+    /// Only the `data:` fields of each event are scanned for JSON. The field
+    /// prefix is stripped, multiple `data:` lines of one event are joined with
+    /// newlines, and the payload is extracted when the blank line ending the
+    /// event arrives. Comments and other fields such as `event:` and `id:` are
+    /// ignored, and so is the `data: [DONE]` marker ending an OpenAI-style
+    /// stream. Each event is a record of its own: a value its payload leaves
+    /// open is dropped at the end of the event rather than joined with the
+    /// next payload, and writer-based extraction holds each value until it
+    /// completes, as [`atomic_values`](Self::atomic_values) does.
+    ///
+    /// # Arguments
+    ///
+    /// * `enabled` - Whether the input uses Server-Sent Events framing.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use surfing::parser::options::ParserOptions;
+    /// use surfing::JSONParser;
+    ///
+    /// let mut parser = JSONParser::with_options(ParserOptions::new().sse_mode(true));
+    /// let mut buffer = Vec::new();
+    /// parser.extract_json_from_stream(&mut buffer, "event: update\ndata: {\"n\":1}\n").unwrap();
+    /// assert!(buffer.is_empty()); // The event has not ended yet
+    ///
+    /// parser.extract_json_from_stream(&mut buffer, "\n").unwrap();
+    /// assert_eq!(String::from_utf8(buffer).unwrap(), "{\"n\":1}");
+    /// ```
+    pub fn sse_mode(mut self, enabled: bool) -> Self {
+        self.sse_mode = enabled;
+        self
+    }
+
+    /// Normalizes relaxed, Python-style literals into JSON while emitting.
+    ///
+    /// This is aimed at the output of Python's `print(dict)` and is
+    /// best-effort. Exactly these substitutions are made inside a JSON
+    /// structure:
+    ///
+    /// - Outside strings, `True`, `False` and `None` become `true`, `false`
+    ///   and `null`.
+    /// - Outside strings, leading zeros are stripped from numbers (`007`
+    ///   becomes `7`, `-00.5` becomes `-0.5`).
+    /// - Single-quoted strings become double-quoted strings, with inner `"`
+    ///   escaped and `\'` unescaped.
+    ///
+    /// Everything else, including other bare words, is emitted unchanged.
+    /// Words and numbers are written once the character following them
+    /// arrives.
+    ///
+    /// # Arguments
+    ///
+    /// * `enabled` - Whether relaxed literals should be normalized.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use surfing::parser::options::ParserOptions;
+    /// use surfing::JSONParser;
+    ///
+    /// let mut parser = JSONParser::with_options(ParserOptions::new().relaxed(true));
+    /// let mut buffer = Vec::new();
+    /// parser
+    ///     .extract_json_from_stream(&mut buffer, "Result: {'ok': True, 'error': None, 'code': 007}")
+    ///     .unwrap();
+    ///
+    /// assert_eq!(
+    ///     String::from_utf8(buffer).unwrap(),
+    ///     "{\"ok\": true, \"error\": null, \"code\": 7}"
+    /// );
+    /// ```
+    pub fn relaxed(mut self, enabled: bool) -> Self {
+        self.relaxed = enabled;
+        self
+    }
+
+    /// Converts typographic quotes inside JSON structures to straight quotes.
+    ///
+    /// Chat interfaces often replace quotes with their typographic forms,
+    /// which JSON does not accept. Exactly these code points are converted:
+    ///
+    /// - U+201C `“`, U+201D `”`, U+201E `„` and U+201F `‟` become `"`.
+    /// - U+2018 `‘`, U+2019 `’`, U+201A `‚` and U+201B `‛` become `'`.
+    ///
+    /// The conversion happens before string tracking, so converted quotes
+    /// open and close strings. A typographic double quote inside a string
+    /// that was opened by a straight quote is converted too, but written as
+    /// `\"` so it does not end the string.
+    ///
+    /// # Arguments
+    ///
+    /// * `enabled` - Whether typographic quotes should be converted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use surfing::parser::options::ParserOptions;
+    /// use surfing::JSONParser;
+    ///
+    /// let mut parser = JSONParser::with_options(ParserOptions::new().normalize_smart_quotes(true));
+    /// let mut buffer = Vec::new();
+    /// parser.extract_json_from_stream(&mut buffer, "{“id”: “a1”}").unwrap();
+    ///
+    /// assert_eq!(String::from_utf8(buffer).unwrap(), "{\"id\": \"a1\"}");
+    /// ```
+    pub fn normalize_smart_quotes(mut self, enabled: bool) -> Self {
+        self.normalize_smart_quotes = enabled;
+        self
+    }
+
+    /// Caps the total number of bytes the parser writes.
+    ///
+    /// Once the next character would exceed the limit, the parser stops
+    /// writing but keeps consuming input, updating only its state, so a
+    /// caller's read loop never stalls. Output is cut on character
+    /// boundaries, and with [`TruncationPolicy::Balance`] the closers needed
+    /// to balance the output are written within the limit. The number of
+    /// suppressed bytes is reported by
+    /// [`JSONParser::check_output_limit`](crate::JSONParser::check_output_limit).
+    ///
+    /// # Arguments
+    ///
+    /// * `limit` - The maximum number of bytes to write.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use surfing::parser::options::ParserOptions;
+    /// use surfing::JSONParser;
+    ///
+    /// let mut parser = JSONParser::with_options(ParserOptions::new().max_output_bytes(8));
+    /// let mut buffer = Vec::new();
+    /// parser.extract_json_from_stream(&mut buffer, "{\"id\": 12345}").unwrap();
+    ///
+    /// assert_eq!(String::from_utf8(buffer).unwrap(), "{\"id\": 1");
+    /// assert_eq!(parser.suppressed_bytes(), 5);
+    /// ```
+    pub fn max_output_bytes(mut self, limit: usize) -> Self {
+        self.max_output_bytes = Some(limit);
+        self
+    }
+
+    /// Sets how the output ends once [`max_output_bytes`](Self::max_output_bytes) is reached.
+    ///
+    /// The balanced output closes every open string and structure, which
+    /// keeps brackets matched but does not make a cut-off value valid JSON in
+    /// every case (an object may end right after a key).
+    ///
+    /// # Arguments
+    ///
+    /// * `policy` - The truncation policy, [`TruncationPolicy::Cut`] by default.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use surfing::parser::options::{ParserOptions, TruncationPolicy};
+    /// use surfing::JSONParser;
+    ///
+    /// let options = ParserOptions::new()
+    ///     .max_output_bytes(12)
+    ///     .truncation_policy(TruncationPolicy::Balance);
+    /// let mut parser = JSONParser::with_options(options);
+    /// let mut buffer = Vec::new();
+    /// parser.extract_json_from_stream(&mut buffer, "{\"ids\": [1, 2, 3, 4]}").unwrap();
+    ///
+    /// assert_eq!(String::from_utf8(buffer).unwrap(), "{\"ids\": [1]}");
+    /// ```
+    pub fn truncation_policy(mut self, policy: TruncationPolicy) -> Self {
+        self.truncation_policy = policy;
+        self
+    }
+
+    /// Spills values larger than `threshold` bytes to temporary files.
+    ///
+    /// Only [`JSONParser::extract_payloads_from_stream`](crate::JSONParser::extract_payloads_from_stream)
+    /// spills values. Files are created in the system temporary directory
+    /// and deleted when the [`SpilledValue`](crate::parser::spill::SpilledValue)
+    /// handed to the callback is dropped.
+    ///
+    /// # Arguments
+    ///
+    /// * `threshold` - The largest value size, in bytes, kept in memory.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use surfing::parser::options::ParserOptions;
+    /// use surfing::JSONParser;
+    ///
+    /// let parser = JSONParser::with_options(ParserOptions::new().spill_threshold(64 * 1024 * 1024));
+    /// ```
+    #[cfg(feature = "spill")]
+    pub fn spill_threshold(mut self, threshold: usize) -> Self {
+        self.spill_threshold = Some(threshold);
+        self
+    }
+
+    /// Unwraps the first value when it is a double-encoded JSON string.
+    ///
+    /// Pipelines that serialize JSON twice produce `"{\"a\":1}"`, from which
+    /// the parser extracts `{\"a\":1}`. With this option, the first extracted
+    /// value is read as the content of a JSON string, and if that content is
+    /// valid JSON it is written instead. Exactly one level is unwrapped, and
+    /// only for the first value, which is held back until it completes.
+    /// Values that are not double-encoded are written unchanged.
+    ///
+    /// # Arguments
+    ///
+    /// * `enabled` - Whether a double-encoded first value should be unwrapped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use surfing::parser::options::ParserOptions;
+    /// use surfing::JSONParser;
+    ///
+    /// let mut parser = JSONParser::with_options(ParserOptions::new().auto_unwrap_json_string(true));
+    /// let mut buffer = Vec::new();
+    /// parser.extract_json_from_stream(&mut buffer, "\"{\\\"a\\\":1}\"").unwrap();
+    ///
+    /// assert_eq!(String::from_utf8(buffer).unwrap(), "{\"a\":1}");
+    /// ```
+    #[cfg(feature = "serde")]
+    pub fn auto_unwrap_json_string(mut self, enabled: bool) -> Self {
+        self.auto_unwrap_json_string = enabled;
+        self
+    }
+
+    /// Sets how NUL characters inside JSON strings are handled.
+    ///
+    /// By default a NUL is preserved, as extraction does not otherwise check
+    /// the content of strings. JSON does not allow raw control characters in
+    /// strings, so under [`strict`](Self::strict) the default is
+    /// [`NulPolicy::Error`] instead. With [`NulPolicy::Error`], the
+    /// extraction call fails at the NUL and the rest of that chunk is not
+    /// processed. NUL characters outside strings are not affected.
+    ///
+    /// # Arguments
+    ///
+    /// * `policy` - The treatment of NUL characters inside strings.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use surfing::parser::options::{NulPolicy, ParserOptions};
+    /// use surfing::JSONParser;
+    ///
+    /// let mut parser = JSONParser::with_options(ParserOptions::new().nul_policy(NulPolicy::Strip));
+    /// let mut buffer = Vec::new();
+    /// parser.extract_json_from_stream(&mut buffer, "{\"id\":\"a\0b\"}").unwrap();
+    ///
+    /// assert_eq!(String::from_utf8(buffer).unwrap(), "{\"id\":\"ab\"}");
+    /// ```
+    pub fn nul_policy(mut self, policy: NulPolicy) -> Self {
+        self.nul_policy = Some(policy);
+        self
+    }
+
+    /// Sets a byte that separates records, such as `0x1E` (the ASCII record separator).
+    ///
+    /// Outside of strings, the separator ends the current record: a value
+    /// that is still open is abandoned and the parser starts afresh, so one
+    /// corrupt record cannot swallow the records after it. Inside string
+    /// values the separator is ordinary content. Nothing of an abandoned
+    /// value is written or reported: writer-based extraction holds each value
+    /// until it completes, as [`atomic_values`](Self::atomic_values) does.
+    ///
+    /// # Arguments
+    ///
+    /// * `byte` - The record separator.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `byte` is not ASCII. The input is UTF-8, where bytes from
+    /// `0x80` on only occur inside multi-byte characters, so they cannot
+    /// separate records.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use surfing::parser::options::ParserOptions;
+    /// use surfing::JSONParser;
+    ///
+    /// let mut parser = JSONParser::with_options(ParserOptions::new().record_separator(0x1E));
+    /// let mut values = Vec::new();
+    /// parser
+    ///     .extract_values_from_stream("{\"id\":1}\u{1E}{\"id\":\u{1E}{\"id\":3}", |value| {
+    ///         values.push(value.to_string())
+    ///     })
+    ///     .unwrap();
+    ///
+    /// assert_eq!(values, vec!["{\"id\":1}", "{\"id\":3}"]);
+    /// ```
+    pub fn record_separator(mut self, byte: u8) -> Self {
+        assert!(
+            byte.is_ascii(),
+            "record separator {byte:#04x} is not an ASCII byte"
+        );
+        self.record_separator = Some(char::from(byte));
+        self
+    }
+
+    /// Treats each line as a record of its own, as in newline-delimited JSON.
+    ///
+    /// A newline outside of strings ends the current record as a
+    /// [`record_separator`](Self::record_separator) does, so a line truncated
+    /// in the middle of a value, for example one missing its closing brace,
+    /// cannot merge with the lines after it. Inside string values a newline
+    /// is ordinary content. Values spanning several lines, such as
+    /// pretty-printed JSON, are dropped in this mode.
+    /// [`JSONParser::ndjson`](crate::JSONParser::ndjson) creates a parser
+    /// with this mode enabled.
+    ///
+    /// # Arguments
+    ///
+    /// * `enabled` - Whether a newline should end the current record.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use surfing::parser::options::ParserOptions;
+    /// use surfing::JSONParser;
+    ///
+    /// let mut parser = JSONParser::with_options(ParserOptions::new().ndjson(true));
+    /// let mut values = Vec::new();
+    /// parser
+    ///     .extract_values_from_stream("{\"id\":1,\"tags\":[\"a\"\n{\"id\":2}\n", |value| {
+    ///         values.push(value.to_string())
+    ///     })
+    ///     .unwrap();
+    ///
+    /// assert_eq!(values, vec!["{\"id\":2}"]);
+    /// ```
+    pub fn ndjson(mut self, enabled: bool) -> Self {
+        self.ndjson = enabled;
+        self
+    }
+
+    /// Sets the largest number of bytes a string inside a JSON value may hold.
+    ///
+    /// This guards against a single gigantic string, such as a base64 blob,
+    /// exhausting memory. Bytes are counted as written in the input, escape
+    /// sequences included and quotes excluded. Once a string exceeds the
+    /// limit, the value holding it is abandoned: the parser follows its
+    /// structure to its end without writing anything more, and the extraction
+    /// call returns [`ParseError::StringTooLong`](crate::parser::error::ParseError::StringTooLong)
+    /// after processing the rest of its input, so the values around it are
+    /// still extracted.
+    ///
+    /// # Arguments
+    ///
+    /// * `limit` - The largest number of bytes in a string.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use surfing::parser::error::ParseError;
+    /// use surfing::parser::options::ParserOptions;
+    /// use surfing::JSONParser;
+    ///
+    /// let mut parser = JSONParser::with_options(ParserOptions::new().max_string_bytes(8));
+    /// let mut values = Vec::new();
+    /// let result = parser.extract_values_from_stream(
+    ///     "[1] {\"blob\":\"aGVsbG8gd29ybGQ=\"} [2]",
+    ///     |value| values.push(value.to_string()),
+    /// );
+    ///
+    /// assert!(matches!(result, Err(ParseError::StringTooLong)));
+    /// assert_eq!(values, vec!["[1]", "[2]"]);
+    /// ```
+    pub fn max_string_bytes(mut self, limit: usize) -> Self {
+        self.max_string_bytes = Some(limit);
+        self
+    }
+
+    /// Sets the deepest nesting of objects and arrays a value may have.
+    ///
+    /// This guards against input such as thousands of `[` in a row, which
+    /// would otherwise make the parser track every open structure. A
+    /// top-level value is at depth 1. Once an opening marker goes past the
+    /// limit, the value is abandoned: the parser follows it to its end by
+    /// counting the markers past the limit instead of tracking them, and
+    /// writes nothing more of it. The extraction call returns
+    /// [`ParseError::DepthLimitExceeded`](crate::parser::error::ParseError::DepthLimitExceeded)
+    /// after processing the rest of its input, so the values around it are
+    /// still extracted.
+    ///
+    /// # Arguments
+    ///
+    /// * `limit` - The deepest nesting allowed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use surfing::parser::error::ParseError;
+    /// use surfing::parser::options::ParserOptions;
+    /// use surfing::JSONParser;
+    ///
+    /// let mut parser = JSONParser::with_options(ParserOptions::new().max_depth(2));
+    /// let mut values = Vec::new();
+    /// let result = parser.extract_values_from_stream("[[1]] [[[2]]] {\"a\":[3]}", |value| {
+    ///     values.push(value.to_string())
+    /// });
+    ///
+    /// assert!(matches!(result, Err(ParseError::DepthLimitExceeded { limit: 2 })));
+    /// assert_eq!(values, vec!["[[1]]", "{\"a\":[3]}"]);
+    /// ```
+    pub fn max_depth(mut self, limit: usize) -> Self {
+        self.max_depth = Some(limit);
+        self
+    }
+
+    /// Sets the largest number of bytes held for a value while it is in progress.
+    ///
+    /// The parser keeps the input of the value being extracted until it
+    /// completes, so a stray `{` followed by megabytes of text that never
+    /// closes, as a language model may produce, would otherwise be held in
+    /// full. Bytes are counted as they appear in the input. Once a value
+    /// grows past the limit, it is abandoned and extraction starts afresh
+    /// with the next character, so the values after it are still extracted,
+    /// and the extraction call returns
+    /// [`ParseError::BufferLimitExceeded`](crate::parser::error::ParseError::BufferLimitExceeded)
+    /// after processing the rest of its input.
+    ///
+    /// # Arguments
+    ///
+    /// * `limit` - The largest number of bytes held for a value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use surfing::parser::error::ParseError;
+    /// use surfing::parser::options::ParserOptions;
+    /// use surfing::JSONParser;
+    ///
+    /// let mut parser = JSONParser::with_options(ParserOptions::new().max_buffer_size(16));
+    /// let mut values = Vec::new();
+    /// let result = parser.extract_values_from_stream(
+    ///     "a stray { brace and a long tail of text, then {\"ok\":1}",
+    ///     |value| values.push(value.to_string()),
+    /// );
+    ///
+    /// assert!(matches!(result, Err(ParseError::BufferLimitExceeded { limit: 16 })));
+    /// assert_eq!(values, vec!["{\"ok\":1}"]);
+    /// ```
+    pub fn max_buffer_size(mut self, limit: usize) -> Self {
+        self.max_buffer_size = Some(limit);
+        self
+    }
+
+    /// Sets the largest number of bytes a single extracted value may span.
+    ///
+    /// This caps the size of any one value, such as a multi-hundred-megabyte
+    /// blob in a log being tailed. Bytes are counted as they appear in the
+    /// input, across calls. Once a value grows past the limit, nothing more
+    /// of it is written or held: the parser keeps consuming its input to
+    /// find where it ends, and extraction resumes with the next value. The
+    /// extraction call returns
+    /// [`ParseError::ObjectTooLarge`](crate::parser::error::ParseError::ObjectTooLarge)
+    /// after processing the rest of its input. Unlike
+    /// [`max_buffer_size`](Self::max_buffer_size), the rest of the oversized
+    /// value is never taken for values of its own.
+    ///
+    /// # Arguments
+    ///
+    /// * `limit` - The largest number of bytes in a value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use surfing::parser::error::ParseError;
+    /// use surfing::parser::options::ParserOptions;
+    /// use surfing::JSONParser;
+    ///
+    /// let mut parser = JSONParser::with_options(ParserOptions::new().max_object_bytes(16));
+    /// let mut values = Vec::new();
+    /// let result = parser.extract_values_from_stream(
+    ///     "{\"blob\":[{\"a\":1},{\"b\":2}]} {\"ok\":1}",
+    ///     |value| values.push(value.to_string()),
+    /// );
+    ///
+    /// assert!(matches!(result, Err(ParseError::ObjectTooLarge { limit: 16 })));
+    /// assert_eq!(values, vec!["{\"ok\":1}"]);
+    /// ```
+    pub fn max_object_bytes(mut self, limit: usize) -> Self {
+        self.max_object_bytes = Some(limit);
+        self
+    }
+
+    /// Surfaces JSON that was percent-encoded, such as a query parameter value.
+    ///
+    /// Outside of JSON structures, a run of URL characters starting with an
+    /// encoded `{` or `[` (`%7B` or `%5B`, in either case) is decoded as it
+    /// streams in. `+` decodes to a space, as in form encoding. As soon as
+    /// the decoded text is a complete and strictly valid JSON value, that
+    /// value is processed as if it had appeared in the input, so it is
+    /// written and reported like any other value. Anything else is left
+    /// alone: a `%` not followed by two hex digits, a run that decodes to
+    /// something other than JSON, and a run ending before its value is
+    /// complete produce no output. Decoded candidates are capped at 64 KiB.
+    ///
+    /// # Arguments
+    ///
+    /// * `enabled` - Whether percent-encoded JSON should be decoded.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use surfing::parser::options::ParserOptions;
+    /// use surfing::JSONParser;
+    ///
+    /// let mut parser = JSONParser::with_options(ParserOptions::new().percent_decode_candidates(true));
+    /// let mut buffer = Vec::new();
+    /// parser
+    ///     .extract_json_from_stream(&mut buffer, "GET /?filter=%7B%22tag%22%3A%22rust%22%7D 100% ok")
+    ///     .unwrap();
+    ///
+    /// assert_eq!(String::from_utf8(buffer).unwrap(), "{\"tag\":\"rust\"}");
+    /// ```
+    pub fn percent_decode_candidates(mut self, enabled: bool) -> Self {
+        self.percent_decode_candidates = enabled;
+        self
+    }
+
+    /// Sets how raw control characters inside JSON strings are handled.
+    ///
+    /// With [`ControlCharPolicy::Escape`], each control character is written
+    /// as its escape sequence, so the output is valid JSON. With
+    /// [`ControlCharPolicy::Reject`], the value holding one is dropped like a
+    /// value exceeding [`max_string_bytes`](Self::max_string_bytes): the
+    /// parser follows it to its end without writing more of it, values
+    /// around it are still extracted, and the extraction call returns
+    /// [`ParseError::ControlCharInString`](crate::parser::error::ParseError::ControlCharInString)
+    /// once its input is processed. Characters are handled one at a time, so
+    /// both work across chunk boundaries.
+    ///
+    /// # Arguments
+    ///
+    /// * `policy` - The treatment of control characters inside strings.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use surfing::parser::options::{ControlCharPolicy, ParserOptions};
+    /// use surfing::JSONParser;
+    ///
+    /// let options = ParserOptions::new().control_char_policy(ControlCharPolicy::Escape);
+    /// let mut parser = JSONParser::with_options(options);
+    /// let mut buffer = Vec::new();
+    /// parser
+    ///     .extract_json_from_stream(&mut buffer, "{\"msg\":\"line 1\nline 2\tend\"}")
+    ///     .unwrap();
+    ///
+    /// assert_eq!(
+    ///     String::from_utf8(buffer).unwrap(),
+    ///     "{\"msg\":\"line 1\\nline 2\\tend\"}"
+    /// );
+    /// ```
+    pub fn control_char_policy(mut self, policy: ControlCharPolicy) -> Self {
+        self.control_char_policy = policy;
+        self
+    }
+
+    /// Sets the characters counted as insignificant whitespace.
+    ///
+    /// By default only JSON's own whitespace counts: space, tab, line feed
+    /// and carriage return. Sources using other separators, such as a
+    /// vertical tab or form feed, can list them here. Outside strings inside
+    /// a value, a listed character JSON does not accept is written as a
+    /// space, so a scalar it terminates stays delimited and the output stays
+    /// valid JSON. JSON whitespace keeps its meaning whether listed or not.
+    ///
+    /// # Arguments
+    ///
+    /// * `chars` - The characters to treat as whitespace.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use surfing::parser::options::ParserOptions;
+    /// use surfing::JSONParser;
+    ///
+    /// let options = ParserOptions::new().whitespace(&[' ', '\n', '\u{b}', '\u{c}']);
+    /// let mut parser = JSONParser::with_options(options);
+    /// let mut buffer = Vec::new();
+    /// parser
+    ///     .extract_json_from_stream(&mut buffer, "{\"n\":\u{b}1\u{c}}")
+    ///     .unwrap();
+    ///
+    /// assert_eq!(String::from_utf8(buffer).unwrap(), "{\"n\": 1 }");
+    /// ```
+    pub fn whitespace(mut self, chars: &[char]) -> Self {
+        self.whitespace = Some(chars.to_vec());
+        self
+    }
+
+    /// Sets how `\u` escapes of lone surrogates inside strings are handled.
+    ///
+    /// By default the escape is written unchanged, also when decoding with
+    /// [`unescape_unicode`](Self::unescape_unicode), so the output never
+    /// holds invalid UTF-8. With [`LoneSurrogatePolicy::Replace`] the escape
+    /// becomes U+FFFD. With [`LoneSurrogatePolicy::Reject`], the value
+    /// holding one is dropped like under
+    /// [`ControlCharPolicy::Reject`], and the extraction call returns
+    /// [`ParseError::LoneSurrogate`](crate::parser::error::ParseError::LoneSurrogate).
+    /// Escape sequences split across chunks are handled once the character
+    /// after them arrives.
+    ///
+    /// # Arguments
+    ///
+    /// * `policy` - The treatment of lone surrogate escapes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use surfing::parser::options::{LoneSurrogatePolicy, ParserOptions};
+    /// use surfing::JSONParser;
+    ///
+    /// let options = ParserOptions::new()
+    ///     .unescape_unicode(true)
+    ///     .lone_surrogate_policy(LoneSurrogatePolicy::Replace);
+    /// let mut parser = JSONParser::with_options(options);
+    /// let mut buffer = Vec::new();
+    /// parser
+    ///     .extract_json_from_stream(&mut buffer, "{\"a\":\"\\ud83d!\"}")
+    ///     .unwrap();
+    ///
+    /// assert_eq!(String::from_utf8(buffer).unwrap(), "{\"a\":\"\u{fffd}!\"}");
+    /// ```
+    pub fn lone_surrogate_policy(mut self, policy: LoneSurrogatePolicy) -> Self {
+        self.lone_surrogate_policy = policy;
+        self
+    }
+
+    /// Flushes the writer after each value is completed.
+    ///
+    /// Writers such as a `BufWriter` around a socket or standard output
+    /// otherwise hold completed values until their buffer fills. With this
+    /// option, the parser calls `flush` on the writer given to the
+    /// extraction method right after writing a value's final closing bracket,
+    /// so consumers see every value as soon as it is complete. A failing
+    /// flush is reported like a failing write.
+    ///
+    /// # Arguments
+    ///
+    /// * `enabled` - Whether the writer should be flushed after each value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::io::{BufWriter, Write};
+    /// use surfing::parser::options::ParserOptions;
+    /// use surfing::JSONParser;
+    ///
+    /// let mut parser = JSONParser::with_options(ParserOptions::new().flush_per_value(true));
+    /// let mut writer = BufWriter::new(Vec::new());
+    /// parser.extract_json_from_stream(&mut writer, "ready {\"id\":1} [2").unwrap();
+    ///
+    /// // The completed value has left the buffer, the partial one has not
+    /// assert_eq!(writer.get_ref().as_slice(), b"{\"id\":1}");
+    /// ```
+    pub fn flush_per_value(mut self, enabled: bool) -> Self {
+        self.flush_per_value = enabled;
+        self
+    }
+
+    /// Writes each value in one piece once it is complete.
+    ///
+    /// By default, characters are written as they arrive, so a value spanning
+    /// several extraction calls is split between the writers given to those
+    /// calls. With this option, the parser holds the value being extracted
+    /// and writes it whole to the writer given to the call that completes it,
+    /// which keeps values intact when output files are rotated between
+    /// chunks. A value that is dropped before completing, such as one with a
+    /// mismatched closer, is never written at all. Options that may drop a
+    /// value part way, such as [`max_string_bytes`](Self::max_string_bytes)
+    /// or [`strict`](Self::strict), hold values this way too.
+    ///
+    /// The value being extracted is held in memory until it completes, so a
+    /// large value costs its full size. [`max_output_bytes`](Self::max_output_bytes)
+    /// still counts every byte of output and bounds what is held; a value cut
+    /// short by it is written as cut once its input completes.
+    ///
+    /// # Arguments
+    ///
+    /// * `enabled` - Whether values should only be written once complete.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use surfing::parser::options::ParserOptions;
+    /// use surfing::JSONParser;
+    ///
+    /// let mut parser = JSONParser::with_options(ParserOptions::new().atomic_values(true));
+    /// let mut first = Vec::new();
+    /// let mut second = Vec::new();
+    ///
+    /// parser.extract_json_from_stream(&mut first, "[1] {\"id\":").unwrap();
+    /// parser.extract_json_from_stream(&mut second, "2} done").unwrap();
+    ///
+    /// assert_eq!(first, b"[1]");
+    /// assert_eq!(second, b"{\"id\":2}");
+    /// ```
+    pub fn atomic_values(mut self, enabled: bool) -> Self {
+        self.atomic_values = enabled;
+        self
+    }
+
+    /// Extracts the elements of a top-level array as values of their own.
+    ///
+    /// The brackets and commas of an array found outside of any value are
+    /// dropped, and each object or array inside it is extracted as if it
+    /// appeared on its own: it is written like any other value, counts as a
+    /// completed value for [`flush_per_value`](Self::flush_per_value),
+    /// [`atomic_values`](Self::atomic_values) and the value-based methods,
+    /// and is the unit suppressed by
+    /// [`JSONParser::suppress_current_value`](crate::JSONParser::suppress_current_value).
+    /// Only one element is held at a time, so a huge array is converted to a
+    /// stream of records in bounded memory.
+    ///
+    /// Elements that are strings, numbers or literals are dropped, as the
+    /// parser only extracts objects and arrays; brackets inside string
+    /// elements are not mistaken for values. A
+    /// [`record_separator`](Self::record_separator) closes the array along
+    /// with any element in progress. Between elements,
+    /// [`JSONParser::is_in_json`](crate::JSONParser::is_in_json) is `false`.
+    ///
+    /// # Arguments
+    ///
+    /// * `enabled` - Whether top-level arrays should be split into their elements.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use surfing::parser::options::ParserOptions;
+    /// use surfing::JSONParser;
+    ///
+    /// let options = ParserOptions::new().split_top_level_arrays(true);
+    /// let mut parser = JSONParser::with_options(options);
+    /// let mut values = Vec::new();
+    ///
+    /// parser
+    ///     .extract_values_from_stream("rows: [{\"id\":1}, [2, [3]], \"x\"] done", |value| {
+    ///         values.push(value.to_string())
+    ///     })
+    ///     .unwrap();
+    ///
+    /// assert_eq!(values, vec!["{\"id\":1}", "[2, [3]]"]);
+    /// ```
+    pub fn split_top_level_arrays(mut self, enabled: bool) -> Self {
+        self.split_top_level_arrays = enabled;
+        self
+    }
+
+    /// Drops braces and brackets that are not followed by something JSON could continue with.
+    ///
+    /// Prose often holds braces that are not JSON, such as template
+    /// placeholders like `{user_name}` or code like `{ return 0; }`. With this
+    /// option, the first character after an opening brace, skipping
+    /// whitespace, has to be a quote or `}`, and the first one after an
+    /// opening bracket has to start a value or be `]`. A backslash is accepted
+    /// too, for values whose strings are escaped. When the check fails, the
+    /// value is dropped and the character that failed it is looked at again
+    /// as plain text, so a brace or bracket there can start a genuine value.
+    ///
+    /// The opening marker of a top-level value is held back until the check
+    /// passes, so nothing of a dropped start is ever written. A check failing
+    /// inside a value drops it the way a mismatched closer does.
+    ///
+    /// # Arguments
+    ///
+    /// * `enabled` - Whether the start of each structure should be validated.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use surfing::parser::options::ParserOptions;
+    /// use surfing::JSONParser;
+    ///
+    /// let options = ParserOptions::new().validate_value_starts(true);
+    /// let mut parser = JSONParser::with_options(options);
+    /// let mut buffer = Vec::new();
+    ///
+    /// parser
+    ///     .extract_json_from_stream(&mut buffer, "render {user_name} with {\"id\":1}")
+    ///     .unwrap();
+    ///
+    /// assert_eq!(String::from_utf8(buffer).unwrap(), "{\"id\":1}");
+    /// ```
+    pub fn validate_value_starts(mut self, enabled: bool) -> Self {
+        self.validate_value_starts = enabled;
+        self
+    }
+
+    /// Extracts JSON strings, numbers, `true`, `false` and `null` found outside of any value.
+    ///
+    /// Only objects and arrays are extracted by default. With this option,
+    /// scalars in plain text, such as the `42` of `count: 42`, are extracted
+    /// as values of their own. A scalar has no closing marker, so its end is
+    /// decided by what follows it:
+    ///
+    /// - A string ends at its closing quote and is dropped if a control
+    ///   character, such as a line break, comes first.
+    /// - A number or literal runs as long as the characters can continue it.
+    ///   It is kept only if the whole run is valid JSON and the character
+    ///   after it is not a letter, a digit, `_`, a quote, `{` or `[`, so
+    ///   `1.2.3`, `2024-01-01`, `3px` and `nullable` give nothing. A single
+    ///   `.` ending a number is taken as the end of a sentence.
+    /// - A scalar right after a letter, a digit, `_`, `.`, `-` or `+` is
+    ///   part of a longer word and is not extracted, as in `v1.2` or `x42`.
+    ///
+    /// So `temperature: 98.6 degrees` extracts just `98.6`. A number or
+    /// literal is only extracted once the character after it arrives, as
+    /// more input could continue it, which means one at the very end of the
+    /// input is not extracted. Scalars are reported as they appear in the
+    /// input, and plain text is inspected character by character. Scalars
+    /// have no closing marker, so writer-based extraction follows each one
+    /// with a newline, which keeps `2` and `2024` from being written as
+    /// `22024`.
+    ///
+    /// # Arguments
+    ///
+    /// * `enabled` - Whether scalars outside of values should be extracted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use surfing::parser::options::ParserOptions;
+    /// use surfing::JSONParser;
+    ///
+    /// let mut parser = JSONParser::with_options(ParserOptions::new().extract_scalars(true));
+    /// let mut values = Vec::new();
+    ///
+    /// parser
+    ///     .extract_values_from_stream(
+    ///         "temperature: 98.6 degrees, name: \"Alice\", ok: true, v1.2 [1]\n",
+    ///         |value| values.push(value.to_string()),
+    ///     )
+    ///     .unwrap();
+    ///
+    /// assert_eq!(values, vec!["98.6", "\"Alice\"", "true", "[1]"]);
+    /// ```
+    pub fn extract_scalars(mut self, enabled: bool) -> Self {
+        self.extract_scalars = enabled;
+        self
+    }
+
+    /// Drops values breaking the JSON grammar instead of extracting everything between markers.
+    ///
+    /// By default, anything between balanced markers is extracted, so
+    /// `{pid=1234}` comes out as if it were JSON. With this option, the
+    /// characters of each value are checked against the JSON grammar as they
+    /// stream through: keys must be strings followed by a colon, members
+    /// must be separated by commas, and strings, numbers and literals must
+    /// be well formed. A value is dropped at the first character breaking
+    /// the grammar, and extraction starts afresh with that character, so a
+    /// `{` or `[` breaking the grammar starts a new value. The
+    /// check keeps only the open structures and the token in progress, so
+    /// valid values are not buffered, and it works across chunk boundaries.
+    ///
+    /// The start of each structure is checked as with
+    /// [`validate_value_starts`](Self::validate_value_starts), so nothing of
+    /// a top-level value failing right after its opening marker is written.
+    /// A value failing further on is dropped the way a mismatched closer
+    /// drops it. With [`relaxed`](Self::relaxed), the translated JSON is
+    /// checked.
+    ///
+    /// # Arguments
+    ///
+    /// * `enabled` - Whether values should be checked against the JSON grammar.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use surfing::parser::options::ParserOptions;
+    /// use surfing::JSONParser;
+    ///
+    /// let mut parser = JSONParser::with_options(ParserOptions::new().strict(true));
+    /// let mut values = Vec::new();
+    ///
+    /// parser
+    ///     .extract_values_from_stream(
+    ///         "worker{pid=1234} sent {\"a\" 1} then {\"job\": [1, 2]}",
+    ///         |value| values.push(value.to_string()),
+    ///     )
+    ///     .unwrap();
+    ///
+    /// assert_eq!(values, vec!["{\"job\": [1, 2]}"]);
+    /// ```
+    pub fn strict(mut self, enabled: bool) -> Self {
+        self.strict = enabled;
+        self
+    }
+
+    /// Drops completed values that `serde_json` does not accept.
+    ///
+    /// Each value is held until it completes and is then parsed with
+    /// `serde_json`. Values that fail to parse are dropped silently, so
+    /// downstream deserialization never sees balanced but invalid candidates
+    /// such as `{"a": }`. Unlike [`strict`](Self::strict), this buffers each
+    /// value, but it checks the output exactly as it would be written.
+    /// [`JSONParser::strict`](crate::JSONParser::strict) enables both.
+    ///
+    /// # Arguments
+    ///
+    /// * `enabled` - Whether completed values should be parsed with `serde_json`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use surfing::parser::options::ParserOptions;
+    /// use surfing::JSONParser;
+    ///
+    /// let mut parser = JSONParser::with_options(ParserOptions::new().validate_values(true));
+    /// let mut buffer = Vec::new();
+    /// parser.extract_json_from_stream(&mut buffer, "{key: 1} {\"key\": 1}").unwrap();
+    ///
+    /// assert_eq!(String::from_utf8(buffer).unwrap(), "{\"key\": 1}");
+    /// ```
+    #[cfg(feature = "serde")]
+    pub fn validate_values(mut self, enabled: bool) -> Self {
+        self.validate_values = enabled;
+        self
+    }
+
+    /// Checks if a character is configured as whitespace but is not whitespace in JSON.
+    ///
+    /// # Arguments
+    ///
+    /// * `item` - The character to check.
+    pub(crate) fn is_extra_whitespace(&self, item: char) -> bool {
+        !is_json_whitespace(item)
+            && self
+                .whitespace
+                .as_ref()
+                .is_some_and(|chars| chars.contains(&item))
+    }
+
+    /// Checks if a value may be dropped after it starts, for a reason other than a mismatched closer.
+    pub(crate) fn drops_values(&self) -> bool {
+        self.strict
+            || self.validate_value_starts
+            || self.sse_mode
+            || self.ndjson
+            || self.record_separator.is_some()
+            || self.max_string_bytes.is_some()
+            || self.max_depth.is_some()
+            || self.max_buffer_size.is_some()
+            || self.max_object_bytes.is_some()
+            || self.control_char_policy == ControlCharPolicy::Reject
+            || self.lone_surrogate_policy == LoneSurrogatePolicy::Reject
+            || self.validates_values()
+    }
+
+    /// Checks if completed values are parsed with `serde_json` before they are emitted.
+    pub(crate) fn validates_values(&self) -> bool {
+        #[cfg(feature = "serde")]
+        return self.validate_values;
+        #[cfg(not(feature = "serde"))]
+        false
+    }
+
+    /// Checks if whitespace beyond JSON's own is configured.
+    pub(crate) fn has_extra_whitespace(&self) -> bool {
+        self.whitespace
+            .as_ref()
+            .is_some_and(|chars| chars.iter().any(|item| !is_json_whitespace(*item)))
+    }
+}
+
+/// Checks if a character is whitespace in JSON text.
+pub(crate) fn is_json_whitespace(item: char) -> bool {
+    matches!(item, ' ' | '\t' | '\n' | '\r')
+}
@@ -0,0 +1,70 @@
+//! Outcome module describing what the parser does with characters and values.
+
+/// What a [`JSONParser`](crate::JSONParser) did with a character fed to
+/// [`JSONParser::push_char`](crate::JSONParser::push_char).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CharOutcome {
+    /// The character was written, continuing the current value.
+    Emitted,
+    /// Nothing was written for the character, such as for plain text outside JSON.
+    Skipped,
+    /// The character opened a new value and was written.
+    Started,
+    /// The character completed a value. A value dropped before completing,
+    /// for example at a record separator, is reported as
+    /// [`Skipped`](Self::Skipped) instead.
+    Completed,
+}
+
+/// Whether a value is kept, as decided by the callback set with
+/// [`JSONParser::on_value_prefix`](crate::JSONParser::on_value_prefix).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValueDecision {
+    /// Write the value, prefix included.
+    Keep,
+    /// Drop the prefix and suppress the rest of the value.
+    Skip,
+}
+
+/// How the input ended, as reported by [`JSONParser::finish`](crate::JSONParser::finish).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StreamEnd {
+    /// The input ended outside of any value.
+    Clean,
+    /// The input ended inside a value that was never completed.
+    Truncated {
+        /// The number of objects and arrays still open
+        open_markers: usize,
+        /// The input text of the value collected so far
+        partial: String,
+    },
+}
+
+/// A writer counting the bytes passed on to the writer it wraps.
+pub(crate) struct CountingWriter<'a, W> {
+    inner: &'a mut W,
+    written: usize,
+}
+
+impl<'a, W: std::io::Write> CountingWriter<'a, W> {
+    pub(crate) fn new(inner: &'a mut W) -> Self {
+        Self { inner, written: 0 }
+    }
+
+    /// Returns the number of bytes written so far.
+    pub(crate) fn written(&self) -> usize {
+        self.written
+    }
+}
+
+impl<W: std::io::Write> std::io::Write for CountingWriter<'_, W> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.written += written;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        self.inner.flush()
+    }
+}
@@ -0,0 +1,142 @@
+//! Detection of percent-encoded JSON outside of JSON structures.
+
+use crate::parser::grammar::is_valid_json;
+
+/// The largest decoded candidate considered, in bytes.
+const MAX_CANDIDATE_BYTES: usize = 64 * 1024;
+
+/// The outcome of feeding a character to a [`PercentDecoder`].
+pub(crate) enum PercentStep {
+    /// The character is not part of a run and is processed as usual
+    Ignored,
+    /// The character was taken into the current run
+    Pending,
+    /// The character completed a run that decodes to valid JSON
+    Decoded(String),
+}
+
+/// Collects runs of percent-encoded text and decodes those holding JSON.
+///
+/// A run starts at a `%` and continues over characters that may appear
+/// unescaped in URLs. It is a candidate only if its first decoded character
+/// is `{` or `[`, and it is reported as soon as the decoded text is a
+/// complete, valid JSON value. A malformed escape or any other character
+/// ends the run without output.
+#[derive(Debug, Default)]
+pub(crate) struct PercentDecoder {
+    active: bool,
+    hex_digits: Option<Vec<u8>>,
+    decoded: Vec<u8>,
+}
+
+impl PercentDecoder {
+    /// Checks if a run is being collected.
+    pub(crate) fn is_active(&self) -> bool {
+        self.active
+    }
+
+    /// Feeds the next character outside of JSON structures.
+    ///
+    /// # Arguments
+    ///
+    /// * `item` - The character to process.
+    pub(crate) fn push(&mut self, item: char) -> PercentStep {
+        if !self.active {
+            if item != '%' {
+                return PercentStep::Ignored;
+            }
+            self.active = true;
+            self.hex_digits = Some(Vec::with_capacity(2));
+            return PercentStep::Pending;
+        }
+
+        let byte = match self.hex_digits.as_mut() {
+            Some(digits) => {
+                let Some(digit) = item.to_digit(16) else {
+                    self.reset();
+                    return self.push(item);
+                };
+                digits.push(digit as u8);
+                if digits.len() < 2 {
+                    return PercentStep::Pending;
+                }
+
+                let byte = digits[0] * 16 + digits[1];
+                self.hex_digits = None;
+                byte
+            }
+            None if item == '%' => {
+                self.hex_digits = Some(Vec::with_capacity(2));
+                return PercentStep::Pending;
+            }
+            None if item == '+' => b' ',
+            None if is_url_char(item) => item as u8,
+            None => {
+                self.reset();
+                return PercentStep::Ignored;
+            }
+        };
+
+        self.decoded.push(byte);
+        if !matches!(self.decoded[0], b'{' | b'[') || self.decoded.len() > MAX_CANDIDATE_BYTES {
+            self.reset();
+            return PercentStep::Pending;
+        }
+
+        if matches!(byte, b'}' | b']') {
+            if let Ok(text) = std::str::from_utf8(&self.decoded) {
+                if is_valid_json(text) {
+                    let json = text.to_string();
+                    self.reset();
+                    return PercentStep::Decoded(json);
+                }
+            }
+        }
+
+        PercentStep::Pending
+    }
+
+    /// Drops the current run.
+    pub(crate) fn reset(&mut self) {
+        self.active = false;
+        self.hex_digits = None;
+        self.decoded.clear();
+    }
+}
+
+/// Checks if the character may appear unescaped in a percent-encoded run.
+fn is_url_char(item: char) -> bool {
+    item.is_ascii_alphanumeric() || "-._~!*'():,;/@$".contains(item)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decode(text: &str) -> Vec<String> {
+        let mut decoder = PercentDecoder::default();
+        text.chars()
+            .filter_map(|item| match decoder.push(item) {
+                PercentStep::Decoded(json) => Some(json),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_percent_decoder_decodes_json_runs() {
+        assert_eq!(
+            decode("?q=%7B%22a%22%3A1%7D&r=%5B1,2%5D"),
+            vec!["{\"a\":1}", "[1,2]"]
+        );
+        assert_eq!(
+            decode("%7b%22s%22:%22x+y%25%22%7d"),
+            vec!["{\"s\":\"x y%\"}"]
+        );
+    }
+
+    #[test]
+    fn test_percent_decoder_ignores_non_json() {
+        assert!(decode("100% sure, 50%off, %20%41, %7Bnot json%7D, %7B%22a%22%7D").is_empty());
+    }
+}
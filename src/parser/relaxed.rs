@@ -0,0 +1,154 @@
+//! Relaxed module for translating Python-style literals into JSON.
+
+/// Translates relaxed, Python-style JSON into strict JSON one character at a time.
+///
+/// The translator sits in front of the regular parsing of a JSON structure and
+/// performs only these substitutions:
+///
+/// - Outside strings, the words `True`, `False` and `None` become `true`,
+///   `false` and `null`.
+/// - Outside strings, numbers lose insignificant leading zeros (`007` becomes
+///   `7`, `-00.5` becomes `-0.5`).
+/// - Single-quoted strings become double-quoted strings: double quotes inside
+///   them are escaped and `\'` becomes `'`.
+///
+/// Words and numbers are held back until the character following them arrives,
+/// so a token split across chunks is still translated as a whole.
+#[derive(Debug, Default)]
+pub(crate) struct RelaxedTranslator {
+    quote: Option<char>,
+    escaped: bool,
+    token: String,
+}
+
+impl RelaxedTranslator {
+    /// Translates a single character, appending the resulting JSON to `output`.
+    ///
+    /// # Arguments
+    ///
+    /// * `item` - The input character.
+    /// * `output` - The string receiving the translated characters.
+    pub(crate) fn translate(&mut self, item: char, output: &mut String) {
+        let Some(quote) = self.quote else {
+            if is_token_char(item) {
+                self.token.push(item);
+                return;
+            }
+
+            self.flush_token(output);
+            if item == '"' || item == '\'' {
+                self.quote = Some(item);
+                output.push('"');
+            } else {
+                output.push(item);
+            }
+            return;
+        };
+
+        if self.escaped {
+            self.escaped = false;
+            if item != '\'' {
+                output.push('\\');
+            }
+            output.push(item);
+        } else if item == '\\' {
+            // The escape is written once we know what it escapes
+            self.escaped = true;
+        } else if item == quote {
+            self.quote = None;
+            output.push('"');
+        } else if item == '"' {
+            output.push_str("\\\"");
+        } else {
+            output.push(item);
+        }
+    }
+
+    /// Clears the translation state for the next structure.
+    pub(crate) fn reset(&mut self) {
+        self.quote = None;
+        self.escaped = false;
+        self.token.clear();
+    }
+
+    /// Writes the pending word or number, applying its substitution.
+    fn flush_token(&mut self, output: &mut String) {
+        match self.token.as_str() {
+            "" => return,
+            "True" => output.push_str("true"),
+            "False" => output.push_str("false"),
+            "None" => output.push_str("null"),
+            token => output.push_str(&strip_leading_zeros(token)),
+        }
+        self.token.clear();
+    }
+}
+
+/// Checks if the character can be part of a bare word or number.
+fn is_token_char(item: char) -> bool {
+    item.is_alphanumeric() || matches!(item, '_' | '-' | '+' | '.')
+}
+
+/// Removes insignificant leading zeros from the integer part of a number.
+fn strip_leading_zeros(token: &str) -> String {
+    let (sign, unsigned) = match token.strip_prefix('-') {
+        Some(unsigned) => ("-", unsigned),
+        None => ("", token),
+    };
+
+    let digits = unsigned.len()
+        - unsigned
+            .trim_start_matches(|c: char| c.is_ascii_digit())
+            .len();
+    if digits < 2 || !unsigned.starts_with('0') {
+        return token.to_string();
+    }
+
+    // Keep the last zero when the integer part has no other digits
+    let stripped = unsigned[..digits].trim_start_matches('0');
+    let kept = if stripped.is_empty() {
+        digits - 1
+    } else {
+        digits - stripped.len()
+    };
+
+    format!("{}{}", sign, &unsigned[kept..])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn translate(input: &str) -> String {
+        let mut translator = RelaxedTranslator::default();
+        let mut output = String::new();
+        for item in input.chars() {
+            translator.translate(item, &mut output);
+        }
+        output
+    }
+
+    #[test]
+    fn test_relaxed_literals() {
+        assert_eq!(
+            translate("[True, False, None,Truey]"),
+            "[true, false, null,Truey]"
+        );
+    }
+
+    #[test]
+    fn test_relaxed_leading_zeros() {
+        assert_eq!(
+            translate("[007, -00.50, 0, 0.5, 000, 10, 1e05]"),
+            "[7, -0.50, 0, 0.5, 0, 10, 1e05]"
+        );
+    }
+
+    #[test]
+    fn test_relaxed_single_quotes() {
+        assert_eq!(
+            translate(r#"{'say': 'it\'s "fine"', "True": 'None\n'}"#),
+            r#"{"say": "it's \"fine\"", "True": "None\n"}"#
+        );
+    }
+}
@@ -0,0 +1,173 @@
+//! Detection of JSON scalars outside of JSON structures.
+
+use crate::parser::grammar::is_valid_json;
+
+/// The longest scalar considered, in bytes.
+const MAX_SCALAR_BYTES: usize = 64 * 1024;
+
+/// The literals a word may complete to.
+const LITERALS: [&str; 3] = ["true", "false", "null"];
+
+/// The outcome of feeding a character to a [`ScalarScanner`].
+pub(crate) enum ScalarStep {
+    /// The character is not part of a scalar and is processed as usual
+    Ignored,
+    /// The character was taken into the current scalar
+    Pending,
+    /// The character closed a string scalar
+    Completed(String),
+    /// The character ended the number or literal before it and is processed as usual
+    Ended(String),
+}
+
+/// Collects candidate scalars in plain text and reports those that are valid JSON.
+///
+/// A candidate starts at a quote, a digit, a `-`, or the first letter of a
+/// literal, unless it follows a letter, a digit, `_`, `.`, `-` or `+`, so
+/// that the tail of a word or version number is not taken for a scalar.
+/// Strings end at their closing quote and are dropped at a control
+/// character. Numbers and literals run as long as their characters can
+/// continue them and are then checked whole: they are reported only if
+/// they are valid JSON and the character ending them is not a letter, a
+/// digit, `_`, or the start of another value. A single trailing `.` is
+/// taken as the end of a sentence, so `is 42.` gives `42`.
+#[derive(Debug, Default)]
+pub(crate) struct ScalarScanner {
+    token: String,
+    escaped: bool,
+    previous: Option<char>,
+}
+
+impl ScalarScanner {
+    /// Feeds the next character outside of JSON structures.
+    ///
+    /// # Arguments
+    ///
+    /// * `item` - The character to process.
+    pub(crate) fn push(&mut self, item: char) -> ScalarStep {
+        let step = self.step(item);
+        self.previous = Some(item);
+        step
+    }
+
+    /// Drops the current candidate and forgets the preceding character.
+    pub(crate) fn reset(&mut self) {
+        self.token.clear();
+        self.escaped = false;
+        self.previous = None;
+    }
+
+    /// Processes a character, with the preceding one still in `previous`.
+    fn step(&mut self, item: char) -> ScalarStep {
+        let Some(first) = self.token.chars().next() else {
+            if starts_scalar(item) && !self.previous.is_some_and(joins_scalar) {
+                self.token.push(item);
+                return ScalarStep::Pending;
+            }
+            return ScalarStep::Ignored;
+        };
+
+        if self.token.len() >= MAX_SCALAR_BYTES {
+            self.token.clear();
+            return ScalarStep::Ignored;
+        }
+
+        if first == '"' {
+            return self.push_string_char(item);
+        }
+
+        if first.is_ascii_alphabetic() && item.is_ascii_alphabetic() {
+            self.token.push(item);
+            if !LITERALS
+                .iter()
+                .any(|literal| literal.starts_with(&self.token))
+            {
+                self.token.clear();
+            }
+            return ScalarStep::Pending;
+        }
+        if !first.is_ascii_alphabetic() && matches!(item, '0'..='9' | '.' | 'e' | 'E' | '+' | '-') {
+            self.token.push(item);
+            return ScalarStep::Pending;
+        }
+
+        let mut token = std::mem::take(&mut self.token);
+        if item.is_alphanumeric() || matches!(item, '_' | '"' | '{' | '[') {
+            return ScalarStep::Ignored;
+        }
+        if !first.is_ascii_alphabetic() && token.ends_with('.') {
+            token.pop();
+        }
+
+        if is_valid_json(&token) {
+            ScalarStep::Ended(token)
+        } else {
+            ScalarStep::Ignored
+        }
+    }
+
+    /// Processes a character of a string candidate.
+    fn push_string_char(&mut self, item: char) -> ScalarStep {
+        if item < ' ' {
+            self.token.clear();
+            self.escaped = false;
+            return ScalarStep::Ignored;
+        }
+
+        self.token.push(item);
+        if self.escaped {
+            self.escaped = false;
+        } else if item == '\\' {
+            self.escaped = true;
+        } else if item == '"' {
+            let token = std::mem::take(&mut self.token);
+            if is_valid_json(&token) {
+                return ScalarStep::Completed(token);
+            }
+        }
+
+        ScalarStep::Pending
+    }
+}
+
+/// Checks if a character can start a scalar.
+fn starts_scalar(item: char) -> bool {
+    matches!(item, '"' | '-' | '0'..='9' | 't' | 'f' | 'n')
+}
+
+/// Checks if a character preceding a scalar would make it part of a longer word.
+fn joins_scalar(item: char) -> bool {
+    item.is_alphanumeric() || matches!(item, '_' | '.' | '-' | '+')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scan(text: &str) -> Vec<String> {
+        let mut scanner = ScalarScanner::default();
+        text.chars()
+            .filter_map(|item| match scanner.push(item) {
+                ScalarStep::Completed(json) | ScalarStep::Ended(json) => Some(json),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_scalar_scanner_finds_scalars() {
+        assert_eq!(
+            scan("temperature: 98.6 degrees, ok=true; n:-1e3 is \"a \\\"b\\\"\" or null."),
+            vec!["98.6", "true", "-1e3", "\"a \\\"b\\\"\"", "null"]
+        );
+        assert_eq!(scan("count: 42. done "), vec!["42"]);
+    }
+
+    #[test]
+    fn test_scalar_scanner_ignores_words_and_malformed_numbers() {
+        assert!(scan(
+            "v1.2 1.2.3 2024-01-01 007 1e 3px x42 nullable trust not - \"a\nb\" 5_000 truefalse "
+        )
+        .is_empty());
+    }
+}
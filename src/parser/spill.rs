@@ -0,0 +1,167 @@
+//! Spill module for writing oversized values to temporary files.
+
+use std::fs::File;
+use std::io::{BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::Path;
+
+use tempfile::NamedTempFile;
+
+/// A completed JSON value handed to the callback of
+/// [`JSONParser::extract_payloads_from_stream`](crate::JSONParser::extract_payloads_from_stream).
+#[derive(Debug)]
+pub enum ValuePayload {
+    /// A value no larger than the spill threshold, kept in memory
+    Inline(String),
+    /// A value larger than the spill threshold, written to a temporary file
+    Spilled(SpilledValue),
+}
+
+/// A JSON value that was written to a temporary file.
+///
+/// The file is deleted when the `SpilledValue` is dropped, unless it is kept
+/// with [`persist`](Self::persist).
+#[derive(Debug)]
+pub struct SpilledValue {
+    file: NamedTempFile,
+    len: u64,
+}
+
+impl SpilledValue {
+    /// Returns the path of the temporary file.
+    pub fn path(&self) -> &Path {
+        self.file.path()
+    }
+
+    /// Returns the size of the value in bytes.
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    /// Checks if the value is empty, which a spilled value never is.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Opens the file for reading from the start of the value.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(File)` - A new handle positioned at the start of the file.
+    /// * `Err(Error)` - If the file could not be opened.
+    pub fn open(&self) -> std::io::Result<File> {
+        let mut file = self.file.reopen()?;
+        file.seek(SeekFrom::Start(0))?;
+        Ok(file)
+    }
+
+    /// Reads the whole value into memory.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(String)` - The JSON value.
+    /// * `Err(Error)` - If the file could not be read.
+    pub fn read_to_string(&self) -> std::io::Result<String> {
+        let mut value = String::with_capacity(self.len as usize);
+        self.open()?.read_to_string(&mut value)?;
+        Ok(value)
+    }
+
+    /// Moves the file to a permanent location so it is not deleted on drop.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The new location of the file.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(File)` - The persisted file.
+    /// * `Err(Error)` - If the file could not be moved.
+    pub fn persist<P: AsRef<Path>>(self, path: P) -> std::io::Result<File> {
+        self.file.persist(path).map_err(|e| e.error)
+    }
+}
+
+/// Tracks the temporary file of the value currently being extracted.
+#[derive(Debug, Default)]
+pub(crate) struct Spill {
+    file: Option<BufWriter<NamedTempFile>>,
+    len: u64,
+    failed: bool,
+}
+
+impl Spill {
+    /// Moves collected output to the spill file once it exceeds the threshold.
+    ///
+    /// If the file cannot be created or written, the error is returned once
+    /// and the rest of the value is discarded.
+    ///
+    /// # Arguments
+    ///
+    /// * `output` - The output collected for the current value.
+    /// * `threshold` - The largest value size kept in memory.
+    pub(crate) fn absorb(&mut self, output: &mut Vec<u8>, threshold: usize) -> std::io::Result<()> {
+        if self.failed {
+            output.clear();
+            return Ok(());
+        }
+
+        if self.file.is_none() && output.len() <= threshold {
+            return Ok(());
+        }
+
+        let result = self.write(output);
+        output.clear();
+        if result.is_err() {
+            self.failed = true;
+            self.file = None;
+        }
+
+        result
+    }
+
+    /// Ends the current value and returns its payload.
+    ///
+    /// # Arguments
+    ///
+    /// * `output` - The output collected for the current value.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Some(ValuePayload))` - The payload of the completed value.
+    /// * `Ok(None)` - If the value was discarded after a spill error.
+    /// * `Err(Error)` - If the spill file could not be flushed.
+    pub(crate) fn finish(&mut self, output: &mut Vec<u8>) -> std::io::Result<Option<ValuePayload>> {
+        let len = std::mem::take(&mut self.len);
+        if std::mem::take(&mut self.failed) {
+            output.clear();
+            return Ok(None);
+        }
+
+        let Some(file) = self.file.take() else {
+            let value = String::from_utf8(std::mem::take(output))
+                .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+            return Ok(Some(ValuePayload::Inline(value)));
+        };
+
+        let file = file.into_inner().map_err(|e| e.into_error())?;
+        Ok(Some(ValuePayload::Spilled(SpilledValue { file, len })))
+    }
+
+    /// Drops the current value, deleting its spill file if one was created.
+    pub(crate) fn discard(&mut self) {
+        self.file = None;
+        self.len = 0;
+        self.failed = false;
+    }
+
+    fn write(&mut self, output: &[u8]) -> std::io::Result<()> {
+        let file = match self.file.as_mut() {
+            Some(file) => file,
+            None => self.file.insert(BufWriter::new(NamedTempFile::new()?)),
+        };
+
+        file.write_all(output)?;
+        self.len += output.len() as u64;
+        Ok(())
+    }
+}
@@ -0,0 +1,142 @@
+//! SSE module for reading payloads out of Server-Sent Events framing.
+
+/// The payload some streaming APIs send as their last event, which is not JSON.
+const DONE_MARKER: &str = "[DONE]";
+
+/// Splits a Server-Sent Events stream into the data payloads of its events.
+///
+/// Lines are collected across chunks and end at a CR, an LF or a CRLF pair,
+/// even one split between chunks. `data:` fields are joined with newlines,
+/// and the joined payload is dispatched when a blank line ends the event.
+/// Comments and all other fields, such as `event:` and `id:`, are ignored,
+/// and so is the `[DONE]` payload ending the stream.
+#[derive(Debug, Default)]
+pub(crate) struct SseFramer {
+    line: String,
+    data: String,
+    /// Whether the last character was a CR, which an LF right after belongs to
+    after_cr: bool,
+}
+
+impl SseFramer {
+    /// Processes a chunk of the event stream.
+    ///
+    /// # Arguments
+    ///
+    /// * `chunk` - The next piece of the event stream.
+    ///
+    /// # Returns
+    ///
+    /// The data payloads of every event completed by this chunk.
+    pub(crate) fn feed(&mut self, chunk: &str) -> Vec<String> {
+        let mut payloads = Vec::new();
+
+        for item in chunk.chars() {
+            let after_cr = std::mem::replace(&mut self.after_cr, item == '\r');
+            match item {
+                // The line already ended at the CR
+                '\n' if after_cr => continue,
+                '\r' | '\n' => {}
+                _ => {
+                    self.line.push(item);
+                    continue;
+                }
+            }
+
+            let line = std::mem::take(&mut self.line);
+            if line.is_empty() {
+                if let Some(payload) = self.dispatch() {
+                    payloads.push(payload);
+                }
+            } else {
+                self.process_line(&line);
+            }
+        }
+
+        payloads
+    }
+
+    /// Forgets the event in progress, keeping the allocated buffers.
+    pub(crate) fn reset(&mut self) {
+        self.line.clear();
+        self.data.clear();
+        self.after_cr = false;
+    }
+
+    /// Handles a single non-blank line of an event.
+    fn process_line(&mut self, line: &str) {
+        let (field, value) = match line.split_once(':') {
+            Some((field, value)) => (field, value.strip_prefix(' ').unwrap_or(value)),
+            None => (line, ""),
+        };
+
+        // Comments have an empty field name and, like other fields, carry no payload
+        if field == "data" {
+            self.data.push_str(value);
+            self.data.push('\n');
+        }
+    }
+
+    /// Ends the current event, returning its payload if it had any data.
+    fn dispatch(&mut self) -> Option<String> {
+        let mut payload = std::mem::take(&mut self.data);
+        payload.pop()?;
+        (payload != DONE_MARKER).then_some(payload)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sse_framer_single_event() {
+        let mut framer = SseFramer::default();
+        let payloads = framer.feed("event: message\nid: 1\ndata: {\"a\":1}\n\n");
+        assert_eq!(payloads, vec!["{\"a\":1}"]);
+    }
+
+    #[test]
+    fn test_sse_framer_multi_line_data_across_chunks() {
+        let mut framer = SseFramer::default();
+        assert!(framer.feed(": keep-alive\r\ndata: {\"a\":").is_empty());
+        assert!(framer.feed("\r\ndata:1}\r\n").is_empty());
+        assert_eq!(framer.feed("\r\n"), vec!["{\"a\":\n1}"]);
+    }
+
+    #[test]
+    fn test_sse_framer_cr_line_breaks() {
+        let mut framer = SseFramer::default();
+        assert_eq!(
+            framer.feed("data: {\"a\":\rdata: 1}\r\rdata: [2]\r"),
+            vec!["{\"a\":\n1}"]
+        );
+
+        // A CRLF split between chunks is a single line break
+        assert!(framer.feed("\n").is_empty());
+        assert_eq!(framer.feed("\r\n"), vec!["[2]"]);
+    }
+
+    #[test]
+    fn test_sse_framer_reset_keeps_capacity() {
+        let mut framer = SseFramer::default();
+        assert!(framer.feed("data: {\"a\":1}\ndata: [2").is_empty());
+        let capacity = (framer.line.capacity(), framer.data.capacity());
+
+        framer.reset();
+        assert_eq!((framer.line.capacity(), framer.data.capacity()), capacity);
+        assert_eq!(framer.feed("\ndata: [3]\n\n"), vec!["[3]"]);
+    }
+
+    #[test]
+    fn test_sse_framer_event_without_data() {
+        let mut framer = SseFramer::default();
+        assert!(framer.feed("event: ping\n\n").is_empty());
+    }
+
+    #[test]
+    fn test_sse_framer_skips_done_marker() {
+        let mut framer = SseFramer::default();
+        assert_eq!(framer.feed("data: [1]\n\ndata: [DONE]\n\n"), vec!["[1]"]);
+    }
+}
@@ -0,0 +1,299 @@
+//! String scanner module for finding the end of JSON strings.
+
+/// The result of feeding a chunk to a [`StringScanner`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScanOutcome {
+    /// The whole chunk belongs to the string, which is still open
+    Continue,
+    /// The string was closed by an unescaped quote
+    Closed {
+        /// The number of bytes of the chunk up to and including the closing quote
+        consumed: usize,
+    },
+    /// An escape sequence is invalid
+    InvalidEscape {
+        /// The byte offset in the chunk of the character making the escape invalid
+        offset: usize,
+    },
+}
+
+/// Where the scanner is within a string.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+enum State {
+    #[default]
+    Text,
+    Escape,
+    Unicode(u8),
+}
+
+/// The result of scanning a single character.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Step {
+    Continue,
+    Closed,
+    Invalid,
+}
+
+/// Scans the inside of a JSON string for its closing quote.
+///
+/// The scanner starts just after the opening quote and tracks escape
+/// sequences, including `\uXXXX`, so that escaped quotes do not close the
+/// string. It is resumable: text can be fed in chunks split anywhere, even
+/// in the middle of an escape sequence.
+///
+/// When an escape sequence turns out to be invalid, the scanner treats the
+/// offending character as plain text. Feeding the chunk again from the
+/// reported offset continues the scan as if the escape had ended there.
+/// After the string is closed, the scanner is ready for the next string.
+///
+/// # Examples
+///
+/// ```
+/// use surfing::parser::string_scanner::{ScanOutcome, StringScanner};
+///
+/// let mut scanner = StringScanner::new();
+///
+/// assert_eq!(scanner.feed("say \\"), ScanOutcome::Continue);
+/// assert!(scanner.is_in_escape());
+/// assert_eq!(scanner.feed("\"hi\\u00"), ScanOutcome::Continue);
+/// assert_eq!(scanner.feed("21\": 1}"), ScanOutcome::Closed { consumed: 3 });
+///
+/// assert_eq!(scanner.feed("bad \\x\""), ScanOutcome::InvalidEscape { offset: 5 });
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct StringScanner {
+    state: State,
+}
+
+impl StringScanner {
+    /// Creates a scanner positioned just after an opening quote.
+    ///
+    /// # Returns
+    ///
+    /// A new StringScanner instance.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Scans a chunk of the string.
+    ///
+    /// Scanning stops at the closing quote or at the first invalid escape,
+    /// so the rest of the chunk is left for the caller.
+    ///
+    /// # Arguments
+    ///
+    /// * `chunk` - The next part of the string.
+    ///
+    /// # Returns
+    ///
+    /// * `ScanOutcome::Continue` - If the string is still open after the chunk.
+    /// * `ScanOutcome::Closed` - If the chunk contains the closing quote.
+    /// * `ScanOutcome::InvalidEscape` - If the chunk completes an invalid escape.
+    pub fn feed(&mut self, chunk: &str) -> ScanOutcome {
+        for (offset, item) in chunk.char_indices() {
+            match self.step(item) {
+                Step::Continue => {}
+                Step::Closed => {
+                    return ScanOutcome::Closed {
+                        consumed: offset + 1,
+                    }
+                }
+                Step::Invalid => return ScanOutcome::InvalidEscape { offset },
+            }
+        }
+
+        ScanOutcome::Continue
+    }
+
+    /// Checks if the scanner is in the middle of an escape sequence.
+    ///
+    /// # Returns
+    ///
+    /// `true` after a backslash or inside the digits of a `\uXXXX` escape.
+    pub fn is_in_escape(&self) -> bool {
+        self.state != State::Text
+    }
+
+    /// Resets the scanner to the start of a string.
+    pub fn reset(&mut self) {
+        self.state = State::Text;
+    }
+
+    /// Scans a single character, recovering from invalid escapes.
+    ///
+    /// # Arguments
+    ///
+    /// * `item` - The next character of the string.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the character closes the string.
+    pub(crate) fn push(&mut self, item: char) -> bool {
+        match self.step(item) {
+            Step::Invalid => self.step(item) == Step::Closed,
+            step => step == Step::Closed,
+        }
+    }
+
+    /// Checks if the last character was a backslash starting an escape.
+    pub(crate) fn is_after_backslash(&self) -> bool {
+        self.state == State::Escape
+    }
+
+    /// Scans a single character without recovering from invalid escapes.
+    ///
+    /// # Arguments
+    ///
+    /// * `item` - The next character of the string.
+    ///
+    /// # Returns
+    ///
+    /// Whether the string continues, closes, or has an invalid escape.
+    pub(crate) fn step(&mut self, item: char) -> Step {
+        match self.state {
+            State::Escape if is_escape_char(item) => {
+                self.state = if item == 'u' {
+                    State::Unicode(4)
+                } else {
+                    State::Text
+                };
+                Step::Continue
+            }
+            State::Unicode(digits) if item.is_ascii_hexdigit() => {
+                self.state = if digits > 1 {
+                    State::Unicode(digits - 1)
+                } else {
+                    State::Text
+                };
+                Step::Continue
+            }
+            State::Escape | State::Unicode(_) => {
+                self.state = State::Text;
+                Step::Invalid
+            }
+            State::Text => match item {
+                '\\' => {
+                    self.state = State::Escape;
+                    Step::Continue
+                }
+                '"' => Step::Closed,
+                _ => Step::Continue,
+            },
+        }
+    }
+}
+
+/// Checks if the character may follow a backslash in a JSON string.
+fn is_escape_char(item: char) -> bool {
+    matches!(item, '"' | '\\' | '/' | 'b' | 'f' | 'n' | 'r' | 't' | 'u')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Feeds the text split at every pair of positions and checks the outcome
+    /// matches feeding it whole.
+    fn assert_split_invariant(text: &str, expected: ScanOutcome) {
+        let mut whole = StringScanner::new();
+        assert_eq!(whole.feed(text), expected, "whole {text:?}");
+
+        let bounds: Vec<usize> = (0..=text.len())
+            .filter(|&i| text.is_char_boundary(i))
+            .collect();
+        for &first in &bounds {
+            for &second in bounds.iter().filter(|&&i| i >= first) {
+                let chunks = [&text[..first], &text[first..second], &text[second..]];
+                let mut scanner = StringScanner::new();
+                let mut start = 0;
+                let mut outcome = ScanOutcome::Continue;
+
+                for chunk in chunks {
+                    outcome = match scanner.feed(chunk) {
+                        ScanOutcome::Continue => {
+                            start += chunk.len();
+                            continue;
+                        }
+                        ScanOutcome::Closed { consumed } => ScanOutcome::Closed {
+                            consumed: start + consumed,
+                        },
+                        ScanOutcome::InvalidEscape { offset } => ScanOutcome::InvalidEscape {
+                            offset: start + offset,
+                        },
+                    };
+                    break;
+                }
+
+                assert_eq!(outcome, expected, "{text:?} split at {first} and {second}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_valid_escapes_split_across_feeds() {
+        for escape in ["\\\"", "\\\\", "\\/", "\\b", "\\f", "\\n", "\\r", "\\t"] {
+            let text = format!("a{escape}b\" tail");
+            assert_split_invariant(&text, ScanOutcome::Closed { consumed: 5 });
+        }
+
+        assert_split_invariant(
+            "\\u00e9\\uD83D\\uDE00\"",
+            ScanOutcome::Closed { consumed: 19 },
+        );
+        assert_split_invariant("é\\\\\"", ScanOutcome::Closed { consumed: 5 });
+        assert_split_invariant("\\\\\\\"x", ScanOutcome::Continue);
+    }
+
+    #[test]
+    fn test_invalid_escapes_split_across_feeds() {
+        assert_split_invariant("ab\\x\"", ScanOutcome::InvalidEscape { offset: 3 });
+        assert_split_invariant("\\u12g4\"", ScanOutcome::InvalidEscape { offset: 4 });
+        assert_split_invariant("\\u00\"", ScanOutcome::InvalidEscape { offset: 4 });
+        assert_split_invariant("\\é", ScanOutcome::InvalidEscape { offset: 1 });
+    }
+
+    #[test]
+    fn test_every_escape_character() {
+        for item in (0..=0x7Fu8).map(char::from) {
+            let text = format!("\\{item}0000\"");
+            let expected = if is_escape_char(item) {
+                ScanOutcome::Closed {
+                    consumed: text.len(),
+                }
+            } else {
+                ScanOutcome::InvalidEscape { offset: 1 }
+            };
+            assert_split_invariant(&text, expected);
+        }
+    }
+
+    #[test]
+    fn test_resume_after_invalid_escape() {
+        let text = "\\u0\"x\"";
+        let mut scanner = StringScanner::new();
+
+        assert_eq!(scanner.feed(text), ScanOutcome::InvalidEscape { offset: 3 });
+        assert!(!scanner.is_in_escape());
+        assert_eq!(
+            scanner.feed(&text[3..]),
+            ScanOutcome::Closed { consumed: 1 }
+        );
+        assert_eq!(
+            scanner.feed(&text[4..]),
+            ScanOutcome::Closed { consumed: 2 }
+        );
+    }
+
+    #[test]
+    fn test_push_recovers_like_feed() {
+        let mut scanner = StringScanner::new();
+
+        assert!(!scanner.push('\\'));
+        assert!(scanner.is_after_backslash());
+        assert!(!scanner.push('u'));
+        assert!(!scanner.push('1'));
+        assert!(scanner.is_in_escape());
+        assert!(scanner.push('"'));
+        assert!(!scanner.is_in_escape());
+    }
+}
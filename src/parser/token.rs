@@ -0,0 +1,20 @@
+//! Token module for driving the parser from an external lexer.
+
+/// A lexical token produced by an external JSON lexer.
+///
+/// Tokens let a caller that has already tokenized its input drive a
+/// [`JSONParser`](crate::JSONParser) without the parser re-scanning the text
+/// to classify it. See [`JSONParser::feed_token`](crate::JSONParser::feed_token).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Token<'a> {
+    /// A structural character: `{`, `}`, `[`, `]`, `:` or `,`.
+    Structural(char),
+    /// Text of a string literal, including its quotes and escape sequences
+    /// exactly as they appear in the input. A long literal may be split
+    /// across several consecutive tokens.
+    String(&'a str),
+    /// Whitespace between tokens.
+    Whitespace(&'a str),
+    /// Any other text, such as numbers, literals or prose outside JSON.
+    Text(&'a str),
+}
@@ -0,0 +1,274 @@
+//! Validator module for checking streamed JSON without extracting it.
+
+use std::fmt;
+
+use crate::parser::grammar::GrammarChecker;
+use crate::parser::options::is_json_whitespace;
+
+/// Why a [`JsonValidator`] rejected its input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InvalidReason {
+    /// A character that cannot appear at this point of the JSON
+    UnexpectedChar(char),
+    /// A backslash not followed by a valid escape sequence
+    InvalidEscape,
+    /// A control character that is not escaped inside a string
+    ControlChar,
+}
+
+impl fmt::Display for InvalidReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InvalidReason::UnexpectedChar(item) => write!(f, "unexpected character {item:?}"),
+            InvalidReason::InvalidEscape => write!(f, "invalid escape sequence"),
+            InvalidReason::ControlChar => write!(f, "unescaped control character in string"),
+        }
+    }
+}
+
+/// The result of feeding a chunk to a [`JsonValidator`].
+///
+/// Offsets are byte offsets in the chunk passed to that call of
+/// [`JsonValidator::feed`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ValidationStatus {
+    /// The JSON is well-formed so far but not complete
+    Incomplete,
+    /// The JSON is complete and well-formed
+    Complete {
+        /// The offset where the text after the JSON starts
+        trailing_offset: usize,
+    },
+    /// The JSON is not well-formed
+    Invalid {
+        /// The offset of the character where the problem was found
+        offset: usize,
+        /// What the problem is
+        reason: InvalidReason,
+    },
+}
+
+/// Where the validator is within the JSON.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    /// Before the opening bracket
+    Start,
+    /// Inside the JSON
+    Open,
+    /// After the closing bracket of the JSON
+    Done,
+    /// After the first problem
+    Failed(InvalidReason),
+}
+
+/// Checks that a stream of chunks holds a well-formed JSON object or array.
+///
+/// Unlike [`JSONParser`](crate::JSONParser), the validator extracts nothing:
+/// it has no writer and does not buffer the value, keeping only the stack of
+/// open brackets. It validates the full grammar with the same checker as
+/// [`ParserOptions::strict`](crate::parser::options::ParserOptions::strict),
+/// so it suits framing messages and aborting bad uploads early.
+///
+/// Leading whitespace is allowed. Once the JSON is complete or invalid, the
+/// validator keeps reporting so until [`reset`](Self::reset) is called.
+///
+/// # Examples
+///
+/// ```
+/// use surfing::parser::validator::{InvalidReason, JsonValidator, ValidationStatus};
+///
+/// let mut validator = JsonValidator::new();
+/// assert_eq!(validator.feed("{\"ids\": [1, 2"), ValidationStatus::Incomplete);
+/// assert_eq!(
+///     validator.feed("]}\nnext"),
+///     ValidationStatus::Complete { trailing_offset: 2 }
+/// );
+///
+/// validator.reset();
+/// assert_eq!(
+///     validator.feed("{\"a\": tru }"),
+///     ValidationStatus::Invalid {
+///         offset: 9,
+///         reason: InvalidReason::UnexpectedChar(' '),
+///     }
+/// );
+/// ```
+#[derive(Debug, Clone)]
+pub struct JsonValidator {
+    grammar: GrammarChecker,
+    state: State,
+}
+
+impl JsonValidator {
+    /// Creates a validator expecting the start of a JSON object or array.
+    ///
+    /// # Returns
+    ///
+    /// A new JsonValidator instance.
+    pub fn new() -> Self {
+        Self {
+            grammar: GrammarChecker::default(),
+            state: State::Start,
+        }
+    }
+
+    /// Validates the next chunk of the stream.
+    ///
+    /// # Arguments
+    ///
+    /// * `chunk` - The next part of the JSON.
+    ///
+    /// # Returns
+    ///
+    /// * `ValidationStatus::Incomplete` - If more input is needed.
+    /// * `ValidationStatus::Complete` - If the JSON has been closed.
+    /// * `ValidationStatus::Invalid` - If the JSON is not well-formed.
+    pub fn feed(&mut self, chunk: &str) -> ValidationStatus {
+        match self.state {
+            State::Done => return ValidationStatus::Complete { trailing_offset: 0 },
+            State::Failed(reason) => return ValidationStatus::Invalid { offset: 0, reason },
+            _ => {}
+        }
+
+        for (offset, item) in chunk.char_indices() {
+            self.push(item);
+
+            match self.state {
+                State::Done => {
+                    return ValidationStatus::Complete {
+                        trailing_offset: offset + item.len_utf8(),
+                    }
+                }
+                State::Failed(reason) => return ValidationStatus::Invalid { offset, reason },
+                _ => {}
+            }
+        }
+
+        ValidationStatus::Incomplete
+    }
+
+    /// Resets the validator to expect a new JSON value.
+    pub fn reset(&mut self) {
+        self.grammar.reset();
+        self.state = State::Start;
+    }
+
+    /// Advances the validator by one character.
+    ///
+    /// # Arguments
+    ///
+    /// * `item` - The character to process.
+    fn push(&mut self, item: char) {
+        // The checker accepts any value, but only an object or array is validated
+        if self.state == State::Start && !is_json_whitespace(item) {
+            if !matches!(item, '{' | '[') {
+                self.state = State::Failed(InvalidReason::UnexpectedChar(item));
+                return;
+            }
+            self.state = State::Open;
+        }
+
+        if let Err(reason) = self.grammar.check(item) {
+            self.state = State::Failed(reason);
+        } else if self.state == State::Open && self.grammar.is_complete() {
+            self.state = State::Done;
+        }
+    }
+}
+
+impl Default for JsonValidator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::grammar::is_valid_json;
+
+    fn validate(chunks: &[&str]) -> ValidationStatus {
+        let mut validator = JsonValidator::new();
+        let mut status = ValidationStatus::Incomplete;
+        for chunk in chunks {
+            status = validator.feed(chunk);
+        }
+        status
+    }
+
+    #[test]
+    fn test_validator_across_chunks() {
+        assert_eq!(
+            validate(&[
+                "  {\"a\\u00",
+                "e9\": [tr",
+                "ue, -1.5e",
+                "+3, null, {}]",
+                "} x"
+            ]),
+            ValidationStatus::Complete { trailing_offset: 1 }
+        );
+        assert_eq!(validate(&["[1, 2", "0"]), ValidationStatus::Incomplete);
+        assert_eq!(
+            validate(&["[1", "0]"]),
+            ValidationStatus::Complete { trailing_offset: 2 }
+        );
+    }
+
+    #[test]
+    fn test_validator_reports_problems() {
+        let invalid = |offset, reason| ValidationStatus::Invalid { offset, reason };
+
+        assert_eq!(
+            validate(&["{\"a\": 1,", "}"]),
+            invalid(0, InvalidReason::UnexpectedChar('}'))
+        );
+        assert_eq!(
+            validate(&["[\"a\\", "x\"]"]),
+            invalid(0, InvalidReason::InvalidEscape)
+        );
+        assert_eq!(
+            validate(&["[\"a\tb\"]"]),
+            invalid(3, InvalidReason::ControlChar)
+        );
+        assert_eq!(
+            validate(&["[01]"]),
+            invalid(2, InvalidReason::UnexpectedChar('1'))
+        );
+        assert_eq!(
+            validate(&["[1}"]),
+            invalid(2, InvalidReason::UnexpectedChar('}'))
+        );
+        assert_eq!(
+            validate(&["x[1]"]),
+            invalid(0, InvalidReason::UnexpectedChar('x'))
+        );
+        assert_eq!(
+            InvalidReason::UnexpectedChar('}').to_string(),
+            "unexpected character '}'"
+        );
+    }
+
+    #[test]
+    fn test_validator_agrees_with_strict_validation() {
+        let alphabet = ['{', '}', '[', ']', '"', ':', ',', '1', '-', 'e', ' '];
+        let mut inputs = vec![String::new()];
+
+        for _ in 0..5 {
+            inputs = inputs
+                .iter()
+                .flat_map(|input| alphabet.iter().map(move |item| format!("{input}{item}")))
+                .collect();
+
+            for input in &inputs {
+                let complete = matches!(
+                    validate(&[input]),
+                    ValidationStatus::Complete { trailing_offset }
+                        if input[trailing_offset..].trim().is_empty()
+                );
+                let expected = input.trim_start().starts_with(['{', '[']) && is_valid_json(input);
+                assert_eq!(complete, expected, "{input:?}");
+            }
+        }
+    }
+}
@@ -0,0 +1,151 @@
+//! Asynchronous deserialization over the `futures-io` traits.
+
+use futures_io::AsyncRead;
+use futures_util::io::AsyncReadExt;
+use serde::de::DeserializeOwned;
+
+use crate::serde::utf8::decode_pending;
+use crate::serde::DeserializeError;
+use crate::JSONParser;
+
+/// The number of bytes read from an async reader at a time.
+const ASYNC_READ_BLOCK_SIZE: usize = 8 * 1024;
+
+/// Deserializes the first JSON value read from an async reader.
+///
+/// The reader is consumed only until the first value completes, so this works
+/// on connections that stay open after sending their payload. Any reader
+/// implementing the `futures-io` [`AsyncRead`] trait is accepted, which covers
+/// async-std and smol; tokio readers can be adapted with `tokio-util`'s
+/// `TokioAsyncReadCompatExt::compat`.
+///
+/// # Arguments
+///
+/// * `reader` - The async reader supplying mixed text with embedded JSON.
+///
+/// # Returns
+///
+/// * `Ok(T)` - The successfully deserialized value.
+/// * `Err(DeserializeError)` - If reading failed, the input ended before a
+///   complete value, or deserialization failed.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "serde")]
+/// # {
+/// use futures::executor::block_on;
+/// use serde::Deserialize;
+/// use surfing::serde::from_async_read;
+///
+/// #[derive(Debug, Deserialize)]
+/// struct Status {
+///     ok: bool,
+/// }
+///
+/// let input = "HTTP/1.1 200 OK\r\n\r\n{\"ok\":true}".as_bytes();
+/// let status: Status = block_on(from_async_read(input)).unwrap();
+/// assert!(status.ok);
+/// # }
+/// ```
+pub async fn from_async_read<T, R>(mut reader: R) -> Result<T, DeserializeError>
+where
+    T: DeserializeOwned,
+    R: AsyncRead + Unpin,
+{
+    let mut parser = JSONParser::new();
+    let mut block = vec![0; ASYNC_READ_BLOCK_SIZE];
+    let mut filled = 0;
+    let mut first = None;
+
+    loop {
+        let read = match reader.read(&mut block[filled..]).await {
+            Ok(read) => read,
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(DeserializeError::Extraction(e.to_string())),
+        };
+        filled += read;
+
+        // Hold back an incomplete character at the end of the block unless
+        // the input has ended.
+        let (text, valid) = decode_pending(&block[..filled], read == 0);
+
+        parser
+            .extract_values_from_stream(&text, |value| {
+                if first.is_none() {
+                    first = Some(value.to_string());
+                }
+            })
+            .map_err(|e| DeserializeError::Extraction(e.to_string()))?;
+
+        if let Some(json) = first {
+            return serde_json::from_str(&json).map_err(DeserializeError::Deserialization);
+        }
+        if read == 0 {
+            return Err(DeserializeError::Extraction(
+                "no complete JSON value found before the end of the input".to_string(),
+            ));
+        }
+
+        block.copy_within(valid..filled, 0);
+        filled -= valid;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+    use tokio_util::compat::TokioAsyncReadCompatExt;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Greeting {
+        text: String,
+    }
+
+    #[tokio::test]
+    async fn test_from_async_read_stops_at_first_value() {
+        let (mut client, server) = tokio::io::duplex(8);
+
+        // The writing half stays open, so reading to the end would hang.
+        let producer = tokio::spawn(async move {
+            use tokio::io::AsyncWriteExt;
+            client
+                .write_all("hi {\"text\":\"¡hola!\"} ".as_bytes())
+                .await
+                .unwrap();
+            client
+        });
+
+        let greeting: Greeting = from_async_read(server.compat()).await.unwrap();
+        assert_eq!(greeting.text, "¡hola!");
+        drop(producer.await.unwrap());
+    }
+
+    #[test]
+    fn test_from_async_read_smol_tcp() {
+        smol::block_on(async {
+            use smol::io::AsyncWriteExt;
+            use smol::net::{TcpListener, TcpStream};
+
+            let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let mut client = TcpStream::connect(listener.local_addr().unwrap())
+                .await
+                .unwrap();
+            let (server, _) = listener.accept().await.unwrap();
+
+            client.write_all(b"> {\"text\":\"hello\"} <").await.unwrap();
+
+            let greeting: Greeting = from_async_read(server).await.unwrap();
+            assert_eq!(greeting.text, "hello");
+        });
+    }
+
+    #[test]
+    fn test_from_async_read_incomplete() {
+        let input = "start {\"text\":\"cut".as_bytes();
+        let result: Result<Greeting, _> = smol::block_on(from_async_read(input));
+
+        assert!(matches!(result, Err(DeserializeError::Extraction(_))));
+    }
+}
@@ -0,0 +1,214 @@
+//! Deserialization of values streamed in an HTTP body.
+
+use std::collections::VecDeque;
+use std::fmt::Display;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use bytes::Buf;
+use futures_core::Stream;
+use http_body::Body;
+use serde::de::DeserializeOwned;
+
+use crate::serde::utf8::decode_pending;
+use crate::serde::{DeserializeError, StreamingDeserializer};
+
+/// A stream of values deserialized from an HTTP body as it arrives.
+///
+/// Created with [`from_body`]. Each data frame is fed to an internal
+/// [`StreamingDeserializer`], and every value that completes is yielded,
+/// including several from one frame and values split across frames.
+/// Trailers are ignored. A body error is yielded as a
+/// [`DeserializeError::Extraction`] and ends the stream; a value still
+/// incomplete when the body ends is dropped.
+pub struct BodyStream<B, T>
+where
+    T: DeserializeOwned,
+{
+    body: B,
+    deserializer: StreamingDeserializer<T>,
+    pending: Vec<u8>,
+    ready: VecDeque<Result<T, DeserializeError>>,
+    done: bool,
+}
+
+// The value type is only ever produced, never pinned
+impl<B: Unpin, T: DeserializeOwned> Unpin for BodyStream<B, T> {}
+
+/// Creates a stream of values deserialized from an HTTP body.
+///
+/// Any [`http_body::Body`] works, such as hyper 1.x's `Incoming`. Bodies that
+/// are not `Unpin` can be pinned with `Box::pin` first.
+///
+/// # Arguments
+///
+/// * `body` - The body containing mixed text with embedded JSON.
+///
+/// # Returns
+///
+/// A [`BodyStream`] yielding `Result<T, DeserializeError>` for each value.
+///
+/// # Examples
+///
+/// ```
+/// use bytes::Bytes;
+/// use futures::StreamExt;
+/// use http_body_util::Full;
+/// use serde::Deserialize;
+/// use surfing::serde::from_body;
+///
+/// #[derive(Debug, Deserialize)]
+/// struct Event {
+///     id: u64,
+/// }
+///
+/// let body = Full::new(Bytes::from("event {\"id\":1} event {\"id\":2}"));
+/// let events: Vec<_> = futures::executor::block_on(from_body::<Event, _>(body).collect());
+///
+/// assert_eq!(events.len(), 2);
+/// assert_eq!(events[1].as_ref().unwrap().id, 2);
+/// ```
+pub fn from_body<T, B>(body: B) -> BodyStream<B, T>
+where
+    T: DeserializeOwned,
+    B: Body,
+{
+    BodyStream {
+        body,
+        deserializer: StreamingDeserializer::new(),
+        pending: Vec::new(),
+        ready: VecDeque::new(),
+        done: false,
+    }
+}
+
+impl<B, T> BodyStream<B, T>
+where
+    T: DeserializeOwned,
+{
+    /// Feeds the pending bytes to the deserializer.
+    ///
+    /// An incomplete character at the end is kept back for the next frame
+    /// unless the body has ended; invalid bytes are processed as U+FFFD.
+    fn feed_pending(&mut self, finished: bool) {
+        let (text, valid) = decode_pending(&self.pending, finished);
+        let ready = &mut self.ready;
+        self.deserializer
+            .process_chunk_values(&text, |result| ready.push_back(result));
+
+        self.pending.drain(..valid);
+    }
+}
+
+impl<B, T> Stream for BodyStream<B, T>
+where
+    B: Body + Unpin,
+    B::Error: Display,
+    T: DeserializeOwned,
+{
+    type Item = Result<T, DeserializeError>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+
+        loop {
+            if let Some(result) = this.ready.pop_front() {
+                return Poll::Ready(Some(result));
+            }
+            if this.done {
+                return Poll::Ready(None);
+            }
+
+            match Pin::new(&mut this.body).poll_frame(cx) {
+                Poll::Pending => return Poll::Pending,
+                Poll::Ready(None) => {
+                    this.done = true;
+                    this.feed_pending(true);
+                }
+                Poll::Ready(Some(Err(e))) => {
+                    this.done = true;
+                    return Poll::Ready(Some(Err(DeserializeError::Extraction(format!(
+                        "error reading the body: {}",
+                        e
+                    )))));
+                }
+                Poll::Ready(Some(Ok(frame))) => {
+                    // Trailers carry no body text
+                    if let Ok(mut data) = frame.into_data() {
+                        while data.has_remaining() {
+                            let chunk = data.chunk();
+                            this.pending.extend_from_slice(chunk);
+                            let length = chunk.len();
+                            data.advance(length);
+                        }
+                        this.feed_pending(false);
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytes::Bytes;
+    use futures::executor::block_on;
+    use futures::StreamExt;
+    use http_body::Frame;
+    use http_body_util::StreamBody;
+    use serde::Deserialize;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Item {
+        id: u64,
+    }
+
+    type FrameResult = Result<Frame<Bytes>, String>;
+
+    fn frames(frames: Vec<FrameResult>) -> impl Body<Data = Bytes, Error = String> + Unpin {
+        StreamBody::new(futures::stream::iter(frames))
+    }
+
+    #[test]
+    fn test_from_body_split_frames_and_trailers() {
+        let text = "é {\"id\":1}{\"id\":2} {\"id\"";
+        let (head, tail) = text.as_bytes().split_at(1);
+
+        let body = frames(vec![
+            Ok(Frame::data(Bytes::copy_from_slice(head))),
+            Ok(Frame::data(Bytes::copy_from_slice(tail))),
+            Ok(Frame::data(Bytes::from(":\"x\"} {\"id\":3}"))),
+            Ok(Frame::trailers(hyper::HeaderMap::new())),
+        ]);
+
+        let results: Vec<_> = block_on(from_body::<Item, _>(body).collect());
+        assert_eq!(results.len(), 4);
+        assert_eq!(results[0].as_ref().unwrap(), &Item { id: 1 });
+        assert_eq!(results[1].as_ref().unwrap(), &Item { id: 2 });
+        assert!(matches!(
+            results[2],
+            Err(DeserializeError::Deserialization(_))
+        ));
+        assert_eq!(results[3].as_ref().unwrap(), &Item { id: 3 });
+    }
+
+    #[test]
+    fn test_from_body_error_ends_stream() {
+        let body = frames(vec![
+            Ok(Frame::data(Bytes::from("{\"id\":1} {\"id\":"))),
+            Err("connection reset".to_string()),
+            Ok(Frame::data(Bytes::from("2}"))),
+        ]);
+
+        let results: Vec<_> = block_on(from_body::<Item, _>(body).collect());
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].as_ref().unwrap(), &Item { id: 1 });
+        match &results[1] {
+            Err(DeserializeError::Extraction(message)) => {
+                assert_eq!(message, "error reading the body: connection reset")
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+}
@@ -3,19 +3,69 @@
 use std::io::Cursor;
 
 #[cfg(feature = "serde")]
-use {serde::de::DeserializeOwned, serde_json::Error as SerdeJsonError};
+use {
+    serde::de::{Deserialize, DeserializeOwned},
+    serde_json::{Error as SerdeJsonError, Value},
+};
 
-use crate::utils::extract_json_to_string;
+use crate::utils::{
+    extract_anchored_json, extract_exactly_one_json, extract_json_objects, extract_json_to_string,
+    extract_largest_json_to_string, Anchor,
+};
 use crate::JSONParser;
 
 /// Error type for deserialization failures.
+///
+/// Some variants only exist with optional features enabled, such as
+/// `jsonschema`, so matches must include a wildcard arm.
 #[derive(Debug)]
 #[cfg(feature = "serde")]
+#[non_exhaustive]
 pub enum DeserializeError {
     /// Error extracting JSON from text
     Extraction(String),
     /// Error deserializing the extracted JSON
     Deserialization(SerdeJsonError),
+    /// A value of a collected source failed to deserialize
+    InvalidValue {
+        /// The position of the value in the source, counting from 0
+        index: usize,
+        /// The raw JSON of the value
+        json: String,
+        /// The error deserializing the value
+        error: SerdeJsonError,
+    },
+    /// A value collected by key lacks the key field, or holds neither a
+    /// string nor a number in it
+    MissingKey {
+        /// The position of the value in the source, counting from 0
+        index: usize,
+        /// The name of the key field
+        key: String,
+    },
+    /// A value collected by key repeats the key of an earlier value
+    DuplicateKey {
+        /// The position of the value in the source, counting from 0
+        index: usize,
+        /// The repeated key
+        key: String,
+    },
+    /// The JSON Schema could not be compiled
+    #[cfg(feature = "jsonschema")]
+    InvalidSchema(String),
+    /// The extracted JSON does not match the JSON Schema
+    #[cfg(feature = "jsonschema")]
+    SchemaViolation(Vec<SchemaViolation>),
+}
+
+/// A place where a value fails to match a JSON Schema.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg(feature = "jsonschema")]
+pub struct SchemaViolation {
+    /// The JSON Pointer to the failing part of the value, empty for the value itself
+    pub path: String,
+    /// The description of the failure
+    pub message: String,
 }
 
 #[cfg(feature = "serde")]
@@ -24,6 +74,30 @@ impl std::fmt::Display for DeserializeError {
         match self {
             DeserializeError::Extraction(e) => write!(f, "JSON extraction error: {}", e),
             DeserializeError::Deserialization(e) => write!(f, "JSON deserialization error: {}", e),
+            DeserializeError::InvalidValue { index, error, .. } => {
+                write!(
+                    f,
+                    "JSON deserialization error in value {}: {}",
+                    index, error
+                )
+            }
+            DeserializeError::MissingKey { index, key } => {
+                write!(f, "value {} has no string or number key `{}`", index, key)
+            }
+            DeserializeError::DuplicateKey { index, key } => {
+                write!(f, "value {} repeats the key `{}`", index, key)
+            }
+            #[cfg(feature = "jsonschema")]
+            DeserializeError::InvalidSchema(e) => write!(f, "invalid JSON Schema: {}", e),
+            #[cfg(feature = "jsonschema")]
+            DeserializeError::SchemaViolation(violations) => {
+                write!(f, "JSON does not match the schema")?;
+                for (index, violation) in violations.iter().enumerate() {
+                    let separator = if index == 0 { ": " } else { "; " };
+                    write!(f, "{}{}: {}", separator, violation.path, violation.message)?;
+                }
+                Ok(())
+            }
         }
     }
 }
@@ -32,8 +106,13 @@ impl std::fmt::Display for DeserializeError {
 impl std::error::Error for DeserializeError {
     fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
         match self {
-            DeserializeError::Extraction(_) => None,
-            DeserializeError::Deserialization(e) => Some(e),
+            DeserializeError::Extraction(_)
+            | DeserializeError::MissingKey { .. }
+            | DeserializeError::DuplicateKey { .. } => None,
+            DeserializeError::Deserialization(e)
+            | DeserializeError::InvalidValue { error: e, .. } => Some(e),
+            #[cfg(feature = "jsonschema")]
+            DeserializeError::InvalidSchema(_) | DeserializeError::SchemaViolation(_) => None,
         }
     }
 }
@@ -90,6 +169,271 @@ where
     serde_json::from_str(&json).map_err(DeserializeError::Deserialization)
 }
 
+/// Deserializes the value anchored to the start, the end or the whole of mixed text.
+///
+/// See [`extract_anchored_json`] for how each [`Anchor`] selects the value.
+/// An anchor violation is reported as an extraction error naming the
+/// offending content.
+///
+/// # Arguments
+///
+/// * `input` - A string slice containing mixed text with embedded JSON.
+/// * `anchor` - Where the value must sit in the input.
+///
+/// # Returns
+///
+/// * `Ok(T)` - The successfully deserialized value.
+/// * `Err(DeserializeError)` - If the anchor is violated or deserialization fails.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "serde")]
+/// # {
+/// use serde::Deserialize;
+/// use surfing::serde::from_mixed_text_anchored;
+/// use surfing::utils::Anchor;
+///
+/// #[derive(Debug, Deserialize)]
+/// struct Verdict {
+///     approved: bool,
+/// }
+///
+/// let text = "A reply like {\"approved\":true} would be wrong. Final: {\"approved\":false}";
+/// let verdict: Verdict = from_mixed_text_anchored(text, Anchor::End).unwrap();
+/// assert!(!verdict.approved);
+///
+/// let result: Result<Verdict, _> = from_mixed_text_anchored(text, Anchor::Whole);
+/// assert!(result.is_err());
+/// # }
+/// ```
+#[cfg(feature = "serde")]
+pub fn from_mixed_text_anchored<T>(input: &str, anchor: Anchor) -> Result<T, DeserializeError>
+where
+    T: DeserializeOwned,
+{
+    let json = extract_anchored_json(input, anchor)
+        .map_err(|e| DeserializeError::Extraction(e.to_string()))?;
+
+    serde_json::from_str(&json).map_err(DeserializeError::Deserialization)
+}
+
+/// Deserializes the only JSON value in mixed text.
+///
+/// This is a validation tool: zero values, or more than one, produce an
+/// extraction error with the number of values and the position of the
+/// second one. See [`extract_exactly_one_json`].
+///
+/// # Arguments
+///
+/// * `input` - A string slice containing mixed text with embedded JSON.
+///
+/// # Returns
+///
+/// * `Ok(T)` - The successfully deserialized value.
+/// * `Err(DeserializeError)` - If there is not exactly one value or deserialization fails.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "serde")]
+/// # {
+/// use serde::Deserialize;
+/// use surfing::serde::from_mixed_text_exactly_one;
+///
+/// #[derive(Debug, Deserialize)]
+/// struct Payload {
+///     id: u64,
+/// }
+///
+/// let payload: Payload = from_mixed_text_exactly_one("sent {\"id\":7}").unwrap();
+/// assert_eq!(payload.id, 7);
+///
+/// let result: Result<Payload, _> = from_mixed_text_exactly_one("sent {\"id\":7} again {\"id\":7}");
+/// assert!(result.unwrap_err().to_string().contains("found 2"));
+/// # }
+/// ```
+#[cfg(feature = "serde")]
+pub fn from_mixed_text_exactly_one<T>(input: &str) -> Result<T, DeserializeError>
+where
+    T: DeserializeOwned,
+{
+    let json =
+        extract_exactly_one_json(input).map_err(|e| DeserializeError::Extraction(e.to_string()))?;
+
+    serde_json::from_str(&json).map_err(DeserializeError::Deserialization)
+}
+
+/// Deserializes the largest JSON value in mixed text.
+///
+/// This picks the value with the greatest length in bytes, the later one
+/// on a tie, which skips small examples shown next to the real payload.
+/// See [`extract_largest_json_to_string`].
+///
+/// # Arguments
+///
+/// * `input` - A string slice containing mixed text with embedded JSON.
+///
+/// # Returns
+///
+/// * `Ok(T)` - The successfully deserialized value.
+/// * `Err(DeserializeError)` - If there is no value or deserialization fails.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "serde")]
+/// # {
+/// use serde::Deserialize;
+/// use surfing::serde::from_mixed_text_largest;
+///
+/// #[derive(Debug, Deserialize)]
+/// struct Answer {
+///     tags: Vec<String>,
+/// }
+///
+/// let text = "Use the form {\"tags\":[]}. Answer: {\"tags\":[\"red\",\"round\"]}";
+/// let answer: Answer = from_mixed_text_largest(text).unwrap();
+/// assert_eq!(answer.tags, vec!["red", "round"]);
+/// # }
+/// ```
+#[cfg(feature = "serde")]
+pub fn from_mixed_text_largest<T>(input: &str) -> Result<T, DeserializeError>
+where
+    T: DeserializeOwned,
+{
+    let json = extract_largest_json_to_string(input)
+        .map_err(|e| DeserializeError::Extraction(e.to_string()))?
+        .ok_or_else(|| DeserializeError::Extraction("no JSON value found".to_string()))?;
+
+    serde_json::from_str(&json).map_err(DeserializeError::Deserialization)
+}
+
+/// Extracts the first JSON value of mixed text as a loosely-typed [`Value`].
+///
+/// This suits callers without a struct for the payload. Unlike
+/// [`from_mixed_text`] with `Value` as the target, candidates that are not
+/// valid JSON, such as the `[INFO]` of a log line, are skipped.
+///
+/// # Arguments
+///
+/// * `input` - A string slice containing mixed text with embedded JSON.
+///
+/// # Returns
+///
+/// * `Ok(Value)` - The first valid JSON value.
+/// * `Err(DeserializeError)` - If the input holds no valid JSON value.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "serde")]
+/// # {
+/// use surfing::serde::extract_json_value;
+///
+/// let value = extract_json_value("[INFO] got {\"user\":{\"id\":7}}").unwrap();
+/// assert_eq!(value["user"]["id"], 7);
+/// # }
+/// ```
+#[cfg(feature = "serde")]
+pub fn extract_json_value(input: &str) -> Result<Value, DeserializeError> {
+    let json = extract_json_objects(input)
+        .next()
+        .ok_or_else(|| DeserializeError::Extraction("no JSON value found".to_string()))?;
+
+    serde_json::from_str(&json).map_err(DeserializeError::Deserialization)
+}
+
+/// Extracts every JSON value of mixed text as loosely-typed [`Value`]s.
+///
+/// Candidates that are not valid JSON are skipped, as with
+/// [`extract_json_value`].
+///
+/// # Arguments
+///
+/// * `input` - A string slice containing mixed text with embedded JSON.
+///
+/// # Returns
+///
+/// The valid JSON values in order of appearance.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "serde")]
+/// # {
+/// use serde_json::json;
+/// use surfing::serde::extract_json_values;
+///
+/// let values = extract_json_values("a {\"n\":1} b [2, 3] c");
+/// assert_eq!(values, vec![json!({"n": 1}), json!([2, 3])]);
+/// # }
+/// ```
+#[cfg(feature = "serde")]
+pub fn extract_json_values(input: &str) -> Vec<Value> {
+    // Only valid JSON is extracted, so parsing fails past serde_json's nesting limit alone
+    extract_json_objects(input)
+        .filter_map(|json| serde_json::from_str(&json).ok())
+        .collect()
+}
+
+/// Deserializes a value borrowing from mixed text containing JSON.
+///
+/// Unlike [`from_mixed_text`], the first complete JSON value is deserialized
+/// directly from the caller's input, so types with `&'de str` or
+/// `Cow<'de, str>` fields borrow from it without copying. Strings containing
+/// escape sequences cannot be borrowed and need an owned or `Cow` field.
+///
+/// This only works when the whole input is available at once; for chunked
+/// input use [`StreamingDeserializer`](crate::serde::StreamingDeserializer).
+///
+/// # Arguments
+///
+/// * `input` - A string slice containing mixed text with embedded JSON.
+///
+/// # Returns
+///
+/// * `Ok(T)` - The successfully deserialized value.
+/// * `Err(DeserializeError)` - If no complete value is found or deserialization fails.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "serde")]
+/// # {
+/// use serde::Deserialize;
+/// use surfing::serde::from_mixed_str;
+///
+/// #[derive(Debug, Deserialize)]
+/// struct Event<'a> {
+///     kind: &'a str,
+///     user: &'a str,
+/// }
+///
+/// let text = String::from("login event: {\"kind\":\"login\",\"user\":\"alice\"} ok");
+/// let event: Event = from_mixed_str(&text).unwrap();
+///
+/// assert_eq!(event.kind, "login");
+/// assert_eq!(event.user, "alice");
+/// # }
+/// ```
+#[cfg(feature = "serde")]
+pub fn from_mixed_str<'de, T>(input: &'de str) -> Result<T, DeserializeError>
+where
+    T: Deserialize<'de>,
+{
+    let mut span = None;
+    JSONParser::new().extract_value_spans(input, |range| {
+        span.get_or_insert(range);
+    });
+
+    let span = span.ok_or_else(|| {
+        DeserializeError::Extraction("No complete JSON value found in input".to_string())
+    })?;
+
+    serde_json::from_str(&input[span]).map_err(DeserializeError::Deserialization)
+}
+
 /// Deserializes a value from mixed text using an existing JSONParser.
 ///
 /// This function allows you to reuse a parser instance, which is useful
@@ -289,4 +633,94 @@ mod tests {
         // Make sure the parser is still in-progress
         assert!(parser.is_in_json());
     }
+
+    #[derive(Debug, Deserialize)]
+    struct BorrowedStruct<'a> {
+        name: &'a str,
+        #[serde(borrow)]
+        tag: std::borrow::Cow<'a, str>,
+    }
+
+    #[test]
+    fn test_from_mixed_str_borrows_from_input() {
+        let input = String::from("noise {\"name\":\"zero\",\"tag\":\"copy\"} trailing");
+        let value: BorrowedStruct = from_mixed_str(&input).unwrap();
+
+        let range = input.as_bytes().as_ptr_range();
+        assert_eq!(value.name, "zero");
+        assert!(range.contains(&value.name.as_ptr()));
+        assert!(
+            matches!(value.tag, std::borrow::Cow::Borrowed(tag) if range.contains(&tag.as_ptr()))
+        );
+    }
+
+    #[test]
+    fn test_from_mixed_str_first_value() {
+        let input = "{\"name\":\"a\",\"value\":1} {\"name\":\"b\",\"value\":2}";
+        let result: TestStruct = from_mixed_str(input).unwrap();
+
+        assert_eq!(result.name, "a");
+    }
+
+    #[test]
+    fn test_from_mixed_str_incomplete() {
+        let result: Result<TestStruct, _> = from_mixed_str("text {\"name\":");
+
+        assert!(matches!(result, Err(DeserializeError::Extraction(_))));
+    }
+
+    #[test]
+    fn test_from_mixed_text_anchored_reports_trailing_content() {
+        let input = "{\"name\":\"a\",\"value\":1} trailing note";
+        let result: Result<TestStruct, _> = from_mixed_text_anchored(input, Anchor::End);
+
+        match result {
+            Err(DeserializeError::Extraction(message)) => {
+                assert!(message.contains("trailing note"))
+            }
+            other => panic!("Expected extraction error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_extract_json_value() {
+        let object = extract_json_value("{\"a\":[1,{\"b\":null}]}").unwrap();
+        assert_eq!(object, serde_json::json!({"a": [1, {"b": null}]}));
+
+        let array = extract_json_value("[true, \"x\"]").unwrap();
+        assert_eq!(array, serde_json::json!([true, "x"]));
+
+        let mixed = extract_json_value("[WARN] retry {bad} then {\"ok\":1} and [2]").unwrap();
+        assert_eq!(mixed, serde_json::json!({"ok": 1}));
+
+        assert!(matches!(
+            extract_json_value("no json here"),
+            Err(DeserializeError::Extraction(_))
+        ));
+    }
+
+    #[test]
+    fn test_extract_json_values() {
+        let values = extract_json_values("[WARN] {\"a\":1} text [2, [3]] {\"cut\":");
+        assert_eq!(
+            values,
+            vec![serde_json::json!({"a": 1}), serde_json::json!([2, [3]])]
+        );
+
+        assert!(extract_json_values("no json here").is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_deserialize_error_crosses_spawn() {
+        fn assert_send_sync<T: Send + Sync + 'static>() {}
+        assert_send_sync::<DeserializeError>();
+
+        let task = tokio::spawn(async {
+            let result = from_mixed_text::<TestStruct>("no json here");
+            tokio::task::yield_now().await;
+            result
+        });
+
+        assert!(task.await.unwrap().is_err());
+    }
 }
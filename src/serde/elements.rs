@@ -0,0 +1,124 @@
+//! Splitting of extracted JSON into the elements of top-level arrays.
+
+use crate::parser::string_scanner::StringScanner;
+
+/// Splits extracted JSON into the elements of its top-level arrays.
+///
+/// The splitter reads the JSON written by the parser, so it only has to
+/// follow strings and nesting. Each element is handed over as soon as it is
+/// complete: containers at their closing bracket, other values at the
+/// following comma or at the end of the array. A top-level value that is not
+/// an array is handed over whole.
+#[derive(Debug, Default)]
+pub(crate) struct ElementSplitter {
+    depth: usize,
+    in_array: bool,
+    in_string: bool,
+    scanner: StringScanner,
+}
+
+impl ElementSplitter {
+    /// Splits a piece of extracted JSON, continuing from the previous piece.
+    ///
+    /// # Arguments
+    ///
+    /// * `json` - The next piece of extracted JSON.
+    /// * `element` - The buffer holding the element being collected.
+    /// * `on_element` - The callback receiving each completed element.
+    pub(crate) fn push_str<F>(&mut self, json: &str, element: &mut String, mut on_element: F)
+    where
+        F: FnMut(&str),
+    {
+        for item in json.chars() {
+            self.push(item, element, &mut on_element);
+        }
+    }
+
+    /// Resets the splitter to the start of a value.
+    pub(crate) fn reset(&mut self) {
+        *self = Self::default();
+    }
+
+    fn push<F>(&mut self, item: char, element: &mut String, on_element: &mut F)
+    where
+        F: FnMut(&str),
+    {
+        if self.in_string {
+            element.push(item);
+            self.in_string = !self.scanner.push(item);
+            return;
+        }
+
+        match item {
+            '"' => {
+                self.in_string = true;
+                self.scanner.reset();
+                element.push(item);
+            }
+            '[' if self.depth == 0 => {
+                self.depth = 1;
+                self.in_array = true;
+            }
+            '{' | '[' => {
+                self.depth += 1;
+                element.push(item);
+            }
+            ']' if self.depth == 1 && self.in_array => {
+                self.depth = 0;
+                self.in_array = false;
+                flush(element, on_element);
+            }
+            '}' | ']' => {
+                self.depth = self.depth.saturating_sub(1);
+                element.push(item);
+
+                // Containers are complete at their closing bracket
+                if self.depth == usize::from(self.in_array) {
+                    flush(element, on_element);
+                }
+            }
+            ',' if self.depth == 1 && self.in_array => flush(element, on_element),
+            _ => element.push(item),
+        }
+    }
+}
+
+/// Hands over the collected element, if any, and clears the buffer.
+fn flush<F>(element: &mut String, on_element: &mut F)
+where
+    F: FnMut(&str),
+{
+    let value = element.trim();
+    if !value.is_empty() {
+        on_element(value);
+    }
+    element.clear();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn split(pieces: &[&str]) -> Vec<String> {
+        let mut splitter = ElementSplitter::default();
+        let mut element = String::new();
+        let mut elements = Vec::new();
+
+        for piece in pieces {
+            splitter.push_str(piece, &mut element, |value| {
+                elements.push(value.to_string())
+            });
+        }
+
+        elements
+    }
+
+    #[test]
+    fn test_split_array_elements_across_pieces() {
+        assert_eq!(
+            split(&["[{\"a\":[1,", "2]}, \"x,]\" , 3", ",[4]]", "{\"b\":{}}"]),
+            vec!["{\"a\":[1,2]}", "\"x,]\"", "3", "[4]", "{\"b\":{}}"]
+        );
+        assert_eq!(split(&["[", "]", "[ ]"]), Vec::<String>::new());
+    }
+}
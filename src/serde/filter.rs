@@ -0,0 +1,527 @@
+//! Filter implementation for selecting and projecting extracted JSON values.
+//!
+//! A filter is a small, jq-like expression evaluated against each extracted
+//! value. The grammar is:
+//!
+//! ```text
+//! filter    := path [ ( "==" | "!=" ) literal ]
+//! path      := "." [ key ] { "." key | "[" subscript "]" }
+//! key       := one or more ASCII letters, digits or '_'
+//! subscript := array index | JSON string
+//! literal   := any JSON value
+//! ```
+//!
+//! - `.` is the whole value, `.user.id` a nested key, `.items[0]` an array
+//!   element and `.["content-type"]` a key that is not a plain identifier.
+//! - A bare path matches when the path exists in the value.
+//! - `==` matches when the path exists and equals the literal, `!=` when it is
+//!   missing or differs from it. Values are compared as JSON values.
+//!
+//! Whitespace is allowed around subscripts and operators.
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde_json::Value;
+
+use crate::JSONParser;
+
+/// A single step of a filter path.
+#[derive(Debug, Clone, PartialEq)]
+enum Segment {
+    Key(String),
+    Index(usize),
+}
+
+/// A comparison applied to the value found at the filter path.
+#[derive(Debug, Clone, PartialEq)]
+enum Predicate {
+    Equals(Value),
+    NotEquals(Value),
+}
+
+/// A parsed filter expression.
+///
+/// See the [module documentation](self) for the grammar.
+///
+/// # Examples
+///
+/// ```
+/// use serde_json::json;
+/// use surfing::serde::JsonFilter;
+///
+/// let filter: JsonFilter = ".meta.kind == \"order\"".parse().unwrap();
+///
+/// assert!(filter.matches(&json!({"meta": {"kind": "order"}})));
+/// assert!(!filter.matches(&json!({"meta": {"kind": "refund"}})));
+/// ```
+#[derive(Debug, Clone)]
+pub struct JsonFilter {
+    path: Vec<Segment>,
+    predicate: Option<Predicate>,
+    /// Byte offset of the predicate operator, used to report misuse.
+    predicate_position: usize,
+}
+
+impl JsonFilter {
+    /// Parses a filter expression.
+    ///
+    /// # Arguments
+    ///
+    /// * `expression` - The filter expression.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(JsonFilter)` - The parsed filter.
+    /// * `Err(FilterParseError)` - If the expression does not follow the grammar.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use surfing::serde::JsonFilter;
+    ///
+    /// assert!(JsonFilter::parse(".items[0].id").is_ok());
+    ///
+    /// let error = JsonFilter::parse(".items[x]").unwrap_err();
+    /// assert_eq!(error.position, 7);
+    /// ```
+    pub fn parse(expression: &str) -> Result<Self, FilterParseError> {
+        expression.parse()
+    }
+
+    /// Checks if a value satisfies the filter.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The JSON value to test.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the value matches the filter.
+    pub fn matches(&self, value: &Value) -> bool {
+        let found = self.select(value);
+
+        match &self.predicate {
+            None => found.is_some(),
+            Some(Predicate::Equals(expected)) => found == Some(expected),
+            Some(Predicate::NotEquals(expected)) => found != Some(expected),
+        }
+    }
+
+    /// Resolves the filter path in a value, ignoring any predicate.
+    ///
+    /// # Arguments
+    ///
+    /// * `value` - The JSON value to walk.
+    ///
+    /// # Returns
+    ///
+    /// The value at the path, or `None` if the path does not exist.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use serde_json::json;
+    /// use surfing::serde::JsonFilter;
+    ///
+    /// let filter = JsonFilter::parse(".user.tags[1]").unwrap();
+    /// let value = json!({"user": {"tags": ["a", "b"]}});
+    ///
+    /// assert_eq!(filter.select(&value), Some(&json!("b")));
+    /// ```
+    pub fn select<'v>(&self, value: &'v Value) -> Option<&'v Value> {
+        self.path
+            .iter()
+            .try_fold(value, |current, segment| match segment {
+                Segment::Key(key) => current.get(key.as_str()),
+                Segment::Index(index) => current.get(*index),
+            })
+    }
+}
+
+impl FromStr for JsonFilter {
+    type Err = FilterParseError;
+
+    fn from_str(expression: &str) -> Result<Self, Self::Err> {
+        let mut scanner = Scanner {
+            expression,
+            position: 0,
+        };
+
+        let path = scanner.parse_path()?;
+        scanner.skip_whitespace();
+
+        let predicate_position = scanner.position;
+        let predicate = if scanner.eat("==") {
+            Some(Predicate::Equals(scanner.parse_literal()?))
+        } else if scanner.eat("!=") {
+            Some(Predicate::NotEquals(scanner.parse_literal()?))
+        } else if scanner.rest().is_empty() {
+            None
+        } else {
+            return Err(scanner.error("expected '==', '!=' or the end of the expression"));
+        };
+
+        Ok(JsonFilter {
+            path,
+            predicate,
+            predicate_position,
+        })
+    }
+}
+
+/// Error returned when a filter expression cannot be parsed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FilterParseError {
+    /// Byte offset in the expression where parsing failed
+    pub position: usize,
+    /// Description of what was expected
+    pub message: String,
+}
+
+impl fmt::Display for FilterParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "invalid filter at position {}: {}",
+            self.position, self.message
+        )
+    }
+}
+
+impl std::error::Error for FilterParseError {}
+
+/// Error type for filtered extraction failures.
+#[derive(Debug)]
+pub enum FilterError {
+    /// The filter expression is invalid
+    Parse(FilterParseError),
+    /// Error extracting JSON from text
+    Extraction(String),
+}
+
+impl fmt::Display for FilterError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FilterError::Parse(e) => write!(f, "{}", e),
+            FilterError::Extraction(e) => write!(f, "JSON extraction error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for FilterError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            FilterError::Parse(e) => Some(e),
+            FilterError::Extraction(_) => None,
+        }
+    }
+}
+
+impl From<FilterParseError> for FilterError {
+    fn from(error: FilterParseError) -> Self {
+        FilterError::Parse(error)
+    }
+}
+
+/// Extracts every JSON value from text and keeps the ones matching a filter.
+///
+/// Extracted candidates that are not valid JSON, such as `{oops}`, are skipped.
+///
+/// # Arguments
+///
+/// * `input` - A string slice containing mixed text with embedded JSON.
+/// * `expression` - The filter expression, see the [module documentation](self).
+///
+/// # Returns
+///
+/// * `Ok(Vec<Value>)` - The matching values, in order of appearance.
+/// * `Err(FilterError)` - If the expression is invalid.
+///
+/// # Examples
+///
+/// ```
+/// use serde_json::json;
+/// use surfing::serde::extract_json_filtered;
+///
+/// let input = "a {\"meta\":{\"kind\":\"order\"},\"id\":1} b {\"meta\":{\"kind\":\"refund\"},\"id\":2}";
+/// let orders = extract_json_filtered(input, ".meta.kind == \"order\"").unwrap();
+///
+/// assert_eq!(orders, vec![json!({"meta": {"kind": "order"}, "id": 1})]);
+/// ```
+pub fn extract_json_filtered(input: &str, expression: &str) -> Result<Vec<Value>, FilterError> {
+    let filter = JsonFilter::parse(expression)?;
+
+    let mut values = extract_values(input)?;
+    values.retain(|value| filter.matches(value));
+
+    Ok(values)
+}
+
+/// Extracts every JSON value from text and projects it through a path.
+///
+/// Values in which the path does not exist are skipped.
+///
+/// # Arguments
+///
+/// * `input` - A string slice containing mixed text with embedded JSON.
+/// * `expression` - A path without a predicate, such as `.payload.id`.
+///
+/// # Returns
+///
+/// * `Ok(Vec<Value>)` - The projected fragments, in order of appearance.
+/// * `Err(FilterError)` - If the expression is invalid or has a predicate.
+///
+/// # Examples
+///
+/// ```
+/// use serde_json::json;
+/// use surfing::serde::extract_json_project;
+///
+/// let input = "{\"payload\":{\"id\":7}} noise {\"payload\":{}} {\"payload\":{\"id\":8}}";
+/// let ids = extract_json_project(input, ".payload.id").unwrap();
+///
+/// assert_eq!(ids, vec![json!(7), json!(8)]);
+/// ```
+pub fn extract_json_project(input: &str, expression: &str) -> Result<Vec<Value>, FilterError> {
+    let filter = JsonFilter::parse(expression)?;
+    if filter.predicate.is_some() {
+        return Err(FilterError::Parse(FilterParseError {
+            position: filter.predicate_position,
+            message: "a projection cannot contain a predicate".to_string(),
+        }));
+    }
+
+    let values = extract_values(input)?;
+
+    Ok(values
+        .iter()
+        .filter_map(|value| filter.select(value).cloned())
+        .collect())
+}
+
+/// Extracts and parses every complete JSON value in the input, skipping invalid candidates.
+fn extract_values(input: &str) -> Result<Vec<Value>, FilterError> {
+    let mut parser = JSONParser::new();
+    let mut values = Vec::new();
+
+    parser
+        .extract_values_from_stream(input, |value| {
+            if let Ok(value) = serde_json::from_str(value) {
+                values.push(value);
+            }
+        })
+        .map_err(|e| FilterError::Extraction(e.to_string()))?;
+
+    Ok(values)
+}
+
+/// Reads a filter expression from left to right.
+struct Scanner<'a> {
+    expression: &'a str,
+    position: usize,
+}
+
+impl<'a> Scanner<'a> {
+    fn rest(&self) -> &'a str {
+        &self.expression[self.position..]
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.position += rest.len() - rest.trim_start().len();
+    }
+
+    fn eat(&mut self, token: &str) -> bool {
+        if self.rest().starts_with(token) {
+            self.position += token.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn error(&self, message: &str) -> FilterParseError {
+        FilterParseError {
+            position: self.position,
+            message: message.to_string(),
+        }
+    }
+
+    fn parse_path(&mut self) -> Result<Vec<Segment>, FilterParseError> {
+        self.skip_whitespace();
+        if !self.eat(".") {
+            return Err(self.error("expected a path starting with '.'"));
+        }
+
+        let mut path = Vec::new();
+        match self.peek() {
+            Some(item) if is_key_char(item) => path.push(self.parse_key()),
+            Some('.') => return Err(self.error("expected a key after '.'")),
+            _ => {}
+        }
+
+        loop {
+            if self.eat("[") {
+                path.push(self.parse_subscript()?);
+            } else if self.eat(".") {
+                if !self.peek().is_some_and(is_key_char) {
+                    return Err(self.error("expected a key after '.'"));
+                }
+                path.push(self.parse_key());
+            } else {
+                return Ok(path);
+            }
+        }
+    }
+
+    fn parse_key(&mut self) -> Segment {
+        let rest = self.rest();
+        let end = rest.find(|item| !is_key_char(item)).unwrap_or(rest.len());
+        self.position += end;
+
+        Segment::Key(rest[..end].to_string())
+    }
+
+    fn parse_subscript(&mut self) -> Result<Segment, FilterParseError> {
+        self.skip_whitespace();
+
+        let segment = if self.peek() == Some('"') {
+            let mut stream = serde_json::Deserializer::from_str(self.rest()).into_iter::<String>();
+            match stream.next() {
+                Some(Ok(key)) => {
+                    self.position += stream.byte_offset();
+                    Segment::Key(key)
+                }
+                _ => return Err(self.error("invalid quoted key")),
+            }
+        } else {
+            let rest = self.rest();
+            let end = rest
+                .find(|item: char| !item.is_ascii_digit())
+                .unwrap_or(rest.len());
+            let index = rest[..end]
+                .parse()
+                .map_err(|_| self.error("expected an array index or a quoted key"))?;
+            self.position += end;
+            Segment::Index(index)
+        };
+
+        self.skip_whitespace();
+        if !self.eat("]") {
+            return Err(self.error("expected ']'"));
+        }
+
+        Ok(segment)
+    }
+
+    fn parse_literal(&mut self) -> Result<Value, FilterParseError> {
+        self.skip_whitespace();
+        if self.rest().is_empty() {
+            return Err(self.error("expected a JSON literal"));
+        }
+
+        serde_json::from_str(self.rest())
+            .map_err(|e| self.error(&format!("invalid JSON literal: {}", e)))
+    }
+}
+
+/// Checks if the character may appear in an unquoted key.
+fn is_key_char(item: char) -> bool {
+    item.is_ascii_alphanumeric() || item == '_'
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_filter_paths() {
+        let value = json!({"a": {"b-c": [10, {"d": null}]}});
+
+        let select = |expression: &str| {
+            JsonFilter::parse(expression)
+                .unwrap()
+                .select(&value)
+                .cloned()
+        };
+        assert_eq!(select("."), Some(value.clone()));
+        assert_eq!(select(".a[\"b-c\"][0]"), Some(json!(10)));
+        assert_eq!(select(".a[ \"b-c\" ][1].d"), Some(Value::Null));
+        assert_eq!(select(".a.missing"), None);
+        assert_eq!(select(".a[\"b-c\"][5]"), None);
+    }
+
+    #[test]
+    fn test_filter_predicates() {
+        let value = json!({"type": "order", "total": 3, "note": null});
+
+        let matches = |expression: &str| JsonFilter::parse(expression).unwrap().matches(&value);
+        assert!(matches(".type == \"order\""));
+        assert!(matches(".total==3"));
+        assert!(matches(".note"));
+        assert!(matches(".type != \"refund\""));
+        assert!(matches(".missing != 1"));
+        assert!(!matches(".missing"));
+        assert!(!matches(".missing == null"));
+        assert!(!matches(".total == \"3\""));
+    }
+
+    #[test]
+    fn test_filter_parse_errors() {
+        let error = |expression: &str| JsonFilter::parse(expression).unwrap_err();
+
+        assert_eq!(error("type").position, 0);
+        assert_eq!(error("..a").position, 1);
+        assert_eq!(error(".a.").position, 3);
+        assert_eq!(error(".a[-1]").position, 3);
+        assert_eq!(error(".a[0").position, 4);
+        assert_eq!(error(".a = 1").position, 3);
+        assert_eq!(error(".a ==").position, 5);
+        assert_eq!(error(".a == order").position, 6);
+        assert!(error(".a == order")
+            .message
+            .starts_with("invalid JSON literal"));
+    }
+
+    #[test]
+    fn test_extract_json_filtered() {
+        let input = "x {\"meta\":{\"kind\":\"order\"},\"id\":1} y [1] z {\"meta\":{\"kind\":\"order\"},\"id\":3}";
+        let values = extract_json_filtered(input, ".meta.kind == \"order\"").unwrap();
+
+        assert_eq!(values.len(), 2);
+        assert_eq!(values[1]["id"], json!(3));
+    }
+
+    #[test]
+    fn test_extract_json_project() {
+        let input = "{\"payload\":{\"id\":\"a\"}} {\"other\":1} {\"payload\":{\"id\":{\"n\":2}}}";
+        let values = extract_json_project(input, ".payload.id").unwrap();
+
+        assert_eq!(values, vec![json!("a"), json!({"n": 2})]);
+    }
+
+    #[test]
+    fn test_extract_json_project_rejects_predicate() {
+        let result = extract_json_project("{}", ".id == 1");
+
+        match result {
+            Err(FilterError::Parse(error)) => assert_eq!(error.position, 4),
+            other => panic!("Expected parse error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_extract_json_filtered_skips_invalid_candidates() {
+        let values = extract_json_filtered("{\"a\":1} {oops} {\"a\":2}", ".a").unwrap();
+        assert_eq!(values, vec![json!({"a": 1}), json!({"a": 2})]);
+
+        let values = extract_json_project("[INFO] {\"a\":3}", ".a").unwrap();
+        assert_eq!(values, vec![json!(3)]);
+    }
+}
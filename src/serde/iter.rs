@@ -0,0 +1,132 @@
+//! Lazy deserialization of the values in mixed text.
+
+use std::marker::PhantomData;
+
+use serde::de::DeserializeOwned;
+
+use crate::serde::DeserializeError;
+use crate::JSONParser;
+
+/// An iterator deserializing the values of mixed text one at a time.
+struct DeserializeIter<'a, T> {
+    input: &'a str,
+    position: usize,
+    parser: JSONParser,
+    _phantom: PhantomData<T>,
+}
+
+impl<T> Iterator for DeserializeIter<'_, T>
+where
+    T: DeserializeOwned,
+{
+    type Item = Result<T, DeserializeError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let span = self
+            .parser
+            .next_value_span(self.input, &mut self.position)?;
+
+        Some(serde_json::from_str(&self.input[span]).map_err(DeserializeError::Deserialization))
+    }
+}
+
+/// Lazily extracts and deserializes every JSON value in mixed text.
+///
+/// Each call to `next` scans the input only up to the end of the next value
+/// and deserializes it straight from the input, without copying it. Stopping
+/// early, for example with `take_while` or `find`, leaves the rest of the
+/// input unscanned. A value that fails to deserialize yields an error and
+/// iteration continues with the next one.
+///
+/// # Arguments
+///
+/// * `input` - A string slice containing mixed text with embedded JSON.
+///
+/// # Returns
+///
+/// An iterator yielding `Result<T, DeserializeError>` for each value.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "serde")]
+/// # {
+/// use serde::Deserialize;
+/// use surfing::serde::iter_deserialize;
+///
+/// #[derive(Debug, Deserialize)]
+/// struct Reading {
+///     celsius: f64,
+/// }
+///
+/// let input = "t1 {\"celsius\":20.5} t2 {\"celsius\":99.0} t3 {\"celsius\":21.0}";
+/// let normal: Vec<f64> = iter_deserialize::<Reading>(input)
+///     .filter_map(Result::ok)
+///     .map(|reading| reading.celsius)
+///     .take_while(|celsius| *celsius < 50.0)
+///     .collect();
+///
+/// assert_eq!(normal, vec![20.5]);
+/// # }
+/// ```
+pub fn iter_deserialize<'a, T>(
+    input: &'a str,
+) -> impl Iterator<Item = Result<T, DeserializeError>> + 'a
+where
+    T: DeserializeOwned + 'a,
+{
+    DeserializeIter {
+        input,
+        position: 0,
+        parser: JSONParser::new(),
+        _phantom: PhantomData,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Item {
+        id: u64,
+    }
+
+    #[test]
+    fn test_iter_deserialize_stops_scanning_early() {
+        let input = "a {\"id\":1} b {\"id\":2} c {\"id\":3 unfinished";
+        let mut iter = DeserializeIter::<Item> {
+            input,
+            position: 0,
+            parser: JSONParser::new(),
+            _phantom: PhantomData,
+        };
+
+        assert_eq!(iter.next().unwrap().unwrap(), Item { id: 1 });
+        assert_eq!(
+            &input[iter.position..],
+            " b {\"id\":2} c {\"id\":3 unfinished"
+        );
+
+        assert_eq!(iter.next().unwrap().unwrap(), Item { id: 2 });
+        assert_eq!(&input[iter.position..], " c {\"id\":3 unfinished");
+
+        assert!(iter.next().is_none());
+        assert_eq!(iter.position, input.len());
+    }
+
+    #[test]
+    fn test_iter_deserialize_continues_after_error() {
+        let results: Vec<_> =
+            iter_deserialize::<Item>("{\"id\":\"x\"} [true] {\"id\":4}").collect();
+
+        assert_eq!(results.len(), 3);
+        assert!(matches!(
+            results[0],
+            Err(DeserializeError::Deserialization(_))
+        ));
+        assert!(results[1].is_err());
+        assert_eq!(results[2].as_ref().unwrap(), &Item { id: 4 });
+    }
+}
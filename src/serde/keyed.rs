@@ -0,0 +1,213 @@
+//! Collecting extracted values into a map keyed by one of their fields.
+
+use std::collections::HashMap;
+
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use crate::serde::{DeserializeError, StreamingDeserializer};
+
+/// What to do when two values collected by key have the same key.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DuplicateKeyPolicy {
+    /// Fail with [`DeserializeError::DuplicateKey`].
+    #[default]
+    Error,
+    /// Keep the value that appeared first.
+    FirstWins,
+    /// Keep the value that appeared last.
+    LastWins,
+}
+
+/// Deserializes every value in mixed text into a map keyed by one of their fields.
+///
+/// The key is read from the top-level field `key` of each value: strings
+/// are used as they are and numbers are written as JSON, so `{"id":7}` is
+/// stored under `"7"`. The whole value, key field included, is deserialized
+/// into `T`.
+///
+/// # Arguments
+///
+/// * `input` - A string slice containing mixed text with embedded JSON.
+/// * `key` - The name of the top-level field holding the key.
+/// * `policy` - What to do with values repeating an earlier key.
+///
+/// # Returns
+///
+/// * `Ok(HashMap<String, T>)` - The values by key.
+/// * `Err(DeserializeError::MissingKey)` - If a value has no string or number key.
+/// * `Err(DeserializeError::DuplicateKey)` - If a key repeats under
+///   [`DuplicateKeyPolicy::Error`].
+/// * `Err(DeserializeError::InvalidValue)` - If a value fails to deserialize.
+/// * `Err(DeserializeError::Extraction)` - If the input ends in the middle of a value.
+///
+/// # Examples
+///
+/// ```
+/// use serde::Deserialize;
+/// use surfing::serde::{from_mixed_text_keyed_by, DuplicateKeyPolicy};
+///
+/// #[derive(Debug, Deserialize)]
+/// struct Job {
+///     state: String,
+/// }
+///
+/// let log = "job {\"id\":1,\"state\":\"queued\"} job {\"id\":\"b\",\"state\":\"done\"} \
+///            job {\"id\":1,\"state\":\"running\"}";
+/// let jobs = from_mixed_text_keyed_by::<Job>(log, "id", DuplicateKeyPolicy::LastWins).unwrap();
+///
+/// assert_eq!(jobs["1"].state, "running");
+/// assert_eq!(jobs["b"].state, "done");
+/// ```
+pub fn from_mixed_text_keyed_by<T>(
+    input: &str,
+    key: &str,
+    policy: DuplicateKeyPolicy,
+) -> Result<HashMap<String, T>, DeserializeError>
+where
+    T: DeserializeOwned,
+{
+    StreamingDeserializer::<T>::collect_keyed_by([input], key, policy)
+}
+
+/// Inserts a value into a map under the key read from its field.
+///
+/// # Arguments
+///
+/// * `map` - The map being collected.
+/// * `index` - The position of the value in the source.
+/// * `value` - The extracted value.
+/// * `key` - The name of the top-level field holding the key.
+/// * `policy` - What to do if the key is already in the map.
+///
+/// # Returns
+///
+/// * `Ok(())` - If the value was inserted or dropped by the policy.
+/// * `Err(DeserializeError)` - If the value has no key, repeats one under
+///   [`DuplicateKeyPolicy::Error`], or fails to deserialize.
+pub(crate) fn insert_keyed<T>(
+    map: &mut HashMap<String, T>,
+    index: usize,
+    value: Value,
+    key: &str,
+    policy: DuplicateKeyPolicy,
+) -> Result<(), DeserializeError>
+where
+    T: DeserializeOwned,
+{
+    let name = match value.get(key) {
+        Some(Value::String(name)) => name.clone(),
+        Some(number @ Value::Number(_)) => number.to_string(),
+        _ => {
+            return Err(DeserializeError::MissingKey {
+                index,
+                key: key.to_string(),
+            })
+        }
+    };
+
+    if map.contains_key(&name) {
+        match policy {
+            DuplicateKeyPolicy::Error => {
+                return Err(DeserializeError::DuplicateKey { index, key: name })
+            }
+            DuplicateKeyPolicy::FirstWins => return Ok(()),
+            DuplicateKeyPolicy::LastWins => {}
+        }
+    }
+
+    let json = value.to_string();
+    let item = serde_json::from_value(value).map_err(|error| DeserializeError::InvalidValue {
+        index,
+        json,
+        error,
+    })?;
+    map.insert(name, item);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Job {
+        state: String,
+    }
+
+    const LOG: &str = "a {\"id\":\"x\",\"state\":\"queued\"} b {\"id\":2,\"state\":\"queued\"} \
+                       c {\"id\":\"x\",\"state\":\"done\"}";
+
+    fn states(jobs: &HashMap<String, Job>) -> Vec<(&str, &str)> {
+        let mut states: Vec<_> = jobs
+            .iter()
+            .map(|(key, job)| (key.as_str(), job.state.as_str()))
+            .collect();
+        states.sort();
+        states
+    }
+
+    #[test]
+    fn test_from_mixed_text_keyed_by_duplicate_policies() {
+        let first = from_mixed_text_keyed_by(LOG, "id", DuplicateKeyPolicy::FirstWins).unwrap();
+        assert_eq!(states(&first), vec![("2", "queued"), ("x", "queued")]);
+
+        let last = from_mixed_text_keyed_by(LOG, "id", DuplicateKeyPolicy::LastWins).unwrap();
+        assert_eq!(states(&last), vec![("2", "queued"), ("x", "done")]);
+
+        let error = from_mixed_text_keyed_by::<Job>(LOG, "id", DuplicateKeyPolicy::Error);
+        assert!(matches!(
+            error,
+            Err(DeserializeError::DuplicateKey { index: 2, ref key }) if key == "x"
+        ));
+    }
+
+    #[test]
+    fn test_from_mixed_text_keyed_by_missing_key() {
+        let input = "{\"id\":1,\"state\":\"a\"} {\"state\":\"b\"} {\"id\":null,\"state\":\"c\"}";
+
+        for policy in [
+            DuplicateKeyPolicy::Error,
+            DuplicateKeyPolicy::FirstWins,
+            DuplicateKeyPolicy::LastWins,
+        ] {
+            let error = from_mixed_text_keyed_by::<Job>(input, "id", policy);
+            assert!(matches!(
+                error,
+                Err(DeserializeError::MissingKey { index: 1, ref key }) if key == "id"
+            ));
+        }
+
+        let error = from_mixed_text_keyed_by::<Job>("[1]", "id", DuplicateKeyPolicy::Error);
+        assert!(matches!(
+            error,
+            Err(DeserializeError::MissingKey { index: 0, .. })
+        ));
+    }
+
+    #[test]
+    fn test_collect_keyed_by_chunks() {
+        let chunks = [
+            "{\"id\":1,\"sta",
+            "te\":\"a\"} noise {\"id\":1.5,",
+            "\"state\":\"b\"}",
+        ];
+        let jobs =
+            StreamingDeserializer::<Job>::collect_keyed_by(chunks, "id", DuplicateKeyPolicy::Error)
+                .unwrap();
+
+        assert_eq!(states(&jobs), vec![("1", "a"), ("1.5", "b")]);
+
+        let error = StreamingDeserializer::<Job>::collect_keyed_by(
+            ["{\"id\":1,\"state\":2}"],
+            "id",
+            DuplicateKeyPolicy::Error,
+        );
+        assert!(matches!(
+            error,
+            Err(DeserializeError::InvalidValue { index: 0, .. })
+        ));
+    }
+}
@@ -71,12 +71,79 @@
 //! assert_eq!(user.id, 42);
 //! # }
 //! ```
+//!
+//! # Filtering and Projection
+//!
+//! Extracted values can be filtered and projected with a small path
+//! expression language, described in the [`filter`] module:
+//!
+//! ```
+//! # #[cfg(feature = "serde")]
+//! # {
+//! use surfing::serde::{extract_json_filtered, extract_json_project};
+//!
+//! let text = "{\"type\":\"order\",\"id\":1} {\"type\":\"ping\"} {\"type\":\"order\",\"id\":2}";
+//!
+//! assert_eq!(extract_json_filtered(text, ".type == \"order\"").unwrap().len(), 2);
+//! assert_eq!(extract_json_project(text, ".id").unwrap(), vec![1, 2]);
+//! # }
+//! ```
 
+#[cfg(feature = "futures-io")]
+mod async_read;
+#[cfg(feature = "http")]
+mod body;
 mod deserializer;
+mod elements;
+pub mod filter;
+mod iter;
+mod keyed;
+mod pool;
+mod pretty;
+#[cfg(feature = "prompt")]
+mod prompt;
+mod raw;
+#[cfg(feature = "jsonschema")]
+mod schema;
+mod sort;
 mod streaming_deserializer;
+#[cfg(feature = "tokio")]
+mod task;
+pub mod testing;
+mod utf8;
 
+#[cfg(feature = "futures-io")]
+pub use async_read::from_async_read;
+#[cfg(feature = "http")]
+pub use body::{from_body, BodyStream};
+pub use deserializer::extract_json_value;
+pub use deserializer::extract_json_values;
 #[doc(inline)]
+pub use deserializer::from_mixed_str;
 pub use deserializer::from_mixed_text;
+pub use deserializer::from_mixed_text_anchored;
+pub use deserializer::from_mixed_text_exactly_one;
+pub use deserializer::from_mixed_text_largest;
 pub use deserializer::from_mixed_text_with_parser;
 pub use deserializer::DeserializeError;
+#[cfg(feature = "jsonschema")]
+pub use deserializer::SchemaViolation;
+pub use filter::{
+    extract_json_filtered, extract_json_project, FilterError, FilterParseError, JsonFilter,
+};
+pub use iter::iter_deserialize;
+pub use keyed::{from_mixed_text_keyed_by, DuplicateKeyPolicy};
+pub use pool::BufferPool;
+pub use pretty::extract_pretty_to_writer;
+#[cfg(feature = "prompt")]
+pub use prompt::{format_hint, format_hint_value};
+pub use raw::{raw_values_from_mixed_text, RawValueDeserializer};
+#[cfg(feature = "jsonschema")]
+pub use schema::from_mixed_text_schema;
+pub use sort::extract_sorted_by;
 pub use streaming_deserializer::StreamingDeserializer;
+#[cfg(feature = "tokio")]
+pub use task::{
+    spawn_extractor, spawn_json_extractor, ChunkSender, ExtractedJson, ExtractorClosed,
+    ValueReceiver, EXTRACTOR_CHANNEL_CAPACITY,
+};
@@ -0,0 +1,115 @@
+//! Shared pool of accumulation buffers for streaming deserializers.
+
+use std::sync::{Arc, Mutex, PoisonError};
+
+/// A bounded pool of buffers shared between [`StreamingDeserializer`](crate::serde::StreamingDeserializer)s.
+///
+/// Deserializers created with
+/// [`StreamingDeserializer::with_pool`](crate::serde::StreamingDeserializer::with_pool)
+/// borrow their accumulation buffer from the pool and return it when dropped,
+/// so short-lived deserializers reuse memory that has already grown instead
+/// of allocating afresh. Clones share the same pool, which holds at most
+/// `max_buffers` idle buffers; buffers returned to a full pool are freed.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "serde")]
+/// # {
+/// use serde::Deserialize;
+/// use surfing::serde::{BufferPool, StreamingDeserializer};
+///
+/// #[derive(Debug, Deserialize)]
+/// struct Ping {
+///     seq: u64,
+/// }
+///
+/// let pool = BufferPool::new(64);
+///
+/// for seq in 0..3 {
+///     let mut deserializer = StreamingDeserializer::<Ping>::with_pool(pool.clone());
+///     let ping = deserializer.process_chunk(&format!("{{\"seq\":{}}}", seq)).unwrap();
+///     assert_eq!(ping.seq, seq);
+/// }
+///
+/// assert_eq!(pool.len(), 1);
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct BufferPool {
+    buffers: Arc<Mutex<Vec<String>>>,
+    max_buffers: usize,
+}
+
+impl BufferPool {
+    /// Creates an empty pool holding at most `max_buffers` idle buffers.
+    ///
+    /// # Arguments
+    ///
+    /// * `max_buffers` - The largest number of idle buffers kept.
+    pub fn new(max_buffers: usize) -> Self {
+        Self {
+            buffers: Arc::new(Mutex::new(Vec::new())),
+            max_buffers,
+        }
+    }
+
+    /// Returns the number of idle buffers in the pool.
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    /// Checks if the pool holds no idle buffers.
+    pub fn is_empty(&self) -> bool {
+        self.lock().is_empty()
+    }
+
+    /// Takes an idle buffer, or a new empty one if there is none.
+    pub(crate) fn take(&self) -> String {
+        self.lock().pop().unwrap_or_default()
+    }
+
+    /// Returns a buffer to the pool, freeing it if the pool is full.
+    pub(crate) fn give(&self, mut buffer: String) {
+        buffer.clear();
+
+        let mut buffers = self.lock();
+        if buffers.len() < self.max_buffers {
+            buffers.push(buffer);
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<String>> {
+        // A panic elsewhere cannot leave the list of buffers inconsistent
+        self.buffers.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_buffer_pool_reuses_capacity() {
+        let pool = BufferPool::new(1);
+
+        let mut buffer = pool.take();
+        buffer.push_str(&"x".repeat(100));
+        let capacity = buffer.capacity();
+        pool.give(buffer);
+
+        let buffer = pool.take();
+        assert!(buffer.is_empty());
+        assert_eq!(buffer.capacity(), capacity);
+    }
+
+    #[test]
+    fn test_buffer_pool_bounded() {
+        let pool = BufferPool::new(2);
+        for _ in 0..5 {
+            pool.give(String::with_capacity(8));
+        }
+
+        assert_eq!(pool.len(), 2);
+    }
+}
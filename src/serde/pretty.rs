@@ -0,0 +1,96 @@
+//! Pretty-printing implementation for extracted JSON.
+
+use std::io::Write;
+
+use serde::Serialize;
+use serde_json::ser::PrettyFormatter;
+use serde_json::{Serializer, Value};
+
+use crate::serde::DeserializeError;
+use crate::JSONParser;
+
+/// Extracts every JSON value from text and writes it pretty-printed.
+///
+/// Each value is printed by serde_json with `indent` spaces per level and
+/// followed by a newline. Values are written in order of appearance; an
+/// invalid value stops the extraction with an error, after the values before
+/// it have been written. Object keys are printed in sorted order.
+///
+/// # Arguments
+///
+/// * `input` - A string slice containing mixed text with embedded JSON.
+/// * `writer` - The writer receiving the pretty-printed values.
+/// * `indent` - The number of spaces per nesting level.
+///
+/// # Returns
+///
+/// * `Ok(())` - If every value was printed.
+/// * `Err(DeserializeError)` - If a value is not valid JSON or writing failed.
+///
+/// # Examples
+///
+/// ```
+/// use surfing::serde::extract_pretty_to_writer;
+///
+/// let mut output = Vec::new();
+/// extract_pretty_to_writer("log {\"id\":1,\"tags\":[\"a\"]} end", &mut output, 2).unwrap();
+///
+/// assert_eq!(
+///     String::from_utf8(output).unwrap(),
+///     "{\n  \"id\": 1,\n  \"tags\": [\n    \"a\"\n  ]\n}\n"
+/// );
+/// ```
+pub fn extract_pretty_to_writer<W: Write>(
+    input: &str,
+    writer: &mut W,
+    indent: usize,
+) -> Result<(), DeserializeError> {
+    let mut parser = JSONParser::new();
+    let mut raw_values = Vec::new();
+
+    parser
+        .extract_values_from_stream(input, |value| raw_values.push(value.to_string()))
+        .map_err(|e| DeserializeError::Extraction(e.to_string()))?;
+
+    let indent = b" ".repeat(indent);
+    for raw in raw_values {
+        let value: Value = serde_json::from_str(&raw).map_err(DeserializeError::Deserialization)?;
+
+        let formatter = PrettyFormatter::with_indent(&indent);
+        let mut serializer = Serializer::with_formatter(&mut *writer, formatter);
+        value
+            .serialize(&mut serializer)
+            .map_err(DeserializeError::Deserialization)?;
+
+        writer
+            .write_all(b"\n")
+            .map_err(|e| DeserializeError::Deserialization(serde_json::Error::io(e)))?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_pretty_to_writer_multiple() {
+        let mut output = Vec::new();
+        extract_pretty_to_writer("a {\"x\":{}} b [1,{\"y\":null}]", &mut output, 4).unwrap();
+
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "{\n    \"x\": {}\n}\n[\n    1,\n    {\n        \"y\": null\n    }\n]\n"
+        );
+    }
+
+    #[test]
+    fn test_extract_pretty_to_writer_invalid_value() {
+        let mut output = Vec::new();
+        let result = extract_pretty_to_writer("[1] {oops} [2]", &mut output, 2);
+
+        assert!(matches!(result, Err(DeserializeError::Deserialization(_))));
+        assert_eq!(String::from_utf8(output).unwrap(), "[\n  1\n]\n");
+    }
+}
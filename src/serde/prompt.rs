@@ -0,0 +1,283 @@
+//! Format hints describing the JSON shape of a type, for use in prompts.
+
+use schemars::{JsonSchema, Schema};
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+/// Describes the JSON shape of a type as a compact skeleton for a prompt.
+///
+/// The skeleton is generated from the type's JSON Schema. Objects list
+/// their fields in the order `serde_json` keeps map keys: alphabetical, or
+/// declaration order when its `preserve_order` feature is enabled. Arrays
+/// hold a single element, and scalars are replaced by the name of their
+/// type, such as `"string"` or `"integer"`. Optional values read `"string | null"`, enumerations list
+/// their variants as `"small | large"`, and a type that contains itself
+/// shows its name where it recurs. Pairing the hint with
+/// [`StreamingDeserializer`](crate::serde::StreamingDeserializer) lets one
+/// type drive both the prompt and the parsing of the answer.
+///
+/// # Returns
+///
+/// The skeleton as compact JSON.
+///
+/// # Examples
+///
+/// ```
+/// use schemars::JsonSchema;
+/// use serde::Deserialize;
+/// use serde_json::{json, Value};
+/// use surfing::serde::format_hint;
+///
+/// #[derive(Deserialize, JsonSchema)]
+/// struct Pizza {
+///     name: String,
+///     toppings: Vec<String>,
+///     slices: u8,
+/// }
+///
+/// let hint: Value = serde_json::from_str(&format_hint::<Pizza>()).unwrap();
+/// assert_eq!(
+///     hint,
+///     json!({"name": "string", "toppings": ["string"], "slices": "integer"})
+/// );
+/// ```
+pub fn format_hint<T: JsonSchema>() -> String {
+    let schema = schemars::schema_for!(T);
+    // The root is referred to as "#" from within itself
+    let mut visiting = vec!["#".to_string()];
+
+    skeleton(schema.as_value(), &schema, &mut visiting).to_string()
+}
+
+/// Describes the JSON shape of a type by serializing its default value.
+///
+/// This is the cheaper counterpart of [`format_hint`] for types without a
+/// JSON Schema: the hint is whatever `T::default()` serializes to, so it
+/// shows example values instead of types.
+///
+/// # Returns
+///
+/// The default value as compact JSON.
+///
+/// # Panics
+///
+/// Panics if the default value fails to serialize, such as a map with
+/// non-string keys.
+///
+/// # Examples
+///
+/// ```
+/// use serde::Serialize;
+/// use surfing::serde::format_hint_value;
+///
+/// #[derive(Default, Serialize)]
+/// struct Pizza {
+///     name: String,
+///     toppings: Vec<String>,
+/// }
+///
+/// assert_eq!(format_hint_value::<Pizza>(), r#"{"name":"","toppings":[]}"#);
+/// ```
+pub fn format_hint_value<T: Serialize + Default>() -> String {
+    serde_json::to_string(&T::default()).expect("the default value serializes to JSON")
+}
+
+/// Builds the skeleton of a schema.
+///
+/// # Arguments
+///
+/// * `schema` - The schema to describe.
+/// * `root` - The root schema holding the definitions.
+/// * `visiting` - The references being described, to stop at recursion.
+fn skeleton(schema: &Value, root: &Schema, visiting: &mut Vec<String>) -> Value {
+    let Some(schema) = schema.as_object() else {
+        return Value::from("any");
+    };
+
+    if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+        let name = match reference {
+            "#" => root.get("title").and_then(Value::as_str).unwrap_or("any"),
+            reference => reference.rsplit('/').next().unwrap_or(reference),
+        };
+        if visiting.iter().any(|visited| visited == reference) {
+            return Value::from(name);
+        }
+        let Some(target) = root.pointer(reference.trim_start_matches('#')) else {
+            return Value::from(name);
+        };
+
+        visiting.push(reference.to_string());
+        let value = skeleton(target, root, visiting);
+        visiting.pop();
+        return value;
+    }
+
+    if let Some(value) = schema.get("const") {
+        return value.clone();
+    }
+    if let Some(Value::Array(values)) = schema.get("enum") {
+        return alternatives(values.clone());
+    }
+    for keyword in ["oneOf", "anyOf"] {
+        if let Some(Value::Array(options)) = schema.get(keyword) {
+            let options = options
+                .iter()
+                .map(|option| skeleton(option, root, visiting))
+                .collect();
+            return alternatives(options);
+        }
+    }
+
+    match schema.get("type") {
+        Some(Value::String(kind)) => typed(kind, schema, root, visiting),
+        Some(Value::Array(kinds)) => {
+            let options = kinds
+                .iter()
+                .filter_map(Value::as_str)
+                .map(|kind| typed(kind, schema, root, visiting))
+                .collect();
+            alternatives(options)
+        }
+        _ => Value::from("any"),
+    }
+}
+
+/// Builds the skeleton of a schema for one of its types.
+///
+/// # Arguments
+///
+/// * `kind` - The type, such as `object` or `string`.
+/// * `schema` - The schema to describe.
+/// * `root` - The root schema holding the definitions.
+/// * `visiting` - The references being described, to stop at recursion.
+fn typed(
+    kind: &str,
+    schema: &Map<String, Value>,
+    root: &Schema,
+    visiting: &mut Vec<String>,
+) -> Value {
+    match kind {
+        "object" => {
+            let mut object = Map::new();
+            if let Some(Value::Object(properties)) = schema.get("properties") {
+                for (name, property) in properties {
+                    object.insert(name.clone(), skeleton(property, root, visiting));
+                }
+            } else if let Some(values @ Value::Object(_)) = schema.get("additionalProperties") {
+                object.insert("key".to_string(), skeleton(values, root, visiting));
+            }
+            Value::Object(object)
+        }
+        "array" => match schema.get("prefixItems") {
+            Some(Value::Array(items)) => items
+                .iter()
+                .map(|item| skeleton(item, root, visiting))
+                .collect(),
+            _ => match schema.get("items") {
+                Some(items) => Value::Array(vec![skeleton(items, root, visiting)]),
+                None => Value::Array(Vec::new()),
+            },
+        },
+        "null" => Value::Null,
+        kind => Value::from(kind),
+    }
+}
+
+/// Combines the skeletons of alternatives into one.
+///
+/// Alternatives that are all strings or null are listed as `"a | b"`;
+/// otherwise the first alternative that is not null stands for them all.
+///
+/// # Arguments
+///
+/// * `options` - The skeletons of the alternatives.
+fn alternatives(mut options: Vec<Value>) -> Value {
+    if options.len() == 1 {
+        return options.remove(0);
+    }
+
+    let names: Option<Vec<&str>> = options
+        .iter()
+        .map(|option| match option {
+            Value::String(name) => Some(name.as_str()),
+            Value::Null => Some("null"),
+            _ => None,
+        })
+        .collect();
+    if let Some(names) = names {
+        return Value::from(names.join(" | "));
+    }
+
+    options
+        .into_iter()
+        .find(|option| !option.is_null())
+        .unwrap_or(Value::Null)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::collections::HashMap;
+
+    #[allow(dead_code)]
+    #[derive(JsonSchema)]
+    enum Size {
+        Small,
+        Large,
+    }
+
+    #[allow(dead_code)]
+    #[derive(JsonSchema)]
+    struct Topping {
+        name: String,
+        extra: Option<bool>,
+    }
+
+    #[allow(dead_code)]
+    #[derive(JsonSchema)]
+    struct Order {
+        id: u64,
+        size: Size,
+        toppings: Vec<Topping>,
+        price: f64,
+        notes: Option<String>,
+        counts: HashMap<String, u32>,
+        batches: Vec<Vec<u8>>,
+    }
+
+    #[allow(dead_code)]
+    #[derive(JsonSchema)]
+    struct Node {
+        label: String,
+        children: Vec<Node>,
+    }
+
+    fn hint<T: JsonSchema>() -> Value {
+        serde_json::from_str(&format_hint::<T>()).unwrap()
+    }
+
+    #[test]
+    fn test_format_hint_nested_structs_and_vectors() {
+        assert_eq!(
+            hint::<Order>(),
+            json!({
+                "id": "integer",
+                "size": "Small | Large",
+                "toppings": [{"name": "string", "extra": "boolean | null"}],
+                "price": "number",
+                "notes": "string | null",
+                "counts": {"key": "integer"},
+                "batches": [["integer"]],
+            })
+        );
+    }
+
+    #[test]
+    fn test_format_hint_recursive_type() {
+        assert_eq!(
+            hint::<Node>(),
+            json!({"label": "string", "children": ["Node"]})
+        );
+    }
+}
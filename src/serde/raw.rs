@@ -0,0 +1,134 @@
+//! Extraction of values as unparsed `RawValue`s.
+
+use serde_json::value::RawValue;
+
+use crate::serde::{DeserializeError, StreamingDeserializer};
+use crate::JSONParser;
+
+/// A [`StreamingDeserializer`] producing each completed value as an unparsed [`RawValue`].
+///
+/// The JSON of every value is checked once while being deserialized, but it
+/// is not turned into a [`Value`](serde_json::Value): the raw value keeps the
+/// extracted bytes exactly, ready to be embedded in another structure.
+///
+/// # Examples
+///
+/// ```
+/// use surfing::serde::RawValueDeserializer;
+///
+/// let mut deserializer = RawValueDeserializer::new();
+/// assert!(deserializer.process_chunk("data: {\"id\": 1,").is_none());
+///
+/// let raw = deserializer.process_chunk(" \"tags\": [\"a\"]}").unwrap();
+/// assert_eq!(raw.get(), "{\"id\": 1, \"tags\": [\"a\"]}");
+/// ```
+pub type RawValueDeserializer = StreamingDeserializer<Box<RawValue>>;
+
+/// Extracts every JSON value in mixed text as an unparsed [`RawValue`].
+///
+/// Use this to forward values untouched, for example after routing them on a
+/// few fields: a raw value serializes back to exactly the bytes it was
+/// extracted from, without building a [`Value`](serde_json::Value) and
+/// serializing it again.
+///
+/// # Arguments
+///
+/// * `input` - A string slice containing mixed text with embedded JSON.
+///
+/// # Returns
+///
+/// * `Ok(Vec<Box<RawValue>>)` - The values in order of appearance.
+/// * `Err(DeserializeError)` - If a value is not valid JSON.
+///
+/// # Examples
+///
+/// ```
+/// use serde::Serialize;
+/// use serde_json::value::RawValue;
+/// use surfing::serde::raw_values_from_mixed_text;
+///
+/// #[derive(Serialize)]
+/// struct Envelope {
+///     source: &'static str,
+///     payload: Box<RawValue>,
+/// }
+///
+/// let mut values = raw_values_from_mixed_text("got {\"b\": 2, \"a\": 1.50}").unwrap();
+/// let envelope = Envelope { source: "log", payload: values.remove(0) };
+///
+/// assert_eq!(
+///     serde_json::to_string(&envelope).unwrap(),
+///     "{\"source\":\"log\",\"payload\":{\"b\": 2, \"a\": 1.50}}"
+/// );
+/// ```
+pub fn raw_values_from_mixed_text(input: &str) -> Result<Vec<Box<RawValue>>, DeserializeError> {
+    let mut parser = JSONParser::new();
+    let mut values = Vec::new();
+
+    parser
+        .extract_values_from_stream(input, |value| values.push(value.to_string()))
+        .map_err(|e| DeserializeError::Extraction(e.to_string()))?;
+
+    values
+        .into_iter()
+        .map(|value| RawValue::from_string(value).map_err(DeserializeError::Deserialization))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Serialize, Deserialize)]
+    struct Envelope {
+        route: String,
+        payload: Box<RawValue>,
+    }
+
+    #[test]
+    fn test_raw_values_embed_byte_identical() {
+        let payloads = ["{\"z\": 1e3,  \"a\": [1, 2]}", "[\"\\u00e9\", null ]"];
+        let input = format!("first {} second {} end", payloads[0], payloads[1]);
+
+        let values = raw_values_from_mixed_text(&input).unwrap();
+        assert_eq!(values.len(), 2);
+
+        for (value, expected) in values.into_iter().zip(payloads) {
+            let envelope = Envelope {
+                route: "audit".to_string(),
+                payload: value,
+            };
+            let json = serde_json::to_string(&envelope).unwrap();
+            assert_eq!(
+                json,
+                format!("{{\"route\":\"audit\",\"payload\":{}}}", expected)
+            );
+
+            let back: Envelope = serde_json::from_str(&json).unwrap();
+            assert_eq!(back.payload.get(), expected);
+        }
+    }
+
+    #[test]
+    fn test_raw_values_reject_invalid_json() {
+        assert!(matches!(
+            raw_values_from_mixed_text("{\"a\": 1,}"),
+            Err(DeserializeError::Deserialization(_))
+        ));
+    }
+
+    #[test]
+    fn test_raw_value_deserializer_across_chunks() {
+        let mut deserializer = RawValueDeserializer::new();
+        let chunks = ["log {\"k\":", " \"v\" ,\"n\":0.10}", " tail"];
+
+        let raws: Vec<String> = chunks
+            .iter()
+            .filter_map(|chunk| deserializer.process_chunk(chunk))
+            .map(|raw| raw.get().to_string())
+            .collect();
+
+        assert_eq!(raws, vec!["{\"k\": \"v\" ,\"n\":0.10}"]);
+    }
+}
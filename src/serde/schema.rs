@@ -0,0 +1,136 @@
+//! Validation of extracted JSON against a JSON Schema.
+
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use crate::serde::deserializer::{DeserializeError, SchemaViolation};
+use crate::utils::extract_first_json;
+
+/// Deserializes the first JSON value in mixed text once it matches a JSON Schema.
+///
+/// The first complete value is extracted and validated against `schema`
+/// before it is deserialized, so a value of the right shape but with values
+/// the schema rules out, such as a port above 65535, is rejected with every
+/// failing path listed.
+///
+/// # Arguments
+///
+/// * `input` - A string slice containing mixed text with embedded JSON.
+/// * `schema` - The JSON Schema the value must match.
+///
+/// # Returns
+///
+/// * `Ok(T)` - The successfully validated and deserialized value.
+/// * `Err(DeserializeError)` - If there is no value, the schema is invalid,
+///   the value does not match it, or deserialization fails.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "jsonschema")]
+/// # {
+/// use serde::Deserialize;
+/// use serde_json::json;
+/// use surfing::serde::{from_mixed_text_schema, DeserializeError};
+///
+/// #[derive(Debug, Deserialize)]
+/// struct Config {
+///     port: u32,
+/// }
+///
+/// let schema = json!({
+///     "type": "object",
+///     "properties": {"port": {"type": "integer", "maximum": 65535}},
+///     "required": ["port"]
+/// });
+///
+/// let config: Config = from_mixed_text_schema("config: {\"port\": 8080}", &schema).unwrap();
+/// assert_eq!(config.port, 8080);
+///
+/// let error = from_mixed_text_schema::<Config>("config: {\"port\": 70000}", &schema).unwrap_err();
+/// assert!(matches!(error, DeserializeError::SchemaViolation(v) if v[0].path == "/port"));
+/// # }
+/// ```
+pub fn from_mixed_text_schema<T>(input: &str, schema: &Value) -> Result<T, DeserializeError>
+where
+    T: DeserializeOwned,
+{
+    let validator = jsonschema::validator_for(schema)
+        .map_err(|e| DeserializeError::InvalidSchema(e.to_string()))?;
+
+    let json = extract_first_json(input)
+        .ok_or_else(|| DeserializeError::Extraction("no JSON value found".to_string()))?;
+    let value: Value = serde_json::from_str(&json).map_err(DeserializeError::Deserialization)?;
+
+    let violations: Vec<SchemaViolation> = validator
+        .iter_errors(&value)
+        .map(|error| SchemaViolation {
+            path: error.instance_path.to_string(),
+            message: error.to_string(),
+        })
+        .collect();
+    if !violations.is_empty() {
+        return Err(DeserializeError::SchemaViolation(violations));
+    }
+
+    serde_json::from_value(value).map_err(DeserializeError::Deserialization)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+    use serde_json::json;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Service {
+        name: String,
+        replicas: u32,
+    }
+
+    fn schema() -> Value {
+        json!({
+            "type": "object",
+            "properties": {
+                "name": {"type": "string", "minLength": 1},
+                "replicas": {"type": "integer", "minimum": 1, "maximum": 10}
+            },
+            "required": ["name", "replicas"]
+        })
+    }
+
+    #[test]
+    fn test_from_mixed_text_schema_valid() {
+        let service: Service =
+            from_mixed_text_schema("deploy {\"name\":\"api\",\"replicas\":3} now", &schema())
+                .unwrap();
+
+        assert_eq!(
+            service,
+            Service {
+                name: "api".to_string(),
+                replicas: 3
+            }
+        );
+    }
+
+    #[test]
+    fn test_from_mixed_text_schema_lists_failing_paths() {
+        let error =
+            from_mixed_text_schema::<Service>("deploy {\"name\":\"\",\"replicas\":50}", &schema())
+                .unwrap_err();
+
+        let DeserializeError::SchemaViolation(violations) = error else {
+            panic!("expected a schema violation, got {:?}", error);
+        };
+        let mut paths: Vec<&str> = violations.iter().map(|v| v.path.as_str()).collect();
+        paths.sort();
+        assert_eq!(paths, vec!["/name", "/replicas"]);
+    }
+
+    #[test]
+    fn test_from_mixed_text_schema_invalid_schema() {
+        let error = from_mixed_text_schema::<Service>("{}", &json!({"type": 12})).unwrap_err();
+        assert!(matches!(error, DeserializeError::InvalidSchema(_)));
+    }
+}
@@ -0,0 +1,120 @@
+//! Sorting implementation for extracted JSON.
+
+use std::cmp::Ordering;
+
+use serde_json::Value;
+
+use crate::serde::DeserializeError;
+use crate::JSONParser;
+
+/// Extracts every JSON value from text and sorts it by a top-level key.
+///
+/// Numbers are compared numerically and strings lexicographically; numbers
+/// sort before strings, and other types after both. Values lacking the key,
+/// including values that are not objects, sort last. The sort is stable, so
+/// values with equal keys keep their order of appearance.
+///
+/// # Arguments
+///
+/// * `input` - A string slice containing mixed text with embedded JSON.
+/// * `key` - The top-level key to sort by.
+///
+/// # Returns
+///
+/// * `Ok(Vec<Value>)` - The sorted values.
+/// * `Err(DeserializeError)` - If a value is not valid JSON.
+///
+/// # Examples
+///
+/// ```
+/// use serde_json::json;
+/// use surfing::serde::extract_sorted_by;
+///
+/// let input = "b: {\"id\":2} a: {\"id\":1} none: {}";
+/// let values = extract_sorted_by(input, "id").unwrap();
+///
+/// assert_eq!(values, vec![json!({"id": 1}), json!({"id": 2}), json!({})]);
+/// ```
+pub fn extract_sorted_by(input: &str, key: &str) -> Result<Vec<Value>, DeserializeError> {
+    let mut parser = JSONParser::new();
+    let mut raw_values = Vec::new();
+
+    parser
+        .extract_values_from_stream(input, |value| raw_values.push(value.to_string()))
+        .map_err(|e| DeserializeError::Extraction(e.to_string()))?;
+
+    let mut values = raw_values
+        .iter()
+        .map(|raw| serde_json::from_str(raw))
+        .collect::<Result<Vec<Value>, _>>()
+        .map_err(DeserializeError::Deserialization)?;
+
+    values.sort_by(|a, b| compare_keys(a.get(key), b.get(key)));
+
+    Ok(values)
+}
+
+/// Orders two sort keys, placing missing keys last.
+fn compare_keys(a: Option<&Value>, b: Option<&Value>) -> Ordering {
+    match (a, b) {
+        (Some(a), Some(b)) => match (a, b) {
+            (Value::Number(a), Value::Number(b)) => {
+                let a = a.as_f64().unwrap_or(f64::NAN);
+                let b = b.as_f64().unwrap_or(f64::NAN);
+                a.total_cmp(&b)
+            }
+            (Value::String(a), Value::String(b)) => a.cmp(b),
+            _ => type_rank(a).cmp(&type_rank(b)),
+        },
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    }
+}
+
+/// Ranks key types so that mixed keys still sort consistently.
+fn type_rank(value: &Value) -> u8 {
+    match value {
+        Value::Number(_) => 0,
+        Value::String(_) => 1,
+        _ => 2,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_extract_sorted_by_numeric_key() {
+        let input = "{\"id\":3,\"n\":\"c\"} x {\"n\":\"none\"} y {\"id\":1,\"n\":\"a\"} z {\"id\":2,\"n\":\"b\"}";
+        let values = extract_sorted_by(input, "id").unwrap();
+
+        assert_eq!(
+            values,
+            vec![
+                json!({"id": 1, "n": "a"}),
+                json!({"id": 2, "n": "b"}),
+                json!({"id": 3, "n": "c"}),
+                json!({"n": "none"}),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_extract_sorted_by_string_key() {
+        let input = "[1] {\"name\":\"bob\"} {\"name\":\"alice\"} {\"name\":10}";
+        let values = extract_sorted_by(input, "name").unwrap();
+
+        assert_eq!(
+            values,
+            vec![
+                json!({"name": 10}),
+                json!({"name": "alice"}),
+                json!({"name": "bob"}),
+                json!([1]),
+            ]
+        );
+    }
+}
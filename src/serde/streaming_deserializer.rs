@@ -4,12 +4,19 @@
 //! chunks of text containing mixed content, extracting and deserializing
 //! JSON objects as they become available.
 
+use std::collections::{HashMap, VecDeque};
 use std::io::Cursor;
 use std::marker::PhantomData;
 
 use serde::de::DeserializeOwned;
+use serde_json::Value;
 
+use crate::parser::outcome::CharOutcome;
 use crate::serde::deserializer::DeserializeError;
+use crate::serde::elements::ElementSplitter;
+use crate::serde::keyed::{insert_keyed, DuplicateKeyPolicy};
+use crate::serde::pool::BufferPool;
+use crate::serde::utf8::decode_pending;
 use crate::JSONParser;
 
 /// A deserializer for processing streams of text containing JSON.
@@ -63,6 +70,10 @@ where
 {
     parser: JSONParser,
     accumulated_json: String,
+    chunk_json: Vec<u8>,
+    pool: Option<BufferPool>,
+    elements: Option<ElementSplitter>,
+    ready_elements: VecDeque<String>,
     _phantom: PhantomData<T>,
 }
 
@@ -93,10 +104,94 @@ where
         Self {
             parser: JSONParser::new(),
             accumulated_json: String::new(),
+            chunk_json: Vec::new(),
+            pool: None,
+            elements: None,
+            ready_elements: VecDeque::new(),
             _phantom: PhantomData,
         }
     }
 
+    /// Creates a new streaming deserializer borrowing its buffer from a pool.
+    ///
+    /// The accumulation buffer is taken from `pool` and returned to it when
+    /// the deserializer is dropped. See [`BufferPool`].
+    ///
+    /// # Arguments
+    ///
+    /// * `pool` - The pool to borrow the buffer from.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "serde")]
+    /// # {
+    /// use serde::Deserialize;
+    /// use surfing::serde::{BufferPool, StreamingDeserializer};
+    ///
+    /// #[derive(Debug, Deserialize)]
+    /// struct LogEntry {
+    ///     level: String,
+    /// }
+    ///
+    /// let pool = BufferPool::new(16);
+    /// let deserializer = StreamingDeserializer::<LogEntry>::with_pool(pool.clone());
+    /// # }
+    /// ```
+    pub fn with_pool(pool: BufferPool) -> Self {
+        Self {
+            parser: JSONParser::new(),
+            accumulated_json: pool.take(),
+            chunk_json: Vec::new(),
+            pool: Some(pool),
+            elements: None,
+            ready_elements: VecDeque::new(),
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Sets whether the elements of top-level arrays are deserialized one by one.
+    ///
+    /// When enabled, an array such as `[{...},{...},...]` is not buffered
+    /// whole. Each element is deserialized into `T` as soon as it completes,
+    /// and only the element being received is kept in memory. This suits
+    /// large arrays streamed over the network. Top-level values that are not
+    /// arrays are still deserialized whole.
+    ///
+    /// A chunk may complete several elements. The first is returned and the
+    /// rest are queued, so they are returned by the following calls; call
+    /// [`process_chunk`](Self::process_chunk) with an empty chunk to drain
+    /// them. Elements that do not deserialize into `T` are skipped.
+    ///
+    /// # Arguments
+    ///
+    /// * `enabled` - Whether array elements should be streamed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "serde")]
+    /// # {
+    /// use serde::Deserialize;
+    /// use surfing::serde::StreamingDeserializer;
+    ///
+    /// #[derive(Debug, Deserialize)]
+    /// struct Row {
+    ///     id: u64,
+    /// }
+    ///
+    /// let mut deserializer = StreamingDeserializer::<Row>::new().stream_array_elements(true);
+    ///
+    /// assert!(deserializer.process_chunk("[{\"id\":").is_none());
+    /// assert_eq!(deserializer.process_chunk("1},{\"id\":2").unwrap().id, 1);
+    /// assert_eq!(deserializer.process_chunk("}]").unwrap().id, 2);
+    /// # }
+    /// ```
+    pub fn stream_array_elements(mut self, enabled: bool) -> Self {
+        self.elements = enabled.then(ElementSplitter::default);
+        self
+    }
+
     /// Process a chunk of text data and attempt to extract and deserialize JSON.
     ///
     /// This method processes the given chunk of text, extracting any JSON content
@@ -149,39 +244,293 @@ where
     /// # }
     /// ```
     pub fn process_chunk(&mut self, chunk: &str) -> Option<T> {
-        // Extract JSON from this chunk
-        let mut buffer = Vec::new();
+        let completed = self.accumulate(chunk);
+        if self.elements.is_some() {
+            return self.pop_element().map(|(value, _)| value);
+        }
+        if !completed {
+            return None;
+        }
+
+        // Deserialize in place, keeping the buffer's capacity for the next object
+        let value = serde_json::from_str::<T>(&self.accumulated_json).ok();
+        self.accumulated_json.clear();
+        value
+    }
+
+    /// Process a chunk of text data, returning the deserialized value together with its raw JSON.
+    ///
+    /// This behaves like [`process_chunk`](Self::process_chunk), but also hands
+    /// back the exact JSON text the value was deserialized from. This is useful
+    /// for logging or forwarding the original payload, since re-serializing `T`
+    /// may not reproduce it byte for byte.
+    ///
+    /// # Arguments
+    ///
+    /// * `chunk` - A string slice containing text data, potentially with embedded JSON.
+    ///
+    /// # Returns
+    ///
+    /// * `Some((T, String))` - If a complete JSON object was found and successfully deserialized.
+    /// * `None` - If the JSON is still incomplete, no JSON was found, or deserialization failed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "serde")]
+    /// # {
+    /// use serde::Deserialize;
+    /// use surfing::serde::StreamingDeserializer;
+    ///
+    /// #[derive(Debug, Deserialize)]
+    /// struct Event {
+    ///     kind: String,
+    /// }
+    ///
+    /// let mut deserializer = StreamingDeserializer::<Event>::new();
+    ///
+    /// assert!(deserializer.process_chunk_with_raw("Event: {\"kind\":").is_none());
+    ///
+    /// let (event, raw) = deserializer.process_chunk_with_raw(" \"login\"} ok").unwrap();
+    /// assert_eq!(event.kind, "login");
+    /// assert_eq!(raw, "{\"kind\": \"login\"}");
+    /// # }
+    /// ```
+    pub fn process_chunk_with_raw(&mut self, chunk: &str) -> Option<(T, String)> {
+        let completed = self.accumulate(chunk);
+        if self.elements.is_some() {
+            return self.pop_element();
+        }
+        if !completed {
+            return None;
+        }
+
+        // Take the accumulated JSON, leaving it empty for the next object
+        let accumulated_json = std::mem::take(&mut self.accumulated_json);
+
+        serde_json::from_str::<T>(&accumulated_json)
+            .ok()
+            .map(|value| (value, accumulated_json))
+    }
+
+    /// Process a chunk of text data, telling incomplete JSON apart from a value that failed to deserialize.
+    ///
+    /// This behaves like [`process_chunk`](Self::process_chunk), which returns
+    /// `None` in both cases, but reports the error of a complete value that
+    /// does not deserialize into `T`.
+    ///
+    /// # Arguments
+    ///
+    /// * `chunk` - A string slice containing text data, potentially with embedded JSON.
+    ///
+    /// # Returns
+    ///
+    /// * `Some(Ok(T))` - If a complete JSON value was found and deserialized.
+    /// * `Some(Err(DeserializeError::Deserialization))` - If a complete JSON
+    ///   value was found but failed to deserialize.
+    /// * `None` - If the JSON is still incomplete or no JSON was found.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "serde")]
+    /// # {
+    /// use serde::Deserialize;
+    /// use surfing::serde::{DeserializeError, StreamingDeserializer};
+    ///
+    /// #[derive(Debug, Deserialize)]
+    /// struct Config {
+    ///     port: u16,
+    /// }
+    ///
+    /// let mut deserializer = StreamingDeserializer::<Config>::new();
+    ///
+    /// assert!(deserializer.process_chunk_result("{\"port\":").is_none());
+    ///
+    /// let result = deserializer.process_chunk_result("\"eighty\"}");
+    /// assert!(matches!(result, Some(Err(DeserializeError::Deserialization(_)))));
+    ///
+    /// let config = deserializer.process_chunk_result("{\"port\":80}").unwrap().unwrap();
+    /// assert_eq!(config.port, 80);
+    /// # }
+    /// ```
+    pub fn process_chunk_result(&mut self, chunk: &str) -> Option<Result<T, DeserializeError>> {
+        let completed = self.accumulate(chunk);
+        if self.elements.is_some() {
+            let raw = self.ready_elements.pop_front()?;
+            return Some(
+                serde_json::from_str::<T>(&raw).map_err(DeserializeError::Deserialization),
+            );
+        }
+        if !completed {
+            return None;
+        }
+
+        let result = serde_json::from_str::<T>(&self.accumulated_json)
+            .map_err(DeserializeError::Deserialization);
+        self.accumulated_json.clear();
+        Some(result)
+    }
+
+    /// Process a chunk of text data, returning every value completed in it.
+    ///
+    /// Unlike [`process_chunk`](Self::process_chunk), which returns a single
+    /// value, this deserializes all values the chunk completes, in order,
+    /// which suits bursty streams delivering several values in one read.
+    /// A value left incomplete at the end of the chunk is kept in the
+    /// accumulated JSON for the next call. Values failing to deserialize
+    /// are skipped.
+    ///
+    /// # Arguments
+    ///
+    /// * `chunk` - A string slice containing text data, potentially with embedded JSON.
+    ///
+    /// # Returns
+    ///
+    /// The values completed in the chunk that deserialized into `T`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "serde")]
+    /// # {
+    /// use serde::Deserialize;
+    /// use surfing::serde::StreamingDeserializer;
+    ///
+    /// #[derive(Debug, Deserialize)]
+    /// struct Tick {
+    ///     n: u32,
+    /// }
+    ///
+    /// let mut deserializer = StreamingDeserializer::<Tick>::new();
+    ///
+    /// let ticks = deserializer.process_chunk_all("{\"n\":1}{\"n\":2} {\"n\"");
+    /// assert_eq!(ticks.iter().map(|tick| tick.n).collect::<Vec<_>>(), vec![1, 2]);
+    ///
+    /// let ticks = deserializer.process_chunk_all(":3}");
+    /// assert_eq!(ticks[0].n, 3);
+    /// # }
+    /// ```
+    pub fn process_chunk_all(&mut self, chunk: &str) -> Vec<T> {
+        if self.elements.is_some() {
+            self.accumulate(chunk);
+            return std::iter::from_fn(|| self.pop_element())
+                .map(|(value, _)| value)
+                .collect();
+        }
+
+        let mut values = Vec::new();
+        for item in chunk.chars() {
+            self.chunk_json.clear();
+            let was_in_json = self.parser.is_in_json();
+            let outcome = self.parser.push_char(item, &mut self.chunk_json);
+
+            // A value started while another was open means the other was dropped
+            if was_in_json && matches!(outcome, Ok(CharOutcome::Started)) {
+                self.accumulated_json.clear();
+            }
+            if let Ok(text) = std::str::from_utf8(&self.chunk_json) {
+                self.accumulated_json.push_str(text);
+            }
+
+            match outcome {
+                Ok(CharOutcome::Completed) => {
+                    if let Ok(value) = serde_json::from_str::<T>(&self.accumulated_json) {
+                        values.push(value);
+                    }
+                    self.accumulated_json.clear();
+                }
+                // Anything left outside of a value, such as a dropped value, is not kept
+                _ if !self.parser.is_in_json() => self.accumulated_json.clear(),
+                _ => {}
+            }
+        }
+
+        values
+    }
+
+    /// Extracts the JSON of a chunk and adds it to the accumulated JSON.
+    ///
+    /// # Arguments
+    ///
+    /// * `chunk` - A string slice containing text data, potentially with embedded JSON.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the accumulated JSON holds a completed object.
+    fn accumulate(&mut self, chunk: &str) -> bool {
+        // Extract JSON from this chunk, reusing the buffer of the previous one
+        self.chunk_json.clear();
         {
-            let mut writer = Cursor::new(&mut buffer);
+            let mut writer = Cursor::new(&mut self.chunk_json);
             if self
                 .parser
                 .extract_json_from_stream(&mut writer, chunk)
                 .is_err()
             {
-                return None;
+                return false;
             }
         }
 
-        // Add this chunk's extracted JSON to our accumulation
-        if let Ok(chunk_json) = String::from_utf8(buffer) {
-            self.accumulated_json.push_str(&chunk_json);
-        } else {
-            return None;
+        let Ok(chunk_json) = std::str::from_utf8(&self.chunk_json) else {
+            return false;
+        };
+
+        // Array elements are collected one at a time and queued once complete
+        if let Some(elements) = self.elements.as_mut() {
+            let ready = &mut self.ready_elements;
+            elements.push_str(chunk_json, &mut self.accumulated_json, |element| {
+                ready.push_back(element.to_string())
+            });
+            return !ready.is_empty();
         }
 
-        // If we've completed a JSON object, try to deserialize it
-        if !self.parser.is_in_json() && !self.accumulated_json.is_empty() {
-            let accumulated_json = self.accumulated_json.clone();
-            // Reset the accumulated JSON for the next object
-            self.accumulated_json.clear();
+        // Add this chunk's extracted JSON to our accumulation
+        self.accumulated_json.push_str(chunk_json);
+
+        // We've completed a JSON object once the parser leaves it
+        !self.parser.is_in_json() && !self.accumulated_json.is_empty()
+    }
 
-            match serde_json::from_str::<T>(&accumulated_json) {
-                Ok(value) => Some(value),
-                Err(_) => None,
+    /// Takes the next queued array element that deserializes into `T`.
+    ///
+    /// # Returns
+    ///
+    /// * `Some((T, String))` - The element and its raw JSON.
+    /// * `None` - If no queued element deserializes.
+    fn pop_element(&mut self) -> Option<(T, String)> {
+        while let Some(raw) = self.ready_elements.pop_front() {
+            if let Ok(value) = serde_json::from_str::<T>(&raw) {
+                return Some((value, raw));
             }
-        } else {
-            // Still waiting for more JSON
-            None
+        }
+
+        None
+    }
+
+    /// Processes a chunk of text, handing every value completed in it to `on_result`.
+    ///
+    /// Unlike [`process_chunk`](Self::process_chunk), several values in one
+    /// chunk are all delivered, and deserialization failures are reported
+    /// instead of skipped. Partial values are kept inside the parser, so a
+    /// deserializer should be driven through this method or the public ones,
+    /// not both.
+    ///
+    /// # Arguments
+    ///
+    /// * `chunk` - A string slice containing text data, potentially with embedded JSON.
+    /// * `on_result` - The callback receiving the result for each completed value.
+    #[cfg(feature = "http")]
+    pub(crate) fn process_chunk_values<F>(&mut self, chunk: &str, mut on_result: F)
+    where
+        F: FnMut(Result<T, DeserializeError>),
+    {
+        let result = self.parser.extract_values_from_stream(chunk, |value| {
+            on_result(serde_json::from_str(value).map_err(DeserializeError::Deserialization))
+        });
+
+        if let Err(e) = result {
+            on_result(Err(DeserializeError::Extraction(e.to_string())));
         }
     }
 
@@ -253,7 +602,8 @@ where
     /// Resets the deserializer state.
     ///
     /// This clears any accumulated JSON and resets the parser,
-    /// allowing you to start processing a new stream.
+    /// allowing you to start processing a new stream. Allocated buffers are
+    /// kept for reuse.
     ///
     /// # Examples
     ///
@@ -278,8 +628,67 @@ where
     /// # }
     /// ```
     pub fn reset(&mut self) {
-        self.parser = JSONParser::new();
+        self.parser.reset();
         self.accumulated_json.clear();
+        self.ready_elements.clear();
+        if let Some(elements) = self.elements.as_mut() {
+            elements.reset();
+        }
+    }
+
+    /// Turns this deserializer into one producing another type, keeping its state.
+    ///
+    /// This suits protocols that open with one kind of object, such as a
+    /// handshake, and continue with another. The parser state and the
+    /// accumulated JSON move over as they are, and a pooled buffer stays
+    /// borrowed, so nothing is re-parsed or copied. Retargeting in the middle
+    /// of a value means that value is deserialized as `U` once it completes.
+    ///
+    /// # Returns
+    ///
+    /// A deserializer for `U` continuing where this one left off.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "serde")]
+    /// # {
+    /// use serde::Deserialize;
+    /// use surfing::serde::StreamingDeserializer;
+    ///
+    /// #[derive(Debug, Deserialize)]
+    /// struct Hello {
+    ///     version: u32,
+    /// }
+    ///
+    /// #[derive(Debug, Deserialize)]
+    /// struct Event {
+    ///     kind: String,
+    /// }
+    ///
+    /// let mut handshake = StreamingDeserializer::<Hello>::new();
+    /// let hello = handshake.process_chunk("HELLO {\"version\":2}").unwrap();
+    /// assert_eq!(hello.version, 2);
+    ///
+    /// let mut events = handshake.retarget::<Event>();
+    /// let event = events.process_chunk("EVENT {\"kind\":\"ping\"}").unwrap();
+    /// assert_eq!(event.kind, "ping");
+    /// # }
+    /// ```
+    pub fn retarget<U>(mut self) -> StreamingDeserializer<U>
+    where
+        U: DeserializeOwned,
+    {
+        // Taking the pool keeps the buffer from being returned when `self` drops
+        StreamingDeserializer {
+            parser: std::mem::take(&mut self.parser),
+            accumulated_json: std::mem::take(&mut self.accumulated_json),
+            chunk_json: std::mem::take(&mut self.chunk_json),
+            pool: self.pool.take(),
+            elements: self.elements.take(),
+            ready_elements: std::mem::take(&mut self.ready_elements),
+            _phantom: PhantomData,
+        }
     }
 
     /// Attempts to finalize and deserialize any accumulated JSON.
@@ -323,6 +732,12 @@ where
     /// # }
     /// ```
     pub fn finalize(&mut self) -> Result<Option<T>, DeserializeError> {
+        if let Some(raw) = self.ready_elements.pop_front() {
+            return serde_json::from_str(&raw)
+                .map(Some)
+                .map_err(DeserializeError::Deserialization);
+        }
+
         if self.accumulated_json.is_empty() {
             return Ok(None);
         }
@@ -335,6 +750,264 @@ where
             Err(e) => Err(DeserializeError::Deserialization(e)),
         }
     }
+
+    /// Extracts and deserializes every value of a finite source, stopping at the first failure.
+    ///
+    /// The chunks are fed in order, and every value completed is collected,
+    /// including several completed by the same chunk. Chunks may be text or
+    /// bytes, and may split values and UTF-8 characters anywhere; bytes that
+    /// are not valid UTF-8 are processed as U+FFFD. Once the source is
+    /// exhausted, a value still open is an error, as when
+    /// [`finalize`](Self::finalize) is called on an incomplete value.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - The chunks, such as a slice of `&str` or an iterator of `Vec<u8>`.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Vec<T>)` - Every value of the source, in order.
+    /// * `Err(DeserializeError::InvalidValue)` - If a value failed to
+    ///   deserialize, with its index and raw JSON.
+    /// * `Err(DeserializeError::Extraction)` - If the source ended in the middle of a value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "serde")]
+    /// # {
+    /// use serde::Deserialize;
+    /// use surfing::serde::StreamingDeserializer;
+    ///
+    /// #[derive(Debug, Deserialize)]
+    /// struct Point {
+    ///     x: i32,
+    /// }
+    ///
+    /// let chunks = ["a {\"x\":1} b {\"x\"", ":2} {\"x\":3}"];
+    /// let points = StreamingDeserializer::<Point>::collect_all(chunks).unwrap();
+    ///
+    /// assert_eq!(points.iter().map(|p| p.x).collect::<Vec<_>>(), vec![1, 2, 3]);
+    /// # }
+    /// ```
+    pub fn collect_all<I>(source: I) -> Result<Vec<T>, DeserializeError>
+    where
+        I: IntoIterator,
+        I::Item: AsRef<[u8]>,
+    {
+        let mut values = Vec::new();
+        let mut failure = None;
+
+        collect_results(source, |result| match result {
+            Ok(value) => {
+                values.push(value);
+                true
+            }
+            Err(e) => {
+                failure = Some(e);
+                false
+            }
+        });
+
+        match failure {
+            Some(e) => Err(e),
+            None => Ok(values),
+        }
+    }
+
+    /// Extracts and deserializes every value of a finite source, collecting failures.
+    ///
+    /// This behaves like [`collect_all`](Self::collect_all), but carries on
+    /// past values that fail to deserialize and a source ending in the middle
+    /// of a value, returning their errors alongside the values.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - The chunks, such as a slice of `&str` or an iterator of `Vec<u8>`.
+    ///
+    /// # Returns
+    ///
+    /// The values that deserialized and the errors of the others, each in order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "serde")]
+    /// # {
+    /// use serde::Deserialize;
+    /// use surfing::serde::{DeserializeError, StreamingDeserializer};
+    ///
+    /// #[derive(Debug, Deserialize)]
+    /// struct Point {
+    ///     x: i32,
+    /// }
+    ///
+    /// let chunks = ["{\"x\":1} {\"x\":\"two\"} {\"x\":3}"];
+    /// let (points, errors) = StreamingDeserializer::<Point>::collect_all_lenient(chunks);
+    ///
+    /// assert_eq!(points.len(), 2);
+    /// assert!(matches!(errors[0], DeserializeError::InvalidValue { index: 1, .. }));
+    /// # }
+    /// ```
+    pub fn collect_all_lenient<I>(source: I) -> (Vec<T>, Vec<DeserializeError>)
+    where
+        I: IntoIterator,
+        I::Item: AsRef<[u8]>,
+    {
+        let mut values = Vec::new();
+        let mut errors = Vec::new();
+
+        collect_results(source, |result| {
+            match result {
+                Ok(value) => values.push(value),
+                Err(e) => errors.push(e),
+            }
+            true
+        });
+
+        (values, errors)
+    }
+
+    /// Extracts and deserializes every value of a finite source into a map keyed by one of their fields.
+    ///
+    /// This is the streaming counterpart of
+    /// [`from_mixed_text_keyed_by`](crate::serde::from_mixed_text_keyed_by),
+    /// fed like [`collect_all`](Self::collect_all). Collection stops at the
+    /// first failure.
+    ///
+    /// # Arguments
+    ///
+    /// * `source` - The chunks, such as a slice of `&str` or an iterator of `Vec<u8>`.
+    /// * `key` - The name of the top-level field holding the key.
+    /// * `policy` - What to do with values repeating an earlier key.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(HashMap<String, T>)` - The values by key.
+    /// * `Err(DeserializeError)` - If a value has no key, repeats one under
+    ///   [`DuplicateKeyPolicy::Error`], or fails to deserialize, or the
+    ///   source ended in the middle of a value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "serde")]
+    /// # {
+    /// use serde::Deserialize;
+    /// use surfing::serde::{DuplicateKeyPolicy, StreamingDeserializer};
+    ///
+    /// #[derive(Debug, Deserialize)]
+    /// struct Host {
+    ///     up: bool,
+    /// }
+    ///
+    /// let chunks = ["{\"name\":\"a\",\"up\":true} {\"na", "me\":\"b\",\"up\":false}"];
+    /// let hosts =
+    ///     StreamingDeserializer::<Host>::collect_keyed_by(chunks, "name", DuplicateKeyPolicy::Error)
+    ///         .unwrap();
+    ///
+    /// assert!(hosts["a"].up);
+    /// assert!(!hosts["b"].up);
+    /// # }
+    /// ```
+    pub fn collect_keyed_by<I>(
+        source: I,
+        key: &str,
+        policy: DuplicateKeyPolicy,
+    ) -> Result<HashMap<String, T>, DeserializeError>
+    where
+        I: IntoIterator,
+        I::Item: AsRef<[u8]>,
+    {
+        let mut map = HashMap::new();
+        let mut index = 0;
+        let mut failure = None;
+
+        collect_results::<Value, _, _>(source, |result| {
+            let result = result.and_then(|value| insert_keyed(&mut map, index, value, key, policy));
+            index += 1;
+            match result {
+                Ok(()) => true,
+                Err(e) => {
+                    failure = Some(e);
+                    false
+                }
+            }
+        });
+
+        match failure {
+            Some(e) => Err(e),
+            None => Ok(map),
+        }
+    }
+}
+
+/// Feeds a finite source to a new parser, handing the result for each value to `on_result`.
+///
+/// # Arguments
+///
+/// * `source` - The chunks of text or bytes.
+/// * `on_result` - The callback receiving each result, returning whether to carry on.
+fn collect_results<T, I, F>(source: I, mut on_result: F)
+where
+    T: DeserializeOwned,
+    I: IntoIterator,
+    I::Item: AsRef<[u8]>,
+    F: FnMut(Result<T, DeserializeError>) -> bool,
+{
+    let mut parser = JSONParser::new();
+    let mut pending = Vec::new();
+    let mut index = 0;
+    let mut carry_on = true;
+    let mut chunks = source.into_iter();
+
+    while carry_on {
+        let chunk = chunks.next();
+        let finished = chunk.is_none();
+        if let Some(chunk) = chunk {
+            pending.extend_from_slice(chunk.as_ref());
+        }
+
+        // Keep an incomplete character back for the next chunk
+        let (text, valid) = decode_pending(&pending, finished);
+
+        let result = parser.extract_values_from_stream(&text, |json| {
+            if carry_on {
+                carry_on = on_result(serde_json::from_str(json).map_err(|error| {
+                    DeserializeError::InvalidValue {
+                        index,
+                        json: json.to_string(),
+                        error,
+                    }
+                }));
+            }
+            index += 1;
+        });
+        if let Err(e) = result {
+            carry_on = carry_on && on_result(Err(DeserializeError::Extraction(e.to_string())));
+        }
+        pending.drain(..valid);
+
+        if finished {
+            if carry_on && parser.is_in_json() {
+                on_result(Err(DeserializeError::Extraction(
+                    "the input ended in the middle of a value".to_string(),
+                )));
+            }
+            break;
+        }
+    }
+}
+
+impl<T> Drop for StreamingDeserializer<T>
+where
+    T: DeserializeOwned,
+{
+    fn drop(&mut self) {
+        if let Some(pool) = &self.pool {
+            pool.give(std::mem::take(&mut self.accumulated_json));
+        }
+    }
 }
 
 impl<T> Default for StreamingDeserializer<T>
@@ -451,6 +1124,27 @@ mod tests {
         assert!(result.is_none());
     }
 
+    #[test]
+    fn test_process_chunk_with_raw() {
+        let mut deserializer = StreamingDeserializer::<TestData>::new();
+
+        let result = deserializer.process_chunk_with_raw("Audit: {\"id\": 9,");
+        assert!(result.is_none());
+
+        let (data, raw) = deserializer
+            .process_chunk_with_raw(" \"name\":\"raw\" } trailing")
+            .unwrap();
+        assert_eq!(
+            data,
+            TestData {
+                id: 9,
+                name: "raw".to_string(),
+            }
+        );
+        assert_eq!(raw, "{\"id\": 9, \"name\":\"raw\" }");
+        assert!(deserializer.accumulated_json().is_empty());
+    }
+
     #[test]
     fn test_multiple_json_objects() {
         let mut deserializer = StreamingDeserializer::<TestData>::new();
@@ -466,4 +1160,218 @@ mod tests {
         // The second object should be ignored (current implementation limitation)
         // A more advanced implementation could handle this by tracking partial objects
     }
+
+    #[test]
+    fn test_process_chunk_result_reports_wrong_type() {
+        let mut deserializer = StreamingDeserializer::<TestData>::new();
+
+        assert!(deserializer.process_chunk_result("text").is_none());
+        assert!(deserializer
+            .process_chunk_result("{\"id\":\"one\",")
+            .is_none());
+        let result = deserializer.process_chunk_result("\"name\":\"a\"} more");
+        assert!(matches!(
+            result,
+            Some(Err(DeserializeError::Deserialization(_)))
+        ));
+
+        // The failed value is not kept, so the next one deserializes on its own
+        assert!(deserializer.accumulated_json().is_empty());
+        let data = deserializer
+            .process_chunk_result("{\"id\":2,\"name\":\"b\"}")
+            .unwrap()
+            .unwrap();
+        assert_eq!(data.id, 2);
+    }
+
+    #[test]
+    fn test_process_chunk_all() {
+        let mut deserializer = StreamingDeserializer::<TestData>::new();
+        let chunks = [
+            "burst: {\"id\":1,\"name\":\"a\"} {\"id\":2,\"na",
+            "me\":\"b\"}{\"id\":3,\"name\":\"c\"} {\"id\":\"bad\"} end",
+        ];
+
+        let first = deserializer.process_chunk_all(chunks[0]);
+        assert_eq!(
+            first.iter().map(|data| data.id).collect::<Vec<_>>(),
+            vec![1]
+        );
+        assert!(deserializer.is_in_json());
+        assert_eq!(deserializer.accumulated_json(), "{\"id\":2,\"na");
+
+        let second = deserializer.process_chunk_all(chunks[1]);
+        assert_eq!(
+            second.iter().map(|data| data.id).collect::<Vec<_>>(),
+            vec![2, 3]
+        );
+        assert_eq!(second[0].name, "b");
+        assert!(deserializer.accumulated_json().is_empty());
+    }
+
+    #[test]
+    fn test_reset_keeps_capacity() {
+        let mut deserializer = StreamingDeserializer::<TestData>::new();
+        deserializer.process_chunk("{\"id\":10,\"name\":\"a long enough name to grow\"");
+        let capacity = deserializer.accumulated_json.capacity();
+
+        deserializer.reset();
+        assert_eq!(deserializer.accumulated_json.capacity(), capacity);
+
+        let data = deserializer
+            .process_chunk("{\"id\":11,\"name\":\"b\"}")
+            .unwrap();
+        assert_eq!(data.id, 11);
+        assert_eq!(deserializer.accumulated_json.capacity(), capacity);
+    }
+
+    #[test]
+    fn test_with_pool_returns_buffer_on_drop() {
+        let pool = BufferPool::new(4);
+
+        let mut deserializer = StreamingDeserializer::<TestData>::with_pool(pool.clone());
+        deserializer.process_chunk("{\"id\":12,\"name\":\"pooled\"");
+        let capacity = deserializer.accumulated_json.capacity();
+        drop(deserializer);
+        assert_eq!(pool.len(), 1);
+
+        let deserializer = StreamingDeserializer::<TestData>::with_pool(pool.clone());
+        assert!(deserializer.accumulated_json().is_empty());
+        assert_eq!(deserializer.accumulated_json.capacity(), capacity);
+        assert!(pool.is_empty());
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Hello {
+        version: u32,
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Event {
+        kind: String,
+    }
+
+    #[test]
+    fn test_retarget_hands_off_after_handshake() {
+        let mut handshake = StreamingDeserializer::<Hello>::new();
+        assert!(handshake.process_chunk("HELLO {\"vers").is_none());
+        assert_eq!(
+            handshake.process_chunk("ion\":2} EVENTS "),
+            Some(Hello { version: 2 })
+        );
+
+        let mut events = handshake.retarget::<Event>();
+        let kinds: Vec<String> = ["{\"kind\":\"start\"} ", "{\"ki", "nd\":\"stop\"}"]
+            .iter()
+            .filter_map(|chunk| events.process_chunk(chunk))
+            .map(|event| event.kind)
+            .collect();
+
+        assert_eq!(kinds, vec!["start", "stop"]);
+    }
+
+    #[test]
+    fn test_retarget_mid_value_keeps_partial_json() {
+        let pool = BufferPool::new(4);
+        let mut handshake = StreamingDeserializer::<Hello>::with_pool(pool.clone());
+        assert_eq!(
+            handshake.process_chunk("{\"version\":1}"),
+            Some(Hello { version: 1 })
+        );
+        assert!(handshake.process_chunk("next {\"kind\":").is_none());
+
+        let mut events = handshake.retarget::<Event>();
+        assert!(events.is_in_json());
+        assert_eq!(events.accumulated_json(), "{\"kind\":");
+        assert!(pool.is_empty());
+
+        assert_eq!(
+            events.process_chunk("\"data\"}"),
+            Some(Event {
+                kind: "data".to_string()
+            })
+        );
+
+        drop(events);
+        assert_eq!(pool.len(), 1);
+    }
+
+    #[test]
+    fn test_stream_array_elements_in_chunks() {
+        let mut deserializer = StreamingDeserializer::<TestData>::new().stream_array_elements(true);
+        let chunks = [
+            "rows: [{\"id\":1,\"na",
+            "me\":\"a\"}, {\"id\":2,\"name\":\"b\"}",
+            ", {\"id\":3,",
+            "\"name\":\"c\"}]",
+        ];
+
+        let mut ids = Vec::new();
+        for chunk in chunks {
+            ids.extend(deserializer.process_chunk(chunk).map(|data| data.id));
+
+            // Only the element being received is buffered
+            assert!(!deserializer.accumulated_json().contains('['));
+            assert!(!deserializer.accumulated_json().contains('}'));
+        }
+
+        assert_eq!(ids, vec![1, 2, 3]);
+        assert!(!deserializer.is_in_json());
+        assert!(deserializer.finalize().unwrap().is_none());
+    }
+
+    #[test]
+    fn test_stream_array_elements_queues_elements_of_one_chunk() {
+        let mut deserializer = StreamingDeserializer::<TestData>::new().stream_array_elements(true);
+
+        let (first, raw) = deserializer
+            .process_chunk_with_raw("[{\"id\":1,\"name\":\"a\"},{\"id\":2,\"name\":\"b\"}]")
+            .unwrap();
+        assert_eq!((first.id, raw.as_str()), (1, "{\"id\":1,\"name\":\"a\"}"));
+        assert_eq!(deserializer.process_chunk("").unwrap().id, 2);
+        assert!(deserializer.process_chunk("").is_none());
+    }
+
+    #[test]
+    fn test_collect_all_ending_mid_value_strict_and_lenient() {
+        // "é" is split between the two byte chunks
+        let chunks: Vec<Vec<u8>> = vec![
+            b"{\"id\":1,\"name\":\"a\"} {\"id\":\"x\"} {\"id\":2,\"name\":\"\xc3".to_vec(),
+            b"\xa9\"} tail {\"id\":3,".to_vec(),
+        ];
+
+        match StreamingDeserializer::<TestData>::collect_all(&chunks) {
+            Err(DeserializeError::InvalidValue { index, json, .. }) => {
+                assert_eq!((index, json.as_str()), (1, "{\"id\":\"x\"}"))
+            }
+            other => panic!("unexpected result: {:?}", other.map(|values| values.len())),
+        }
+
+        let (values, errors) = StreamingDeserializer::<TestData>::collect_all_lenient(&chunks);
+        assert_eq!(
+            values,
+            vec![
+                TestData {
+                    id: 1,
+                    name: "a".to_string()
+                },
+                TestData {
+                    id: 2,
+                    name: "é".to_string()
+                },
+            ]
+        );
+        assert_eq!(errors.len(), 2);
+        assert!(matches!(
+            &errors[1],
+            DeserializeError::Extraction(message) if message == "the input ended in the middle of a value"
+        ));
+
+        // Without the invalid value, strict mode fails on the open one
+        let chunks = ["{\"id\":1,\"name\":\"a\"} {\"id\":3,"];
+        assert!(matches!(
+            StreamingDeserializer::<TestData>::collect_all(chunks),
+            Err(DeserializeError::Extraction(_))
+        ));
+    }
 }
@@ -0,0 +1,374 @@
+//! Extraction in a background Tokio task, fed and drained through channels.
+
+use std::fmt;
+
+use serde::de::DeserializeOwned;
+use tokio::sync::mpsc;
+
+use crate::parser::options::ParserOptions;
+use crate::serde::utf8::decode_pending;
+use crate::serde::DeserializeError;
+use crate::JSONParser;
+
+/// The number of chunks and of values each channel holds before the sending side waits.
+pub const EXTRACTOR_CHANNEL_CAPACITY: usize = 16;
+
+/// Error returned when sending a chunk to an extractor task that has stopped.
+///
+/// The task stops once its [`ValueReceiver`] is dropped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExtractorClosed;
+
+impl fmt::Display for ExtractorClosed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "the extractor task has stopped")
+    }
+}
+
+impl std::error::Error for ExtractorClosed {}
+
+/// The raw JSON of a value extracted by [`spawn_json_extractor`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExtractedJson(String);
+
+impl ExtractedJson {
+    /// Returns the JSON text.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Returns the JSON text, consuming the value.
+    pub fn into_string(self) -> String {
+        self.0
+    }
+}
+
+impl fmt::Display for ExtractedJson {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// The sending side of an extractor task, accepting text or bytes.
+///
+/// Dropping every clone of the sender ends the input: the task finishes the
+/// values in progress and closes the [`ValueReceiver`].
+#[derive(Debug, Clone)]
+pub struct ChunkSender {
+    sender: mpsc::Sender<Vec<u8>>,
+}
+
+impl ChunkSender {
+    /// Sends the next chunk of the stream, waiting while the channel is full.
+    ///
+    /// Chunks may split values and UTF-8 characters anywhere. Bytes that
+    /// are not valid UTF-8 are processed as U+FFFD.
+    ///
+    /// # Arguments
+    ///
+    /// * `chunk` - The next chunk, such as a `&str`, `String`, `&[u8]` or `Vec<u8>`.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` - If the chunk was queued.
+    /// * `Err(ExtractorClosed)` - If the task has stopped.
+    pub async fn send(&self, chunk: impl Into<Vec<u8>>) -> Result<(), ExtractorClosed> {
+        self.sender
+            .send(chunk.into())
+            .await
+            .map_err(|_| ExtractorClosed)
+    }
+}
+
+/// The receiving side of an extractor task.
+#[derive(Debug)]
+pub struct ValueReceiver<T> {
+    receiver: mpsc::Receiver<Result<T, DeserializeError>>,
+}
+
+impl<T> ValueReceiver<T> {
+    /// Receives the next value, waiting until one completes.
+    ///
+    /// # Returns
+    ///
+    /// * `Some(Ok(T))` - A value that completed.
+    /// * `Some(Err(DeserializeError))` - A value that did not deserialize, or
+    ///   the input ending in the middle of a value.
+    /// * `None` - Once the input has ended and every value was received.
+    pub async fn recv(&mut self) -> Option<Result<T, DeserializeError>> {
+        self.receiver.recv().await
+    }
+}
+
+/// Spawns a Tokio task extracting and deserializing values from the chunks it is sent.
+///
+/// Both channels are bounded to [`EXTRACTOR_CHANNEL_CAPACITY`], so a slow
+/// consumer makes [`ChunkSender::send`] wait instead of buffering without
+/// limit. When the sender is dropped, the task delivers the values still
+/// pending and, if the input ended in the middle of a value, a final
+/// [`DeserializeError::Extraction`] before closing the receiver. Dropping
+/// the receiver stops the task.
+///
+/// This must be called from within a Tokio runtime.
+///
+/// # Arguments
+///
+/// * `options` - The options the parser is created with.
+///
+/// # Returns
+///
+/// The sender accepting chunks and the receiver yielding values.
+///
+/// # Examples
+///
+/// ```
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() {
+/// use serde::Deserialize;
+/// use surfing::parser::options::ParserOptions;
+/// use surfing::serde::spawn_extractor;
+///
+/// #[derive(Debug, Deserialize)]
+/// struct Event {
+///     id: u64,
+/// }
+///
+/// let (sender, mut values) = spawn_extractor::<Event>(ParserOptions::new());
+///
+/// sender.send("event {\"i").await.unwrap();
+/// sender.send("d\":7} done").await.unwrap();
+/// drop(sender);
+///
+/// assert_eq!(values.recv().await.unwrap().unwrap().id, 7);
+/// assert!(values.recv().await.is_none());
+/// # }
+/// ```
+pub fn spawn_extractor<T>(options: ParserOptions) -> (ChunkSender, ValueReceiver<T>)
+where
+    T: DeserializeOwned + Send + 'static,
+{
+    spawn_with(options, |json| {
+        serde_json::from_str(json).map_err(DeserializeError::Deserialization)
+    })
+}
+
+/// Spawns a Tokio task extracting the raw JSON of the values in the chunks it is sent.
+///
+/// This behaves like [`spawn_extractor`], but hands out each value's JSON
+/// instead of deserializing it.
+///
+/// # Arguments
+///
+/// * `options` - The options the parser is created with.
+///
+/// # Returns
+///
+/// The sender accepting chunks and the receiver yielding the values' JSON.
+///
+/// # Examples
+///
+/// ```
+/// # #[tokio::main(flavor = "current_thread")]
+/// # async fn main() {
+/// use surfing::parser::options::ParserOptions;
+/// use surfing::serde::spawn_json_extractor;
+///
+/// let (sender, mut values) = spawn_json_extractor(ParserOptions::new());
+///
+/// sender.send(b"log [1, 2] {\"open\":".to_vec()).await.unwrap();
+/// drop(sender);
+///
+/// assert_eq!(values.recv().await.unwrap().unwrap().as_str(), "[1, 2]");
+/// assert!(values.recv().await.unwrap().is_err());
+/// assert!(values.recv().await.is_none());
+/// # }
+/// ```
+pub fn spawn_json_extractor(options: ParserOptions) -> (ChunkSender, ValueReceiver<ExtractedJson>) {
+    spawn_with(options, |json| Ok(ExtractedJson(json.to_string())))
+}
+
+/// Spawns the extractor task, converting each value with `convert`.
+///
+/// # Arguments
+///
+/// * `options` - The options the parser is created with.
+/// * `convert` - The conversion applied to each value's JSON.
+fn spawn_with<T, F>(options: ParserOptions, convert: F) -> (ChunkSender, ValueReceiver<T>)
+where
+    T: Send + 'static,
+    F: Fn(&str) -> Result<T, DeserializeError> + Send + 'static,
+{
+    let (chunk_sender, mut chunks) = mpsc::channel::<Vec<u8>>(EXTRACTOR_CHANNEL_CAPACITY);
+    let (value_sender, values) = mpsc::channel(EXTRACTOR_CHANNEL_CAPACITY);
+
+    tokio::spawn(async move {
+        let mut parser = JSONParser::with_options(options);
+        let mut pending = Vec::new();
+        let mut ready = Vec::new();
+
+        loop {
+            let chunk = chunks.recv().await;
+            let finished = chunk.is_none();
+            if let Some(chunk) = chunk {
+                pending.extend_from_slice(&chunk);
+            }
+
+            let fed = feed_pending(&mut parser, &mut pending, finished, |json| {
+                ready.push(convert(json))
+            });
+            if let Err(e) = fed {
+                ready.push(Err(e));
+            }
+            if finished && parser.is_in_json() {
+                ready.push(Err(DeserializeError::Extraction(
+                    "the input ended in the middle of a value".to_string(),
+                )));
+            }
+
+            for result in ready.drain(..) {
+                if value_sender.send(result).await.is_err() {
+                    // The receiver is gone, so nobody wants the rest
+                    return;
+                }
+            }
+            if finished {
+                return;
+            }
+        }
+    });
+
+    (
+        ChunkSender {
+            sender: chunk_sender,
+        },
+        ValueReceiver { receiver: values },
+    )
+}
+
+/// Feeds the pending bytes to the parser, handing each completed value to `on_value`.
+///
+/// An incomplete character at the end is kept back for the next chunk
+/// unless the input has ended; invalid bytes are processed as U+FFFD.
+///
+/// # Arguments
+///
+/// * `parser` - The parser extracting the values.
+/// * `pending` - The bytes not processed yet.
+/// * `finished` - Whether the input has ended.
+/// * `on_value` - The callback receiving each completed value.
+///
+/// # Returns
+///
+/// * `Ok(())` - If the bytes were processed.
+/// * `Err(DeserializeError::Extraction)` - If a value was rejected, as under
+///   [`ParserOptions::max_string_bytes`]. The values completed before it were
+///   handed to `on_value`, and the bytes are consumed either way.
+fn feed_pending<F>(
+    parser: &mut JSONParser,
+    pending: &mut Vec<u8>,
+    finished: bool,
+    on_value: F,
+) -> Result<(), DeserializeError>
+where
+    F: FnMut(&str),
+{
+    let (text, valid) = decode_pending(pending, finished);
+    let result = parser.extract_values_from_stream(&text, on_value);
+
+    pending.drain(..valid);
+    result.map_err(|e| DeserializeError::Extraction(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Reading {
+        sensor: String,
+        value: f64,
+    }
+
+    #[tokio::test]
+    async fn test_spawn_extractor_between_tasks() {
+        let (sender, mut values) = spawn_extractor::<Reading>(ParserOptions::new());
+
+        let producer = tokio::spawn(async move {
+            let text = "boot ok {\"sensor\":\"t\u{e9}mp\",\"value\":2\
+                        1.5}\n{\"sensor\":\"h\",\"value\":0.4} noise {\"sensor\":\"p\",\"val";
+            let bytes = text.as_bytes();
+
+            // Split in awkward places, including inside "é"
+            let cuts = [1, 13, 20, 21, 22, 40, 41, bytes.len()];
+            let mut start = 0;
+            for end in cuts {
+                sender.send(&bytes[start..end]).await.unwrap();
+                start = end;
+                tokio::task::yield_now().await;
+            }
+        });
+
+        let consumer = tokio::spawn(async move {
+            let mut results = Vec::new();
+            while let Some(result) = values.recv().await {
+                results.push(result);
+            }
+            results
+        });
+
+        producer.await.unwrap();
+        let results = consumer.await.unwrap();
+
+        assert_eq!(results.len(), 3);
+        assert_eq!(
+            results[0].as_ref().unwrap(),
+            &Reading {
+                sensor: "témp".to_string(),
+                value: 21.5
+            }
+        );
+        assert_eq!(results[1].as_ref().unwrap().sensor, "h");
+        match &results[2] {
+            Err(DeserializeError::Extraction(message)) => {
+                assert_eq!(message, "the input ended in the middle of a value")
+            }
+            other => panic!("unexpected result: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_spawn_json_extractor_reports_rejected_values() {
+        let options = ParserOptions::new().max_string_bytes(4);
+        let (sender, mut values) = spawn_json_extractor(options);
+
+        sender.send("{\"a\":\"long string\"} [1]").await.unwrap();
+        drop(sender);
+
+        // The error follows the values its chunk completed
+        assert_eq!(values.recv().await.unwrap().unwrap().as_str(), "[1]");
+        assert!(matches!(
+            values.recv().await,
+            Some(Err(DeserializeError::Extraction(_)))
+        ));
+        assert!(values.recv().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_dropping_receiver_stops_the_task() {
+        let (sender, values) = spawn_json_extractor(ParserOptions::new());
+        drop(values);
+
+        // The task notices the closed receiver once it has a value to send
+        let mut closed = false;
+        for _ in 0..64 {
+            if sender.send("[1]").await.is_err() {
+                closed = true;
+                break;
+            }
+            tokio::task::yield_now().await;
+        }
+
+        assert!(closed);
+    }
+}
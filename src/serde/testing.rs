@@ -0,0 +1,285 @@
+//! Helpers for asserting on extracted JSON in tests.
+//!
+//! Extracted JSON keeps the spacing and key order of its source, so
+//! comparing it byte for byte makes tests brittle. The helpers here compare
+//! JSON structurally instead: key order and whitespace are ignored, and
+//! numbers are compared by value, so `1`, `1.0` and `1e0` are equal.
+//! Two integers are compared exactly. A number with a fraction or exponent
+//! is compared as an `f64`, and so is an integer compared with it, which
+//! loses precision beyond 2^53.
+
+use serde_json::{Number, Value};
+
+use crate::JSONParser;
+
+/// Returns whether two JSON texts hold the same value.
+///
+/// Key order, whitespace and number representation are ignored, as
+/// described in the [module documentation](self). Text that is not valid
+/// JSON is never equal to anything.
+///
+/// # Arguments
+///
+/// * `a` - The first JSON text.
+/// * `b` - The second JSON text.
+///
+/// # Returns
+///
+/// `true` if both texts are valid JSON holding the same value.
+///
+/// # Examples
+///
+/// ```
+/// use surfing::serde::testing::json_eq;
+///
+/// assert!(json_eq("{\"a\":1,\"b\":[true]}", "{ \"b\": [true], \"a\": 1.0 }"));
+/// assert!(!json_eq("{\"a\":1}", "{\"a\":\"1\"}"));
+/// ```
+pub fn json_eq(a: &str, b: &str) -> bool {
+    match (
+        serde_json::from_str::<Value>(a),
+        serde_json::from_str::<Value>(b),
+    ) {
+        (Ok(a), Ok(b)) => first_difference(&a, &b).is_none(),
+        _ => false,
+    }
+}
+
+/// Asserts that the JSON extracted from some text equals the expected JSON.
+///
+/// The values extracted from `input` are compared, in order, with the
+/// values of `expected`, which may hold several values separated by
+/// whitespace. Values are compared as with
+/// [`json_eq`](crate::serde::testing::json_eq). On a mismatch, the panic
+/// message names the first value and the JSON Pointer to the first place
+/// where they differ.
+///
+/// # Examples
+///
+/// ```
+/// use surfing::assert_json_extracted;
+///
+/// assert_json_extracted!(
+///     "user {\"name\":\"Ada\",\"id\":1} tags [\"a\"]",
+///     "{\"id\": 1, \"name\": \"Ada\"} [\"a\"]"
+/// );
+/// ```
+#[macro_export]
+macro_rules! assert_json_extracted {
+    ($input:expr, $expected:expr $(,)?) => {
+        $crate::serde::testing::assert_json_extracted(&$input, &$expected)
+    };
+}
+
+/// Implements [`assert_json_extracted!`](crate::assert_json_extracted).
+///
+/// # Arguments
+///
+/// * `input` - The text to extract from.
+/// * `expected` - The expected values.
+#[doc(hidden)]
+#[track_caller]
+pub fn assert_json_extracted(input: &str, expected: &str) {
+    let mut extracted = Vec::new();
+    if let Err(e) =
+        JSONParser::new().extract_values_from_stream(input, |json| extracted.push(json.to_string()))
+    {
+        panic!("failed to extract JSON: {}", e);
+    }
+
+    let extracted: Vec<Value> = extracted
+        .iter()
+        .enumerate()
+        .map(|(index, json)| {
+            serde_json::from_str(json).unwrap_or_else(|e| {
+                panic!(
+                    "extracted value {} is not valid JSON ({}): {}",
+                    index, e, json
+                )
+            })
+        })
+        .collect();
+    let expected: Vec<Value> = serde_json::Deserializer::from_str(expected)
+        .into_iter()
+        .collect::<Result<_, _>>()
+        .unwrap_or_else(|e| panic!("the expected JSON is not valid: {}", e));
+
+    if extracted.len() != expected.len() {
+        panic!(
+            "extracted {} values, expected {}\n  extracted: {}\n   expected: {}",
+            extracted.len(),
+            expected.len(),
+            join(&extracted),
+            join(&expected)
+        );
+    }
+
+    for (index, (left, right)) in extracted.iter().zip(&expected).enumerate() {
+        if let Some(path) = first_difference(left, right) {
+            panic!(
+                "extracted value {} differs from the expected one at `{}`\n  extracted: {}\n   expected: {}",
+                index,
+                path,
+                describe(left.pointer(&path)),
+                describe(right.pointer(&path))
+            );
+        }
+    }
+}
+
+/// Finds the first place where two values differ.
+///
+/// # Arguments
+///
+/// * `a` - The first value.
+/// * `b` - The second value.
+///
+/// # Returns
+///
+/// The JSON Pointer to the first difference, empty for the values
+/// themselves, or `None` if they are equal.
+fn first_difference(a: &Value, b: &Value) -> Option<String> {
+    let mut path = String::new();
+    difference_at(a, b, &mut path).then_some(path)
+}
+
+/// Returns whether two values differ, leaving the path to the first difference in `path`.
+///
+/// # Arguments
+///
+/// * `a` - The first value.
+/// * `b` - The second value.
+/// * `path` - The JSON Pointer to the values, extended on a difference.
+fn difference_at(a: &Value, b: &Value, path: &mut String) -> bool {
+    match (a, b) {
+        (Value::Object(a), Value::Object(b)) => {
+            for (key, value) in a {
+                let end = path.len();
+                push_token(path, key);
+                match b.get(key) {
+                    Some(other) if !difference_at(value, other, path) => path.truncate(end),
+                    _ => return true,
+                }
+            }
+            // Every key of `a` matched, so any difference is a key only `b` has
+            match b.keys().find(|key| !a.contains_key(*key)) {
+                Some(key) => {
+                    push_token(path, key);
+                    true
+                }
+                None => false,
+            }
+        }
+        (Value::Array(a), Value::Array(b)) => {
+            for (index, (value, other)) in a.iter().zip(b).enumerate() {
+                let end = path.len();
+                push_token(path, &index.to_string());
+                if difference_at(value, other, path) {
+                    return true;
+                }
+                path.truncate(end);
+            }
+            if a.len() != b.len() {
+                push_token(path, &a.len().min(b.len()).to_string());
+                return true;
+            }
+            false
+        }
+        (Value::Number(a), Value::Number(b)) => !numbers_eq(a, b),
+        (a, b) => a != b,
+    }
+}
+
+/// Returns whether two numbers have the same value.
+fn numbers_eq(a: &Number, b: &Number) -> bool {
+    if a.is_f64() || b.is_f64() {
+        return a.as_f64() == b.as_f64();
+    }
+
+    match (a.as_i64(), b.as_i64()) {
+        (Some(a), Some(b)) => a == b,
+        _ => a.as_u64() == b.as_u64(),
+    }
+}
+
+/// Appends a reference token to a JSON Pointer, escaping it.
+fn push_token(path: &mut String, token: &str) {
+    path.push('/');
+    path.push_str(&token.replace('~', "~0").replace('/', "~1"));
+}
+
+/// Describes a value for a panic message.
+fn describe(value: Option<&Value>) -> String {
+    match value {
+        Some(value) => value.to_string(),
+        None => "(missing)".to_string(),
+    }
+}
+
+/// Joins values for a panic message.
+fn join(values: &[Value]) -> String {
+    values
+        .iter()
+        .map(Value::to_string)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn difference(a: &str, b: &str) -> Option<String> {
+        first_difference(
+            &serde_json::from_str(a).unwrap(),
+            &serde_json::from_str(b).unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_json_eq_ignores_order_whitespace_and_number_representation() {
+        assert!(json_eq(
+            "{\"a\":[1,2.5,{\"b\":null}],\"c\":-3}",
+            "{ \"c\": -3.0, \"a\": [ 1e0, 2.5, { \"b\": null } ] }"
+        ));
+        assert!(!json_eq("[1,2]", "[2,1]"));
+        assert!(!json_eq("{\"a\":1}", "{\"a\":1.5}"));
+        assert!(!json_eq("{\"a\":1}", "{\"a\":1"));
+        assert!(!json_eq("[18446744073709551615]", "[-1]"));
+    }
+
+    #[test]
+    fn test_first_difference_paths() {
+        assert_eq!(difference("{\"a\":1}", "{\"a\":1.0}"), None);
+        assert_eq!(
+            difference("{\"a\":{\"b/c\":[1,2]}}", "{\"a\":{\"b/c\":[1,3]}}"),
+            Some("/a/b~1c/1".to_string())
+        );
+        assert_eq!(
+            difference("{\"a\":1}", "{\"a\":1,\"z\":2}"),
+            Some("/z".to_string())
+        );
+        assert_eq!(difference("[1]", "[1,2]"), Some("/1".to_string()));
+        assert_eq!(difference("true", "1"), Some(String::new()));
+    }
+
+    #[test]
+    fn test_assert_json_extracted_passes() {
+        assert_json_extracted!(
+            String::from("log {\"b\":2, \"a\":[1]} then [3.0]"),
+            "{\"a\":[1],\"b\":2}\n[3]"
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "extracted value 1 differs from the expected one at `/x/0`")]
+    fn test_assert_json_extracted_reports_first_difference() {
+        assert_json_extracted!("[1] {\"x\":[2]}", "[1] {\"x\":[3]}");
+    }
+
+    #[test]
+    #[should_panic(expected = "extracted 1 values, expected 2")]
+    fn test_assert_json_extracted_reports_count() {
+        assert_json_extracted!("only [1]", "[1] [2]");
+    }
+}
@@ -0,0 +1,54 @@
+//! Decoding of byte chunks that may split UTF-8 characters.
+
+use std::borrow::Cow;
+
+/// Decodes the pending bytes, keeping an incomplete character at the end back.
+///
+/// Bytes that are not valid UTF-8 are decoded as U+FFFD. Once the input has
+/// ended, an incomplete character at the end is decoded that way too.
+///
+/// # Arguments
+///
+/// * `pending` - The bytes not decoded yet.
+/// * `finished` - Whether the input has ended.
+///
+/// # Returns
+///
+/// The decoded text and the number of bytes it covers, which the caller
+/// removes from `pending` once the text is processed.
+pub(crate) fn decode_pending(pending: &[u8], finished: bool) -> (Cow<'_, str>, usize) {
+    let mut valid = 0;
+
+    let valid = loop {
+        match std::str::from_utf8(&pending[valid..]) {
+            Ok(_) => break pending.len(),
+            // Invalid bytes become U+FFFD, so an incomplete character may still follow
+            Err(e) => match e.error_len() {
+                Some(len) => valid += e.valid_up_to() + len,
+                None if finished => break pending.len(),
+                None => break valid + e.valid_up_to(),
+            },
+        }
+    };
+
+    (String::from_utf8_lossy(&pending[..valid]), valid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_pending_holds_back_split_character() {
+        // "é" is split after its first byte, behind an invalid byte
+        let pending = b"a\xffb\xc3";
+
+        let (text, valid) = decode_pending(pending, false);
+        assert_eq!(text, "a\u{FFFD}b");
+        assert_eq!(valid, 3);
+
+        let (text, valid) = decode_pending(pending, true);
+        assert_eq!(text, "a\u{FFFD}b\u{FFFD}");
+        assert_eq!(valid, 4);
+    }
+}
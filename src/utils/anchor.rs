@@ -0,0 +1,190 @@
+//! Utility functions for extracting JSON anchored to the edges of the input.
+
+use std::fmt;
+use std::ops::Range;
+
+use crate::JSONParser;
+
+/// The number of characters of offending content quoted in error messages,
+/// such as those of an [`AnchorError`] or an [`ExactlyOneError`](crate::utils::ExactlyOneError).
+const PREVIEW_LENGTH: usize = 32;
+
+/// Where the extracted JSON value must sit in the input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Anchor {
+    /// The first value, which must be preceded only by whitespace
+    Start,
+    /// The last value, which must be followed only by whitespace
+    End,
+    /// A single value making up the whole input, apart from surrounding whitespace
+    Whole,
+}
+
+/// Error returned when the input does not satisfy an [`Anchor`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AnchorError {
+    /// The input contains no complete JSON value
+    NoValue,
+    /// Content other than whitespace precedes the value
+    LeadingContent {
+        /// The start of the offending content
+        found: String,
+    },
+    /// Content other than whitespace follows the value
+    TrailingContent {
+        /// The start of the offending content
+        found: String,
+    },
+}
+
+impl fmt::Display for AnchorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AnchorError::NoValue => write!(f, "no complete JSON value found in input"),
+            AnchorError::LeadingContent { found } => write!(
+                f,
+                "expected only whitespace before the JSON value, found {:?}",
+                found
+            ),
+            AnchorError::TrailingContent { found } => write!(
+                f,
+                "expected only whitespace after the JSON value, found {:?}",
+                found
+            ),
+        }
+    }
+}
+
+impl std::error::Error for AnchorError {}
+
+/// Extracts the JSON value anchored to the start, the end or the whole input.
+///
+/// This guards against decoy values: with [`Anchor::End`], a value mentioned
+/// earlier in the prose cannot be taken for the answer that ends the input.
+///
+/// # Arguments
+///
+/// * `input` - The string slice containing mixed text and JSON.
+/// * `anchor` - Where the value must sit in the input.
+///
+/// # Returns
+///
+/// * `Ok(String)` - The anchored JSON value.
+/// * `Err(AnchorError)` - If there is no value, or content other than
+///   whitespace sits between the value and the anchored edge.
+///
+/// # Examples
+///
+/// ```
+/// use surfing::utils::{extract_anchored_json, Anchor, AnchorError};
+///
+/// let input = "Unlike {\"ok\":false}, the answer is:\n{\"ok\":true}\n";
+/// assert_eq!(extract_anchored_json(input, Anchor::End).unwrap(), "{\"ok\":true}");
+///
+/// let error = extract_anchored_json("{\"ok\":true} Hope this helps!", Anchor::End).unwrap_err();
+/// assert_eq!(
+///     error,
+///     AnchorError::TrailingContent { found: "Hope this helps!".to_string() }
+/// );
+/// ```
+pub fn extract_anchored_json(input: &str, anchor: Anchor) -> Result<String, AnchorError> {
+    let mut first: Option<Range<usize>> = None;
+    let mut last: Option<Range<usize>> = None;
+    JSONParser::new().extract_value_spans(input, |span| {
+        first.get_or_insert_with(|| span.clone());
+        last = Some(span);
+    });
+
+    let span = match anchor {
+        Anchor::Start | Anchor::Whole => first,
+        Anchor::End => last,
+    }
+    .ok_or(AnchorError::NoValue)?;
+
+    if anchor != Anchor::End {
+        let leading = input[..span.start].trim();
+        if !leading.is_empty() {
+            return Err(AnchorError::LeadingContent {
+                found: preview(leading),
+            });
+        }
+    }
+
+    if anchor != Anchor::Start {
+        let trailing = input[span.end..].trim();
+        if !trailing.is_empty() {
+            return Err(AnchorError::TrailingContent {
+                found: preview(trailing),
+            });
+        }
+    }
+
+    Ok(input[span].to_string())
+}
+
+/// Shortens offending content for an error message to [`PREVIEW_LENGTH`] characters.
+pub(crate) fn preview(content: &str) -> String {
+    match content.char_indices().nth(PREVIEW_LENGTH) {
+        Some((end, _)) => format!("{}...", &content[..end]),
+        None => content.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_anchored_json_end_skips_decoy() {
+        let input = "Example: {\"id\":0}. Result:\n{\"id\":1}\n\t ";
+        assert_eq!(
+            extract_anchored_json(input, Anchor::End).unwrap(),
+            "{\"id\":1}"
+        );
+    }
+
+    #[test]
+    fn test_extract_anchored_json_start() {
+        assert_eq!(
+            extract_anchored_json("  [1] and then [2]", Anchor::Start).unwrap(),
+            "[1]"
+        );
+        assert_eq!(
+            extract_anchored_json("Sure! [1]", Anchor::Start).unwrap_err(),
+            AnchorError::LeadingContent {
+                found: "Sure!".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn test_extract_anchored_json_whole() {
+        assert_eq!(
+            extract_anchored_json("\n{\"a\":[1]}\n", Anchor::Whole).unwrap(),
+            "{\"a\":[1]}"
+        );
+        assert!(matches!(
+            extract_anchored_json("{\"a\":1} {\"b\":", Anchor::Whole),
+            Err(AnchorError::TrailingContent { found }) if found == "{\"b\":"
+        ));
+    }
+
+    #[test]
+    fn test_extract_anchored_json_errors() {
+        assert_eq!(
+            extract_anchored_json("no json", Anchor::End),
+            Err(AnchorError::NoValue)
+        );
+
+        let error = extract_anchored_json(&format!("[1] {}", "x".repeat(40)), Anchor::End)
+            .unwrap_err()
+            .to_string();
+        assert_eq!(
+            error,
+            format!(
+                "expected only whitespace after the JSON value, found \"{}...\"",
+                "x".repeat(32)
+            )
+        );
+    }
+}
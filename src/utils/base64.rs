@@ -0,0 +1,191 @@
+//! Utility functions for extracting JSON embedded in base64 blobs.
+
+use std::ops::Range;
+
+use crate::parser::grammar::is_valid_json;
+use crate::JSONParser;
+
+/// The shortest run of base64 characters considered, excluding padding.
+pub const MIN_BASE64_RUN: usize = 16;
+
+/// A value decoded from a base64 run.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Base64DecodedValue {
+    /// The decoded JSON
+    pub json: String,
+    /// The byte range of the encoded run in the input, including padding
+    pub span: Range<usize>,
+}
+
+/// Extracts JSON from base64 blobs in text, such as `payload=eyJ1c2VyIjoiYWRhIn0=`.
+///
+/// The text is scanned for runs of at least [`MIN_BASE64_RUN`] base64
+/// characters, from either the standard or the URL-safe alphabet, with
+/// optional `=` padding. Each run is decoded, and only reported if the
+/// decoded bytes are valid UTF-8 and hold at least one complete, strictly
+/// valid JSON value. Every such value is returned, tagged with the span of
+/// the run it came from. Runs must be delimited by characters outside the
+/// base64 alphabet, so a blob glued to a preceding word is not found.
+///
+/// # Arguments
+///
+/// * `input` - The string slice containing text with base64 blobs.
+///
+/// # Returns
+///
+/// The decoded values in order of appearance.
+///
+/// # Examples
+///
+/// ```
+/// use surfing::utils::extract_json_from_base64;
+///
+/// let input = "login ok payload=eyJ1c2VyIjoiYWRhIn0= trace=dGhpcyBpcyBub3QganNvbg==";
+/// let values = extract_json_from_base64(input);
+///
+/// assert_eq!(values.len(), 1);
+/// assert_eq!(values[0].json, "{\"user\":\"ada\"}");
+/// assert_eq!(&input[values[0].span.clone()], "eyJ1c2VyIjoiYWRhIn0=");
+/// ```
+pub fn extract_json_from_base64(input: &str) -> Vec<Base64DecodedValue> {
+    let mut values = Vec::new();
+
+    for span in base64_runs(input.as_bytes()) {
+        let Some(bytes) = decode_base64(&input.as_bytes()[span.clone()]) else {
+            continue;
+        };
+        let Ok(decoded) = String::from_utf8(bytes) else {
+            continue;
+        };
+
+        JSONParser::new().extract_value_spans(&decoded, |range| {
+            let json = &decoded[range];
+            if is_valid_json(json) {
+                values.push(Base64DecodedValue {
+                    json: json.to_string(),
+                    span: span.clone(),
+                });
+            }
+        });
+    }
+
+    values
+}
+
+/// Finds the runs of base64 characters long enough to be considered.
+///
+/// # Arguments
+///
+/// * `bytes` - The text to scan.
+///
+/// # Returns
+///
+/// The byte range of each run, including up to two `=` of padding.
+fn base64_runs(bytes: &[u8]) -> Vec<Range<usize>> {
+    let mut runs = Vec::new();
+    let mut position = 0;
+
+    while position < bytes.len() {
+        if sextet(bytes[position]).is_none() {
+            position += 1;
+            continue;
+        }
+
+        let start = position;
+        while position < bytes.len() && sextet(bytes[position]).is_some() {
+            position += 1;
+        }
+        let length = position - start;
+
+        let padding = bytes[position..]
+            .iter()
+            .take(2)
+            .take_while(|&&byte| byte == b'=')
+            .count();
+        position += padding;
+
+        if length >= MIN_BASE64_RUN {
+            runs.push(start..position);
+        }
+    }
+
+    runs
+}
+
+/// Decodes base64 text, with or without padding.
+///
+/// # Arguments
+///
+/// * `encoded` - The base64 text.
+///
+/// # Returns
+///
+/// The decoded bytes, or `None` if the text is not valid base64.
+fn decode_base64(encoded: &[u8]) -> Option<Vec<u8>> {
+    let data = encoded.strip_suffix(b"=").unwrap_or(encoded);
+    let data = data.strip_suffix(b"=").unwrap_or(data);
+
+    // A single character left over cannot hold a whole byte
+    if data.len() % 4 == 1 {
+        return None;
+    }
+
+    let mut decoded = Vec::with_capacity(data.len() * 3 / 4);
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+
+    for &byte in data {
+        buffer = (buffer << 6) | u32::from(sextet(byte)?);
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            decoded.push((buffer >> bits) as u8);
+            buffer &= (1 << bits) - 1;
+        }
+    }
+
+    Some(decoded)
+}
+
+/// Returns the 6-bit value of a base64 character from either alphabet.
+fn sextet(byte: u8) -> Option<u8> {
+    match byte {
+        b'A'..=b'Z' => Some(byte - b'A'),
+        b'a'..=b'z' => Some(byte - b'a' + 26),
+        b'0'..=b'9' => Some(byte - b'0' + 52),
+        b'+' | b'-' => Some(62),
+        b'/' | b'_' => Some(63),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_base64() {
+        assert_eq!(decode_base64(b"aGVsbG8=").unwrap(), b"hello");
+        assert_eq!(decode_base64(b"aGVsbG8").unwrap(), b"hello");
+        assert_eq!(decode_base64(b"-_8").unwrap(), [0xFB, 0xFF]);
+        assert!(decode_base64(b"aGVsb").is_none());
+    }
+
+    #[test]
+    fn test_extract_json_from_base64_spans_and_false_positives() {
+        // got {"a":1} and [2] end, then URL-safe {"k":"???>>>"} without padding
+        let input = "a=Z290IHsiYSI6MX0gYW5kIFsyXSBlbmQ=, b=eyJrIjoiPz8_Pj4-In0 \
+                     path=/usr/local/share/applications/x id=AAAAAAAAAAAAAAAAAAAA \
+                     short=e30= broken=eyJ1c2VyIjoiYWRh";
+        let values = extract_json_from_base64(input);
+
+        let json: Vec<&str> = values.iter().map(|value| value.json.as_str()).collect();
+        assert_eq!(json, vec!["{\"a\":1}", "[2]", "{\"k\":\"???>>>\"}"]);
+        assert_eq!(values[0].span, values[1].span);
+        assert_eq!(
+            &input[values[0].span.clone()],
+            "Z290IHsiYSI6MX0gYW5kIFsyXSBlbmQ="
+        );
+        assert_eq!(&input[values[2].span.clone()], "eyJrIjoiPz8_Pj4-In0");
+    }
+}
@@ -0,0 +1,378 @@
+//! Utility functions for extracting JSON from files with a report of what was found.
+
+use std::fs::File;
+use std::io::{ErrorKind, Read};
+use std::ops::Range;
+use std::path::Path;
+
+use crate::parser::options::ParserOptions;
+use crate::utils::stdio::ExtractError;
+use crate::utils::warnings::{CandidateEvent, CandidateScan};
+
+/// The number of bytes read from the file at a time.
+const FILE_BLOCK_SIZE: usize = 64 * 1024;
+
+/// A value found in a file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileValue {
+    /// The extracted JSON, or `None` if it was handed to a callback instead
+    pub json: Option<String>,
+    /// The byte range of the value in the file
+    pub span: Range<u64>,
+    /// The line the value starts on, counting from 1
+    pub line: usize,
+}
+
+/// What was found while extracting JSON from a file.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FileReport {
+    /// The valid values, in order of appearance
+    pub values: Vec<FileValue>,
+    /// The number of values dropped before completing, such as at a record
+    /// separator, past a length or depth limit, or because they broke the
+    /// grammar under [`ParserOptions::strict`]
+    pub skipped: usize,
+    /// The number of candidates left out because they are not valid JSON
+    pub invalid: usize,
+    /// Whether the file ended in the middle of a value
+    pub ended_mid_value: bool,
+}
+
+/// Extracts the JSON values of a file, reporting where they are and what was left out.
+///
+/// The file is streamed in blocks, so it is never read into memory as a
+/// whole. Bytes that are not valid UTF-8 are processed as U+FFFD, which
+/// drops them outside of JSON. The options are applied as with
+/// [`JSONParser::with_options`](crate::JSONParser::with_options), and every
+/// extracted value is checked to be strictly valid JSON: values that are not
+/// are counted in [`invalid`](FileReport::invalid) instead of being reported.
+///
+/// # Arguments
+///
+/// * `path` - The path of the file to read.
+/// * `options` - The options the parser is created with.
+///
+/// # Returns
+///
+/// * `Ok(FileReport)` - The values found and what was left out.
+/// * `Err(ExtractError)` - If the file could not be read or the parser rejected it.
+///
+/// # Examples
+///
+/// ```no_run
+/// use surfing::parser::options::ParserOptions;
+/// use surfing::utils::process_file;
+///
+/// let report = process_file("audit.log", ParserOptions::new()).unwrap();
+/// for value in &report.values {
+///     println!("line {}: {}", value.line, value.json.as_deref().unwrap_or_default());
+/// }
+/// eprintln!("{} invalid, {} skipped", report.invalid, report.skipped);
+/// ```
+pub fn process_file<P: AsRef<Path>>(
+    path: P,
+    options: ParserOptions,
+) -> Result<FileReport, ExtractError> {
+    let file = File::open(path).map_err(ExtractError::Read)?;
+    scan_reader(file, options, None)
+}
+
+/// Extracts the JSON values of a file, handing each one to a callback.
+///
+/// This behaves like [`process_file`], but the values are passed to
+/// `values_to` as they complete instead of being collected, so the report
+/// only holds their spans and lines and its [`json`](FileValue::json) fields
+/// are `None`.
+///
+/// # Arguments
+///
+/// * `path` - The path of the file to read.
+/// * `options` - The options the parser is created with.
+/// * `values_to` - The callback receiving each valid value.
+///
+/// # Returns
+///
+/// * `Ok(FileReport)` - The spans of the values found and what was left out.
+/// * `Err(ExtractError)` - If the file could not be read or the parser rejected it.
+///
+/// # Examples
+///
+/// ```no_run
+/// use surfing::parser::options::ParserOptions;
+/// use surfing::utils::process_file_values_to;
+///
+/// let mut count = 0;
+/// let report =
+///     process_file_values_to("audit.log", ParserOptions::new(), |_json| count += 1).unwrap();
+/// assert_eq!(report.values.len(), count);
+/// ```
+pub fn process_file_values_to<P, F>(
+    path: P,
+    options: ParserOptions,
+    mut values_to: F,
+) -> Result<FileReport, ExtractError>
+where
+    P: AsRef<Path>,
+    F: FnMut(&str),
+{
+    let file = File::open(path).map_err(ExtractError::Read)?;
+    scan_reader(file, options, Some(&mut values_to))
+}
+
+/// Extracts the JSON values of a reader into a report.
+///
+/// # Arguments
+///
+/// * `reader` - The reader supplying the bytes.
+/// * `options` - The options the parser is created with.
+/// * `values_to` - The callback receiving each valid value, if any.
+fn scan_reader<R: Read>(
+    mut reader: R,
+    options: ParserOptions,
+    values_to: Option<&mut dyn FnMut(&str)>,
+) -> Result<FileReport, ExtractError> {
+    let mut scan = FileScan {
+        scan: CandidateScan::new(options),
+        values_to,
+        report: FileReport::default(),
+        offset: 0,
+        line: 1,
+    };
+    let mut block = vec![0; FILE_BLOCK_SIZE];
+    let mut filled = 0;
+
+    loop {
+        let read = match reader.read(&mut block[filled..]) {
+            Ok(read) => read,
+            Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+            Err(e) => return Err(ExtractError::Read(e)),
+        };
+        filled += read;
+
+        let consumed = scan.push_bytes(&block[..filled], read == 0)?;
+        if read == 0 {
+            break;
+        }
+
+        block.copy_within(consumed..filled, 0);
+        filled -= consumed;
+    }
+
+    scan.report.ended_mid_value = scan.scan.is_in_json();
+    Ok(scan.report)
+}
+
+/// The state of an extraction from a file.
+struct FileScan<'a> {
+    /// The scan of the values, which start at an offset and line
+    scan: CandidateScan<(u64, usize)>,
+    values_to: Option<&'a mut dyn FnMut(&str)>,
+    report: FileReport,
+    /// The offset of the next byte in the file
+    offset: u64,
+    /// The line of the next byte in the file
+    line: usize,
+}
+
+impl FileScan<'_> {
+    /// Processes a block of bytes, returning how many were consumed.
+    ///
+    /// A trailing incomplete UTF-8 sequence is left for the next block,
+    /// unless `last` is set.
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes` - The bytes available.
+    /// * `last` - Whether no more bytes follow.
+    fn push_bytes(&mut self, bytes: &[u8], last: bool) -> Result<usize, ExtractError> {
+        let mut position = 0;
+
+        while position < bytes.len() {
+            let (text, invalid) = match std::str::from_utf8(&bytes[position..]) {
+                Ok(text) => (text, None),
+                Err(e) => {
+                    let valid = &bytes[position..position + e.valid_up_to()];
+                    let text = std::str::from_utf8(valid).unwrap_or_default();
+                    match e.error_len() {
+                        Some(len) => (text, Some(len)),
+                        None if last => (text, Some(bytes.len() - position - valid.len())),
+                        None => (text, None),
+                    }
+                }
+            };
+
+            for item in text.chars() {
+                self.push_char(item, item.len_utf8())?;
+            }
+            position += text.len();
+
+            match invalid {
+                Some(len) => {
+                    self.push_char(char::REPLACEMENT_CHARACTER, len)?;
+                    position += len;
+                }
+                None => break,
+            }
+        }
+
+        Ok(position)
+    }
+
+    /// Processes a character taking `len` bytes of the file.
+    ///
+    /// # Arguments
+    ///
+    /// * `item` - The character to process.
+    /// * `len` - The number of bytes it takes in the file.
+    fn push_char(&mut self, item: char, len: usize) -> Result<(), ExtractError> {
+        let event = self
+            .scan
+            .push_char(item, (self.offset, self.line))
+            .map_err(ExtractError::Parse)?;
+
+        let end = self.offset + len as u64;
+        match event {
+            CandidateEvent::Pending => {}
+            CandidateEvent::Abandoned { .. } => self.report.skipped += 1,
+            CandidateEvent::Invalid { .. } => self.report.invalid += 1,
+            CandidateEvent::Valid {
+                start: (start, line),
+                json,
+            } => self.record_value(json, start..end, line),
+        }
+
+        self.offset = end;
+        if item == '\n' {
+            self.line += 1;
+        }
+        Ok(())
+    }
+
+    /// Records a valid value, handing it to the callback if there is one.
+    ///
+    /// # Arguments
+    ///
+    /// * `json` - The extracted JSON.
+    /// * `span` - The byte range of the value in the file.
+    /// * `line` - The line the value starts on.
+    fn record_value(&mut self, json: String, span: Range<u64>, line: usize) {
+        let json = match self.values_to.as_mut() {
+            Some(values_to) => {
+                values_to(&json);
+                None
+            }
+            None => Some(json),
+        };
+        self.report.values.push(FileValue { json, span, line });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A reader handing out one piece per read.
+    struct PieceReader(Vec<&'static [u8]>);
+
+    impl Read for PieceReader {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            if self.0.is_empty() {
+                return Ok(0);
+            }
+            let piece = self.0.remove(0);
+            buf[..piece.len()].copy_from_slice(piece);
+            Ok(piece.len())
+        }
+    }
+
+    #[test]
+    fn test_scan_reader_reports_values_and_problems() {
+        let reader = PieceReader(vec![
+            b"start \xff\xfe {\"a\":",
+            b" \"\xc3",
+            b"\xa9\"}\nbad {\"b\":1,}\n",
+            b"[1, 2] then {\"cut\": 1\x1e [3",
+        ]);
+        let options = ParserOptions::new().record_separator(0x1E);
+
+        let report = scan_reader(reader, options, None).unwrap();
+
+        assert_eq!(
+            report.values,
+            vec![
+                FileValue {
+                    json: Some("{\"a\": \"é\"}".to_string()),
+                    span: 9..20,
+                    line: 1,
+                },
+                FileValue {
+                    json: Some("[1, 2]".to_string()),
+                    span: 34..40,
+                    line: 3,
+                },
+            ]
+        );
+        assert_eq!(report.invalid, 1);
+        assert_eq!(report.skipped, 1);
+        assert!(report.ended_mid_value);
+    }
+
+    #[test]
+    fn test_scan_reader_recovers_after_corrupt_values() {
+        let reader = PieceReader(vec![
+            b"bad {\"a\": 1,,} ok {\"b\": ",
+            b"2}\nbad {\"x\" {\"c\": [3]}} end",
+        ]);
+
+        let report = scan_reader(reader, ParserOptions::new().strict(true), None).unwrap();
+
+        let values: Vec<_> = report
+            .values
+            .iter()
+            .map(|value| (value.json.as_deref().unwrap(), value.span.clone()))
+            .collect();
+        assert_eq!(
+            values,
+            vec![("{\"b\": 2}", 18..26), ("{\"c\": [3]}", 36..46)]
+        );
+        assert_eq!(report.skipped, 2);
+        assert_eq!(report.invalid, 0);
+        assert!(!report.ended_mid_value);
+    }
+
+    #[test]
+    fn test_scan_reader_skips_values_past_depth_limit() {
+        let deep = format!("{}{}", "[".repeat(10_000), "]".repeat(10_000));
+        let input = format!("{} {{\"a\":{{\"b\":[1]}}}}", deep);
+        let reader = input.as_bytes();
+
+        let report = scan_reader(reader, ParserOptions::new().max_depth(3), None).unwrap();
+
+        assert_eq!(report.values.len(), 1);
+        assert_eq!(
+            report.values[0].json.as_deref(),
+            Some("{\"a\":{\"b\":[1]}}")
+        );
+        assert_eq!(report.skipped, 1);
+        assert!(!report.ended_mid_value);
+    }
+
+    #[test]
+    fn test_process_file_values_to() {
+        let path = std::env::temp_dir().join(format!("surfing-file-{}.log", std::process::id()));
+        std::fs::write(&path, b"a {\"x\":1}\nb [2]\xff").unwrap();
+
+        let mut values = Vec::new();
+        let report = process_file_values_to(&path, ParserOptions::new(), |json| {
+            values.push(json.to_string())
+        })
+        .unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(values, vec!["{\"x\":1}", "[2]"]);
+        assert_eq!(report.values[1].json, None);
+        assert_eq!(report.values[1].span, 12..15);
+        assert_eq!(report.values[1].line, 2);
+        assert!(!report.ended_mid_value);
+    }
+}
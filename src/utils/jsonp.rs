@@ -0,0 +1,152 @@
+//! Utility functions for extracting JSON from JSONP responses.
+
+use std::ops::Range;
+
+use crate::JSONParser;
+
+/// A value extracted from text that may hold JSONP calls.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JsonpValue {
+    /// The name of the function the value is passed to, or `None` for a
+    /// value outside of a JSONP call
+    pub callback: Option<String>,
+    /// The extracted JSON
+    pub json: String,
+    /// The byte range of the value in the input
+    pub span: Range<usize>,
+}
+
+/// Extracts JSON from JSONP responses such as `callback({"a":1});`, reporting the callback.
+///
+/// A value is taken as an argument of a JSONP call when it follows a
+/// function name and `(`, or a `,` after another argument of the call, and
+/// is followed by `,` or `)`. The name may be dotted, as in
+/// `jQuery.cb_1`. Every value of every call is reported with the name of
+/// its function; any other value is reported as in normal extraction, with
+/// no callback. Parentheses inside strings do not affect the detection.
+///
+/// # Arguments
+///
+/// * `input` - The string slice containing JSONP calls or mixed text.
+///
+/// # Returns
+///
+/// The values in order of appearance, with their callbacks and spans.
+///
+/// # Examples
+///
+/// ```
+/// use surfing::utils::extract_jsonp;
+///
+/// let values = extract_jsonp("handle({\"id\":1}, [2]); other({\"id\":3})");
+///
+/// assert_eq!(values[0].callback.as_deref(), Some("handle"));
+/// assert_eq!(values[1].json, "[2]");
+/// assert_eq!(values[1].callback.as_deref(), Some("handle"));
+/// assert_eq!(values[2].callback.as_deref(), Some("other"));
+/// ```
+pub fn extract_jsonp(input: &str) -> Vec<JsonpValue> {
+    let mut values = Vec::new();
+    // The callback of the call whose arguments are being read, and where the last one ended
+    let mut open_call: Option<(String, usize)> = None;
+
+    JSONParser::new().extract_value_spans(input, |span| {
+        let callback = match open_call.take() {
+            Some((callback, end)) if input[end..span.start].trim() == "," => Some(callback),
+            _ => callback_before(&input[..span.start]),
+        };
+
+        let rest = input[span.end..].trim_start();
+        let callback = callback.filter(|_| rest.starts_with(',') || rest.starts_with(')'));
+        if let Some(callback) = callback.as_ref().filter(|_| rest.starts_with(',')) {
+            open_call = Some((callback.clone(), span.end));
+        }
+
+        values.push(JsonpValue {
+            callback,
+            json: input[span.clone()].to_string(),
+            span,
+        });
+    });
+
+    values
+}
+
+/// Finds the function name and opening parenthesis that text ends with.
+///
+/// # Arguments
+///
+/// * `text` - The text before a value.
+///
+/// # Returns
+///
+/// The function name, or `None` if the text does not end with a call.
+fn callback_before(text: &str) -> Option<String> {
+    let text = text.trim_end().strip_suffix('(')?.trim_end();
+    let start = text
+        .char_indices()
+        .rev()
+        .take_while(|(_, item)| is_name_char(*item))
+        .last()?
+        .0;
+    let name = &text[start..];
+
+    let valid = !name.starts_with(|item: char| item.is_ascii_digit() || item == '.')
+        && !name.ends_with('.');
+    valid.then(|| name.to_string())
+}
+
+/// Checks if a character can be part of a dotted function name.
+fn is_name_char(item: char) -> bool {
+    item.is_alphanumeric() || matches!(item, '_' | '$' | '.')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn callbacks(values: &[JsonpValue]) -> Vec<(Option<&str>, &str)> {
+        values
+            .iter()
+            .map(|value| (value.callback.as_deref(), value.json.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn test_extract_jsonp_calls_and_fallback() {
+        let input = "/**/ jQuery.cb_1({\"a\":1});\n\
+                     log [0] text\n\
+                     $done ( [\"x\"] ,\n {\"b\":2} )\n\
+                     broken({\"c\":3} garbage) 2({\"d\":4})";
+        let values = extract_jsonp(input);
+
+        assert_eq!(
+            callbacks(&values),
+            vec![
+                (Some("jQuery.cb_1"), "{\"a\":1}"),
+                (None, "[0]"),
+                (Some("$done"), "[\"x\"]"),
+                (Some("$done"), "{\"b\":2}"),
+                (None, "{\"c\":3}"),
+                (None, "{\"d\":4}"),
+            ]
+        );
+        assert_eq!(&input[values[3].span.clone()], "{\"b\":2}");
+    }
+
+    #[test]
+    fn test_extract_jsonp_parentheses_inside_strings() {
+        let input = "cb({\"msg\":\"smile :) (or not\"}, [\")\", \"(\"]); \
+                     next({\"f\":\"g(\"});";
+        let values = extract_jsonp(input);
+
+        assert_eq!(
+            callbacks(&values),
+            vec![
+                (Some("cb"), "{\"msg\":\"smile :) (or not\"}"),
+                (Some("cb"), "[\")\", \"(\"]"),
+                (Some("next"), "{\"f\":\"g(\"}"),
+            ]
+        );
+    }
+}
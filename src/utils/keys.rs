@@ -0,0 +1,175 @@
+//! Utility functions for listing the top-level keys of extracted JSON.
+
+use crate::parser::escape::{read_unit, unescape_sequence, Unescaped, Unit};
+use crate::parser::string_scanner::StringScanner;
+use crate::JSONParser;
+
+/// Lists the top-level keys of every value extracted from a string.
+///
+/// Each value is scanned structurally once it is found: keys are recorded
+/// at depth 1 and everything nested is skipped without being parsed, so
+/// no `serde_json::Value` is built. Keys are unescaped and listed in order
+/// of appearance, duplicates included. An extracted array yields an empty
+/// key list, or no list at all if `skip_arrays` is set.
+///
+/// # Arguments
+///
+/// * `input` - The string slice containing the JSON values.
+/// * `skip_arrays` - Whether arrays are left out instead of yielding an empty list.
+///
+/// # Returns
+///
+/// One key list per extracted value, in order of appearance.
+///
+/// # Examples
+///
+/// ```
+/// use surfing::utils::extract_top_level_keys;
+///
+/// let input = "route {\"user\":{\"id\":1},\"event\":\"login\"} then [1] {\"ping\":true}";
+///
+/// assert_eq!(
+///     extract_top_level_keys(input, false),
+///     vec![vec!["user", "event"], vec![], vec!["ping"]]
+/// );
+/// assert_eq!(extract_top_level_keys(input, true).len(), 2);
+/// ```
+pub fn extract_top_level_keys(input: &str, skip_arrays: bool) -> Vec<Vec<String>> {
+    let mut keys = Vec::new();
+
+    JSONParser::new().extract_value_spans(input, |span| {
+        let value = &input[span];
+        if value.starts_with('{') {
+            keys.push(object_keys(value));
+        } else if !skip_arrays {
+            keys.push(Vec::new());
+        }
+    });
+
+    keys
+}
+
+/// Collects the keys at depth 1 of a complete JSON object.
+///
+/// # Arguments
+///
+/// * `object` - The text of the object, from `{` to `}`.
+///
+/// # Returns
+///
+/// The unescaped keys of the object.
+fn object_keys(object: &str) -> Vec<String> {
+    let mut keys = Vec::new();
+    let mut scanner = StringScanner::new();
+    let mut depth = 0usize;
+    // The start of the string being scanned, if inside one
+    let mut string_start: Option<usize> = None;
+    // Whether the next string at depth 1 is a key rather than a value
+    let mut expecting_key = false;
+
+    for (offset, item) in object.char_indices() {
+        if let Some(start) = string_start {
+            if scanner.push(item) {
+                string_start = None;
+                if depth == 1 && expecting_key {
+                    keys.push(unescape(&object[start..offset]));
+                    expecting_key = false;
+                }
+            }
+            continue;
+        }
+
+        match item {
+            '"' => string_start = Some(offset + 1),
+            '{' | '[' => {
+                depth += 1;
+                expecting_key = depth == 1;
+            }
+            '}' | ']' => depth = depth.saturating_sub(1),
+            ',' if depth == 1 => expecting_key = true,
+            _ => {}
+        }
+    }
+
+    keys
+}
+
+/// Decodes the escape sequences of the inside of a JSON string.
+///
+/// Lone surrogates and malformed `\u` escapes are replaced with U+FFFD.
+fn unescape(text: &str) -> String {
+    let mut output = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = rest.find('\\') {
+        output.push_str(&rest[..start]);
+        let (item, len) = decode_escape(&rest[start..]);
+        output.push(item);
+        rest = &rest[start + len..];
+    }
+
+    output.push_str(rest);
+    output
+}
+
+/// Decodes the escape sequence at the start of the text, returning the character and its length.
+fn decode_escape(text: &str) -> (char, usize) {
+    match unescape_sequence(text) {
+        // A character past the BMP comes from the escape of a surrogate pair
+        Unescaped::Char(item) => (item, 6 * item.len_utf16()),
+        Unescaped::LoneSurrogate => ('\u{FFFD}', 6),
+        Unescaped::Incomplete | Unescaped::Verbatim => {
+            // Characters JSON keeps escaped are still decoded in a key
+            if let Unit::Complete(unit) = read_unit(text) {
+                return (char::from_u32(unit).unwrap_or('\u{FFFD}'), 6);
+            }
+
+            let item = match text[1..].chars().next() {
+                Some('b') => '\u{8}',
+                Some('f') => '\u{c}',
+                Some('n') => '\n',
+                Some('r') => '\r',
+                Some('t') => '\t',
+                Some('u') => return ('\u{FFFD}', 2),
+                Some(other) => other,
+                None => return ('\\', 1),
+            };
+            (item, 1 + item.len_utf8())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_top_level_keys_skips_nested_values() {
+        let input = "a {\"id\":1,\"meta\":{\"id\":2,\"x\":[{\"y\":3}]},\"tags\":[\"k\",\"v\"],\
+                     \"s\":\"{\\\"fake\\\":1,\",\"last\":null} b [{\"in\":1}] c {}";
+
+        assert_eq!(
+            extract_top_level_keys(input, false),
+            vec![
+                vec!["id", "meta", "tags", "s", "last"],
+                Vec::new(),
+                Vec::new()
+            ]
+        );
+        assert_eq!(
+            extract_top_level_keys(input, true),
+            vec![vec!["id", "meta", "tags", "s", "last"], Vec::new()]
+        );
+    }
+
+    #[test]
+    fn test_extract_top_level_keys_unescapes_keys() {
+        let input =
+            r#"{"a\"b":1, "é\n":2, "\ud83d\ude00":3, "\ud800x":4, "k\\":5, "\u0022\u00e9":6}"#;
+
+        assert_eq!(
+            extract_top_level_keys(input, false),
+            vec![vec!["a\"b", "é\n", "😀", "\u{FFFD}x", "k\\", "\"é"]]
+        );
+    }
+}
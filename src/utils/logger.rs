@@ -0,0 +1,189 @@
+//! Utility functions for extracting JSON from structured logger output.
+//!
+//! Structured loggers such as `tracing` and `slog` write lines of
+//! logfmt-style `key=value` pairs, for example:
+//!
+//! ```text
+//! 2024-05-01T12:00:00Z INFO app: level=info msg="request done" fields={"status":200}
+//! ```
+//!
+//! Two value conventions are supported:
+//!
+//! * Bare values: the JSON follows the `=` directly, as `fields={"a":1}`.
+//!   The value ends where the JSON value closes, so it may contain spaces.
+//! * Double-quoted values: the JSON is inside a quoted string, as
+//!   `fields="{\"a\":1}"`. The escapes written by logfmt and by Rust's
+//!   `Debug` formatting are undone: `\"`, `\\`, `\'`, `\n`, `\r`, `\t`, `\0`
+//!   and `\u{...}`. Other escapes are kept as written. The quoted string
+//!   must hold a single JSON value and nothing else.
+//!
+//! Keys are matched exactly. Single-quoted values and values spanning
+//! several lines are not supported.
+
+use crate::utils::anchor::{extract_anchored_json, Anchor};
+use crate::JSONParser;
+
+/// Extracts the JSON held by every occurrence of a named field in structured log output.
+///
+/// The input is read line by line as `key=value` pairs, following the
+/// conventions described in the [module documentation](self). Occurrences
+/// whose value is not JSON are skipped, and text inside the quoted value of
+/// another field is never taken for a field.
+///
+/// # Arguments
+///
+/// * `input` - The log output, one record per line.
+/// * `field` - The name of the field holding JSON.
+///
+/// # Returns
+///
+/// The JSON value of each occurrence, in order of appearance.
+///
+/// # Examples
+///
+/// ```
+/// use surfing::utils::extract_logger_field;
+///
+/// let log = "2024-05-01T12:00:00Z INFO api: level=info msg=\"done\" fields={\"a\":1}\n\
+///            2024-05-01T12:00:01Z WARN api: level=warn fields=\"{\\\"b\\\":[2]}\"";
+///
+/// assert_eq!(extract_logger_field(log, "fields"), vec!["{\"a\":1}", "{\"b\":[2]}"]);
+/// ```
+pub fn extract_logger_field(input: &str, field: &str) -> Vec<String> {
+    let mut values = Vec::new();
+
+    for line in input.lines() {
+        let mut rest = line;
+
+        loop {
+            rest = rest.trim_start();
+            if rest.is_empty() {
+                break;
+            }
+
+            let key_end = rest
+                .find(|c: char| c == '=' || c.is_whitespace())
+                .unwrap_or(rest.len());
+            let key = &rest[..key_end];
+            rest = &rest[key_end..];
+
+            // A word without a value, such as the level or target
+            let Some(value) = rest.strip_prefix('=') else {
+                continue;
+            };
+
+            let (json, length) = read_value(value);
+            if key == field {
+                values.extend(json);
+            }
+            rest = &value[length..];
+        }
+    }
+
+    values
+}
+
+/// Reads the value of a field, returning its JSON if it holds any and the length it spans.
+///
+/// # Arguments
+///
+/// * `value` - The text following the `=` of the field.
+fn read_value(value: &str) -> (Option<String>, usize) {
+    if let Some(quoted) = value.strip_prefix('"') {
+        return match unquote(quoted) {
+            Some((content, length)) => (
+                extract_anchored_json(&content, Anchor::Whole).ok(),
+                length + 1,
+            ),
+            None => (None, value.len()),
+        };
+    }
+
+    if value.starts_with(['{', '[']) {
+        let mut first = None;
+        JSONParser::new().extract_value_spans(value, |span| {
+            first.get_or_insert(span);
+        });
+
+        if let Some(span) = first.filter(|span| span.start == 0) {
+            return (Some(value[span.clone()].to_string()), span.end);
+        }
+    }
+
+    let end = value.find(char::is_whitespace).unwrap_or(value.len());
+    (None, end)
+}
+
+/// Undoes the escapes of a quoted value.
+///
+/// # Arguments
+///
+/// * `quoted` - The text following the opening quote.
+///
+/// # Returns
+///
+/// * `Some((String, usize))` - The content and the length up to and including the closing quote.
+/// * `None` - If the closing quote is missing.
+fn unquote(quoted: &str) -> Option<(String, usize)> {
+    let mut content = String::new();
+    let mut chars = quoted.char_indices();
+
+    while let Some((position, item)) = chars.next() {
+        match item {
+            '"' => return Some((content, position + 1)),
+            '\\' => {
+                let (_, escaped) = chars.next()?;
+                match escaped {
+                    'n' => content.push('\n'),
+                    'r' => content.push('\r'),
+                    't' => content.push('\t'),
+                    '0' => content.push('\0'),
+                    '"' | '\\' | '\'' => content.push(escaped),
+                    'u' if chars.as_str().starts_with('{') => {
+                        let digits = &chars.as_str()[1..];
+                        let end = digits.find('}')?;
+                        let decoded = u32::from_str_radix(&digits[..end], 16)
+                            .ok()
+                            .and_then(char::from_u32)?;
+                        content.push(decoded);
+                        chars.nth(end + 1);
+                    }
+                    _ => {
+                        content.push('\\');
+                        content.push(escaped);
+                    }
+                }
+            }
+            _ => content.push(item),
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_logger_field_tracing_line() {
+        let line = "2024-05-01T12:00:00.000Z  INFO request{id=7}: api::handler: msg=\"handled\" fields={\"a\":1} elapsed=3ms";
+        assert_eq!(extract_logger_field(line, "fields"), vec!["{\"a\":1}"]);
+    }
+
+    #[test]
+    fn test_extract_logger_field_quoted_debug_escapes() {
+        let line = r#"level=info payload="{\"text\":\"line\\nbreak\",\"e\":\"\u{e9}\"}" other="{\"x\":1}""#;
+        assert_eq!(
+            extract_logger_field(line, "payload"),
+            vec!["{\"text\":\"line\\nbreak\",\"e\":\"é\"}"]
+        );
+    }
+
+    #[test]
+    fn test_extract_logger_field_skips_non_json_and_nested_text() {
+        let log = "level=info msg=\"see fields={\\\"fake\\\":1}\" fields=none\n\
+                   level=info fields=[1, 2] fieldsx={\"no\":0}";
+        assert_eq!(extract_logger_field(log, "fields"), vec!["[1, 2]"]);
+    }
+}
@@ -1,5 +1,31 @@
 //! Utility functions for the surfing library.
 
+pub mod anchor;
+pub mod base64;
+pub mod file;
+pub mod jsonp;
+pub mod keys;
+pub mod logger;
+pub mod os_str;
+pub mod percent;
+pub mod single;
+pub mod stdio;
 pub mod string_extract;
+pub mod warnings;
 
-pub use string_extract::extract_json_to_string;
+pub use anchor::{extract_anchored_json, Anchor, AnchorError};
+pub use base64::{extract_json_from_base64, Base64DecodedValue, MIN_BASE64_RUN};
+pub use file::{process_file, process_file_values_to, FileReport, FileValue};
+pub use jsonp::{extract_jsonp, JsonpValue};
+pub use keys::extract_top_level_keys;
+pub use logger::extract_logger_field;
+pub use os_str::{extract_json_from_bytes, extract_json_from_os_str};
+pub use percent::{extract_json_percent_decoded, PercentDecodedValue};
+pub use single::{extract_exactly_one_json, ExactlyOneError};
+pub use stdio::{extract_json_from_stdin_to_stdout, ExtractError, ExtractSummary};
+pub use string_extract::{
+    extract_first_json, extract_in_range, extract_json_arcs, extract_json_objects,
+    extract_json_objects_rev, extract_json_spans, extract_json_to_cow, extract_json_to_string,
+    extract_largest_json_to_string, extract_last_json, extract_with_max_depth,
+};
+pub use warnings::{extract_json_with_warnings, Warning, WarningKind};
@@ -0,0 +1,108 @@
+//! Utility functions for extracting JSON from bytes that may not be UTF-8.
+
+use std::ffi::OsStr;
+
+use crate::parser::error::ParseError;
+use crate::JSONParser;
+
+/// Extracts JSON from arbitrary bytes and returns the result as a String.
+///
+/// The bytes go through [`JSONParser::consume_from`], so valid UTF-8 is
+/// processed as is and every invalid sequence becomes U+FFFD. An incomplete
+/// sequence at the very end is replaced the same way. Invalid bytes outside
+/// JSON are simply skipped, and those inside a value show up as U+FFFD in
+/// the output, so no input makes this panic.
+///
+/// # Arguments
+///
+/// * `input` - The bytes containing mixed text and JSON.
+///
+/// # Returns
+///
+/// * `Ok(String)` - The extracted JSON as a String.
+/// * `Err(ParseError)` - If an error occurred during parsing.
+///
+/// # Examples
+///
+/// ```
+/// use surfing::utils::extract_json_from_bytes;
+///
+/// let input = b"\xff\xfe report {\"ok\":true} \xc3";
+/// assert_eq!(extract_json_from_bytes(input).unwrap(), "{\"ok\":true}");
+/// ```
+pub fn extract_json_from_bytes(input: &[u8]) -> Result<String, ParseError> {
+    let mut parser = JSONParser::new();
+    let mut buffer = Vec::new();
+
+    let consumed = parser.consume_from(input, &mut buffer)?;
+    if consumed < input.len() {
+        let rest = String::from_utf8_lossy(&input[consumed..]);
+        parser.extract_json_from_stream(&mut buffer, &rest)?;
+    }
+
+    // Every character written came from UTF-8 text
+    String::from_utf8(buffer)
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e).into())
+}
+
+/// Extracts JSON from an OS string, such as a file name or an environment variable.
+///
+/// OS strings need not be valid UTF-8: on Unix they are arbitrary bytes and
+/// on Windows they may hold unpaired surrogates. The string is read through
+/// [`OsStr::as_encoded_bytes`] and handled by [`extract_json_from_bytes`],
+/// so anything that is not valid UTF-8 is replaced with U+FFFD instead of
+/// failing the extraction.
+///
+/// # Arguments
+///
+/// * `input` - The OS string containing mixed text and JSON.
+///
+/// # Returns
+///
+/// * `Ok(String)` - The extracted JSON as a String.
+/// * `Err(ParseError)` - If an error occurred during parsing.
+///
+/// # Examples
+///
+/// ```
+/// use std::path::Path;
+/// use surfing::utils::extract_json_from_os_str;
+///
+/// let path = Path::new("/tmp/run-{\"seed\":7}.log");
+/// assert_eq!(
+///     extract_json_from_os_str(path.as_os_str()).unwrap(),
+///     "{\"seed\":7}"
+/// );
+/// ```
+pub fn extract_json_from_os_str(input: &OsStr) -> Result<String, ParseError> {
+    extract_json_from_bytes(input.as_encoded_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_json_from_bytes_with_invalid_utf8() {
+        let input = b"\x80name\xff {\"a\":\"x\xe9y\"} \xf0\x9f [1] \xe2\x82";
+        assert_eq!(
+            extract_json_from_bytes(input).unwrap(),
+            "{\"a\":\"x\u{FFFD}y\"}[1]"
+        );
+    }
+
+    #[test]
+    fn test_extract_json_from_bytes_never_panics() {
+        let input: Vec<u8> = (0..=255u8).cycle().take(4096).collect();
+        assert!(extract_json_from_bytes(&input).is_ok());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_extract_json_from_os_str_non_utf8() {
+        use std::os::unix::ffi::OsStrExt;
+
+        let name = OsStr::from_bytes(b"dump-\xfe\xff-{\"id\":3}.json");
+        assert_eq!(extract_json_from_os_str(name).unwrap(), "{\"id\":3}");
+    }
+}
@@ -0,0 +1,181 @@
+//! Utility functions for extracting JSON from percent-encoded text.
+
+use std::ops::Range;
+
+use crate::JSONParser;
+
+/// A value extracted from percent-encoded text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PercentDecodedValue {
+    /// The decoded JSON
+    pub json: String,
+    /// The byte range of the value in the original, encoded input
+    pub span: Range<usize>,
+}
+
+/// Extracts JSON from text after percent-decoding it, such as query strings and form bodies.
+///
+/// Every `%XX` sequence is decoded before the text is scanned, so payloads
+/// that are fully encoded (`%7B%22a%22%3A1%7D`) and partially encoded
+/// (`{%22a%22:1}`) are both found. A `%` not followed by two hex digits,
+/// as in "100% sure", is kept as it is. With `plus_as_space`, `+` decodes
+/// to a space as in form encoding. Decoded bytes that are not valid UTF-8
+/// become U+FFFD.
+///
+/// Unlike [`ParserOptions::percent_decode_candidates`](crate::parser::options::ParserOptions::percent_decode_candidates),
+/// which only decodes runs starting with an encoded bracket while
+/// streaming, this decodes the whole input and reports where each value
+/// came from.
+///
+/// # Arguments
+///
+/// * `input` - The string slice containing percent-encoded text.
+/// * `plus_as_space` - Whether `+` decodes to a space.
+///
+/// # Returns
+///
+/// The values in order of appearance, with their spans in `input`.
+///
+/// # Examples
+///
+/// ```
+/// use surfing::utils::extract_json_percent_decoded;
+///
+/// let input = "GET /search?q=%7B%22tag%22%3A%22a+b%22%7D 200 100% ok";
+/// let values = extract_json_percent_decoded(input, true);
+///
+/// assert_eq!(values[0].json, "{\"tag\":\"a b\"}");
+/// assert_eq!(&input[values[0].span.clone()], "%7B%22tag%22%3A%22a+b%22%7D");
+/// ```
+pub fn extract_json_percent_decoded(input: &str, plus_as_space: bool) -> Vec<PercentDecodedValue> {
+    let (decoded, origins) = percent_decode(input, plus_as_space);
+    let mut values = Vec::new();
+
+    JSONParser::new().extract_value_spans(&decoded, |span| {
+        values.push(PercentDecodedValue {
+            json: decoded[span.clone()].to_string(),
+            span: origins[span.start]..origins[span.end],
+        });
+    });
+
+    values
+}
+
+/// Percent-decodes the input, keeping track of where each decoded byte came from.
+///
+/// # Arguments
+///
+/// * `input` - The text to decode.
+/// * `plus_as_space` - Whether `+` decodes to a space.
+///
+/// # Returns
+///
+/// The decoded text, and for each of its bytes the offset in `input` of
+/// the text it was decoded from, followed by the length of `input`.
+fn percent_decode(input: &str, plus_as_space: bool) -> (String, Vec<usize>) {
+    let bytes = input.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut origins = Vec::with_capacity(bytes.len() + 1);
+    let mut position = 0;
+
+    while position < bytes.len() {
+        let (byte, length) = match bytes[position] {
+            b'%' => match bytes.get(position + 1..position + 3).and_then(decode_hex) {
+                Some(byte) => (byte, 3),
+                None => (b'%', 1),
+            },
+            b'+' if plus_as_space => (b' ', 1),
+            byte => (byte, 1),
+        };
+
+        decoded.push(byte);
+        origins.push(position);
+        position += length;
+    }
+    origins.push(bytes.len());
+
+    // Decoded bytes need not be UTF-8
+    let mut text = String::with_capacity(decoded.len());
+    let mut text_origins = Vec::with_capacity(origins.len());
+    let mut index = 0;
+
+    for chunk in decoded.utf8_chunks() {
+        let valid = chunk.valid();
+        text.push_str(valid);
+        text_origins.extend_from_slice(&origins[index..index + valid.len()]);
+        index += valid.len();
+
+        if !chunk.invalid().is_empty() {
+            let replacement = char::REPLACEMENT_CHARACTER;
+            text.push(replacement);
+            text_origins.extend(std::iter::repeat_n(origins[index], replacement.len_utf8()));
+            index += chunk.invalid().len();
+        }
+    }
+    text_origins.push(origins[index]);
+
+    (text, text_origins)
+}
+
+/// Decodes two hex digits into a byte.
+fn decode_hex(digits: &[u8]) -> Option<u8> {
+    let high = char::from(digits[0]).to_digit(16)?;
+    let low = char::from(digits[1]).to_digit(16)?;
+    Some((high * 16 + low) as u8)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encoded_spans<'a>(input: &'a str, values: &[PercentDecodedValue]) -> Vec<&'a str> {
+        values
+            .iter()
+            .map(|value| &input[value.span.clone()])
+            .collect()
+    }
+
+    #[test]
+    fn test_extract_json_percent_decoded_fully_and_partially_encoded() {
+        let input = "a=%7B%22n%22%3A%22Jos%C3%A9%22%7D&b={%22x%22:[1,%202]}&c=%5Btrue%5D";
+        let values = extract_json_percent_decoded(input, false);
+
+        let json: Vec<&str> = values.iter().map(|value| value.json.as_str()).collect();
+        assert_eq!(json, vec!["{\"n\":\"José\"}", "{\"x\":[1, 2]}", "[true]"]);
+        assert_eq!(
+            encoded_spans(input, &values),
+            vec![
+                "%7B%22n%22%3A%22Jos%C3%A9%22%7D",
+                "{%22x%22:[1,%202]}",
+                "%5Btrue%5D"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_extract_json_percent_decoded_tolerates_stray_percent() {
+        let input = "100% done, 5%z off, %FF%7B%22p%22:%2250%%22%7D %4";
+        let values = extract_json_percent_decoded(input, false);
+
+        assert_eq!(values.len(), 1);
+        assert_eq!(values[0].json, "{\"p\":\"50%\"}");
+        assert_eq!(
+            encoded_spans(input, &values),
+            vec!["%7B%22p%22:%2250%%22%7D"]
+        );
+    }
+
+    #[test]
+    fn test_extract_json_percent_decoded_plus_as_space() {
+        let input = "q={\"s\":\"a+b\"}";
+
+        assert_eq!(
+            extract_json_percent_decoded(input, true)[0].json,
+            "{\"s\":\"a b\"}"
+        );
+        assert_eq!(
+            extract_json_percent_decoded(input, false)[0].json,
+            "{\"s\":\"a+b\"}"
+        );
+    }
+}
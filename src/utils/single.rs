@@ -0,0 +1,117 @@
+//! Utility functions for validating that text holds exactly one JSON value.
+
+use std::fmt;
+use std::ops::Range;
+
+use crate::utils::anchor::preview;
+use crate::JSONParser;
+
+/// Error returned when text does not hold exactly one complete JSON value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExactlyOneError {
+    /// The input contains no complete JSON value
+    NoValue,
+    /// The input contains more than one complete JSON value
+    TooMany {
+        /// The number of complete values found
+        count: usize,
+        /// The byte range of the second value in the input
+        second: Range<usize>,
+        /// The start of the second value
+        preview: String,
+    },
+}
+
+impl fmt::Display for ExactlyOneError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExactlyOneError::NoValue => {
+                write!(f, "expected exactly one JSON value, found none")
+            }
+            ExactlyOneError::TooMany {
+                count,
+                second,
+                preview,
+            } => write!(
+                f,
+                "expected exactly one JSON value, found {}; the second is at bytes {}..{}: {}",
+                count, second.start, second.end, preview
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ExactlyOneError {}
+
+/// Extracts the only complete JSON value in a string.
+///
+/// Unlike taking the first value, this validates the input: finding no value
+/// or more than one is an error, which reports how many values there are and
+/// where the second one is.
+///
+/// # Arguments
+///
+/// * `input` - The string slice containing mixed text and JSON.
+///
+/// # Returns
+///
+/// * `Ok(String)` - The single JSON value.
+/// * `Err(ExactlyOneError)` - If there are zero or several complete values.
+///
+/// # Examples
+///
+/// ```
+/// use surfing::utils::{extract_exactly_one_json, ExactlyOneError};
+///
+/// assert_eq!(extract_exactly_one_json("ok: {\"id\":1}").unwrap(), "{\"id\":1}");
+///
+/// let error = extract_exactly_one_json("{\"id\":1} dup {\"id\":1}").unwrap_err();
+/// assert!(matches!(error, ExactlyOneError::TooMany { count: 2, second, .. } if second == (13..21)));
+/// ```
+pub fn extract_exactly_one_json(input: &str) -> Result<String, ExactlyOneError> {
+    let mut spans = Vec::with_capacity(2);
+    let mut count = 0;
+    JSONParser::new().extract_value_spans(input, |span| {
+        count += 1;
+        if spans.len() < 2 {
+            spans.push(span);
+        }
+    });
+
+    match &spans[..] {
+        [] => Err(ExactlyOneError::NoValue),
+        [only] => Ok(input[only.clone()].to_string()),
+        [_, second, ..] => Err(ExactlyOneError::TooMany {
+            count,
+            second: second.clone(),
+            preview: preview(&input[second.clone()]),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_exactly_one_json() {
+        assert_eq!(
+            extract_exactly_one_json("log [1, {\"a\":2}] done {\"partial\":").unwrap(),
+            "[1, {\"a\":2}]"
+        );
+    }
+
+    #[test]
+    fn test_extract_exactly_one_json_errors() {
+        assert_eq!(
+            extract_exactly_one_json("nothing"),
+            Err(ExactlyOneError::NoValue)
+        );
+
+        let error = extract_exactly_one_json("[1] then [2] and [3]").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "expected exactly one JSON value, found 3; the second is at bytes 9..12: [2]"
+        );
+    }
+}
@@ -0,0 +1,275 @@
+//! Utility functions for filtering standard input to standard output.
+
+use std::fmt;
+use std::io::{ErrorKind, Read, Write};
+
+use crate::parser::error::ParseError;
+use crate::parser::options::ParserOptions;
+use crate::parser::outcome::CountingWriter;
+use crate::JSONParser;
+
+/// The number of bytes read from the input at a time.
+const STDIN_BLOCK_SIZE: usize = 64 * 1024;
+
+/// What an extraction from standard input did.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExtractSummary {
+    /// The number of bytes read from the input
+    pub bytes_read: u64,
+    /// The number of bytes written to the output
+    pub bytes_written: u64,
+    /// The number of top-level values written in full
+    pub values: usize,
+    /// Whether the output was closed early, such as by `head -1`
+    pub broken_pipe: bool,
+    /// The errors the parser reported while extraction went on, such as for
+    /// a value dropped past [`ParserOptions::max_string_bytes`], at most one
+    /// per block read
+    pub errors: Vec<String>,
+}
+
+/// Error returned when an extraction from standard input fails.
+#[derive(Debug)]
+pub enum ExtractError {
+    /// Reading the input failed
+    Read(std::io::Error),
+    /// Writing the output failed for a reason other than a closed pipe
+    Write(std::io::Error),
+    /// The parser rejected the input, such as a NUL under [`NulPolicy::Error`](crate::parser::options::NulPolicy::Error)
+    Parse(ParseError),
+}
+
+impl fmt::Display for ExtractError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExtractError::Read(e) => write!(f, "failed to read input: {}", e),
+            ExtractError::Write(e) => write!(f, "failed to write output: {}", e),
+            ExtractError::Parse(e) => write!(f, "failed to extract JSON: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for ExtractError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ExtractError::Read(e) | ExtractError::Write(e) => Some(e),
+            ExtractError::Parse(e) => Some(e),
+        }
+    }
+}
+
+/// Extracts JSON from standard input and writes it to standard output.
+///
+/// Both streams are locked for the duration of the call. The output is
+/// flushed whenever a top-level value completes, so downstream commands see
+/// each value as soon as it is available. If the output is closed early, as
+/// when piping into `head -1`, extraction stops cleanly and the summary
+/// reports [`broken_pipe`](ExtractSummary::broken_pipe) instead of an error.
+/// Values the parser rejects are dropped and reported in
+/// [`errors`](ExtractSummary::errors), and the rest of the input is still
+/// filtered.
+///
+/// # Arguments
+///
+/// * `options` - The options controlling how extracted JSON is emitted.
+///
+/// # Returns
+///
+/// * `Ok(ExtractSummary)` - What was read and written.
+/// * `Err(ExtractError)` - If reading or writing failed.
+///
+/// # Examples
+///
+/// ```no_run
+/// use surfing::parser::options::ParserOptions;
+/// use surfing::utils::extract_json_from_stdin_to_stdout;
+///
+/// let summary = extract_json_from_stdin_to_stdout(ParserOptions::new()).unwrap();
+/// eprintln!("extracted {} values", summary.values);
+/// ```
+pub fn extract_json_from_stdin_to_stdout(
+    options: ParserOptions,
+) -> Result<ExtractSummary, ExtractError> {
+    let stdin = std::io::stdin();
+    let stdout = std::io::stdout();
+
+    extract_json_filter(options, stdin.lock(), &mut stdout.lock())
+}
+
+/// Extracts JSON from a reader to a writer, flushing after completed values.
+///
+/// # Arguments
+///
+/// * `options` - The options controlling how extracted JSON is emitted.
+/// * `reader` - The reader supplying the text.
+/// * `writer` - The writer receiving the extracted JSON.
+fn extract_json_filter<R: Read, W: Write>(
+    options: ParserOptions,
+    mut reader: R,
+    writer: &mut W,
+) -> Result<ExtractSummary, ExtractError> {
+    // The parser flushes as each value completes, before the next one starts
+    let mut parser = JSONParser::with_options(options.flush_per_value(true));
+
+    let mut summary = ExtractSummary::default();
+    let mut output = CountingWriter::new(writer);
+    let mut block = vec![0; STDIN_BLOCK_SIZE];
+    let mut filled = 0;
+
+    loop {
+        let read = match reader.read(&mut block[filled..]) {
+            Ok(read) => read,
+            Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+            Err(e) => return Err(ExtractError::Read(e)),
+        };
+
+        let result = if read == 0 {
+            let rest = String::from_utf8_lossy(&block[..filled]).into_owned();
+            // Hand over what is left of a value the input ends in
+            parser
+                .consume_block(rest.as_bytes(), &mut output)
+                .and_then(|consumed| {
+                    output.flush()?;
+                    Ok(consumed)
+                })
+        } else {
+            summary.bytes_read += read as u64;
+            filled += read;
+            parser.consume_block(&block[..filled], &mut output)
+        };
+        summary.bytes_written = output.written() as u64;
+        summary.values = parser.values_written();
+
+        let consumed = match result {
+            Ok(consumed) => consumed,
+            Err(ParseError::Io(e)) if e.kind() == ErrorKind::BrokenPipe => {
+                summary.broken_pipe = true;
+                return Ok(summary);
+            }
+            Err(ParseError::Io(e)) => return Err(ExtractError::Write(e)),
+            Err(e) => {
+                // The parser stopped at the error, so the rest of the block is not processed
+                summary.errors.push(e.to_string());
+                filled
+            }
+        };
+        // A rejected value is only dropped, so extraction goes on
+        if let Err(e) = parser.take_string_error() {
+            summary.errors.push(e.to_string());
+        }
+        if read == 0 {
+            return Ok(summary);
+        }
+
+        block.copy_within(consumed..filled, 0);
+        filled -= consumed;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A reader handing out one piece per read.
+    struct PieceReader(Vec<&'static str>);
+
+    impl Read for PieceReader {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            if self.0.is_empty() {
+                return Ok(0);
+            }
+            let piece = self.0.remove(0).as_bytes();
+            buf[..piece.len()].copy_from_slice(piece);
+            Ok(piece.len())
+        }
+    }
+
+    /// A writer recording its output at each flush, closing after `capacity` flushes.
+    #[derive(Default)]
+    struct FlushRecorder {
+        bytes: Vec<u8>,
+        flushed: Vec<String>,
+        capacity: Option<usize>,
+    }
+
+    impl Write for FlushRecorder {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            if self.capacity == Some(self.flushed.len()) {
+                return Err(ErrorKind::BrokenPipe.into());
+            }
+            self.bytes.extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            self.flushed
+                .push(String::from_utf8(self.bytes.clone()).unwrap());
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_extract_json_filter_flushes_completed_values() {
+        let reader = PieceReader(vec!["log {\"a\":", "1} more [2", "] tail {\"open\""]);
+        let mut writer = FlushRecorder::default();
+
+        let summary = extract_json_filter(ParserOptions::new(), reader, &mut writer).unwrap();
+
+        assert_eq!(
+            writer.flushed,
+            vec!["{\"a\":1}", "{\"a\":1}[2]", "{\"a\":1}[2]{\"open\""]
+        );
+        assert_eq!(
+            summary,
+            ExtractSummary {
+                bytes_read: 33,
+                bytes_written: 17,
+                values: 2,
+                broken_pipe: false,
+                errors: Vec::new(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_extract_json_filter_stops_on_broken_pipe() {
+        let reader = PieceReader(vec!["{\"a\":1}", " [2]", " [3]"]);
+        let mut writer = FlushRecorder {
+            capacity: Some(1),
+            ..Default::default()
+        };
+
+        let summary = extract_json_filter(ParserOptions::new(), reader, &mut writer).unwrap();
+
+        assert_eq!(writer.bytes, b"{\"a\":1}");
+        assert!(summary.broken_pipe);
+        assert_eq!(summary.bytes_read, 11);
+    }
+
+    #[test]
+    fn test_extract_json_filter_reports_rejected_values_and_goes_on() {
+        let reader = PieceReader(vec!["{\"s\":\"too long\"} [1]", " {\"s\":\"ok\"}"]);
+        let mut writer = FlushRecorder::default();
+        let options = ParserOptions::new().max_string_bytes(4);
+
+        let summary = extract_json_filter(options, reader, &mut writer).unwrap();
+
+        assert_eq!(writer.bytes, b"[1]{\"s\":\"ok\"}");
+        assert_eq!(summary.values, 2);
+        assert_eq!(summary.errors, vec![ParseError::StringTooLong.to_string()]);
+    }
+
+    #[test]
+    fn test_extract_json_filter_counts_written_values() {
+        // The cut-off record is dropped and the scalar has no closer
+        let reader = PieceReader(vec!["{\"cut\":\n[1]\n", "id 7, done\n"]);
+        let mut writer = FlushRecorder::default();
+        let options = ParserOptions::new().ndjson(true).extract_scalars(true);
+
+        let summary = extract_json_filter(options, reader, &mut writer).unwrap();
+
+        assert_eq!(writer.bytes, b"[1]7\n");
+        assert_eq!(summary.values, 2);
+        assert!(summary.errors.is_empty());
+    }
+}
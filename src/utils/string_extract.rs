@@ -1,7 +1,14 @@
 //! Utility functions for extracting JSON to strings.
 
+use std::borrow::Cow;
 use std::io::Cursor;
+use std::ops::Range;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
 
+use crate::parser::error::ParseError;
+use crate::parser::grammar::is_valid_json;
+use crate::parser::json_parser::is_single_value;
 use crate::JSONParser;
 
 /// Extracts JSON from a string and returns the result as a String.
@@ -16,7 +23,7 @@ use crate::JSONParser;
 /// # Returns
 ///
 /// * `Ok(String)` - The extracted JSON as a String.
-/// * `Err(Error)` - If an error occurred during parsing or UTF-8 conversion.
+/// * `Err(ParseError)` - If an error occurred during parsing.
 ///
 /// # Examples
 ///
@@ -36,7 +43,46 @@ use crate::JSONParser;
 /// let json = extract_json_to_string(input).unwrap();
 /// assert_eq!(json, "{\"id\":1}{\"id\":2}");
 /// ```
-pub fn extract_json_to_string(input: &str) -> Result<String, Box<dyn std::error::Error>> {
+pub fn extract_json_to_string(input: &str) -> Result<String, ParseError> {
+    extract_json_to_cow(input).map(Cow::into_owned)
+}
+
+/// Extracts JSON from a string, borrowing the input when it is already pure JSON.
+///
+/// Inputs that are exactly one JSON object or array, apart from surrounding
+/// whitespace, are recognized with a quick scan of their markers and
+/// returned as a trimmed slice of the input, without running the parser.
+/// Any other input goes through the parser, and the result is always the
+/// same as that of [`extract_json_to_string`].
+///
+/// # Arguments
+///
+/// * `input` - The string slice containing mixed text and JSON.
+///
+/// # Returns
+///
+/// * `Ok(Cow<str>)` - The extracted JSON, borrowed from the input when possible.
+/// * `Err(ParseError)` - If an error occurred during parsing.
+///
+/// # Examples
+///
+/// ```
+/// use std::borrow::Cow;
+/// use surfing::utils::extract_json_to_cow;
+///
+/// let clean = extract_json_to_cow("  {\"id\":1}\n").unwrap();
+/// assert!(matches!(clean, Cow::Borrowed("{\"id\":1}")));
+///
+/// let mixed = extract_json_to_cow("Result: {\"id\":1}").unwrap();
+/// assert!(matches!(mixed, Cow::Owned(_)));
+/// assert_eq!(mixed, "{\"id\":1}");
+/// ```
+pub fn extract_json_to_cow(input: &str) -> Result<Cow<'_, str>, ParseError> {
+    let trimmed = input.trim();
+    if is_single_value(trimmed) {
+        return Ok(Cow::Borrowed(trimmed));
+    }
+
     let mut parser = JSONParser::new();
     let mut buffer = Vec::new();
 
@@ -45,7 +91,372 @@ pub fn extract_json_to_string(input: &str) -> Result<String, Box<dyn std::error:
         parser.extract_json_from_stream(&mut writer, input)?;
     }
 
-    Ok(String::from_utf8(buffer)?)
+    String::from_utf8(buffer)
+        .map(Cow::Owned)
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e).into())
+}
+
+/// Extracts each JSON value from a string as a shared `Arc<str>`.
+///
+/// Every top-level JSON object or array becomes its own `Arc<str>`, which can be
+/// handed to several threads without copying the text. As with
+/// [`extract_json_objects`], extracted structures that are not valid JSON
+/// are skipped.
+///
+/// # Arguments
+///
+/// * `input` - The string slice containing mixed text and JSON.
+///
+/// # Returns
+///
+/// A vector with one `Arc<str>` per valid JSON value, in order of appearance.
+///
+/// # Examples
+///
+/// ```
+/// use surfing::utils::extract_json_arcs;
+///
+/// let input = "First: {\"id\":1} Second: [2]";
+/// let values = extract_json_arcs(input);
+///
+/// assert_eq!(values.len(), 2);
+/// assert_eq!(&*values[0], "{\"id\":1}");
+/// assert_eq!(&*values[1], "[2]");
+/// ```
+pub fn extract_json_arcs(input: &str) -> Vec<Arc<str>> {
+    extract_json_objects(input).map(Arc::from).collect()
+}
+
+/// Extracts the first complete JSON value from a string.
+///
+/// As with [`extract_json_objects`], extracted structures that are not
+/// valid JSON are skipped, so the `[INFO]` of a log line is never taken for
+/// the value.
+///
+/// # Arguments
+///
+/// * `input` - The string slice containing mixed text and JSON.
+///
+/// # Returns
+///
+/// The first valid JSON value, or `None` if there is none.
+///
+/// # Examples
+///
+/// ```
+/// use surfing::utils::extract_first_json;
+///
+/// let input = "[INFO] First: {\"id\":1} Second: {\"id\":2}";
+/// assert_eq!(extract_first_json(input).as_deref(), Some("{\"id\":1}"));
+/// ```
+pub fn extract_first_json(input: &str) -> Option<String> {
+    valid_value_spans(input)
+        .next()
+        .map(|span| input[span].to_string())
+}
+
+/// Extracts the last complete JSON value from a string.
+///
+/// As with [`extract_json_objects`], extracted structures that are not
+/// valid JSON are skipped.
+///
+/// # Arguments
+///
+/// * `input` - The string slice containing mixed text and JSON.
+///
+/// # Returns
+///
+/// The last valid JSON value, or `None` if there is none.
+///
+/// # Examples
+///
+/// ```
+/// use surfing::utils::extract_last_json;
+///
+/// let input = "First: {\"id\":1} Second: {\"id\":2} Third: {\"id\":";
+/// assert_eq!(extract_last_json(input).as_deref(), Some("{\"id\":2}"));
+/// ```
+pub fn extract_last_json(input: &str) -> Option<String> {
+    valid_value_spans(input)
+        .last()
+        .map(|span| input[span].to_string())
+}
+
+/// Extracts the largest complete JSON value from a string.
+///
+/// Values are compared by their length in bytes, and of values of equal
+/// length the later one wins. This suits answers that show a small example
+/// before or after the real payload. Each value is dropped as soon as a
+/// larger one completes, so only the value being extracted and the largest
+/// so far are held in memory.
+///
+/// # Arguments
+///
+/// * `input` - The string slice containing mixed text and JSON.
+///
+/// # Returns
+///
+/// * `Ok(Some(String))` - The largest complete JSON value.
+/// * `Ok(None)` - If there is no complete JSON value.
+/// * `Err(ParseError)` - If an error occurred during parsing.
+///
+/// # Examples
+///
+/// ```
+/// use surfing::utils::extract_largest_json_to_string;
+///
+/// let input = "Reply like {\"ok\":1}. Here it is: {\"ok\":1,\"items\":[1,2,3]}";
+/// let json = extract_largest_json_to_string(input).unwrap();
+/// assert_eq!(json.as_deref(), Some("{\"ok\":1,\"items\":[1,2,3]}"));
+/// ```
+pub fn extract_largest_json_to_string(input: &str) -> Result<Option<String>, ParseError> {
+    let mut largest: Option<String> = None;
+
+    JSONParser::new().extract_values_from_stream(input, |value| {
+        if largest
+            .as_ref()
+            .is_none_or(|largest| value.len() >= largest.len())
+        {
+            let largest = largest.get_or_insert_with(String::new);
+            largest.clear();
+            largest.push_str(value);
+        }
+    })?;
+
+    Ok(largest)
+}
+
+/// Extracts every complete JSON value from a string, one at a time.
+///
+/// Unlike [`extract_json_to_string`], which concatenates the values, each
+/// top-level object or array is yielded as a string of its own, so every
+/// item can be parsed on its own. Text between the values is skipped, and
+/// so are extracted structures that are not valid JSON, such as the
+/// `[INFO]` of a log line. The input is scanned lazily, one value per call
+/// to `next`.
+///
+/// # Arguments
+///
+/// * `input` - The string slice containing mixed text and JSON.
+///
+/// # Returns
+///
+/// An iterator over the valid JSON values in order of appearance.
+///
+/// # Examples
+///
+/// ```
+/// use surfing::utils::extract_json_objects;
+///
+/// let input = "[INFO] {\"id\":1}{\"id\":2} then [3, 4]";
+/// let values: Vec<String> = extract_json_objects(input).collect();
+///
+/// assert_eq!(values, vec!["{\"id\":1}", "{\"id\":2}", "[3, 4]"]);
+/// ```
+pub fn extract_json_objects(input: &str) -> impl Iterator<Item = String> + '_ {
+    valid_value_spans(input).map(|span| input[span].to_string())
+}
+
+/// Scans a string for the byte ranges of its valid JSON values, one per call to `next`.
+///
+/// # Arguments
+///
+/// * `input` - The string slice containing mixed text and JSON.
+fn valid_value_spans(input: &str) -> impl Iterator<Item = Range<usize>> + '_ {
+    let mut parser = JSONParser::new();
+    let mut position = 0;
+
+    std::iter::from_fn(move || loop {
+        let span = parser.next_value_span(input, &mut position)?;
+        if is_valid_json(&input[span.clone()]) {
+            return Some(span);
+        }
+    })
+}
+
+/// Extracts every complete JSON value from a string, newest first.
+///
+/// The values are those of [`extract_json_objects`], in reverse order of
+/// appearance. Unlike [`extract_last_json`], all of them are returned, which
+/// suits tail viewers showing the most recent entry of a log first.
+///
+/// # Arguments
+///
+/// * `input` - The string slice containing mixed text and JSON.
+///
+/// # Returns
+///
+/// The valid JSON values, from the last to the first.
+///
+/// # Examples
+///
+/// ```
+/// use surfing::utils::extract_json_objects_rev;
+///
+/// let log = "09:00 {\"n\":1}\n09:01 [INFO] {\"n\":2}\n09:02 {\"n\":";
+/// assert_eq!(extract_json_objects_rev(log), vec!["{\"n\":2}", "{\"n\":1}"]);
+/// ```
+pub fn extract_json_objects_rev(input: &str) -> Vec<String> {
+    let mut values: Vec<String> = extract_json_objects(input).collect();
+    values.reverse();
+    values
+}
+
+/// Returns where each complete JSON value lies in a string.
+///
+/// Each span is a half-open `(start, end)` byte range of a top-level object
+/// or array, so `&input[start..end]` is the value exactly as extracted. This
+/// suits tools that highlight or redact the JSON in a log line rather than
+/// copy it out. The spans are those of the values of
+/// [`extract_json_objects`], so structures that are not valid JSON are
+/// skipped.
+///
+/// # Arguments
+///
+/// * `input` - The string slice containing mixed text and JSON.
+///
+/// # Returns
+///
+/// The byte ranges of the valid JSON values, in order of appearance.
+///
+/// # Examples
+///
+/// ```
+/// use surfing::utils::extract_json_spans;
+///
+/// let input = "id {\"n\":1} and [2]";
+/// let spans = extract_json_spans(input);
+///
+/// assert_eq!(spans, vec![(3, 10), (15, 18)]);
+/// assert_eq!(&input[spans[0].0..spans[0].1], "{\"n\":1}");
+/// ```
+pub fn extract_json_spans(input: &str) -> Vec<(usize, usize)> {
+    valid_value_spans(input)
+        .map(|span| (span.start, span.end))
+        .collect()
+}
+
+/// Extracts every complete JSON value and the deepest nesting reached.
+///
+/// The depth counts open objects and arrays, so `{}` has depth 1 and
+/// `{"a":[1]}` depth 2. It covers the whole input, including values left
+/// incomplete at its end, which makes it a guide for sizing depth limits.
+///
+/// # Arguments
+///
+/// * `input` - The string slice containing mixed text and JSON.
+///
+/// # Returns
+///
+/// The complete JSON values in order of appearance, and the maximum depth.
+///
+/// # Examples
+///
+/// ```
+/// use surfing::utils::extract_with_max_depth;
+///
+/// let (values, depth) = extract_with_max_depth("a {\"b\":[1]} c [2]");
+/// assert_eq!(values, vec!["{\"b\":[1]}", "[2]"]);
+/// assert_eq!(depth, 2);
+/// ```
+pub fn extract_with_max_depth(input: &str) -> (Vec<String>, usize) {
+    let max_depth = Arc::new(AtomicUsize::new(0));
+    let observed = Arc::clone(&max_depth);
+    let mut parser = JSONParser::new().on_open(move |depth, _| {
+        observed.fetch_max(depth + 1, Ordering::Relaxed);
+    });
+
+    let mut values = Vec::new();
+    parser.extract_value_spans(input, |span| values.push(input[span].to_string()));
+
+    (values, max_depth.load(Ordering::Relaxed))
+}
+
+/// The number of bytes read at a time while finishing a value past the range end.
+const TAIL_CHUNK_SIZE: usize = 4096;
+
+/// Extracts the JSON values that start within a byte range of the input.
+///
+/// This supports sharded processing: each worker owns the values whose
+/// opening marker lies in its range `[start, end)`. A value starting inside
+/// the range is returned whole even if it extends past `end`, and a value that
+/// started before `start` is skipped even if it extends into the range, so
+/// adjacent ranges never return the same value.
+///
+/// The text before `start` is scanned to learn whether the range begins
+/// inside a value, and the text after `end` is only read as far as the last
+/// value extends. Offsets that fall inside a multi-byte character are moved
+/// forward to the next character. As with [`extract_json_objects`],
+/// extracted structures that are not valid JSON are skipped.
+///
+/// # Arguments
+///
+/// * `input` - The string slice containing mixed text and JSON.
+/// * `range` - The byte range in which values must start.
+///
+/// # Returns
+///
+/// A vector with one `String` per valid JSON value starting in the range.
+///
+/// # Examples
+///
+/// ```
+/// use surfing::utils::extract_in_range;
+///
+/// let input = "{\"a\":1} text {\"b\":2} more {\"c\":3}";
+///
+/// // The range starts inside the first value and ends inside the second
+/// assert_eq!(extract_in_range(input, 3..18), vec!["{\"b\":2}"]);
+/// assert_eq!(extract_in_range(input, 18..input.len()), vec!["{\"c\":3}"]);
+/// ```
+pub fn extract_in_range(input: &str, range: Range<usize>) -> Vec<String> {
+    let end = ceil_char_boundary(input, range.end);
+    let start = ceil_char_boundary(input, range.start).min(end);
+    let mut parser = JSONParser::new();
+    let mut values = Vec::new();
+
+    // A value belongs to the range its opening marker lies in
+    let mut position = 0;
+    while let Some(span) = parser.next_value_span(&input[..end], &mut position) {
+        if span.start >= start && is_valid_json(&input[span.clone()]) {
+            values.push(input[span].to_string());
+        }
+    }
+
+    // The input of the value in progress is held as it appears, so it tells where the value started
+    let value_start = end - parser.current_json().len();
+    if !parser.is_in_json() || value_start < start {
+        return values;
+    }
+
+    while parser.is_in_json() && position < input.len() {
+        let chunk_start = position;
+        let next = ceil_char_boundary(input, position + TAIL_CHUNK_SIZE);
+        match parser.next_value_span(&input[..next], &mut position) {
+            // Only the value in progress completes without starting in the chunk
+            Some(span) if span.start == chunk_start => {
+                let value = &input[value_start..span.end];
+                if is_valid_json(value) {
+                    values.push(value.to_string());
+                }
+                break;
+            }
+            // The value in progress was dropped, and one starting past the range completed
+            Some(_) => break,
+            None => {}
+        }
+    }
+
+    values
+}
+
+/// Returns the first character boundary at or after `index`, capped at the length.
+fn ceil_char_boundary(input: &str, index: usize) -> usize {
+    let mut index = index.min(input.len());
+    while !input.is_char_boundary(index) {
+        index += 1;
+    }
+    index
 }
 
 #[cfg(test)]
@@ -79,4 +490,257 @@ mod tests {
         let result = extract_json_to_string(input).unwrap();
         assert_eq!(result, "[1,2,3]");
     }
+
+    #[test]
+    fn test_extract_json_arcs() {
+        let input = "a {\"id\":1} b [1,{\"x\":2}] c {\"partial\":";
+        let values = extract_json_arcs(input);
+
+        assert_eq!(values.len(), 2);
+        assert_eq!(&*values[0], "{\"id\":1}");
+        assert_eq!(&*values[1], "[1,{\"x\":2}]");
+
+        let shared = Arc::clone(&values[0]);
+        let handle = std::thread::spawn(move || shared.len());
+        assert_eq!(handle.join().unwrap(), 8);
+    }
+
+    #[test]
+    fn test_extract_json_arcs_skips_invalid_values() {
+        let values = extract_json_arcs("[1] x {\"a\"}");
+
+        assert_eq!(values.len(), 1);
+        assert_eq!(&*values[0], "[1]");
+    }
+
+    #[test]
+    fn test_extract_json_objects_keeps_deeply_nested_values() {
+        let deep = format!("x {}{} y", "[".repeat(200), "]".repeat(200));
+
+        assert_eq!(extract_json_objects(&deep).count(), 1);
+        assert_eq!(extract_json_arcs(&deep).len(), 1);
+        assert_eq!(extract_json_spans(&deep).len(), 1);
+    }
+
+    #[test]
+    fn test_extract_json_arcs_no_json() {
+        assert!(extract_json_arcs("nothing here").is_empty());
+    }
+
+    #[test]
+    fn test_extract_in_range() {
+        let input = "{\"skip\":[1,2]} log {\"keep\":1} log {\"long\":\"value\"} {\"next\":2}";
+        let long_start = input.find("{\"long").unwrap();
+
+        // Starts inside the first value and ends inside the third
+        let values = extract_in_range(input, 5..long_start + 3);
+        assert_eq!(values, vec!["{\"keep\":1}", "{\"long\":\"value\"}"]);
+
+        let values = extract_in_range(input, long_start + 3..input.len());
+        assert_eq!(values, vec!["{\"next\":2}"]);
+    }
+
+    #[test]
+    fn test_extract_in_range_after_dropped_value() {
+        // The value started before the range is dropped at its mismatched closer
+        let input = "{\"a\":[1} {\"b\":2}";
+        assert_eq!(extract_in_range(input, 3..input.len()), vec!["{\"b\":2}"]);
+
+        // A value crossing the range end is dropped past it
+        let input = "x {\"a\":[1, 2} {\"b\":3}";
+        assert!(extract_in_range(input, 0..5).is_empty());
+        assert_eq!(extract_in_range(input, 5..input.len()), vec!["{\"b\":3}"]);
+    }
+
+    #[test]
+    fn test_extract_in_range_shards_cover_input_once() {
+        let input = "é [1] x {\"a\":[{\"b\":2}]} y [3,4] ".repeat(50);
+        let expected = extract_json_arcs(&input);
+
+        for shard_size in [1, 7, 64, 1000] {
+            let mut values = Vec::new();
+            let mut start = 0;
+            while start < input.len() {
+                values.extend(extract_in_range(&input, start..start + shard_size));
+                start += shard_size;
+            }
+
+            let expected: Vec<&str> = expected.iter().map(|value| &**value).collect();
+            assert_eq!(values, expected, "shard size {}", shard_size);
+        }
+    }
+
+    #[test]
+    fn test_extract_first_and_last_json() {
+        let input = "a [1] b {\"x\":[2]} c {\"y\":";
+        assert_eq!(extract_first_json(input).as_deref(), Some("[1]"));
+        assert_eq!(extract_last_json(input).as_deref(), Some("{\"x\":[2]}"));
+
+        assert_eq!(extract_first_json("none"), None);
+        assert_eq!(extract_last_json("none"), None);
+    }
+
+    #[test]
+    fn test_value_queries_skip_log_level_prefixes() {
+        let input = "[INFO] user {\"id\":1} [WARN] done";
+        let value_start = input.find('{').unwrap();
+
+        assert_eq!(
+            extract_json_spans(input),
+            vec![(value_start, value_start + 8)]
+        );
+        assert_eq!(extract_first_json(input).as_deref(), Some("{\"id\":1}"));
+        assert_eq!(extract_last_json(input).as_deref(), Some("{\"id\":1}"));
+        assert_eq!(extract_in_range(input, 0..input.len()), vec!["{\"id\":1}"]);
+
+        // A level tag crossing the range end is not taken for a value either
+        assert!(extract_in_range(input, 0..2).is_empty());
+    }
+
+    #[test]
+    fn test_extract_largest_json_to_string() {
+        let payload = "{\"items\":[{\"id\":1},{\"id\":2}],\"total\":2}";
+        let decoy = "{\"items\":[]}";
+
+        let decoy_first = format!("Format: {} Answer: {} Thanks", decoy, payload);
+        let payload_first = format!("Answer: {} e.g. {} [1", payload, decoy);
+        for input in [decoy_first, payload_first] {
+            assert_eq!(
+                extract_largest_json_to_string(&input).unwrap().as_deref(),
+                Some(payload)
+            );
+        }
+
+        // Ties go to the later value
+        assert_eq!(
+            extract_largest_json_to_string("[1] [2] {}")
+                .unwrap()
+                .as_deref(),
+            Some("[2]")
+        );
+        assert_eq!(extract_largest_json_to_string("none {").unwrap(), None);
+    }
+
+    #[test]
+    fn test_extract_json_objects() {
+        let input = "a {\"id\":1}{\"id\":2} b [1, [2]] [WARN] {\"s\":\"}{\"}\n[] {\"open\":";
+        let values: Vec<String> = extract_json_objects(input).collect();
+
+        assert_eq!(
+            values,
+            vec![
+                "{\"id\":1}",
+                "{\"id\":2}",
+                "[1, [2]]",
+                "{\"s\":\"}{\"}",
+                "[]"
+            ]
+        );
+        assert!(extract_json_objects("no JSON here").next().is_none());
+    }
+
+    #[test]
+    fn test_extract_json_objects_rev() {
+        let input = "1 {\"n\":1} 2 {\"n\":2} 3 {\"n\":3}";
+        assert_eq!(
+            extract_json_objects_rev(input),
+            vec!["{\"n\":3}", "{\"n\":2}", "{\"n\":1}"]
+        );
+
+        assert!(extract_json_objects_rev("none").is_empty());
+    }
+
+    #[test]
+    fn test_extract_json_objects_rev_skips_invalid_values() {
+        let input = "[INFO] {\"id\":1} [WARN] [2]";
+        let mut forward: Vec<String> = extract_json_objects(input).collect();
+        forward.reverse();
+
+        assert_eq!(extract_json_objects_rev(input), vec!["[2]", "{\"id\":1}"]);
+        assert_eq!(extract_json_objects_rev(input), forward);
+    }
+
+    #[test]
+    fn test_extract_json_spans() {
+        let input = "née {\"a\":\"☕\"} → [1, {\"b\":[]}] then {\"cut\":";
+        let spans = extract_json_spans(input);
+
+        let sliced: Vec<&str> = spans
+            .iter()
+            .map(|&(start, end)| &input[start..end])
+            .collect();
+        assert_eq!(sliced, vec!["{\"a\":\"☕\"}", "[1, {\"b\":[]}]"]);
+        assert_eq!(sliced, extract_json_objects(input).collect::<Vec<_>>());
+
+        assert!(extract_json_spans("no JSON here").is_empty());
+    }
+
+    fn extract_slow(input: &str) -> Result<String, ()> {
+        let mut buffer = Vec::new();
+        JSONParser::new()
+            .extract_json_from_stream(&mut buffer, input)
+            .map_err(|_| ())?;
+        Ok(String::from_utf8(buffer).unwrap())
+    }
+
+    #[test]
+    fn test_extract_json_to_cow_borrows_clean_json() {
+        let input = "\n {\"a\": [1, {\"b\": \"c\"}]}\t";
+        let json = extract_json_to_cow(input).unwrap();
+
+        assert!(matches!(json, Cow::Borrowed(_)));
+        assert_eq!(json, input.trim());
+    }
+
+    #[test]
+    fn test_extract_json_to_cow_matches_slow_path() {
+        let alphabet = ['{', '}', '[', ']', '"', '\\', ' ', 'a', '\0'];
+        let mut inputs = vec![String::new()];
+        let mut level = vec![String::new()];
+        for _ in 0..5 {
+            level = level
+                .iter()
+                .flat_map(|input| {
+                    alphabet
+                        .iter()
+                        .map(move |item| format!("{}{}", input, item))
+                })
+                .collect();
+            inputs.extend(level.iter().cloned());
+        }
+        inputs.extend(
+            [
+                "{\"s\":\"}\"}",
+                "{\"s\":\"\\\"{\"}",
+                "[\"]\", \"\\\\\"]",
+                "[1] trailing",
+                "prefix [1]",
+                "{} {}",
+                " \u{FEFF}{\"bom\":1}",
+                "{\"é\":\"ü\"}\u{3000}",
+            ]
+            .map(String::from),
+        );
+
+        for input in &inputs {
+            assert_eq!(
+                extract_json_to_cow(input)
+                    .map(Cow::into_owned)
+                    .map_err(|_| ()),
+                extract_slow(input),
+                "{:?}",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn test_extract_with_max_depth() {
+        let input = "x [1] y {\"a\":{\"b\":[{\"c\":1}]}} z {\"d\":[2]}";
+        let (values, depth) = extract_with_max_depth(input);
+
+        assert_eq!(values.len(), 3);
+        assert_eq!(depth, 4);
+        assert_eq!(extract_with_max_depth("no json"), (Vec::new(), 0));
+    }
 }
@@ -0,0 +1,291 @@
+//! Utility functions for extracting JSON while collecting non-fatal warnings.
+
+use std::fmt;
+
+use crate::parser::error::ParseError;
+use crate::parser::grammar::is_valid_json;
+use crate::parser::options::ParserOptions;
+use crate::parser::outcome::CharOutcome;
+use crate::JSONParser;
+
+/// What a [`Warning`] is about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WarningKind {
+    /// A candidate was skipped because it is not valid JSON
+    InvalidCandidate,
+    /// A value was rewritten by relaxed parsing to make it valid JSON
+    RelaxedRewrite,
+    /// A value was dropped before completing, at a record separator or
+    /// past a length, depth or size limit
+    Abandoned,
+}
+
+/// A problem found during extraction that did not stop it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Warning {
+    /// What the warning is about
+    pub kind: WarningKind,
+    /// The byte offset in the input where the value concerned starts
+    pub offset: usize,
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.kind {
+            WarningKind::InvalidCandidate => write!(
+                f,
+                "skipped a candidate that failed validation at offset {}",
+                self.offset
+            ),
+            WarningKind::RelaxedRewrite => {
+                write!(f, "rewrote relaxed JSON at offset {}", self.offset)
+            }
+            WarningKind::Abandoned => {
+                write!(f, "abandoned an incomplete value at offset {}", self.offset)
+            }
+        }
+    }
+}
+
+/// Extracts the JSON values of a string, collecting warnings instead of failing.
+///
+/// Every value the parser extracts is checked to be strictly valid JSON.
+/// Values that are not, such as `{"a": 1,}` or `{not json}`, are left out
+/// and reported as [`WarningKind::InvalidCandidate`]. With
+/// [`ParserOptions::relaxed`], a value that had to be rewritten is kept and
+/// reported as [`WarningKind::RelaxedRewrite`], and values dropped through
+/// [`ParserOptions::record_separator`], [`ParserOptions::ndjson`],
+/// [`ParserOptions::max_string_bytes`], [`ParserOptions::max_depth`],
+/// [`ParserOptions::max_buffer_size`] or [`ParserOptions::max_object_bytes`]
+/// are reported as [`WarningKind::Abandoned`].
+///
+/// # Arguments
+///
+/// * `input` - The string slice containing mixed text and JSON.
+/// * `options` - The options the parser is created with.
+///
+/// # Returns
+///
+/// The valid values in order of appearance, and the warnings in the order
+/// they arose.
+///
+/// # Examples
+///
+/// ```
+/// use surfing::parser::options::ParserOptions;
+/// use surfing::utils::{extract_json_with_warnings, WarningKind};
+///
+/// let (values, warnings) =
+///     extract_json_with_warnings("ok {\"a\":1} bad {\"b\":2,}", ParserOptions::new());
+///
+/// assert_eq!(values, vec!["{\"a\":1}"]);
+/// assert_eq!(warnings[0].kind, WarningKind::InvalidCandidate);
+/// assert_eq!(warnings[0].offset, 15);
+/// ```
+pub fn extract_json_with_warnings(
+    input: &str,
+    options: ParserOptions,
+) -> (Vec<String>, Vec<Warning>) {
+    let relaxed = options.relaxed;
+    let mut scan = CandidateScan::new(options);
+    let mut values = Vec::new();
+    let mut warnings = Vec::new();
+
+    for (offset, item) in input.char_indices() {
+        let Ok(event) = scan.push_char(item, offset) else {
+            continue;
+        };
+
+        match event {
+            CandidateEvent::Pending => {}
+            CandidateEvent::Abandoned { start } => warnings.push(Warning {
+                kind: WarningKind::Abandoned,
+                offset: start,
+            }),
+            CandidateEvent::Invalid { start } => warnings.push(Warning {
+                kind: WarningKind::InvalidCandidate,
+                offset: start,
+            }),
+            CandidateEvent::Valid { start, json } => {
+                let end = offset + item.len_utf8();
+                if relaxed && json != input[start..end] {
+                    warnings.push(Warning {
+                        kind: WarningKind::RelaxedRewrite,
+                        offset: start,
+                    });
+                }
+                values.push(json);
+            }
+        }
+    }
+
+    (values, warnings)
+}
+
+/// What became of the values in progress after a character was fed to a [`CandidateScan`].
+#[derive(Debug)]
+pub(crate) enum CandidateEvent<S> {
+    /// No value was completed or dropped
+    Pending,
+    /// A value was dropped before completing
+    Abandoned {
+        /// Where the value started
+        start: S,
+    },
+    /// A value completed but is not strictly valid JSON
+    Invalid {
+        /// Where the value started
+        start: S,
+    },
+    /// A valid value completed
+    Valid {
+        /// Where the value started
+        start: S,
+        /// The extracted JSON
+        json: String,
+    },
+}
+
+/// Feeds characters to a parser one at a time, classifying the values it extracts.
+///
+/// Values dropped past a length, depth or size limit are reported as
+/// [`CandidateEvent::Abandoned`] rather than as errors, and every completed
+/// value is checked to be strictly valid JSON.
+pub(crate) struct CandidateScan<S> {
+    parser: JSONParser,
+    /// The output of the value being extracted
+    output: Vec<u8>,
+    /// Where the value being extracted starts
+    start: Option<S>,
+}
+
+impl<S: Copy> CandidateScan<S> {
+    /// Creates a scan with a parser configured with the given options.
+    ///
+    /// # Arguments
+    ///
+    /// * `options` - The options the parser is created with.
+    pub(crate) fn new(options: ParserOptions) -> Self {
+        Self {
+            parser: JSONParser::with_options(options),
+            output: Vec::new(),
+            start: None,
+        }
+    }
+
+    /// Checks if the parser is inside a value.
+    pub(crate) fn is_in_json(&self) -> bool {
+        self.parser.is_in_json()
+    }
+
+    /// Processes a character, classifying what became of the value in progress.
+    ///
+    /// # Arguments
+    ///
+    /// * `item` - The character to process.
+    /// * `at` - Where the character lies, recorded as the start of a value it begins.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(CandidateEvent)` - What became of the value in progress.
+    /// * `Err(ParseError)` - If the parser rejected the input for a reason
+    ///   other than a limit.
+    pub(crate) fn push_char(&mut self, item: char, at: S) -> Result<CandidateEvent<S>, ParseError> {
+        if !self.parser.is_in_json() {
+            // Drop anything written outside of a value
+            self.output.clear();
+        }
+
+        let was_in_json = self.parser.is_in_json();
+        let outcome = match self.parser.push_char(item, &mut self.output) {
+            Ok(outcome) => outcome,
+            // Writing to a vector cannot fail, so this is a value dropped past a limit
+            Err(
+                ParseError::StringTooLong
+                | ParseError::DepthLimitExceeded { .. }
+                | ParseError::BufferLimitExceeded { .. }
+                | ParseError::ObjectTooLarge { .. },
+            ) => CharOutcome::Skipped,
+            Err(e) => return Err(e),
+        };
+
+        let event = match outcome {
+            CharOutcome::Started => {
+                let dropped = self.start.replace(at);
+                if was_in_json {
+                    // The value before was dropped at the character starting this one
+                    self.output.clear();
+                    CandidateEvent::Abandoned {
+                        start: dropped.unwrap_or(at),
+                    }
+                } else {
+                    CandidateEvent::Pending
+                }
+            }
+            CharOutcome::Completed => {
+                // Percent-decoded values complete without starting in the input
+                let start = self.start.take().unwrap_or(at);
+                let json = String::from_utf8_lossy(&self.output).into_owned();
+                self.output.clear();
+
+                if is_valid_json(&json) {
+                    CandidateEvent::Valid { start, json }
+                } else {
+                    CandidateEvent::Invalid { start }
+                }
+            }
+            _ if was_in_json && !self.parser.is_in_json() => CandidateEvent::Abandoned {
+                start: self.start.take().unwrap_or(at),
+            },
+            _ => CandidateEvent::Pending,
+        };
+
+        Ok(event)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_json_with_warnings_rejects_invalid_candidate() {
+        let input = "good {\"a\": [1]} then {\"b\": 2,} end";
+        let (values, warnings) = extract_json_with_warnings(input, ParserOptions::new());
+
+        assert_eq!(values, vec!["{\"a\": [1]}"]);
+        assert_eq!(
+            warnings,
+            vec![Warning {
+                kind: WarningKind::InvalidCandidate,
+                offset: 21,
+            }]
+        );
+        assert_eq!(
+            warnings[0].to_string(),
+            "skipped a candidate that failed validation at offset 21"
+        );
+    }
+
+    #[test]
+    fn test_extract_json_with_warnings_reports_rewrites_and_abandoned_values() {
+        let options = ParserOptions::new().relaxed(true).record_separator(0x1E);
+        let input = "{'a': True} [1] {\"cut\": 1\u{1e}";
+        let (values, warnings) = extract_json_with_warnings(input, options);
+
+        assert_eq!(values, vec!["{\"a\": true}", "[1]"]);
+        assert_eq!(
+            warnings,
+            vec![
+                Warning {
+                    kind: WarningKind::RelaxedRewrite,
+                    offset: 0,
+                },
+                Warning {
+                    kind: WarningKind::Abandoned,
+                    offset: 16,
+                },
+            ]
+        );
+    }
+}